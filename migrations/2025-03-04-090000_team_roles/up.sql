@@ -0,0 +1,22 @@
+CREATE TABLE team_roles (
+    id SERIAL PRIMARY KEY,
+    team_id INTEGER NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    discord_id VARCHAR(50) NOT NULL,
+    role VARCHAR(20) NOT NULL CHECK (role IN ('owner', 'manager', 'viewer', 'member')),
+    granted_by VARCHAR(50),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (team_id, discord_id)
+);
+
+-- Team creators become owners, existing members keep the member role
+INSERT INTO team_roles (team_id, discord_id, role)
+SELECT teams.id, users.discord_id, 'owner'
+FROM teams
+JOIN users ON users.id = teams.admin_id;
+
+INSERT INTO team_roles (team_id, discord_id, role)
+SELECT team_id, discord_id, 'member'
+FROM members
+WHERE team_id IS NOT NULL
+ON CONFLICT (team_id, discord_id) DO NOTHING;
+
@@ -0,0 +1,3 @@
+ALTER TABLE teams DROP CONSTRAINT teams_guild_id_name_key;
+ALTER TABLE teams ADD CONSTRAINT teams_name_key UNIQUE (name);
+ALTER TABLE teams DROP COLUMN guild_id;
@@ -0,0 +1,19 @@
+ALTER TABLE teams ADD COLUMN guild_id VARCHAR(50);
+
+-- Teams created before guild support are assigned to the guild configured with
+-- `ALTER DATABASE ... SET attendance.default_guild_id = '<guild_id>'`. Upgrading a database
+-- that already has teams fails until it is set, instead of guessing a guild for them.
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM teams)
+        AND COALESCE(current_setting('attendance.default_guild_id', true), '') = '' THEN
+        RAISE EXCEPTION 'Set attendance.default_guild_id to the Discord server of the existing teams before running this migration';
+    END IF;
+END
+$$;
+
+UPDATE teams SET guild_id = current_setting('attendance.default_guild_id', true);
+
+ALTER TABLE teams ALTER COLUMN guild_id SET NOT NULL;
+ALTER TABLE teams DROP CONSTRAINT teams_name_key;
+ALTER TABLE teams ADD CONSTRAINT teams_guild_id_name_key UNIQUE (guild_id, name);
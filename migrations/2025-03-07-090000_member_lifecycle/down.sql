@@ -0,0 +1,2 @@
+ALTER TABLE members DROP CONSTRAINT members_team_id_discord_id_key;
+ALTER TABLE members DROP COLUMN left_date;
@@ -0,0 +1,22 @@
+ALTER TABLE members ADD COLUMN left_date DATE;
+
+-- Merge duplicate members of a team into the oldest row before enforcing uniqueness,
+-- keeping the attendance history of the duplicates.
+UPDATE member_attendance
+SET member_id = keep.id
+FROM members duplicate
+JOIN (
+    SELECT MIN(id) AS id, team_id, discord_id
+    FROM members
+    GROUP BY team_id, discord_id
+) keep ON keep.team_id = duplicate.team_id AND keep.discord_id = duplicate.discord_id
+WHERE member_attendance.member_id = duplicate.id
+    AND duplicate.id <> keep.id;
+
+DELETE FROM members duplicate
+USING members keep
+WHERE duplicate.team_id = keep.team_id
+    AND duplicate.discord_id = keep.discord_id
+    AND duplicate.id > keep.id;
+
+ALTER TABLE members ADD CONSTRAINT members_team_id_discord_id_key UNIQUE (team_id, discord_id);
@@ -0,0 +1 @@
+ALTER TABLE teams ADD COLUMN discord_role_id VARCHAR(50);
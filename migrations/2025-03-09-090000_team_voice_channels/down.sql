@@ -0,0 +1,5 @@
+ALTER TABLE teams
+    DROP COLUMN voice_debounce_seconds,
+    DROP COLUMN voice_min_minutes;
+
+DROP TABLE team_voice_channels;
@@ -0,0 +1,13 @@
+CREATE TABLE team_voice_channels (
+    id SERIAL PRIMARY KEY,
+    team_id INTEGER NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    channel_id VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (team_id, channel_id)
+);
+
+-- Voice sessions shorter than the minimum are discarded, leaving and rejoining
+-- within the debounce window keeps the session open
+ALTER TABLE teams
+    ADD COLUMN voice_min_minutes INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN voice_debounce_seconds INTEGER NOT NULL DEFAULT 60;
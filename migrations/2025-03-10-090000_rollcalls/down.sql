@@ -0,0 +1,5 @@
+ALTER TABLE member_attendance
+    DROP CONSTRAINT member_attendance_rollcall_id_member_id_key,
+    DROP COLUMN rollcall_id;
+
+DROP TABLE rollcalls;
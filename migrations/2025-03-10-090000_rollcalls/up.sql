@@ -0,0 +1,15 @@
+CREATE TABLE rollcalls (
+    id SERIAL PRIMARY KEY,
+    team_id INTEGER NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    channel_id VARCHAR(50) NOT NULL,
+    message_id VARCHAR(50),
+    started_by VARCHAR(50) NOT NULL,
+    closes_at TIMESTAMP NOT NULL,
+    closed_at TIMESTAMP,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Roll-call answers are stored as attendance records, one per member and roll-call
+ALTER TABLE member_attendance
+    ADD COLUMN rollcall_id INTEGER REFERENCES rollcalls(id) ON DELETE SET NULL,
+    ADD CONSTRAINT member_attendance_rollcall_id_member_id_key UNIQUE (rollcall_id, member_id);
@@ -0,0 +1,3 @@
+ALTER TABLE member_attendance DROP COLUMN event_id;
+
+DROP TABLE events;
@@ -0,0 +1,15 @@
+CREATE TABLE events (
+    id SERIAL PRIMARY KEY,
+    team_id INTEGER NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    title VARCHAR(100) NOT NULL,
+    starts_at TIMESTAMP NOT NULL,
+    ends_at TIMESTAMP NOT NULL,
+    -- Secret of the rotating check-in code, NULL when the event accepts check-ins without code
+    code_secret VARCHAR(64),
+    created_by VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    CHECK (ends_at > starts_at)
+);
+
+ALTER TABLE member_attendance
+    ADD COLUMN event_id INTEGER REFERENCES events(id) ON DELETE SET NULL;
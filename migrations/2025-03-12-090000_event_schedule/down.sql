@@ -0,0 +1,6 @@
+DROP INDEX member_attendance_event_id_idx;
+DROP INDEX events_team_id_starts_at_idx;
+
+ALTER TABLE events
+    DROP COLUMN cancelled_at,
+    DROP COLUMN recurrence_rule;
@@ -0,0 +1,6 @@
+ALTER TABLE events
+    ADD COLUMN recurrence_rule VARCHAR(255),
+    ADD COLUMN cancelled_at TIMESTAMP;
+
+CREATE INDEX events_team_id_starts_at_idx ON events (team_id, starts_at);
+CREATE INDEX member_attendance_event_id_idx ON member_attendance (event_id);
@@ -0,0 +1,9 @@
+-- Occurrences of a repeating event that do not take place (iCalendar EXDATE),
+-- identified by the start of the skipped occurrence
+CREATE TABLE event_exceptions (
+    id SERIAL PRIMARY KEY,
+    event_id INTEGER NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    starts_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (event_id, starts_at)
+);
@@ -0,0 +1,5 @@
+ALTER TABLE teams
+    DROP COLUMN leave_channel_id;
+
+DROP TABLE leave_balances;
+DROP TABLE leave_requests;
@@ -0,0 +1,33 @@
+CREATE TABLE leave_requests (
+    id SERIAL PRIMARY KEY,
+    team_id INTEGER NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    member_id INTEGER NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+    leave_type VARCHAR(20) NOT NULL,
+    starts_on DATE NOT NULL,
+    ends_on DATE NOT NULL,
+    reason VARCHAR(255) NOT NULL,
+    -- pending, approved, rejected or cancelled
+    status VARCHAR(10) NOT NULL DEFAULT 'pending',
+    reviewed_by VARCHAR(50),
+    reviewed_at TIMESTAMP,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    CHECK (ends_on >= starts_on)
+);
+
+CREATE INDEX leave_requests_team_id_starts_on_idx ON leave_requests (team_id, starts_on);
+CREATE INDEX leave_requests_member_id_idx ON leave_requests (member_id);
+
+-- Days of leave a member may take per year and type, members without a row are not limited
+CREATE TABLE leave_balances (
+    id SERIAL PRIMARY KEY,
+    member_id INTEGER NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+    year INTEGER NOT NULL,
+    leave_type VARCHAR(20) NOT NULL,
+    allowance_days INTEGER NOT NULL CHECK (allowance_days >= 0),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (member_id, year, leave_type)
+);
+
+-- Channel leave requests are posted to for review, the team admin gets a DM when it is not set
+ALTER TABLE teams
+    ADD COLUMN leave_channel_id VARCHAR(50);
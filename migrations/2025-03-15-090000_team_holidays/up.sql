@@ -0,0 +1,9 @@
+-- Days off of a team (public holidays, office closures), one row per day
+CREATE TABLE team_holidays (
+    id SERIAL PRIMARY KEY,
+    team_id INTEGER NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    holiday_date DATE NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (team_id, holiday_date)
+);
@@ -0,0 +1,8 @@
+ALTER TABLE member_attendance
+    DROP COLUMN system_generated;
+
+ALTER TABLE teams
+    DROP COLUMN absences_checked_on,
+    DROP COLUMN report_channel_id,
+    DROP COLUMN schedule_grace_minutes,
+    DROP COLUMN schedule_start;
@@ -0,0 +1,12 @@
+-- Daily work schedule (UTC) of a team. Once the start time plus the grace period has passed on
+-- a working day, members without an attendance record are recorded as absent. Teams without a
+-- start time are not checked.
+ALTER TABLE teams
+    ADD COLUMN schedule_start TIME,
+    ADD COLUMN schedule_grace_minutes INTEGER NOT NULL DEFAULT 15,
+    ADD COLUMN report_channel_id VARCHAR(50),
+    ADD COLUMN absences_checked_on DATE;
+
+-- Absences recorded by the daily check rather than by a member or a roll-call
+ALTER TABLE member_attendance
+    ADD COLUMN system_generated BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+DROP TABLE reminder_preferences;
+DROP TABLE reminder_rules;
@@ -0,0 +1,26 @@
+CREATE TABLE reminder_rules (
+    id SERIAL PRIMARY KEY,
+    team_id INTEGER NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    -- check_in reminds the members without an attendance record for the day,
+    -- check_out the members who are still checked in
+    kind VARCHAR(10) NOT NULL,
+    -- a rule fires either some minutes before the schedule start of the team or at a fixed time (UTC)
+    minutes_before_start INTEGER CHECK (minutes_before_start > 0),
+    remind_at TIME,
+    last_sent_on DATE,
+    created_by VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((minutes_before_start IS NULL) <> (remind_at IS NULL))
+);
+
+CREATE INDEX reminder_rules_team_id_idx ON reminder_rules (team_id);
+
+-- Users without a row get every reminder
+CREATE TABLE reminder_preferences (
+    discord_id VARCHAR(50) PRIMARY KEY,
+    opted_out BOOLEAN NOT NULL DEFAULT FALSE,
+    quiet_start TIME,
+    quiet_end TIME,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((quiet_start IS NULL) = (quiet_end IS NULL))
+);
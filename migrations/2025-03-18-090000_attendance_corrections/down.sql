@@ -0,0 +1,2 @@
+DROP TABLE attendance_audit;
+DROP TABLE attendance_corrections;
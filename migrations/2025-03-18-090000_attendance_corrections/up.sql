@@ -0,0 +1,44 @@
+-- Changes members ask for on their own attendance, applied once an owner or manager approves them
+CREATE TABLE attendance_corrections (
+    id SERIAL PRIMARY KEY,
+    team_id INTEGER NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    member_id INTEGER NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+    -- the record to change, or NULL to add the missing record of attendance_date
+    attendance_id INTEGER REFERENCES member_attendance(id) ON DELETE CASCADE,
+    attendance_date DATE NOT NULL,
+    -- the requested values, NULL keeps the current one
+    check_in_time TIMESTAMP,
+    check_out_time TIMESTAMP,
+    clear_check_out BOOLEAN NOT NULL DEFAULT FALSE,
+    attendance_status VARCHAR(20),
+    reason VARCHAR(255) NOT NULL,
+    -- pending, approved or rejected
+    status VARCHAR(10) NOT NULL DEFAULT 'pending',
+    reviewed_by VARCHAR(50),
+    reviewed_at TIMESTAMP,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX attendance_corrections_team_id_status_idx ON attendance_corrections (team_id, status);
+
+-- Every change made to an attendance record by hand, with its values before and after
+CREATE TABLE attendance_audit (
+    id SERIAL PRIMARY KEY,
+    -- NULL once the record is deleted, the trail of its changes is kept
+    attendance_id INTEGER REFERENCES member_attendance(id) ON DELETE SET NULL,
+    correction_id INTEGER REFERENCES attendance_corrections(id) ON DELETE SET NULL,
+    -- insert or update
+    action VARCHAR(10) NOT NULL,
+    -- the Discord ID of the user who made or approved the change
+    actor_id VARCHAR(50) NOT NULL,
+    reason VARCHAR(255) NOT NULL,
+    before_check_in_time TIMESTAMP,
+    before_check_out_time TIMESTAMP,
+    before_status VARCHAR(20),
+    after_check_in_time TIMESTAMP,
+    after_check_out_time TIMESTAMP,
+    after_status VARCHAR(20),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX attendance_audit_attendance_id_idx ON attendance_audit (attendance_id);
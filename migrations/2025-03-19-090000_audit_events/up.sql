@@ -0,0 +1,34 @@
+-- Administrative actions on teams, members and settings, kept for AUDIT_RETENTION_DAYS
+CREATE TABLE audit_events (
+    id SERIAL PRIMARY KEY,
+    -- the Discord ID of the user who made the change, or "system"
+    actor_id VARCHAR(50) NOT NULL,
+    -- discord, api or system
+    source VARCHAR(10) NOT NULL,
+    -- what was done, e.g. team.rename or member.add
+    action VARCHAR(50) NOT NULL,
+    -- no foreign key, the events of a deleted team are kept
+    team_id INTEGER,
+    team_name VARCHAR(50),
+    guild_id VARCHAR(50),
+    channel_id VARCHAR(50),
+    -- what the action applies to, e.g. the Discord ID of a member
+    target VARCHAR(100),
+    -- JSON object with the changed values, {"field": {"from": ..., "to": ...}}
+    details TEXT NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX audit_events_team_id_created_at_idx ON audit_events (team_id, created_at);
+CREATE INDEX audit_events_created_at_idx ON audit_events (created_at);
+
+-- Events are never changed, only removed once they are older than the retention period
+CREATE FUNCTION audit_events_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_events_append_only
+    BEFORE UPDATE ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION audit_events_append_only();
@@ -0,0 +1,3 @@
+ALTER TABLE teams
+    DROP COLUMN presence_message_id,
+    DROP COLUMN presence_channel_id;
@@ -0,0 +1,5 @@
+-- Message listing who is checked in, kept up to date by the bot in a channel of the team.
+-- The message is posted again when it was deleted.
+ALTER TABLE teams
+    ADD COLUMN presence_channel_id VARCHAR(50),
+    ADD COLUMN presence_message_id VARCHAR(50);
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use actix_web::{
+    body::{BoxBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::Method,
+    middleware::Next,
+    web, Error, HttpMessage, HttpResponse,
+};
+
+use crate::{
+    api::application::{
+        api_key_service::{verify_api_key, API_KEY_PREFIX},
+        auth_service::verify_access_token,
+    },
+    config::database::DBPool,
+};
+
+enum Credentials {
+    Bearer(String),
+    ApiKey(String),
+}
+
+// Reject requests without a valid `Authorization` header. Two schemes are accepted:
+// - `Bearer {jwt}`: the decoded `Claims` are stored in the request extensions.
+// - `ApiKey {key}`: the resolved `ApiKeyPrincipal` is stored in the request extensions,
+//   read-only keys are limited to GET requests.
+pub async fn authenticate(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let credentials = req
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            if let Some(token) = value.strip_prefix("Bearer ") {
+                Some(Credentials::Bearer(token.to_string()))
+            } else {
+                value
+                    .strip_prefix("ApiKey ")
+                    .map(|key| Credentials::ApiKey(key.to_string()))
+            }
+        });
+
+    verify(req, credentials, next).await
+}
+
+// Same as `authenticate`, for clients that cannot set headers such as the browser `EventSource`:
+// the access token or API key is taken from the `token` query parameter instead.
+pub async fn authenticate_query(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let credentials = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .ok()
+        .and_then(|mut query| query.remove("token"))
+        .map(|token| {
+            if token.starts_with(API_KEY_PREFIX) {
+                Credentials::ApiKey(token)
+            } else {
+                Credentials::Bearer(token)
+            }
+        });
+
+    verify(req, credentials, next).await
+}
+
+async fn verify(
+    req: ServiceRequest,
+    credentials: Option<Credentials>,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let Some(credentials) = credentials else {
+        return Ok(unauthorized(req, "Missing bearer token or API key"));
+    };
+
+    let pool = req
+        .app_data::<web::Data<DBPool>>()
+        .expect("DBPool is not registered")
+        .clone();
+
+    match credentials {
+        Credentials::Bearer(token) => {
+            match web::block(move || verify_access_token(&pool, &token)).await {
+                Ok(Ok(claims)) => {
+                    req.extensions_mut().insert(claims);
+                }
+                Ok(Err(e)) => return Ok(unauthorized(req, e)),
+                Err(e) => return Ok(threading_error(req, e)),
+            }
+        }
+        Credentials::ApiKey(key) => match web::block(move || verify_api_key(&pool, &key)).await {
+            Ok(Ok(principal)) => {
+                if principal.read_only && req.method() != Method::GET {
+                    return Ok(req.into_response(
+                        HttpResponse::Forbidden().body("API key is read-only"),
+                    ));
+                }
+                req.extensions_mut().insert(principal);
+            }
+            Ok(Err(e)) => return Ok(unauthorized(req, e)),
+            Err(e) => return Ok(threading_error(req, e)),
+        },
+    }
+
+    next.call(req).await.map(ServiceResponse::map_into_boxed_body)
+}
+
+fn unauthorized(req: ServiceRequest, message: &'static str) -> ServiceResponse<BoxBody> {
+    req.into_response(HttpResponse::Unauthorized().body(message))
+}
+
+fn threading_error(req: ServiceRequest, e: impl std::fmt::Display) -> ServiceResponse<BoxBody> {
+    req.into_response(HttpResponse::InternalServerError().body(format!("Threading error: {}", e)))
+}
@@ -0,0 +1,100 @@
+use actix_web::{web, HttpResponse, Responder};
+
+use crate::{
+    api::{
+        application::api_key_service::{create_api_key, list_api_keys, revoke_api_key},
+        domain::dto::{Claims, CreateApiKeyRequest, CreateApiKeyResponse},
+        infrastructure::auth_repository::find_by_username,
+    },
+    bot::domain::audit::AuditActor,
+    config::database::DBPool,
+};
+
+// API keys can only be managed with a login token, never with another API key.
+// Returns the ID of the user and the actor recorded in the audit log.
+fn owner(
+    pool: &DBPool,
+    claims: Option<web::ReqData<Claims>>,
+) -> Result<(i32, AuditActor), HttpResponse> {
+    let claims = claims.ok_or_else(|| {
+        HttpResponse::Forbidden().body("API keys can only be managed with a login token")
+    })?;
+
+    find_by_username(pool, &claims.sub)
+        .map(|user| (user.id, AuditActor::api(&user.discord_id)))
+        .ok_or_else(|| HttpResponse::Unauthorized().body("User not found"))
+}
+
+pub async fn list_api_keys_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+) -> impl Responder {
+    let (owner, _) = match owner(&pool, claims) {
+        Ok(owner) => owner,
+        Err(response) => return response,
+    };
+
+    match web::block(move || {
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        list_api_keys(&mut conn, owner)
+    })
+    .await
+    {
+        Ok(Ok(keys)) => HttpResponse::Ok().json(keys),
+        Ok(Err(e)) => HttpResponse::InternalServerError().body(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn create_api_key_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    form: web::Json<CreateApiKeyRequest>,
+) -> impl Responder {
+    let (owner, actor) = match owner(&pool, claims) {
+        Ok(owner) => owner,
+        Err(response) => return response,
+    };
+
+    match web::block(move || {
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        create_api_key(
+            &mut conn,
+            owner,
+            &actor,
+            &form.name,
+            &form.scope,
+            form.guild_id.as_deref(),
+            form.team_name.as_deref(),
+        )
+    })
+    .await
+    {
+        Ok(Ok((id, key))) => HttpResponse::Created().json(CreateApiKeyResponse { id, key }),
+        Ok(Err(e)) => HttpResponse::BadRequest().body(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn revoke_api_key_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let (owner, actor) = match owner(&pool, claims) {
+        Ok(owner) => owner,
+        Err(response) => return response,
+    };
+    let key_id = path.into_inner();
+
+    match web::block(move || {
+        let mut conn = pool.get().expect("Failed to get DB connection");
+        revoke_api_key(&mut conn, owner, key_id, &actor)
+    })
+    .await
+    {
+        Ok(Ok(())) => HttpResponse::NoContent().finish(),
+        Ok(Err(e)) => HttpResponse::NotFound().body(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
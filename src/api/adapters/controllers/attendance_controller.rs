@@ -1,18 +1,69 @@
 use actix_web::{web, HttpResponse, Responder};
 
 use crate::{
-    api::application::attendance_service::show_member_attendance, config::database::DBPool,
+    api::{
+        adapters::controllers::team_controller::team_error_response,
+        application::{
+            attendance_service::{check_in_with_code, show_member_attendance},
+            auth_service::caller_discord_id,
+        },
+        domain::dto::{ApiKeyPrincipal, CheckInRequest, Claims, TeamError},
+    },
+    config::database::DBPool,
 };
 
 pub async fn show_member_attendance_handler(
     pool: web::Data<DBPool>,
-    path: web::Path<String>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
 ) -> impl Responder {
-    let team_name = path.into_inner();
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
 
-    match web::block(move || show_member_attendance(&pool, &team_name)).await {
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        show_member_attendance(&pool, &caller, principal.as_ref(), &guild_id, &team_name)
+    })
+    .await;
+
+    match result {
         Ok(Ok(attendance)) => HttpResponse::Ok().json(attendance),
-        Ok(Err(e)) => HttpResponse::InternalServerError().body(e),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn check_in_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    form: web::Json<CheckInRequest>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        check_in_with_code(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &form.code,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(event)) => HttpResponse::Created().json(event),
+        Ok(Err(e)) => team_error_response(e),
         Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
     }
 }
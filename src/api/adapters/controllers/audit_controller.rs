@@ -0,0 +1,34 @@
+use actix_web::{web, HttpResponse, Responder};
+
+use crate::{
+    api::{
+        adapters::controllers::team_controller::team_error_response,
+        application::{audit_service::list_audit_events, auth_service::caller_discord_id},
+        domain::dto::{ApiKeyPrincipal, AuditQuery, Claims, TeamError},
+    },
+    config::database::DBPool,
+};
+
+pub async fn list_audit_events_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    query: web::Query<AuditQuery>,
+) -> impl Responder {
+    let query = query.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        list_audit_events(&pool, &caller, principal.as_ref(), &query)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(events)) => HttpResponse::Ok().json(events),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
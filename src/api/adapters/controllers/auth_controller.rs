@@ -1,13 +1,93 @@
-use crate::api::domain::dto::AuthRequest;
+use crate::api::domain::dto::{AuthRequest, Claims, LoginError, LogoutRequest, RefreshRequest};
 use crate::{
-    api::{application::auth_service::login_user, domain::dto::AuthResponse},
+    api::{
+        adapters::controllers::team_controller::team_error_response,
+        application::{
+            auth_service::{login_user, logout_user, refresh_session},
+            login_throttle::LoginThrottle,
+        },
+        infrastructure::discord_notifier::send_dm,
+    },
     config::database::DBPool,
 };
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{http::header::RETRY_AFTER, web, HttpRequest, HttpResponse, Responder};
+use serenity::http::Http;
 
-pub async fn login(pool: web::Data<DBPool>, form: web::Json<AuthRequest>) -> impl Responder {
-    match login_user(&pool, &form.username, &form.password) {
-        Ok(token) => HttpResponse::Ok().json(AuthResponse { token }),
-        Err(e) => HttpResponse::Unauthorized().body(e),
+pub async fn login(
+    pool: web::Data<DBPool>,
+    throttle: web::Data<LoginThrottle>,
+    discord: web::Data<Http>,
+    req: HttpRequest,
+    form: web::Json<AuthRequest>,
+) -> impl Responder {
+    let client = req
+        .peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_default();
+
+    if let Some(retry_after) = throttle.retry_after(&client) {
+        return too_many_requests(retry_after);
+    }
+
+    let form = form.into_inner();
+    match web::block(move || login_user(&pool, &form.username, &form.password)).await {
+        Ok(Ok(tokens)) => {
+            throttle.reset(&client);
+            HttpResponse::Ok().json(tokens)
+        }
+        Ok(Err(LoginError::InvalidCredentials)) => {
+            throttle.record_failure(&client);
+            HttpResponse::Unauthorized().body("Invalid credentials")
+        }
+        Ok(Err(LoginError::TooManyAttempts { retry_after })) => too_many_requests(retry_after),
+        Ok(Err(LoginError::AccountLocked {
+            retry_after,
+            discord_id,
+        })) => {
+            throttle.record_failure(&client);
+            send_dm(
+                &discord,
+                &discord_id,
+                &format!(
+                    "🔒 Your Attendance Bot API account has been locked for {} minutes after too many failed login attempts.",
+                    retry_after / 60
+                ),
+            )
+            .await;
+            too_many_requests(retry_after)
+        }
+        Ok(Err(LoginError::Internal(e))) => HttpResponse::InternalServerError().body(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn refresh(pool: web::Data<DBPool>, form: web::Json<RefreshRequest>) -> impl Responder {
+    match web::block(move || refresh_session(&pool, &form.refresh_token)).await {
+        Ok(Ok(tokens)) => HttpResponse::Ok().json(tokens),
+        Ok(Err(e)) => HttpResponse::Unauthorized().body(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
     }
 }
+
+pub async fn logout(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    form: Option<web::Json<LogoutRequest>>,
+) -> impl Responder {
+    let Some(claims) = claims.map(|claims| claims.into_inner()) else {
+        return HttpResponse::BadRequest().body("Logout requires a login token");
+    };
+    let refresh_token = form.and_then(|form| form.into_inner().refresh_token);
+
+    match web::block(move || logout_user(&pool, &claims, refresh_token.as_deref())).await {
+        Ok(Ok(())) => HttpResponse::NoContent().finish(),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+fn too_many_requests(retry_after: u64) -> HttpResponse {
+    HttpResponse::TooManyRequests()
+        .insert_header((RETRY_AFTER, retry_after.to_string()))
+        .body("Too many login attempts, try again later")
+}
@@ -0,0 +1,216 @@
+use actix_web::{web, HttpResponse, Responder};
+use serenity::http::Http;
+
+use crate::{
+    api::{
+        adapters::controllers::team_controller::team_error_response,
+        application::{
+            auth_service::caller_discord_id,
+            correction_service::{
+                add_attendance, attendance_audit, list_corrections, request_correction,
+                review_correction, update_attendance,
+            },
+        },
+        domain::dto::{
+            AddAttendanceRequest, ApiKeyPrincipal, Claims, CorrectionQuery,
+            CreateCorrectionRequest, TeamError, UpdateAttendanceRequest,
+        },
+    },
+    bot::adapters::handler::corrections::notify_member,
+    config::database::DBPool,
+};
+
+pub async fn update_attendance_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+    form: web::Json<UpdateAttendanceRequest>,
+) -> impl Responder {
+    let attendance_id = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        update_attendance(&pool, &caller, principal.as_ref(), attendance_id, &form)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(record)) => HttpResponse::Ok().json(record),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn add_attendance_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    form: web::Json<AddAttendanceRequest>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        add_attendance(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &form,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(record)) => HttpResponse::Created().json(record),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn list_corrections_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    query: web::Query<CorrectionQuery>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let pending_only = query.into_inner().pending.unwrap_or(false);
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        list_corrections(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            pending_only,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(corrections)) => HttpResponse::Ok().json(corrections),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn request_correction_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    form: web::Json<CreateCorrectionRequest>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        request_correction(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &form,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(correction)) => HttpResponse::Created().json(correction),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn approve_correction_handler(
+    pool: web::Data<DBPool>,
+    discord: web::Data<Http>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    review(pool, discord, claims, principal, path.into_inner(), true).await
+}
+
+pub async fn reject_correction_handler(
+    pool: web::Data<DBPool>,
+    discord: web::Data<Http>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    review(pool, discord, claims, principal, path.into_inner(), false).await
+}
+
+pub async fn attendance_audit_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let attendance_id = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        attendance_audit(&pool, &caller, principal.as_ref(), attendance_id)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(entries)) => HttpResponse::Ok().json(entries),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+// Shared by the approve and reject routes, the member gets the outcome in a DM.
+async fn review(
+    pool: web::Data<DBPool>,
+    discord: web::Data<Http>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    correction_id: i32,
+    approve: bool,
+) -> HttpResponse {
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        review_correction(&pool, &caller, principal.as_ref(), correction_id, approve)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(review)) => {
+            notify_member(&discord, &review).await;
+            HttpResponse::NoContent().finish()
+        }
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
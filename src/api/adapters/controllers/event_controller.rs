@@ -0,0 +1,204 @@
+use actix_web::{web, HttpResponse, Responder};
+
+use crate::{
+    api::{
+        adapters::controllers::team_controller::{team_error_response, team_response},
+        application::{
+            auth_service::caller_discord_id,
+            event_service::{
+                cancel_event, create_event, current_check_in_code, event_attendance, list_events,
+                skip_occurrence, start_check_in_code,
+            },
+        },
+        domain::dto::{
+            ApiKeyPrincipal, Claims, CreateEventRequest, EventOccurrenceQuery,
+            SkipOccurrenceRequest, StartCheckInCodeRequest, TeamError,
+        },
+    },
+    config::database::DBPool,
+};
+
+pub async fn start_check_in_code_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    form: web::Json<StartCheckInCodeRequest>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        start_check_in_code(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &form,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(event)) => HttpResponse::Created().json(event),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn check_in_code_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let event_id = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        current_check_in_code(&pool, &caller, principal.as_ref(), event_id)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(code)) => HttpResponse::Ok().json(code),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn list_events_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        list_events(&pool, &caller, principal.as_ref(), &guild_id, &team_name)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(events)) => HttpResponse::Ok().json(events),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn create_event_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    form: web::Json<CreateEventRequest>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        create_event(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &form,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(event)) => HttpResponse::Created().json(event),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn cancel_event_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let event_id = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        cancel_event(&pool, &caller, principal.as_ref(), event_id)
+    })
+    .await;
+
+    team_response(result)
+}
+
+pub async fn skip_occurrence_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+    form: web::Json<SkipOccurrenceRequest>,
+) -> impl Responder {
+    let event_id = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        skip_occurrence(&pool, &caller, principal.as_ref(), event_id, &form)
+    })
+    .await;
+
+    team_response(result)
+}
+
+pub async fn event_attendance_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+    query: web::Query<EventOccurrenceQuery>,
+) -> impl Responder {
+    let event_id = path.into_inner();
+    let date = query.into_inner().date;
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        event_attendance(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            event_id,
+            date.as_deref(),
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(attendance)) => HttpResponse::Ok().json(attendance),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
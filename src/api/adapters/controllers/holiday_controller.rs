@@ -0,0 +1,138 @@
+use actix_web::{web, HttpResponse, Responder};
+
+use crate::{
+    api::{
+        adapters::controllers::team_controller::{team_error_response, team_response},
+        application::{
+            auth_service::caller_discord_id,
+            holiday_service::{add_holiday, import_holidays, list_holidays, remove_holiday},
+        },
+        domain::dto::{ApiKeyPrincipal, Claims, CreateHolidayRequest, HolidayQuery, TeamError},
+    },
+    config::database::DBPool,
+};
+
+pub async fn list_holidays_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    query: web::Query<HolidayQuery>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let year = query.into_inner().year;
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        list_holidays(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            year,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(holidays)) => HttpResponse::Ok().json(holidays),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn add_holiday_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    form: web::Json<CreateHolidayRequest>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        add_holiday(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &form,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(holiday)) => HttpResponse::Created().json(holiday),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+// The body is the raw content of the .ics file
+pub async fn import_holidays_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    body: String,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        import_holidays(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &body,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(import)) => HttpResponse::Ok().json(import),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn remove_holiday_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String, String)>,
+) -> impl Responder {
+    let (guild_id, team_name, date) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        remove_holiday(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &date,
+        )
+    })
+    .await;
+
+    team_response(result)
+}
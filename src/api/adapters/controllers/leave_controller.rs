@@ -0,0 +1,231 @@
+use actix_web::{web, HttpResponse, Responder};
+use serenity::http::Http;
+
+use crate::{
+    api::{
+        adapters::controllers::team_controller::{team_error_response, team_response},
+        application::{
+            auth_service::caller_discord_id,
+            leave_service::{
+                cancel_leave_request, leave_balances, list_leave_requests, request_leave,
+                review_leave_request, set_leave_allowance,
+            },
+        },
+        domain::dto::{
+            ApiKeyPrincipal, Claims, CreateLeaveRequest, LeaveBalanceQuery, LeaveRequestQuery,
+            SetLeaveAllowanceRequest, TeamError,
+        },
+    },
+    bot::{
+        adapters::handler::leave::{notify_member, post_for_review},
+        application::services::leave_service::leave_request_table,
+    },
+    config::database::DBPool,
+};
+
+pub async fn list_leave_requests_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    query: web::Query<LeaveRequestQuery>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let pending_only = query.into_inner().pending.unwrap_or(false);
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        list_leave_requests(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            pending_only,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(requests)) => HttpResponse::Ok().json(requests),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn request_leave_handler(
+    pool: web::Data<DBPool>,
+    discord: web::Data<Http>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    form: web::Json<CreateLeaveRequest>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        request_leave(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &form,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(review)) => {
+            // The request is stored either way, reviewers can still list pending requests
+            if let Err(e) = post_for_review(&discord, &review).await {
+                log::error!("❌ Failed to post leave request for review: {}", e);
+            }
+            HttpResponse::Created().json(leave_request_table(
+                &review.request,
+                &review.username,
+                review.days,
+            ))
+        }
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn approve_leave_handler(
+    pool: web::Data<DBPool>,
+    discord: web::Data<Http>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    review_leave(pool, discord, claims, principal, path.into_inner(), true).await
+}
+
+pub async fn reject_leave_handler(
+    pool: web::Data<DBPool>,
+    discord: web::Data<Http>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    review_leave(pool, discord, claims, principal, path.into_inner(), false).await
+}
+
+pub async fn cancel_leave_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let request_id = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        cancel_leave_request(&pool, &caller, principal.as_ref(), request_id)
+    })
+    .await;
+
+    team_response(result)
+}
+
+pub async fn leave_balances_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    query: web::Query<LeaveBalanceQuery>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let year = query.into_inner().year;
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        leave_balances(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            year,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(balances)) => HttpResponse::Ok().json(balances),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn set_leave_allowance_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String, String)>,
+    form: web::Json<SetLeaveAllowanceRequest>,
+) -> impl Responder {
+    let (guild_id, team_name, discord_id) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        set_leave_allowance(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &discord_id,
+            &form,
+        )
+    })
+    .await;
+
+    team_response(result)
+}
+
+// Shared by the approve and reject routes, the member gets the outcome in a DM.
+async fn review_leave(
+    pool: web::Data<DBPool>,
+    discord: web::Data<Http>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    request_id: i32,
+    approve: bool,
+) -> HttpResponse {
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        review_leave_request(&pool, &caller, principal.as_ref(), request_id, approve)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(review)) => {
+            notify_member(&discord, &review).await;
+            HttpResponse::NoContent().finish()
+        }
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
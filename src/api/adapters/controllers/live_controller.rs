@@ -0,0 +1,75 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::Utc;
+use futures_util::StreamExt;
+
+use crate::{
+    api::{
+        adapters::controllers::team_controller::team_error_response,
+        application::{
+            auth_service::caller_discord_id,
+            live_service::{authorize_live_feed, live_feed, live_feed_deadline, team_presence},
+        },
+        domain::dto::{ApiKeyPrincipal, Claims, PresenceResponse, TeamError},
+    },
+    config::database::DBPool,
+};
+
+pub async fn live_feed_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let team_id = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+    let closes_at = live_feed_deadline(claims.as_ref());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        authorize_live_feed(&pool, &caller, principal.as_ref(), team_id)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .insert_header(("Cache-Control", "no-cache"))
+            .insert_header(("X-Accel-Buffering", "no"))
+            .streaming(
+                live_feed(team_id, closes_at)
+                    .map(|event| Ok::<_, actix_web::Error>(web::Bytes::from(event))),
+            ),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn presence_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let team_id = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        team_presence(&pool, &caller, principal.as_ref(), team_id)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(members)) => HttpResponse::Ok().json(PresenceResponse {
+            team_id,
+            members,
+            at: Utc::now().naive_utc(),
+        }),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
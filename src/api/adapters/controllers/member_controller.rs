@@ -1,16 +1,129 @@
 use actix_web::{web, HttpResponse, Responder};
 
-use crate::{api::application::member_service::show_members, config::database::DBPool};
+use crate::{
+    api::{
+        adapters::controllers::team_controller::{team_error_response, team_response},
+        application::{
+            auth_service::caller_discord_id,
+            member_service::{add_member, remove_member, show_members, update_member},
+        },
+        domain::dto::{AddMemberRequest, ApiKeyPrincipal, Claims, TeamError, UpdateMemberRequest},
+    },
+    config::database::DBPool,
+};
 
 pub async fn show_member_handler(
     pool: web::Data<DBPool>,
-    path: web::Path<String>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
 ) -> impl Responder {
-    let team_name = path.into_inner();
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
 
-    match web::block(move || show_members(&pool, &team_name)).await {
+    let result = web::block(move || {
+        let caller = caller(&pool, claims.as_ref(), principal.as_ref())?;
+        show_members(&pool, &caller, principal.as_ref(), &guild_id, &team_name)
+    })
+    .await;
+
+    match result {
         Ok(Ok(members)) => HttpResponse::Ok().json(members),
-        Ok(Err(e)) => HttpResponse::InternalServerError().body(e),
+        Ok(Err(e)) => team_error_response(e),
         Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
     }
 }
+
+pub async fn add_member_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
+    form: web::Json<AddMemberRequest>,
+) -> impl Responder {
+    let (guild_id, team_name) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller(&pool, claims.as_ref(), principal.as_ref())?;
+        add_member(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &form,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => HttpResponse::Created().finish(),
+        result => team_response(result),
+    }
+}
+
+pub async fn update_member_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String, String)>,
+    form: web::Json<UpdateMemberRequest>,
+) -> impl Responder {
+    let (guild_id, team_name, discord_id) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller(&pool, claims.as_ref(), principal.as_ref())?;
+        update_member(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &discord_id,
+            &form,
+        )
+    })
+    .await;
+
+    team_response(result)
+}
+
+pub async fn remove_member_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String, String)>,
+) -> impl Responder {
+    let (guild_id, team_name, discord_id) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller(&pool, claims.as_ref(), principal.as_ref())?;
+        remove_member(
+            &pool,
+            &caller,
+            principal.as_ref(),
+            &guild_id,
+            &team_name,
+            &discord_id,
+        )
+    })
+    .await;
+
+    team_response(result)
+}
+
+fn caller(
+    pool: &DBPool,
+    claims: Option<&Claims>,
+    principal: Option<&ApiKeyPrincipal>,
+) -> Result<String, TeamError> {
+    caller_discord_id(pool, claims, principal)
+        .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))
+}
@@ -2,3 +2,13 @@ pub mod auth_controller;
 pub mod team_controller;
 pub mod member_controller;
 pub mod attendance_controller;
+pub mod api_key_controller;
+pub mod oauth_controller;
+pub mod event_controller;
+pub mod leave_controller;
+pub mod holiday_controller;
+pub mod correction_controller;
+pub mod audit_controller;
+pub mod webhook_controller;
+pub mod live_controller;
+pub mod ws_controller;
@@ -0,0 +1,90 @@
+use actix_web::{
+    cookie::{time, Cookie, SameSite},
+    http::header::LOCATION,
+    web, HttpRequest, HttpResponse, Responder,
+};
+
+use crate::{
+    api::{
+        application::{
+            auth_service::login_discord_user,
+            oauth_service::{
+                authorize_url, create_state, discord_id_from_code, verify_state,
+                OAUTH_STATE_TTL_MINUTES,
+            },
+        },
+        domain::dto::OAuthCallbackQuery,
+    },
+    config::{database::DBPool, settings::DiscordOAuthSettings},
+};
+
+const STATE_COOKIE: &str = "ab_oauth_state";
+
+// Redirect the browser to the Discord consent screen.
+pub async fn discord_login(settings: web::Data<Option<DiscordOAuthSettings>>) -> impl Responder {
+    let Some(settings) = settings.get_ref() else {
+        return HttpResponse::ServiceUnavailable().body("Discord login is not configured");
+    };
+
+    let (state, nonce) = match create_state() {
+        Ok(state) => state,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+
+    match authorize_url(settings, &state) {
+        Ok(url) => HttpResponse::Found()
+            .insert_header((LOCATION, url))
+            .cookie(
+                Cookie::build(STATE_COOKIE, nonce)
+                    .path("/auth/discord")
+                    .http_only(true)
+                    .same_site(SameSite::Lax)
+                    .max_age(time::Duration::minutes(OAUTH_STATE_TTL_MINUTES))
+                    .finish(),
+            )
+            .finish(),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+// Finish the authorization-code flow and issue our own token pair.
+pub async fn discord_callback(
+    pool: web::Data<DBPool>,
+    settings: web::Data<Option<DiscordOAuthSettings>>,
+    req: HttpRequest,
+    query: web::Query<OAuthCallbackQuery>,
+) -> impl Responder {
+    let Some(settings) = settings.get_ref() else {
+        return HttpResponse::ServiceUnavailable().body("Discord login is not configured");
+    };
+
+    let query = query.into_inner();
+    if let Some(error) = query.error {
+        return HttpResponse::Unauthorized().body(format!("Discord login failed: {}", error));
+    }
+    let (Some(code), Some(state)) = (query.code, query.state) else {
+        return HttpResponse::BadRequest().body("Missing code or state");
+    };
+
+    let nonce = req
+        .cookie(STATE_COOKIE)
+        .map(|cookie| cookie.value().to_string())
+        .unwrap_or_default();
+    if let Err(e) = verify_state(&state, &nonce) {
+        return HttpResponse::Unauthorized().body(e);
+    }
+
+    let discord_id = match discord_id_from_code(settings, &code).await {
+        Ok(discord_id) => discord_id,
+        Err(e) => return HttpResponse::BadGateway().body(e),
+    };
+
+    let mut removal = Cookie::build(STATE_COOKIE, "").path("/auth/discord").finish();
+    removal.make_removal();
+
+    match web::block(move || login_discord_user(&pool, &discord_id)).await {
+        Ok(Ok(tokens)) => HttpResponse::Ok().cookie(removal).json(tokens),
+        Ok(Err(e)) => HttpResponse::Unauthorized().cookie(removal).body(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
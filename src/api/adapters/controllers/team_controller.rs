@@ -1,15 +1,102 @@
 use actix_web::{web, HttpResponse, Responder};
 
-use crate::{api::application::team_service::show_teams, config::database::DBPool};
+use crate::{
+    api::{
+        application::{
+            auth_service::caller_discord_id,
+            team_service::{delete_team, show_teams, update_team},
+        },
+        domain::dto::{ApiKeyPrincipal, Claims, TeamError, UpdateTeamRequest},
+    },
+    config::database::DBPool,
+};
 
 pub async fn show_teams_handler(
     pool: web::Data<DBPool>,
-    path: web::Path<String>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<(String, String)>,
 ) -> impl Responder {
-    let admin_discord_id = path.into_inner();
+    let (guild_id, admin_discord_id) = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
 
-    match show_teams(&pool, &admin_discord_id) {
-        Ok(teams) => HttpResponse::Ok().json(teams),
-        Err(e) => HttpResponse::InternalServerError().body(e),
+    // callers only see their own teams
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        if caller != admin_discord_id {
+            return Err(TeamError::Forbidden(
+                "You are not allowed to access these teams".to_string(),
+            ));
+        }
+        show_teams(&pool, &guild_id, &admin_discord_id).map_err(TeamError::Internal)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(teams)) => HttpResponse::Ok().json(teams),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub async fn update_team_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+    form: web::Json<UpdateTeamRequest>,
+) -> impl Responder {
+    let team_id = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        update_team(&pool, &caller, principal.as_ref(), team_id, &form)
+    })
+    .await;
+
+    team_response(result)
+}
+
+pub async fn delete_team_handler(
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let team_id = path.into_inner();
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+
+    let result = web::block(move || {
+        let caller = caller_discord_id(&pool, claims.as_ref(), principal.as_ref())
+            .ok_or_else(|| TeamError::Forbidden("User not found".to_string()))?;
+        delete_team(&pool, &caller, principal.as_ref(), team_id)
+    })
+    .await;
+
+    team_response(result)
+}
+
+pub fn team_response(
+    result: Result<Result<(), TeamError>, actix_web::error::BlockingError>,
+) -> HttpResponse {
+    match result {
+        Ok(Ok(())) => HttpResponse::NoContent().finish(),
+        Ok(Err(e)) => team_error_response(e),
+        Err(e) => HttpResponse::InternalServerError().body(format!("Threading error: {}", e)),
+    }
+}
+
+pub fn team_error_response(error: TeamError) -> HttpResponse {
+    match error {
+        TeamError::NotFound(e) => HttpResponse::NotFound().body(e),
+        TeamError::Forbidden(e) => HttpResponse::Forbidden().body(e),
+        TeamError::Invalid(e) => HttpResponse::BadRequest().body(e),
+        TeamError::Internal(e) => HttpResponse::InternalServerError().body(e),
     }
 }
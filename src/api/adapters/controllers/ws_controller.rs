@@ -0,0 +1,280 @@
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+
+use actix_codec::{Decoder, Encoder};
+use actix_http::ws::{handshake, CloseCode, CloseReason, Codec, Frame, Message};
+use actix_web::{
+    body::{BodyStream, BoxBody},
+    rt,
+    web::{self, Bytes, BytesMut},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use futures_util::{stream, StreamExt};
+use tokio::{
+    sync::{
+        broadcast::error::RecvError,
+        mpsc::{self, Receiver, Sender},
+    },
+    time::{interval_at, sleep_until, Duration, Instant},
+};
+
+use crate::{
+    api::{
+        adapters::controllers::team_controller::team_error_response,
+        application::{
+            auth_service::caller_discord_id,
+            live_service::{live_feed_deadline, team_presence},
+        },
+        domain::dto::{ApiKeyPrincipal, Claims, TeamError, WsClientMessage, WsServerMessage},
+    },
+    bot::infrastructure::event_bus,
+    config::{
+        constant::{
+            LIVE_FEED_KEEP_ALIVE_SECONDS, WS_CLIENT_TIMEOUT_SECONDS, WS_MAX_FRAME_BYTES,
+            WS_MAX_SUBSCRIPTIONS, WS_SEND_BUFFER,
+        },
+        database::DBPool,
+    },
+};
+
+// One WebSocket connection: who opened it and which teams it follows
+struct WsSession {
+    pool: web::Data<DBPool>,
+    caller: String,
+    principal: Option<ApiKeyPrincipal>,
+    teams: BTreeSet<i32>,
+    codec: Codec,
+    outgoing: Sender<Bytes>,
+}
+
+// GET /ws, upgrades to a WebSocket that streams the attendance of the teams it subscribes to
+pub async fn ws_handler(
+    req: HttpRequest,
+    payload: web::Payload,
+    pool: web::Data<DBPool>,
+    claims: Option<web::ReqData<Claims>>,
+    principal: Option<web::ReqData<ApiKeyPrincipal>>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut response = handshake(req.head())?;
+    let claims = claims.map(|claims| claims.into_inner());
+    let principal = principal.map(|principal| principal.into_inner());
+    let closes_at = live_feed_deadline(claims.as_ref());
+
+    let lookup_pool = pool.clone();
+    let lookup_principal = principal.clone();
+    let caller = web::block(move || {
+        caller_discord_id(&lookup_pool, claims.as_ref(), lookup_principal.as_ref())
+    })
+    .await?;
+    let Some(caller) = caller else {
+        return Ok(team_error_response(TeamError::Forbidden(
+            "User not found".to_string(),
+        )));
+    };
+
+    let (outgoing, frames) = mpsc::channel(WS_SEND_BUFFER);
+    let session = WsSession {
+        pool,
+        caller,
+        principal,
+        teams: BTreeSet::new(),
+        codec: Codec::new().max_size(WS_MAX_FRAME_BYTES),
+        outgoing,
+    };
+    // The payload cannot leave this worker, so the session runs on its local task set
+    rt::spawn(run_session(session, payload, closes_at));
+
+    let body = BodyStream::new(outgoing_frames(frames));
+    Ok(HttpResponse::from(
+        response.message_body(BoxBody::new(body))?,
+    ))
+}
+
+// The encoded frames of a session, until it ends
+fn outgoing_frames(
+    frames: Receiver<Bytes>,
+) -> impl futures_util::Stream<Item = Result<Bytes, Infallible>> {
+    stream::unfold(frames, |mut frames| async move {
+        frames.recv().await.map(|frame| (Ok(frame), frames))
+    })
+}
+
+// Read client messages and forward the updates of subscribed teams until either side closes,
+// the client goes silent or the token the connection was opened with expires.
+async fn run_session(mut session: WsSession, mut payload: web::Payload, closes_at: Instant) {
+    let period = Duration::from_secs(LIVE_FEED_KEEP_ALIVE_SECONDS);
+    let timeout = Duration::from_secs(WS_CLIENT_TIMEOUT_SECONDS);
+    let mut updates = event_bus::subscribe();
+    let mut heartbeat = interval_at(Instant::now() + period, period);
+    let mut buffer = BytesMut::new();
+    let mut last_seen = Instant::now();
+
+    let reason = loop {
+        tokio::select! {
+            chunk = payload.next() => match chunk {
+                Some(Ok(bytes)) => {
+                    last_seen = Instant::now();
+                    buffer.extend_from_slice(&bytes);
+                    if let Some(reason) = session.read_frames(&mut buffer).await {
+                        break reason;
+                    }
+                }
+                // The connection is gone, there is nobody left to say goodbye to
+                Some(Err(_)) | None => return,
+            },
+            update = updates.recv() => match update {
+                Ok(update) if session.teams.contains(&update.team_id) => {
+                    session.send(WsServerMessage::Event(update)).await;
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(_)) => session.resync().await,
+                Err(RecvError::Closed) => break close(CloseCode::Away, "Server is shutting down"),
+            },
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > timeout {
+                    break close(CloseCode::Policy, "No response to pings");
+                }
+                session.write(Message::Ping(Bytes::new())).await;
+            },
+            _ = sleep_until(closes_at) => {
+                break close(CloseCode::Policy, "Token expired, reconnect with a new one");
+            },
+        }
+    };
+
+    session.write(Message::Close(reason)).await;
+}
+
+impl WsSession {
+    // Handle every complete frame in the buffer, returns how to close the connection if it should
+    async fn read_frames(&mut self, buffer: &mut BytesMut) -> Option<Option<CloseReason>> {
+        loop {
+            let frame = match self.codec.decode(buffer) {
+                Ok(Some(frame)) => frame,
+                Ok(None) => return None,
+                Err(e) => return Some(close(CloseCode::Protocol, &e.to_string())),
+            };
+
+            match frame {
+                Frame::Text(text) => match serde_json::from_slice::<WsClientMessage>(&text) {
+                    Ok(message) => self.handle(message).await,
+                    Err(e) => {
+                        self.send(error(format!("Invalid message: {}", e), None))
+                            .await
+                    }
+                },
+                Frame::Binary(_) => {
+                    self.send(error("Only text messages are supported".to_string(), None))
+                        .await;
+                }
+                Frame::Continuation(_) => {
+                    return Some(close(
+                        CloseCode::Unsupported,
+                        "Fragmented messages are not supported",
+                    ));
+                }
+                Frame::Ping(bytes) => self.write(Message::Pong(bytes)).await,
+                Frame::Pong(_) => {}
+                Frame::Close(reason) => return Some(reason),
+            }
+        }
+    }
+
+    async fn handle(&mut self, message: WsClientMessage) {
+        match message {
+            WsClientMessage::Subscribe { team_id } => self.subscribe(team_id).await,
+            WsClientMessage::Unsubscribe { team_id } => {
+                self.teams.remove(&team_id);
+                self.send(WsServerMessage::Unsubscribed { team_id }).await;
+            }
+            WsClientMessage::Ping => self.send(WsServerMessage::Pong).await,
+        }
+    }
+
+    // Check the caller may watch the team, then send who is in before any of its updates
+    async fn subscribe(&mut self, team_id: i32) {
+        if !self.teams.contains(&team_id) && self.teams.len() >= WS_MAX_SUBSCRIPTIONS {
+            let message = format!(
+                "A connection can subscribe to at most {} teams",
+                WS_MAX_SUBSCRIPTIONS
+            );
+            self.send(error(message, Some(team_id))).await;
+            return;
+        }
+
+        match self.snapshot(team_id).await {
+            Ok(snapshot) => {
+                self.teams.insert(team_id);
+                self.send(snapshot).await;
+            }
+            Err(e) => self.send(error(e, Some(team_id))).await,
+        }
+    }
+
+    // Missed updates cannot be replayed, send fresh snapshots of every team instead.
+    // A team the caller lost access to in the meantime is dropped.
+    async fn resync(&mut self) {
+        for team_id in self.teams.clone() {
+            match self.snapshot(team_id).await {
+                Ok(snapshot) => self.send(snapshot).await,
+                Err(e) => {
+                    self.teams.remove(&team_id);
+                    self.send(error(e, Some(team_id))).await;
+                    self.send(WsServerMessage::Unsubscribed { team_id }).await;
+                }
+            }
+        }
+    }
+
+    async fn snapshot(&self, team_id: i32) -> Result<WsServerMessage, String> {
+        let pool = self.pool.clone();
+        let caller = self.caller.clone();
+        let principal = self.principal.clone();
+
+        let members =
+            web::block(move || team_presence(&pool, &caller, principal.as_ref(), team_id))
+                .await
+                .map_err(|e| format!("Threading error: {}", e))?
+                .map_err(error_message)?;
+        Ok(WsServerMessage::Snapshot {
+            team_id,
+            members,
+            at: Utc::now().naive_utc(),
+        })
+    }
+
+    async fn send(&mut self, message: WsServerMessage) {
+        let json = serde_json::to_string(&message).unwrap_or_default();
+        self.write(Message::Text(json.into())).await;
+    }
+
+    // Queue a frame, waits while the client is slow to read. A client that is gone is noticed
+    // when its payload ends.
+    async fn write(&mut self, message: Message) {
+        let mut frame = BytesMut::new();
+        if self.codec.encode(message, &mut frame).is_ok() {
+            let _ = self.outgoing.send(frame.freeze()).await;
+        }
+    }
+}
+
+fn close(code: CloseCode, description: &str) -> Option<CloseReason> {
+    Some(CloseReason {
+        code,
+        description: Some(description.to_string()),
+    })
+}
+
+fn error(message: String, team_id: Option<i32>) -> WsServerMessage {
+    WsServerMessage::Error { message, team_id }
+}
+
+fn error_message(error: TeamError) -> String {
+    match error {
+        TeamError::NotFound(e)
+        | TeamError::Forbidden(e)
+        | TeamError::Invalid(e)
+        | TeamError::Internal(e) => e,
+    }
+}
@@ -1,13 +1,54 @@
 use std::env;
 
-use crate::api::adapters::controllers::attendance_controller::show_member_attendance_handler;
-use crate::api::adapters::controllers::team_controller::show_teams_handler;
+use crate::api::adapters::auth_middleware::{authenticate, authenticate_query};
+use crate::api::adapters::controllers::api_key_controller::{
+    create_api_key_handler, list_api_keys_handler, revoke_api_key_handler,
+};
+use crate::api::adapters::controllers::audit_controller::list_audit_events_handler;
+use crate::api::adapters::controllers::attendance_controller::{
+    check_in_handler, show_member_attendance_handler,
+};
+use crate::api::adapters::controllers::correction_controller::{
+    add_attendance_handler, approve_correction_handler, attendance_audit_handler,
+    list_corrections_handler, reject_correction_handler, request_correction_handler,
+    update_attendance_handler,
+};
+use crate::api::adapters::controllers::event_controller::{
+    cancel_event_handler, check_in_code_handler, create_event_handler, event_attendance_handler,
+    list_events_handler, skip_occurrence_handler, start_check_in_code_handler,
+};
+use crate::api::adapters::controllers::holiday_controller::{
+    add_holiday_handler, import_holidays_handler, list_holidays_handler, remove_holiday_handler,
+};
+use crate::api::adapters::controllers::live_controller::{live_feed_handler, presence_handler};
+use crate::api::adapters::controllers::leave_controller::{
+    approve_leave_handler, cancel_leave_handler, leave_balances_handler,
+    list_leave_requests_handler, reject_leave_handler, request_leave_handler,
+    set_leave_allowance_handler,
+};
+use crate::api::adapters::controllers::team_controller::{
+    delete_team_handler, show_teams_handler, update_team_handler,
+};
+use crate::api::adapters::controllers::webhook_controller::{
+    create_webhook_handler, delete_webhook_handler, list_webhook_deliveries_handler,
+    list_webhooks_handler, replay_webhook_delivery_handler,
+};
+use crate::api::adapters::controllers::ws_controller::ws_handler;
 use crate::api::adapters::controllers::{
-    auth_controller::login, member_controller::show_member_handler,
+    auth_controller::{login, logout, refresh},
+    member_controller::{
+        add_member_handler, remove_member_handler, show_member_handler, update_member_handler,
+    },
+    oauth_controller::{discord_callback, discord_login},
 };
+use crate::api::application::login_throttle::LoginThrottle;
+use crate::config::constant::HOLIDAY_IMPORT_MAX_BYTES;
 use crate::config::database::establish_connection;
+use crate::config::settings::{DiscordOAuthSettings, Settings};
+use actix_web::middleware::from_fn;
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
 use dotenvy::dotenv;
+use serenity::http::Http;
 
 // Check if the API is running
 async fn health_check() -> impl Responder {
@@ -28,17 +69,178 @@ pub async fn start_api() {
     // Wrap the pool in `web::Data`
     let db_pool_data = web::Data::new(db_pool);
 
+    // Shared across workers so attempts are counted per client, not per worker
+    let login_throttle = web::Data::new(LoginThrottle::new());
+
+    // Discord HTTP client used to notify admins (e.g. on account lockout) and post leave requests
+    let discord_http = web::Data::new(Http::new(&Settings::new().discord_token));
+
+    // Discord OAuth2 application, `/auth/discord/*` answers 503 when it is not configured
+    let discord_oauth = web::Data::new(DiscordOAuthSettings::from_env());
+
     let server = HttpServer::new(move || {
         App::new()
             .app_data(db_pool_data.clone()) // Pass the wrapped pool to the app
+            .app_data(login_throttle.clone())
+            .app_data(discord_http.clone())
+            .app_data(discord_oauth.clone())
             .route("/", web::get().to(health_check))
             .route("/login", web::post().to(login))
-            .route(
-                "/teams/{admin_discord_id}",
-                web::get().to(show_teams_handler),
+            .route("/auth/refresh", web::post().to(refresh))
+            .route("/auth/discord/login", web::get().to(discord_login))
+            .route("/auth/discord/callback", web::get().to(discord_callback))
+            // Browsers cannot set headers on an EventSource or a WebSocket, these take `?token=` instead
+            .service(
+                web::resource("/teams/{id}/live")
+                    .wrap(from_fn(authenticate_query))
+                    .route(web::get().to(live_feed_handler)),
+            )
+            .service(
+                web::resource("/ws")
+                    .wrap(from_fn(authenticate_query))
+                    .route(web::get().to(ws_handler)),
+            )
+            // Everything below requires a valid access token or API key
+            .service(
+                web::scope("")
+                    .wrap(from_fn(authenticate))
+                    .route("/auth/logout", web::post().to(logout))
+                    .route("/api-keys", web::get().to(list_api_keys_handler))
+                    .route("/api-keys", web::post().to(create_api_key_handler))
+                    .route("/api-keys/{id}", web::delete().to(revoke_api_key_handler))
+                    .route("/audit", web::get().to(list_audit_events_handler))
+                    .route(
+                        "/guilds/{guild_id}/teams/{admin_discord_id}",
+                        web::get().to(show_teams_handler),
+                    )
+                    .route("/teams/{id}", web::patch().to(update_team_handler))
+                    .route("/teams/{id}", web::delete().to(delete_team_handler))
+                    .route("/teams/{id}/presence", web::get().to(presence_handler))
+                    .route(
+                        "/guilds/{guild_id}/members/{team_name}",
+                        web::get().to(show_member_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/members/{team_name}",
+                        web::post().to(add_member_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/members/{team_name}/{discord_id}",
+                        web::patch().to(update_member_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/members/{team_name}/{discord_id}",
+                        web::delete().to(remove_member_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/attendance/{team_name}",
+                        web::get().to(show_member_attendance_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/attendance/{team_name}/check-in",
+                        web::post().to(check_in_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/attendance/{team_name}/records",
+                        web::post().to(add_attendance_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/attendance/{team_name}/corrections",
+                        web::get().to(list_corrections_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/attendance/{team_name}/corrections",
+                        web::post().to(request_correction_handler),
+                    )
+                    .route("/attendance/{id}", web::patch().to(update_attendance_handler))
+                    .route(
+                        "/attendance/{id}/audit",
+                        web::get().to(attendance_audit_handler),
+                    )
+                    .route(
+                        "/attendance-corrections/{id}/approve",
+                        web::post().to(approve_correction_handler),
+                    )
+                    .route(
+                        "/attendance-corrections/{id}/reject",
+                        web::post().to(reject_correction_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/check-in-codes/{team_name}",
+                        web::post().to(start_check_in_code_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/events/{team_name}",
+                        web::get().to(list_events_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/events/{team_name}",
+                        web::post().to(create_event_handler),
+                    )
+                    .route("/events/{id}", web::delete().to(cancel_event_handler))
+                    .route(
+                        "/events/{id}/exceptions",
+                        web::post().to(skip_occurrence_handler),
+                    )
+                    .route(
+                        "/events/{id}/attendance",
+                        web::get().to(event_attendance_handler),
+                    )
+                    .route("/events/{id}/code", web::get().to(check_in_code_handler))
+                    .route(
+                        "/guilds/{guild_id}/leave/{team_name}",
+                        web::get().to(list_leave_requests_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/leave/{team_name}",
+                        web::post().to(request_leave_handler),
+                    )
+                    .route("/leave/{id}/approve", web::post().to(approve_leave_handler))
+                    .route("/leave/{id}/reject", web::post().to(reject_leave_handler))
+                    .route("/leave/{id}", web::delete().to(cancel_leave_handler))
+                    .route(
+                        "/guilds/{guild_id}/leave-balances/{team_name}",
+                        web::get().to(leave_balances_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/leave-balances/{team_name}/{discord_id}",
+                        web::put().to(set_leave_allowance_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/holidays/{team_name}",
+                        web::get().to(list_holidays_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/holidays/{team_name}",
+                        web::post().to(add_holiday_handler),
+                    )
+                    .service(
+                        web::resource("/guilds/{guild_id}/holidays/{team_name}/import")
+                            .app_data(web::PayloadConfig::new(HOLIDAY_IMPORT_MAX_BYTES as usize))
+                            .route(web::post().to(import_holidays_handler)),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/holidays/{team_name}/{date}",
+                        web::delete().to(remove_holiday_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/webhooks/{team_name}",
+                        web::get().to(list_webhooks_handler),
+                    )
+                    .route(
+                        "/guilds/{guild_id}/webhooks/{team_name}",
+                        web::post().to(create_webhook_handler),
+                    )
+                    .route("/webhooks/{id}", web::delete().to(delete_webhook_handler))
+                    .route(
+                        "/webhooks/{id}/deliveries",
+                        web::get().to(list_webhook_deliveries_handler),
+                    )
+                    .route(
+                        "/webhook-deliveries/{id}/replay",
+                        web::post().to(replay_webhook_delivery_handler),
+                    ),
             )
-            .route("/members/{team_name}", web::get().to(show_member_handler))
-            .route("/attendance/{team_name}", web::get().to(show_member_attendance_handler))
     })
     .bind(&api_address)
     .expect("Failed to bind API server")
@@ -1,2 +1,3 @@
 pub mod http_server;
 pub mod controllers;
+pub mod auth_middleware;
@@ -0,0 +1,155 @@
+use crate::{
+    api::{
+        application::auth_service::{hash_token, random_token},
+        domain::{dto::ApiKeyPrincipal, model::NewApiKey},
+        infrastructure::api_key_repository,
+    },
+    bot::{
+        application::services::{audit_service, role_service, team_service},
+        domain::{
+            audit::{AuditAction, AuditActor},
+            table::ApiKeyTable,
+        },
+    },
+    config::database::DBPool,
+};
+use diesel::PgConnection;
+use serde_json::json;
+
+const API_KEY_SCOPES: [&str; 2] = ["read", "write"];
+
+// every API key starts with it, which tells keys apart from access tokens
+pub const API_KEY_PREFIX: &str = "ab_";
+
+// Create a new API key for an admin. The plain key is returned only here,
+// the database keeps its hash and a short prefix to recognise it by.
+// The guild is only needed when the admin has teams with the same name in several guilds.
+pub fn create_api_key(
+    conn: &mut PgConnection,
+    owner_id: i32,
+    actor: &AuditActor,
+    name: &str,
+    scope: &str,
+    guild_id: Option<&str>,
+    team_name: Option<&str>,
+) -> Result<(i32, String), String> {
+    if !API_KEY_SCOPES.contains(&scope) {
+        return Err(format!("Invalid scope '{}', use 'read' or 'write'", scope));
+    }
+    if name.is_empty() || name.len() > 50 {
+        return Err("API key name must be between 1 and 50 characters".to_string());
+    }
+
+    let team_id = match team_name {
+        Some(team_name) => Some(team_service::find_admin_team(
+            conn, guild_id, team_name, owner_id,
+        )?),
+        None => None,
+    };
+
+    let key = format!("{}{}", API_KEY_PREFIX, random_token(24));
+    let new_key = NewApiKey {
+        user_id: owner_id,
+        team_id,
+        name: name.to_string(),
+        key_prefix: key[..11].to_string(),
+        key_hash: hash_token(&key),
+        scope: scope.to_string(),
+    };
+
+    let key_id = api_key_repository::insert_api_key(conn, &new_key)?;
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::ApiKeyCreate,
+        team_id,
+        Some(&key_id.to_string()),
+        json!({ "name": name, "scope": scope }),
+    );
+    Ok((key_id, key))
+}
+
+pub fn list_api_keys(conn: &mut PgConnection, owner_id: i32) -> Result<Vec<ApiKeyTable>, String> {
+    let keys = api_key_repository::get_user_api_keys(conn, owner_id)?;
+
+    Ok(keys
+        .into_iter()
+        .map(|(key, team)| ApiKeyTable {
+            id: key.id,
+            name: key.name,
+            prefix: key.key_prefix,
+            scope: key.scope,
+            team: team
+                .map(|(guild_id, team_name)| format!("{}/{}", guild_id, team_name))
+                .unwrap_or_else(|| "All".to_string()),
+            last_used_at: key
+                .last_used_at
+                .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| "Never".to_string()),
+            status: if key.revoked_at.is_some() {
+                "Revoked".to_string()
+            } else {
+                "Active".to_string()
+            },
+        })
+        .collect())
+}
+
+pub fn revoke_api_key(
+    conn: &mut PgConnection,
+    owner_id: i32,
+    key_id: i32,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    api_key_repository::revoke_api_key(conn, owner_id, key_id)?;
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::ApiKeyRevoke,
+        None,
+        Some(&key_id.to_string()),
+        json!({}),
+    );
+    Ok(())
+}
+
+// Resolve an `Authorization: ApiKey ...` value and record its use.
+pub fn verify_api_key(pool: &DBPool, key: &str) -> Result<ApiKeyPrincipal, &'static str> {
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+
+    let (api_key, team, owner_discord_id) =
+        api_key_repository::find_active_api_key(&mut conn, &hash_token(key))
+            .ok_or("Invalid API key")?;
+
+    if let Err(e) = api_key_repository::touch_api_key(&mut conn, api_key.id) {
+        log::error!("❌ {}", e);
+    }
+
+    Ok(ApiKeyPrincipal {
+        owner_discord_id,
+        read_only: api_key.scope != "write",
+        team,
+    })
+}
+
+// Team scoped API keys may only access data of their own team,
+// unscoped ones only the teams their owner holds a role in.
+pub fn can_access_team(
+    conn: &mut PgConnection,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+) -> bool {
+    match principal {
+        Some(ApiKeyPrincipal {
+            team: Some((allowed_guild, allowed_team)),
+            ..
+        }) => allowed_guild == guild_id && allowed_team == team_name,
+        Some(ApiKeyPrincipal {
+            owner_discord_id, ..
+        }) => team_service::find_team_id(conn, guild_id, team_name)
+            .and_then(|team_id| role_service::get_role(conn, team_id, owner_discord_id))
+            .is_ok_and(|role| role.is_some()),
+        None => true,
+    }
+}
@@ -1,13 +1,77 @@
-use actix_web::{web, Responder};
+use crate::{
+    api::{
+        application::{api_key_service::can_access_team, event_service::event_response},
+        domain::dto::{ApiKeyPrincipal, CheckInCodeEventResponse, TeamError},
+    },
+    bot::{
+        application::services::{attendance_service, member_service, role_service, team_service},
+        domain::{permission::Permission, table::MemberAttendanceTable},
+    },
+    config::database::DBPool,
+};
 
-use crate::{bot::{application::services::attendance_service, domain::table::MemberAttendanceTable}, config::database::DBPool};
+// Attendance of every member of a team, for callers allowed to view it.
+pub fn show_member_attendance(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+) -> Result<Vec<MemberAttendanceTable>, TeamError> {
+    let mut conn = pool.get().expect("Failed to connect to database!");
+    if !can_access_team(&mut conn, principal, guild_id, team_name) {
+        return Err(TeamError::Forbidden(
+            "API key is not allowed to access this team".to_string(),
+        ));
+    }
 
+    let team_id =
+        team_service::find_team_id(&mut conn, guild_id, team_name).map_err(TeamError::NotFound)?;
+    role_service::authorize(
+        &mut conn,
+        team_id,
+        caller_discord_id,
+        Permission::ViewAttendance,
+    )
+    .map_err(TeamError::Forbidden)?;
 
+    attendance_service::get_member_attendance(&mut conn, guild_id, team_name)
+        .map_err(TeamError::Internal)
+}
 
-pub fn show_member_attendance(
-        pool: &DBPool,
-        team_name: &str
-    ) -> Result<Vec<MemberAttendanceTable>, String> {
+// Check the caller in to the on-site event of a team the code belongs to.
+pub fn check_in_with_code(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    code: &str,
+) -> Result<CheckInCodeEventResponse, TeamError> {
     let mut conn = pool.get().expect("Failed to connect to database!");
-    attendance_service::get_member_attendance(&mut conn, team_name)
+    if !can_access_team(&mut conn, principal, guild_id, team_name) {
+        return Err(TeamError::Forbidden(
+            "API key is not allowed to access this team".to_string(),
+        ));
+    }
+
+    let team_id =
+        team_service::find_team_id(&mut conn, guild_id, team_name).map_err(TeamError::NotFound)?;
+    let member = member_service::find_member(&mut conn, team_id, caller_discord_id)
+        .ok()
+        .filter(|member| member.left_date.is_none())
+        .ok_or_else(|| {
+            TeamError::Forbidden("You are not an active member of this team.".to_string())
+        })?;
+    role_service::authorize(
+        &mut conn,
+        team_id,
+        caller_discord_id,
+        Permission::RecordAttendance,
+    )
+    .map_err(TeamError::Forbidden)?;
+
+    attendance_service::check_in_with_code(&mut conn, member.id, team_id, code)
+        .map(|event| event_response(&event))
+        .map_err(TeamError::Invalid)
 }
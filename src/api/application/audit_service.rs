@@ -0,0 +1,79 @@
+use crate::{
+    api::{
+        application::event_service::{authorized_team, parse_date},
+        domain::dto::{ApiKeyPrincipal, AuditEventResponse, AuditQuery, TeamError},
+    },
+    bot::{
+        application::services::audit_service,
+        domain::{
+            audit::{AuditFilter, DEFAULT_AUDIT_LIMIT},
+            model::AuditEvent,
+            permission::Permission,
+        },
+    },
+    config::database::DBPool,
+};
+
+// Audit log of a team, or of every team the caller owns together with their own actions.
+// An API key restricted to a team only ever sees the log of that team.
+pub fn list_audit_events(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    query: &AuditQuery,
+) -> Result<Vec<AuditEventResponse>, TeamError> {
+    let filter = AuditFilter {
+        actor_id: query.actor.clone(),
+        action: query.action.as_deref().map(str::to_lowercase),
+        since: query.since.as_deref().map(parse_date).transpose()?,
+        until: query.until.as_deref().map(parse_date).transpose()?,
+        limit: query.limit.unwrap_or(DEFAULT_AUDIT_LIMIT),
+    };
+    filter.validate().map_err(TeamError::Invalid)?;
+
+    let team = match (&query.guild_id, &query.team) {
+        (Some(guild_id), Some(team_name)) => Some((guild_id.clone(), team_name.clone())),
+        (None, None) => principal.and_then(|principal| principal.team.clone()),
+        _ => {
+            return Err(TeamError::Invalid(
+                "`guild_id` and `team` must be given together".to_string(),
+            ))
+        }
+    };
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let events = match team {
+        Some((guild_id, team_name)) => {
+            let team_id = authorized_team(
+                &mut conn,
+                caller_discord_id,
+                principal,
+                &guild_id,
+                &team_name,
+                Permission::ManageTeam,
+            )?;
+            audit_service::get_team_events(&mut conn, team_id, &filter)
+        }
+        None => audit_service::get_owner_events(&mut conn, caller_discord_id, &filter),
+    }
+    .map_err(TeamError::Internal)?;
+
+    Ok(events.into_iter().map(event_response).collect())
+}
+
+fn event_response(event: AuditEvent) -> AuditEventResponse {
+    AuditEventResponse {
+        id: event.id,
+        actor_id: event.actor_id,
+        source: event.source,
+        action: event.action,
+        team_id: event.team_id,
+        team_name: event.team_name,
+        guild_id: event.guild_id,
+        channel_id: event.channel_id,
+        target: event.target,
+        details: serde_json::from_str(&event.details)
+            .unwrap_or(serde_json::Value::String(event.details)),
+        created_at: event.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
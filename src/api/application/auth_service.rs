@@ -1,45 +1,327 @@
 use crate::{
-    api::{domain::dto::Claims, infrastructure::auth_repository::find_by_username},
-    config::database::DBPool,
+    api::{
+        application::login_throttle::backoff_seconds,
+        domain::{
+            dto::{ApiKeyPrincipal, AuthResponse, Claims, LoginError, TeamError},
+            model::NewRefreshToken,
+        },
+        infrastructure::{
+            auth_repository::{
+                find_by_discord_id, find_by_id, find_by_username, lock_account,
+                record_failed_login, reset_failed_logins,
+            },
+            token_repository,
+        },
+    },
+    config::{
+        constant::{
+            ACCESS_TOKEN_TTL_MINUTES, LOGIN_LOCKOUT_MINUTES, LOGIN_LOCKOUT_THRESHOLD,
+            REFRESH_TOKEN_TTL_DAYS,
+        },
+        database::DBPool,
+    },
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use diesel::{Connection, PgConnection};
 use dotenvy::dotenv;
-use jsonwebtoken::{encode, EncodingKey, Header};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
 use std::env;
+use std::sync::OnceLock;
 
-pub fn login_user(pool: &DBPool, username: &str, password: &str) -> Result<String, &'static str> {
-    dotenv().ok();
-    let secret_key = env::var("JWT_SECRET").expect("JWT_SECRET is not set in .env file!");
+// Hash of a password nobody has, at the cost real passwords are hashed with.
+fn dummy_password_hash() -> &'static str {
+    static DUMMY_PASSWORD_HASH: OnceLock<String> = OnceLock::new();
+    DUMMY_PASSWORD_HASH.get_or_init(|| {
+        bcrypt::hash(random_token(16), bcrypt::DEFAULT_COST).expect("Password hashing failed!")
+    })
+}
+
+pub fn login_user(
+    pool: &DBPool,
+    username: &str,
+    password: &str,
+) -> Result<AuthResponse, LoginError> {
+    let Some(user) = find_by_username(pool, username) else {
+        // verify anyway so unknown usernames take as long to reject as wrong passwords
+        let _ = bcrypt::verify(password, dummy_password_hash());
+        log::warn!("❌ Login failed: unknown user");
+        return Err(LoginError::InvalidCredentials);
+    };
+
+    let now = Utc::now().naive_utc();
+
+    // Locked accounts and accounts in backoff are rejected before checking the password
+    if let Some(locked_until) = user.locked_until.filter(|until| *until > now) {
+        return Err(LoginError::TooManyAttempts {
+            retry_after: seconds_until(now, locked_until),
+        });
+    }
+    if let Some(last_failure) = user.last_failed_login_at {
+        let backoff = backoff_seconds(user.failed_login_attempts.max(0) as u32);
+        let next_attempt = last_failure + Duration::seconds(backoff as i64);
+        if next_attempt > now {
+            return Err(LoginError::TooManyAttempts {
+                retry_after: seconds_until(now, next_attempt),
+            });
+        }
+    }
+
+    if !bcrypt::verify(password, &user.password_hash).unwrap_or(false) {
+        log::warn!("❌ Login failed for user #{}", user.id);
 
-    if let Some(user) = find_by_username(pool, username) {
-        log::debug!("🔍 User found: {:?}", user);
+        let failures = record_failed_login(pool, user.id)
+            .map_err(|_| LoginError::Internal("Failed to record login attempt"))?;
 
-        if bcrypt::verify(password, &user.password_hash).unwrap_or(false) {
-            log::info!("✅ Login successful!");
+        if failures >= LOGIN_LOCKOUT_THRESHOLD {
+            let locked_until = now + Duration::minutes(LOGIN_LOCKOUT_MINUTES);
+            lock_account(pool, user.id, locked_until)
+                .map_err(|_| LoginError::Internal("Failed to lock account"))?;
+            log::warn!(
+                "🔒 Account of user #{} locked until {}",
+                user.id,
+                locked_until
+            );
 
-            let expiration = Utc::now()
-                .checked_add_signed(Duration::hours(24))
-                .expect("Invalid timestamp")
-                .timestamp() as usize;
+            return Err(LoginError::AccountLocked {
+                retry_after: (LOGIN_LOCKOUT_MINUTES * 60) as u64,
+                discord_id: user.discord_id,
+            });
+        }
+
+        return Err(LoginError::InvalidCredentials);
+    }
+
+    log::info!("✅ Login successful!");
+
+    if user.failed_login_attempts > 0 || user.locked_until.is_some() {
+        reset_failed_logins(pool, user.id)
+            .map_err(|_| LoginError::Internal("Failed to reset login attempts"))?;
+    }
+
+    let mut conn = pool
+        .get()
+        .map_err(|_| LoginError::Internal("Failed to get DB connection"))?;
+    issue_tokens(&mut conn, user.id, user.username).map_err(LoginError::Internal)
+}
 
-            let claims = Claims {
-                sub: user.username,
-                exp: expiration,
+// Issue a token pair for the registered user linked to a Discord account.
+pub fn login_discord_user(pool: &DBPool, discord_id: &str) -> Result<AuthResponse, &'static str> {
+    let Some(user) = find_by_discord_id(pool, discord_id) else {
+        log::warn!("❌ Discord login failed: no registered user");
+        return Err("No admin is registered for this Discord account");
+    };
+
+    if user
+        .locked_until
+        .is_some_and(|until| until > Utc::now().naive_utc())
+    {
+        return Err("Account is locked");
+    }
+
+    log::info!("✅ Discord login successful!");
+
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+    issue_tokens(&mut conn, user.id, user.username)
+}
+
+// Exchange a refresh token for a new access/refresh token pair. The presented
+// refresh token is revoked; presenting an already revoked one revokes every
+// session of its owner since the token has most likely leaked.
+pub fn refresh_session(pool: &DBPool, refresh_token: &str) -> Result<AuthResponse, &'static str> {
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+    let hash = hash_token(refresh_token);
+
+    // The presented token is revoked in the same transaction the new pair is stored in, and
+    // of concurrent refreshes with the same token only the first one can revoke it
+    let rotated = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            let Some(stored) = token_repository::claim_refresh_token(conn, &hash)
+                .map_err(|_| diesel::result::Error::RollbackTransaction)?
+            else {
+                return Ok(None);
             };
-            let token = encode(
-                &Header::default(),
-                &claims,
-                &EncodingKey::from_secret(secret_key.as_ref()),
-            )
-            .unwrap();
-
-            return Ok(token);
-        } else {
-            log::error!("❌ Password mismatch for user {}", username);
+            let user = find_by_id(conn, stored.user_id)
+                .ok_or(diesel::result::Error::RollbackTransaction)?;
+            issue_tokens(conn, user.id, user.username)
+                .map(Some)
+                .map_err(|_| diesel::result::Error::RollbackTransaction)
+        })
+        .map_err(|_| "Failed to rotate refresh token")?;
+    if let Some(tokens) = rotated {
+        return Ok(tokens);
+    }
+
+    let stored =
+        token_repository::find_refresh_token(&mut conn, &hash).ok_or("Invalid refresh token")?;
+    if stored.revoked_at.is_some() {
+        log::warn!(
+            "⚠️ Reuse of revoked refresh token for user {}",
+            stored.user_id
+        );
+        revoke_all_sessions(&mut conn, stored.user_id).map_err(|_| "Invalid refresh token")?;
+        return Err("Invalid refresh token");
+    }
+    if stored.expires_at <= Utc::now().naive_utc() {
+        return Err("Refresh token expired");
+    }
+
+    Err("Invalid refresh token")
+}
+
+// Revoke the access token of the caller and, if given, its refresh token. A refresh
+// token of another user is refused before anything is revoked.
+pub fn logout_user(
+    pool: &DBPool,
+    claims: &Claims,
+    refresh_token: Option<&str>,
+) -> Result<(), TeamError> {
+    let internal = |e: &str| TeamError::Internal(e.to_string());
+    let mut conn = pool
+        .get()
+        .map_err(|_| internal("Failed to get DB connection"))?;
+
+    let stored = refresh_token.and_then(|refresh_token| {
+        token_repository::find_refresh_token(&mut conn, &hash_token(refresh_token))
+    });
+    if let Some(stored) = &stored {
+        let owner = find_by_username(pool, &claims.sub).map(|user| user.id);
+        if owner != Some(stored.user_id) {
+            return Err(TeamError::Forbidden(
+                "Refresh token does not belong to you".to_string(),
+            ));
+        }
+    }
+
+    let expires_at = DateTime::from_timestamp(claims.exp as i64, 0)
+        .map(|dt| dt.naive_utc())
+        .unwrap_or_else(|| Utc::now().naive_utc());
+
+    token_repository::revoke_jti(&mut conn, &claims.jti, expires_at)
+        .map_err(|_| internal("Failed to revoke token"))?;
+
+    if let Some(stored) = stored {
+        token_repository::revoke_refresh_token(&mut conn, stored.id)
+            .map_err(|_| internal("Failed to revoke refresh token"))?;
+    }
+
+    if let Err(e) = token_repository::purge_expired_revocations(&mut conn) {
+        log::error!("❌ {}", e);
+    }
+
+    Ok(())
+}
+
+// Revoke every refresh token of a user together with the access tokens issued
+// alongside them. Returns the number of sessions that were revoked.
+pub fn revoke_all_sessions(conn: &mut PgConnection, user_id: i32) -> Result<usize, String> {
+    let access_jtis = token_repository::revoke_user_refresh_tokens(conn, user_id)?;
+
+    // access tokens never outlive their TTL, so the list entry can expire with them
+    let expires_at = (Utc::now() + Duration::minutes(ACCESS_TOKEN_TTL_MINUTES)).naive_utc();
+    for jti in &access_jtis {
+        token_repository::revoke_jti(conn, jti, expires_at)?;
+    }
+
+    Ok(access_jtis.len())
+}
+
+// Decode an access token and make sure it has not been revoked.
+pub fn verify_access_token(pool: &DBPool, token: &str) -> Result<Claims, &'static str> {
+    let claims = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(jwt_secret().as_ref()),
+        &Validation::default(),
+    )
+    .map_err(|_| "Invalid token")?
+    .claims;
+
+    let mut conn = pool.get().map_err(|_| "Failed to get DB connection")?;
+    match token_repository::is_jti_revoked(&mut conn, &claims.jti) {
+        Ok(false) => Ok(claims),
+        Ok(true) => Err("Token has been revoked"),
+        Err(e) => {
+            log::error!("❌ {}", e);
+            Err("Failed to verify token")
         }
-    } else {
-        log::error!("❌ User not found: {}", username);
     }
+}
+
+// Discord ID of the user behind an access token or API key.
+pub fn caller_discord_id(
+    pool: &DBPool,
+    claims: Option<&Claims>,
+    principal: Option<&ApiKeyPrincipal>,
+) -> Option<String> {
+    if let Some(principal) = principal {
+        return Some(principal.owner_discord_id.clone());
+    }
+
+    claims
+        .and_then(|claims| find_by_username(pool, &claims.sub))
+        .map(|user| user.discord_id)
+}
+
+fn issue_tokens(
+    conn: &mut PgConnection,
+    user_id: i32,
+    username: String,
+) -> Result<AuthResponse, &'static str> {
+    let jti = random_token(16);
+    let expiration = Utc::now()
+        .checked_add_signed(Duration::minutes(ACCESS_TOKEN_TTL_MINUTES))
+        .expect("Invalid timestamp")
+        .timestamp() as usize;
+
+    let claims = Claims {
+        sub: username,
+        exp: expiration,
+        jti: jti.clone(),
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret().as_ref()),
+    )
+    .map_err(|_| "Failed to create token")?;
+
+    let refresh_token = random_token(32);
+    token_repository::insert_refresh_token(
+        conn,
+        &NewRefreshToken {
+            user_id,
+            token_hash: hash_token(&refresh_token),
+            access_jti: jti,
+            expires_at: (Utc::now() + Duration::days(REFRESH_TOKEN_TTL_DAYS)).naive_utc(),
+        },
+    )
+    .map_err(|_| "Failed to create refresh token")?;
+
+    Ok(AuthResponse {
+        token,
+        refresh_token,
+        expires_in: ACCESS_TOKEN_TTL_MINUTES * 60,
+    })
+}
+
+fn seconds_until(now: NaiveDateTime, until: NaiveDateTime) -> u64 {
+    (until - now).num_seconds().max(1) as u64
+}
+
+pub fn jwt_secret() -> String {
+    dotenv().ok();
+    env::var("JWT_SECRET").expect("JWT_SECRET is not set in .env file!")
+}
+
+// Random hex string built from `len` bytes of the OS RNG.
+pub fn random_token(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
 
-    Err("Invalid credentials")
+// Refresh tokens and API keys are only stored as their SHA-256 hash.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
 }
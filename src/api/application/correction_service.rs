@@ -0,0 +1,282 @@
+use crate::{
+    api::{
+        application::{
+            api_key_service::can_access_team,
+            event_service::{authorized_team, parse_date, parse_time},
+        },
+        domain::dto::{
+            AddAttendanceRequest, ApiKeyPrincipal, CreateCorrectionRequest, TeamError,
+            UpdateAttendanceRequest,
+        },
+    },
+    bot::{
+        application::services::{
+            correction_service::{self, CorrectionReview},
+            role_service, team_service,
+        },
+        domain::{
+            audit::AuditActor,
+            correction::AttendanceChange,
+            model::{AttendanceCorrection, MemberAttendance},
+            permission::Permission,
+            table::{AttendanceAuditTable, AttendanceCorrectionTable},
+        },
+    },
+    config::database::DBPool,
+};
+use chrono::Utc;
+use diesel::PgConnection;
+
+// Change an attendance record, recorded in its audit trail with the caller as actor.
+pub fn update_attendance(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    attendance_id: i32,
+    request: &UpdateAttendanceRequest,
+) -> Result<MemberAttendance, TeamError> {
+    let change = parse_change(
+        request.check_in_time.as_deref(),
+        request.check_out_time.as_deref(),
+        request.status.as_deref(),
+    )?;
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let record = authorized_record(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        attendance_id,
+        Permission::ManageMembers,
+    )?;
+    let team_id = record.team_id.unwrap_or_default();
+
+    correction_service::edit_attendance(
+        &mut conn,
+        team_id,
+        record.id,
+        &change,
+        &AuditActor::api(caller_discord_id),
+        &request.reason,
+    )
+    .map_err(TeamError::Invalid)
+}
+
+// Add a missing attendance record to a member of a team.
+pub fn add_attendance(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    request: &AddAttendanceRequest,
+) -> Result<MemberAttendance, TeamError> {
+    let date = parse_date(&request.date)?;
+    let change = parse_change(
+        Some(&request.check_in_time),
+        request.check_out_time.as_deref(),
+        request.status.as_deref(),
+    )?;
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ManageMembers,
+    )?;
+
+    correction_service::add_attendance(
+        &mut conn,
+        team_id,
+        &request.discord_id,
+        date,
+        &change,
+        &AuditActor::api(caller_discord_id),
+        &request.reason,
+    )
+    .map_err(TeamError::Invalid)
+}
+
+// Correction requests of a team, optionally only those waiting for review.
+pub fn list_corrections(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    pending_only: bool,
+) -> Result<Vec<AttendanceCorrectionTable>, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ManageMembers,
+    )?;
+
+    correction_service::get_corrections(&mut conn, team_id, pending_only)
+        .map_err(TeamError::Internal)
+}
+
+// Ask for a correction of one of the caller's records, or for a missing one.
+pub fn request_correction(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    request: &CreateCorrectionRequest,
+) -> Result<AttendanceCorrection, TeamError> {
+    let date = match (&request.date, request.attendance_id) {
+        (Some(date), None) => parse_date(date)?,
+        (None, Some(_)) => Utc::now().date_naive(),
+        _ => {
+            return Err(TeamError::Invalid(
+                "Give either attendance_id or the date of a missing record".to_string(),
+            ))
+        }
+    };
+    let change = parse_change(
+        request.check_in_time.as_deref(),
+        request.check_out_time.as_deref(),
+        request.status.as_deref(),
+    )?;
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::RecordAttendance,
+    )?;
+
+    correction_service::request_correction(
+        &mut conn,
+        team_id,
+        caller_discord_id,
+        request.attendance_id,
+        date,
+        &change,
+        &request.reason,
+    )
+    .map_err(TeamError::Invalid)
+}
+
+// Approve or reject a pending correction request.
+pub fn review_correction(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    correction_id: i32,
+    approve: bool,
+) -> Result<CorrectionReview, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let correction = correction_service::find_correction(&mut conn, correction_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(|| {
+            TeamError::NotFound(format!("Correction request {} not found", correction_id))
+        })?;
+    authorized_team_id(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        correction.team_id,
+        Permission::ManageMembers,
+    )?;
+
+    correction_service::review_correction(
+        &mut conn,
+        correction_id,
+        &AuditActor::api(caller_discord_id),
+        approve,
+    )
+    .map_err(TeamError::Invalid)
+}
+
+// Changes made by hand to an attendance record, visible to its member and to the roles that
+// may view the attendance of the team.
+pub fn attendance_audit(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    attendance_id: i32,
+) -> Result<Vec<AttendanceAuditTable>, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    authorized_record(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        attendance_id,
+        Permission::ViewMembers,
+    )?;
+
+    correction_service::get_history(&mut conn, attendance_id, caller_discord_id)
+        .map_err(TeamError::Forbidden)
+}
+
+// Resolve an attendance record and check the caller may act on the team it belongs to.
+fn authorized_record(
+    conn: &mut PgConnection,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    attendance_id: i32,
+    permission: Permission,
+) -> Result<MemberAttendance, TeamError> {
+    let not_found =
+        || TeamError::NotFound(format!("Attendance record {} not found", attendance_id));
+    let record = correction_service::find_record(conn, attendance_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(not_found)?;
+    let team_id = record.team_id.ok_or_else(not_found)?;
+
+    authorized_team_id(conn, caller_discord_id, principal, team_id, permission)?;
+
+    Ok(record)
+}
+
+// Same checks as authorized_team, for a team found through one of its records.
+fn authorized_team_id(
+    conn: &mut PgConnection,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    team_id: i32,
+    permission: Permission,
+) -> Result<(), TeamError> {
+    let team = team_service::find_team(conn, team_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(|| TeamError::NotFound(format!("Team {} not found", team_id)))?;
+
+    if !can_access_team(conn, principal, &team.guild_id, &team.name) {
+        return Err(TeamError::Forbidden(
+            "API key is not allowed to access this team".to_string(),
+        ));
+    }
+    role_service::authorize(conn, team.id, caller_discord_id, permission)
+        .map_err(TeamError::Forbidden)?;
+
+    Ok(())
+}
+
+// An empty check_out_time removes the check-out, a missing field keeps the current value.
+fn parse_change(
+    check_in_time: Option<&str>,
+    check_out_time: Option<&str>,
+    status: Option<&str>,
+) -> Result<AttendanceChange, TeamError> {
+    Ok(AttendanceChange {
+        check_in_time: check_in_time.map(parse_time).transpose()?,
+        check_out_time: check_out_time
+            .filter(|time| !time.is_empty())
+            .map(parse_time)
+            .transpose()?,
+        clear_check_out: check_out_time.is_some_and(str::is_empty),
+        status: status.map(str::to_string),
+    })
+}
@@ -0,0 +1,293 @@
+use crate::{
+    api::{
+        application::api_key_service::can_access_team,
+        domain::dto::{
+            ApiKeyPrincipal, CheckInCodeEventResponse, CheckInCodeResponse, CreateEventRequest,
+            SkipOccurrenceRequest, StartCheckInCodeRequest, TeamError,
+        },
+    },
+    bot::{
+        application::services::{event_service, role_service, team_service},
+        domain::{
+            audit::AuditActor,
+            model::Event,
+            permission::Permission,
+            table::{EventAttendanceTable, EventTable},
+        },
+    },
+    config::database::DBPool,
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+use diesel::PgConnection;
+
+// longest on-site check-in window, in minutes
+const MAX_CODE_EVENT_MINUTES: i64 = 24 * 60;
+
+// Open an on-site check-in for a team, check-ins then require its rotating code.
+pub fn start_check_in_code(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    request: &StartCheckInCodeRequest,
+) -> Result<CheckInCodeEventResponse, TeamError> {
+    if !(1..=MAX_CODE_EVENT_MINUTES).contains(&request.duration_minutes) {
+        return Err(TeamError::Invalid(format!(
+            "duration_minutes must be between 1 and {}",
+            MAX_CODE_EVENT_MINUTES
+        )));
+    }
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ManageMembers,
+    )?;
+
+    let event = event_service::start_code_event(
+        &mut conn,
+        team_id,
+        request.title.as_deref().unwrap_or("On-site check-in"),
+        Duration::minutes(request.duration_minutes),
+        &AuditActor::api(caller_discord_id),
+    )
+    .map_err(TeamError::Invalid)?;
+
+    Ok(event_response(&event))
+}
+
+// Code currently shown for an on-site check-in, for screens polling it.
+pub fn current_check_in_code(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    event_id: i32,
+) -> Result<CheckInCodeResponse, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let event = authorized_event(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        event_id,
+        Permission::ManageMembers,
+    )?;
+
+    let (code, expires_in) = event_service::current_code(&event, Utc::now().naive_utc())
+        .ok_or_else(|| TeamError::NotFound("Check-in for this event is closed".to_string()))?;
+
+    Ok(CheckInCodeResponse {
+        code,
+        expires_in,
+        ends_at: event.ends_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+    })
+}
+
+pub fn event_response(event: &Event) -> CheckInCodeEventResponse {
+    CheckInCodeEventResponse {
+        event_id: event.id,
+        title: event.title.clone(),
+        ends_at: event.ends_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
+
+// Upcoming and repeating events of a team.
+pub fn list_events(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+) -> Result<Vec<EventTable>, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ViewMembers,
+    )?;
+
+    event_service::get_upcoming_events(&mut conn, team_id).map_err(TeamError::Internal)
+}
+
+// Schedule an event for a team.
+pub fn create_event(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    request: &CreateEventRequest,
+) -> Result<EventTable, TeamError> {
+    let starts_at = parse_time(&request.starts_at)?;
+    let ends_at = parse_time(&request.ends_at)?;
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ManageMembers,
+    )?;
+
+    let event = event_service::create_event(
+        &mut conn,
+        team_id,
+        &request.title,
+        starts_at,
+        ends_at,
+        request.recurrence_rule.as_deref(),
+        &AuditActor::api(caller_discord_id),
+    )
+    .map_err(TeamError::Invalid)?;
+
+    Ok(event_service::event_table(&event))
+}
+
+// Cancel an event, its attendance stays recorded.
+pub fn cancel_event(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    event_id: i32,
+) -> Result<(), TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let event = authorized_event(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        event_id,
+        Permission::ManageMembers,
+    )?;
+
+    event_service::cancel_event(&mut conn, event.id, &AuditActor::api(caller_discord_id))
+        .map_err(TeamError::Invalid)
+}
+
+// Skip one occurrence of a repeating event (an iCalendar EXDATE).
+pub fn skip_occurrence(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    event_id: i32,
+    request: &SkipOccurrenceRequest,
+) -> Result<(), TeamError> {
+    let date = parse_date(&request.date)?;
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let event = authorized_event(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        event_id,
+        Permission::ManageMembers,
+    )?;
+
+    event_service::skip_occurrence(&mut conn, &event, date, &AuditActor::api(caller_discord_id))
+        .map(|_| ())
+        .map_err(TeamError::Invalid)
+}
+
+// Who checked in to an occurrence of an event, followed by the members who did not show up.
+// Repeating events default to their latest occurrence.
+pub fn event_attendance(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    event_id: i32,
+    date: Option<&str>,
+) -> Result<Vec<EventAttendanceTable>, TeamError> {
+    let date = date.map(parse_date).transpose()?;
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let event = authorized_event(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        event_id,
+        Permission::ViewAttendance,
+    )?;
+
+    let occurrence =
+        event_service::find_occurrence(&mut conn, &event, date).map_err(TeamError::NotFound)?;
+    event_service::get_event_attendance(&mut conn, &occurrence).map_err(TeamError::Internal)
+}
+
+// Resolve a team and check the caller (and API key scope) may act on it.
+pub(crate) fn authorized_team(
+    conn: &mut PgConnection,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    permission: Permission,
+) -> Result<i32, TeamError> {
+    if !can_access_team(conn, principal, guild_id, team_name) {
+        return Err(TeamError::Forbidden(
+            "API key is not allowed to access this team".to_string(),
+        ));
+    }
+
+    let team_id =
+        team_service::find_team_id(conn, guild_id, team_name).map_err(TeamError::NotFound)?;
+    role_service::authorize(conn, team_id, caller_discord_id, permission)
+        .map_err(TeamError::Forbidden)?;
+
+    Ok(team_id)
+}
+
+// Resolve an event and check the caller may act on the team it belongs to.
+fn authorized_event(
+    conn: &mut PgConnection,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    event_id: i32,
+    permission: Permission,
+) -> Result<Event, TeamError> {
+    let event = event_service::find_event(conn, event_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(|| TeamError::NotFound(format!("Event {} not found", event_id)))?;
+    let team = team_service::find_team(conn, event.team_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(|| TeamError::NotFound(format!("Event {} not found", event_id)))?;
+
+    if !can_access_team(conn, principal, &team.guild_id, &team.name) {
+        return Err(TeamError::Forbidden(
+            "API key is not allowed to access this team".to_string(),
+        ));
+    }
+    role_service::authorize(conn, team.id, caller_discord_id, permission)
+        .map_err(TeamError::Forbidden)?;
+
+    Ok(event)
+}
+
+pub(crate) fn parse_date(value: &str) -> Result<NaiveDate, TeamError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| TeamError::Invalid(format!("Invalid date '{}', expected YYYY-MM-DD", value)))
+}
+
+// Accepts "2025-03-14 18:00", "2025-03-14 18:00:00" and "2025-03-14T18:00:00"
+pub(crate) fn parse_time(value: &str) -> Result<NaiveDateTime, TeamError> {
+    [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .ok_or_else(|| {
+        TeamError::Invalid(format!(
+            "Invalid time '{}', expected YYYY-MM-DD HH:MM[:SS] in UTC",
+            value
+        ))
+    })
+}
@@ -0,0 +1,115 @@
+use crate::{
+    api::{
+        application::event_service::{authorized_team, parse_date},
+        domain::dto::{ApiKeyPrincipal, CreateHolidayRequest, HolidayImportResponse, TeamError},
+    },
+    bot::{
+        application::services::holiday_service,
+        domain::{audit::AuditActor, permission::Permission, table::HolidayTable},
+    },
+    config::database::DBPool,
+};
+use chrono::{Datelike, Utc};
+
+// Holiday calendar of a team for a year.
+pub fn list_holidays(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    year: Option<i32>,
+) -> Result<Vec<HolidayTable>, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ViewMembers,
+    )?;
+
+    let year = year.unwrap_or_else(|| Utc::now().year());
+    holiday_service::get_holidays(&mut conn, team_id, year).map_err(TeamError::Invalid)
+}
+
+// Add a day off to the holiday calendar of a team.
+pub fn add_holiday(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    request: &CreateHolidayRequest,
+) -> Result<HolidayTable, TeamError> {
+    let date = parse_date(&request.date)?;
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ManageMembers,
+    )?;
+
+    let actor = AuditActor::api(caller_discord_id);
+    holiday_service::add_holiday(&mut conn, team_id, date, &request.name, &actor)
+        .map_err(TeamError::Invalid)
+}
+
+// Add the days of an iCalendar file to the holiday calendar of a team.
+pub fn import_holidays(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    content: &str,
+) -> Result<HolidayImportResponse, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ManageMembers,
+    )?;
+
+    let actor = AuditActor::api(caller_discord_id);
+    let import = holiday_service::import_holidays(&mut conn, team_id, content, &actor)
+        .map_err(TeamError::Invalid)?;
+    Ok(HolidayImportResponse {
+        added: import.added,
+        existing: import.existing,
+        skipped: import.skipped,
+    })
+}
+
+// Remove a day off from the holiday calendar of a team.
+pub fn remove_holiday(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    date: &str,
+) -> Result<(), TeamError> {
+    let date = parse_date(date)?;
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ManageMembers,
+    )?;
+
+    let actor = AuditActor::api(caller_discord_id);
+    holiday_service::remove_holiday(&mut conn, team_id, date, &actor).map_err(TeamError::NotFound)
+}
@@ -0,0 +1,204 @@
+use crate::{
+    api::{
+        application::{
+            api_key_service::can_access_team,
+            event_service::{authorized_team, parse_date},
+        },
+        domain::dto::{ApiKeyPrincipal, CreateLeaveRequest, SetLeaveAllowanceRequest, TeamError},
+    },
+    bot::{
+        application::services::{
+            leave_service::{self, LeaveReview},
+            role_service, team_service,
+        },
+        domain::{
+            audit::AuditActor,
+            model::LeaveRequest,
+            permission::Permission,
+            table::{LeaveBalanceTable, LeaveRequestTable},
+        },
+    },
+    config::database::DBPool,
+};
+use chrono::{Datelike, Utc};
+use diesel::PgConnection;
+
+// Leave requests of a team, optionally only those waiting for review.
+pub fn list_leave_requests(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    pending_only: bool,
+) -> Result<Vec<LeaveRequestTable>, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ViewAttendance,
+    )?;
+
+    leave_service::get_requests(&mut conn, team_id, pending_only).map_err(TeamError::Internal)
+}
+
+// Request leave for the caller, the controller then posts it for review on Discord.
+pub fn request_leave(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    request: &CreateLeaveRequest,
+) -> Result<LeaveReview, TeamError> {
+    let starts_on = parse_date(&request.starts_on)?;
+    let ends_on = parse_date(&request.ends_on)?;
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::RecordAttendance,
+    )?;
+
+    leave_service::request_leave(
+        &mut conn,
+        team_id,
+        caller_discord_id,
+        starts_on,
+        ends_on,
+        &request.leave_type,
+        &request.reason,
+    )
+    .map_err(TeamError::Invalid)
+}
+
+// Approve or reject a pending leave request.
+pub fn review_leave_request(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    request_id: i32,
+    approve: bool,
+) -> Result<LeaveReview, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    authorized_request(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        request_id,
+        Permission::ManageMembers,
+    )?;
+
+    let actor = AuditActor::api(caller_discord_id);
+    leave_service::review_request(&mut conn, request_id, &actor, approve)
+        .map_err(TeamError::Invalid)
+}
+
+// Withdraw one of the caller's own leave requests.
+pub fn cancel_leave_request(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    request_id: i32,
+) -> Result<(), TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let request = authorized_request(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        request_id,
+        Permission::RecordAttendance,
+    )?;
+
+    leave_service::cancel_request(&mut conn, request.team_id, request.id, caller_discord_id)
+        .map(|_| ())
+        .map_err(TeamError::Invalid)
+}
+
+// Leave taken by the members of a team in a year against their allowances.
+pub fn leave_balances(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    year: Option<i32>,
+) -> Result<Vec<LeaveBalanceTable>, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ViewAttendance,
+    )?;
+
+    let year = year.unwrap_or_else(|| Utc::now().year());
+    leave_service::get_balances(&mut conn, team_id, year).map_err(TeamError::Invalid)
+}
+
+// Set how many working days of a leave type a member may take in a year.
+pub fn set_leave_allowance(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    discord_id: &str,
+    request: &SetLeaveAllowanceRequest,
+) -> Result<(), TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ManageMembers,
+    )?;
+
+    leave_service::set_allowance(
+        &mut conn,
+        team_id,
+        discord_id,
+        request.year,
+        &request.leave_type,
+        request.days,
+        &AuditActor::api(caller_discord_id),
+    )
+    .map_err(TeamError::Invalid)
+}
+
+// Resolve a leave request and check the caller may act on the team it belongs to.
+fn authorized_request(
+    conn: &mut PgConnection,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    request_id: i32,
+    permission: Permission,
+) -> Result<LeaveRequest, TeamError> {
+    let request = leave_service::find_request(conn, request_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(|| TeamError::NotFound(format!("Leave request {} not found", request_id)))?;
+    let team = team_service::find_team(conn, request.team_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(|| TeamError::NotFound(format!("Leave request {} not found", request_id)))?;
+
+    if !can_access_team(conn, principal, &team.guild_id, &team.name) {
+        return Err(TeamError::Forbidden(
+            "API key is not allowed to access this team".to_string(),
+        ));
+    }
+    role_service::authorize(conn, team.id, caller_discord_id, permission)
+        .map_err(TeamError::Forbidden)?;
+
+    Ok(request)
+}
@@ -0,0 +1,117 @@
+use crate::{
+    api::{
+        application::api_key_service::can_access_team,
+        domain::dto::{ApiKeyPrincipal, Claims, TeamError},
+    },
+    bot::{
+        application::services::{live_service, role_service, team_service},
+        domain::{
+            live::{sse_event, LiveUpdate, Presence},
+            permission::Permission,
+        },
+        infrastructure::event_bus,
+    },
+    config::{
+        constant::{
+            LIVE_FEED_KEEP_ALIVE_SECONDS, LIVE_FEED_MAX_SECONDS, LIVE_FEED_RETRY_MILLISECONDS,
+        },
+        database::DBPool,
+    },
+};
+use chrono::Utc;
+use futures_util::{stream, Stream, StreamExt};
+use tokio::{
+    sync::broadcast::{error::RecvError, Receiver},
+    time::{interval_at, sleep_until, Duration, Instant, Interval},
+};
+
+struct LiveFeed {
+    team_id: i32,
+    updates: Receiver<LiveUpdate>,
+    keep_alive: Interval,
+    closes_at: Instant,
+}
+
+// Check the caller may watch the attendance of a team, given by ID.
+pub fn authorize_live_feed(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    team_id: i32,
+) -> Result<(), TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team = team_service::find_team(&mut conn, team_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(|| TeamError::NotFound(format!("Team {} not found", team_id)))?;
+
+    if !can_access_team(&mut conn, principal, &team.guild_id, &team.name) {
+        return Err(TeamError::Forbidden(
+            "API key is not allowed to access this team".to_string(),
+        ));
+    }
+    role_service::authorize(
+        &mut conn,
+        team.id,
+        caller_discord_id,
+        Permission::ViewAttendance,
+    )
+    .map_err(TeamError::Forbidden)?;
+
+    Ok(())
+}
+
+// Who is in a team given by ID right now, checking the caller may watch its attendance.
+pub fn team_presence(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    team_id: i32,
+) -> Result<Vec<Presence>, TeamError> {
+    authorize_live_feed(pool, caller_discord_id, principal, team_id)?;
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    live_service::get_presence(&mut conn, team_id).map_err(TeamError::Internal)
+}
+
+// When a live feed closes: after LIVE_FEED_MAX_SECONDS, or sooner when the access token it was
+// opened with expires, so the client reconnects and is authenticated again.
+pub fn live_feed_deadline(claims: Option<&Claims>) -> Instant {
+    let mut seconds = LIVE_FEED_MAX_SECONDS;
+    if let Some(claims) = claims {
+        let remaining = (claims.exp as i64 - Utc::now().timestamp()).max(0) as u64;
+        seconds = seconds.min(remaining);
+    }
+    Instant::now() + Duration::from_secs(seconds)
+}
+
+// The attendance changes of a team as Server-Sent Events, from now until `closes_at`.
+// A client that falls behind gets a `resync` event and should fetch the attendance again.
+pub fn live_feed(team_id: i32, closes_at: Instant) -> impl Stream<Item = String> {
+    let period = Duration::from_secs(LIVE_FEED_KEEP_ALIVE_SECONDS);
+    let feed = LiveFeed {
+        team_id,
+        updates: event_bus::subscribe(),
+        keep_alive: interval_at(Instant::now() + period, period),
+        closes_at,
+    };
+
+    stream::once(async { format!("retry: {}\n\n", LIVE_FEED_RETRY_MILLISECONDS) })
+        .chain(stream::unfold(feed, next_event))
+}
+
+async fn next_event(mut feed: LiveFeed) -> Option<(String, LiveFeed)> {
+    loop {
+        tokio::select! {
+            update = feed.updates.recv() => match update {
+                Ok(update) if update.team_id == feed.team_id => {
+                    return Some((update.to_sse(), feed));
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(_)) => return Some((sse_event("resync", "{}"), feed)),
+                Err(RecvError::Closed) => return None,
+            },
+            _ = feed.keep_alive.tick() => return Some((": keep-alive\n\n".to_string(), feed)),
+            _ = sleep_until(feed.closes_at) => return None,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::config::constant::{LOGIN_FREE_ATTEMPTS, LOGIN_MAX_BACKOFF_SECONDS};
+
+// Failures older than this are forgotten.
+const FAILURE_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+struct FailedAttempts {
+    count: u32,
+    last_failure: Instant,
+}
+
+// In-memory per-IP login throttle shared by all API workers.
+#[derive(Default)]
+pub struct LoginThrottle {
+    attempts: Mutex<HashMap<String, FailedAttempts>>,
+}
+
+impl LoginThrottle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Seconds the client has to wait before the next attempt, if any.
+    pub fn retry_after(&self, client: &str) -> Option<u64> {
+        let attempts = self.attempts.lock().expect("Login throttle lock poisoned");
+        let failed = attempts.get(client)?;
+
+        let wait = Duration::from_secs(backoff_seconds(failed.count));
+        let elapsed = failed.last_failure.elapsed();
+        (elapsed < wait).then(|| (wait - elapsed).as_secs().max(1))
+    }
+
+    pub fn record_failure(&self, client: &str) {
+        let mut attempts = self.attempts.lock().expect("Login throttle lock poisoned");
+        attempts.retain(|_, failed| failed.last_failure.elapsed() < FAILURE_WINDOW);
+
+        let failed = attempts.entry(client.to_string()).or_insert(FailedAttempts {
+            count: 0,
+            last_failure: Instant::now(),
+        });
+        failed.count += 1;
+        failed.last_failure = Instant::now();
+    }
+
+    pub fn reset(&self, client: &str) {
+        self.attempts
+            .lock()
+            .expect("Login throttle lock poisoned")
+            .remove(client);
+    }
+}
+
+// Exponential backoff: free attempts first, then 1s, 2s, 4s, ... capped.
+pub fn backoff_seconds(failures: u32) -> u64 {
+    if failures < LOGIN_FREE_ATTEMPTS {
+        return 0;
+    }
+
+    let exponent = (failures - LOGIN_FREE_ATTEMPTS).min(16);
+    (1u64 << exponent).min(LOGIN_MAX_BACKOFF_SECONDS)
+}
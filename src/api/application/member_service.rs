@@ -1,9 +1,156 @@
 use crate::{
-    bot::{application::services::team_service::get_members_by_team, domain::table::MemberTable},
+    api::{
+        application::api_key_service::can_access_team,
+        domain::dto::{AddMemberRequest, ApiKeyPrincipal, TeamError, UpdateMemberRequest},
+    },
+    bot::{
+        application::services::{
+            member_service, role_service,
+            team_service::{self, get_members_by_team},
+        },
+        domain::{audit::AuditActor, permission::Permission, table::MemberTable},
+    },
     config::database::DBPool,
 };
+use diesel::PgConnection;
 
-pub fn show_members(pool: &DBPool, team_name: &str) -> Result<Vec<MemberTable>, String> {
+// Members of a team, for callers holding a role in it.
+pub fn show_members(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+) -> Result<Vec<MemberTable>, TeamError> {
     let mut conn = pool.get().expect("Failed to get DB connection");
-    get_members_by_team(&mut conn, team_name)
+    authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ViewMembers,
+    )?;
+
+    get_members_by_team(&mut conn, guild_id, team_name).map_err(TeamError::Internal)
+}
+
+pub fn add_member(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    member: &AddMemberRequest,
+) -> Result<(), TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = managed_team(&mut conn, caller_discord_id, principal, guild_id, team_name)?;
+
+    team_service::add_member(
+        &mut conn,
+        &member.discord_id,
+        member.username.clone(),
+        team_id,
+        &AuditActor::api(caller_discord_id),
+    )
+    .map_err(TeamError::Invalid)
+}
+
+// Rename, move or (de)activate a member of a team.
+pub fn update_member(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    member_discord_id: &str,
+    changes: &UpdateMemberRequest,
+) -> Result<(), TeamError> {
+    if changes.username.is_none() && changes.position.is_none() && changes.active.is_none() {
+        return Err(TeamError::Invalid("Nothing to update".to_string()));
+    }
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = managed_team(&mut conn, caller_discord_id, principal, guild_id, team_name)?;
+    member_service::find_member(&mut conn, team_id, member_discord_id)
+        .map_err(TeamError::NotFound)?;
+    let actor = AuditActor::api(caller_discord_id);
+
+    if let Some(username) = &changes.username {
+        member_service::rename_member(&mut conn, team_id, member_discord_id, username, &actor)
+            .map_err(TeamError::Invalid)?;
+    }
+    if let Some(position) = &changes.position {
+        member_service::set_position(&mut conn, team_id, member_discord_id, position, &actor)
+            .map_err(TeamError::Invalid)?;
+    }
+    match changes.active {
+        Some(true) => {
+            member_service::reactivate_member(&mut conn, team_id, member_discord_id, &actor)
+                .map_err(TeamError::Invalid)?
+        }
+        Some(false) => {
+            member_service::deactivate_member(&mut conn, team_id, member_discord_id, &actor)
+                .map_err(TeamError::Invalid)?
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+pub fn remove_member(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    member_discord_id: &str,
+) -> Result<(), TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = managed_team(&mut conn, caller_discord_id, principal, guild_id, team_name)?;
+
+    let actor = AuditActor::api(caller_discord_id);
+    member_service::remove_member(&mut conn, team_id, member_discord_id, &actor)
+        .map_err(TeamError::NotFound)
+}
+
+// Only owners and managers may change members, and team scoped API keys only in their own team.
+fn managed_team(
+    conn: &mut PgConnection,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+) -> Result<i32, TeamError> {
+    authorized_team(
+        conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ManageMembers,
+    )
+}
+
+fn authorized_team(
+    conn: &mut PgConnection,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    permission: Permission,
+) -> Result<i32, TeamError> {
+    if !can_access_team(conn, principal, guild_id, team_name) {
+        return Err(TeamError::Forbidden(
+            "API key is not allowed to access this team".to_string(),
+        ));
+    }
+
+    let team_id =
+        team_service::find_team_id(conn, guild_id, team_name).map_err(TeamError::NotFound)?;
+    role_service::authorize(conn, team_id, caller_discord_id, permission)
+        .map_err(TeamError::Forbidden)?;
+
+    Ok(team_id)
 }
@@ -2,3 +2,13 @@ pub mod auth_service;
 pub mod team_service;
 pub mod member_service;
 pub mod attendance_service;
+pub mod api_key_service;
+pub mod login_throttle;
+pub mod oauth_service;
+pub mod event_service;
+pub mod leave_service;
+pub mod holiday_service;
+pub mod correction_service;
+pub mod audit_service;
+pub mod webhook_service;
+pub mod live_service;
@@ -0,0 +1,86 @@
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use reqwest::Url;
+
+use crate::{
+    api::{
+        application::auth_service::{jwt_secret, random_token},
+        domain::dto::OAuthState,
+        infrastructure::discord_oauth_client,
+    },
+    config::settings::DiscordOAuthSettings,
+};
+
+// How long the user has to complete the Discord consent screen.
+pub const OAUTH_STATE_TTL_MINUTES: i64 = 10;
+
+// Create the signed `state` parameter and the nonce the browser keeps in a cookie.
+pub fn create_state() -> Result<(String, String), &'static str> {
+    let nonce = random_token(16);
+    let claims = OAuthState {
+        nonce: nonce.clone(),
+        exp: (Utc::now() + Duration::minutes(OAUTH_STATE_TTL_MINUTES)).timestamp() as usize,
+    };
+
+    let state = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret().as_ref()),
+    )
+    .map_err(|_| "Failed to create OAuth state")?;
+
+    Ok((state, nonce))
+}
+
+// The state must be signed by us, unexpired and issued to the same browser.
+pub fn verify_state(state: &str, nonce: &str) -> Result<(), &'static str> {
+    let claims = decode::<OAuthState>(
+        state,
+        &DecodingKey::from_secret(jwt_secret().as_ref()),
+        &Validation::default(),
+    )
+    .map_err(|_| "Invalid OAuth state")?
+    .claims;
+
+    if claims.nonce != nonce {
+        return Err("Invalid OAuth state");
+    }
+
+    Ok(())
+}
+
+pub fn authorize_url(settings: &DiscordOAuthSettings, state: &str) -> Result<String, &'static str> {
+    Url::parse_with_params(
+        &settings.authorize_url,
+        &[
+            ("client_id", settings.client_id.as_str()),
+            ("redirect_uri", settings.redirect_uri.as_str()),
+            ("response_type", "code"),
+            ("scope", "identify"),
+            ("state", state),
+        ],
+    )
+    .map(String::from)
+    .map_err(|_| "Invalid DISCORD_OAUTH_AUTHORIZE_URL")
+}
+
+// Resolve an authorization code to the Discord ID of the user who granted it.
+pub async fn discord_id_from_code(
+    settings: &DiscordOAuthSettings,
+    code: &str,
+) -> Result<String, &'static str> {
+    let access_token = discord_oauth_client::exchange_code(settings, code)
+        .await
+        .map_err(|e| {
+            log::error!("❌ {}", e);
+            "Failed to exchange authorization code"
+        })?;
+
+    discord_oauth_client::fetch_current_user(settings, &access_token)
+        .await
+        .map(|user| user.id)
+        .map_err(|e| {
+            log::error!("❌ {}", e);
+            "Failed to read Discord user"
+        })
+}
@@ -1,9 +1,91 @@
 use crate::{
-    bot::{application::services::team_service, domain::table::TeamTable},
-    config::database::DBPool,
+    api::{
+        application::api_key_service::can_access_team,
+        domain::dto::{ApiKeyPrincipal, TeamError, UpdateTeamRequest},
+    },
+    bot::{
+        application::services::{role_service, team_service},
+        domain::{audit::AuditActor, model::Team, permission::Permission, table::TeamTable},
+    },
+    config::database::{self, DBPool},
 };
+use diesel::PgConnection;
 
-pub fn show_teams(pool: &DBPool, admin_discord_id: &str) -> Result<Vec<TeamTable>, String> {
+pub fn show_teams(
+    pool: &DBPool,
+    guild_id: &str,
+    admin_discord_id: &str,
+) -> Result<Vec<TeamTable>, String> {
     let mut conn = pool.get().expect("Failed to connect to database!");
-    team_service::show_team(&mut conn, admin_discord_id)
+    team_service::show_team(&mut conn, guild_id, admin_discord_id)
+}
+
+// Rename, archive or transfer a team on behalf of one of its owners.
+pub fn update_team(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    team_id: i32,
+    changes: &UpdateTeamRequest,
+) -> Result<(), TeamError> {
+    if changes.name.is_none() && changes.archived.is_none() && changes.owner_discord_id.is_none() {
+        return Err(TeamError::Invalid("Nothing to update".to_string()));
+    }
+
+    let mut conn = pool.get().expect("Failed to connect to database!");
+    owned_team(&mut conn, caller_discord_id, principal, team_id)?;
+    let actor = AuditActor::api(caller_discord_id);
+
+    // Either every change is applied or none is
+    database::transaction(&mut conn, |conn| {
+        if let Some(name) = &changes.name {
+            team_service::rename_team(conn, team_id, name, &actor).map_err(TeamError::Invalid)?;
+        }
+        if let Some(owner) = &changes.owner_discord_id {
+            team_service::transfer_team(conn, team_id, owner, &actor)
+                .map_err(TeamError::Invalid)?;
+        }
+        if let Some(archived) = changes.archived {
+            team_service::archive_team(conn, team_id, archived, &actor)
+                .map_err(TeamError::Internal)?;
+        }
+
+        Ok(())
+    })
+}
+
+// Delete a team on behalf of one of its owners.
+pub fn delete_team(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    team_id: i32,
+) -> Result<(), TeamError> {
+    let mut conn = pool.get().expect("Failed to connect to database!");
+    owned_team(&mut conn, caller_discord_id, principal, team_id)?;
+    team_service::delete_team(&mut conn, team_id, &AuditActor::api(caller_discord_id))
+        .map_err(TeamError::Internal)
+}
+
+// Only owners may manage a team, and team scoped API keys only their own team.
+fn owned_team(
+    conn: &mut PgConnection,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    team_id: i32,
+) -> Result<Team, TeamError> {
+    let team = team_service::find_team(conn, team_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(|| TeamError::NotFound("Team not found".to_string()))?;
+
+    if !can_access_team(conn, principal, &team.guild_id, &team.name) {
+        return Err(TeamError::Forbidden(
+            "API key is not allowed to access this team".to_string(),
+        ));
+    }
+
+    role_service::authorize(conn, team_id, caller_discord_id, Permission::ManageTeam)
+        .map_err(TeamError::Forbidden)?;
+
+    Ok(team)
 }
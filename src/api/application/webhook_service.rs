@@ -0,0 +1,199 @@
+use crate::{
+    api::{
+        application::{api_key_service::can_access_team, event_service::authorized_team},
+        domain::dto::{
+            ApiKeyPrincipal, CreateWebhookRequest, CreateWebhookResponse, TeamError,
+            WebhookDeliveryResponse, WebhookResponse,
+        },
+    },
+    bot::{
+        application::services::{role_service, team_service, webhook_service},
+        domain::{
+            audit::AuditActor,
+            model::{Webhook, WebhookDelivery},
+            permission::Permission,
+            webhook::DeliveryStatus,
+        },
+    },
+    config::database::DBPool,
+};
+use chrono::NaiveDateTime;
+use diesel::PgConnection;
+
+// Webhooks of a team with the number of pending and failed deliveries of each.
+pub fn list_webhooks(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+) -> Result<Vec<WebhookResponse>, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ManageTeam,
+    )?;
+
+    let webhooks =
+        webhook_service::get_team_webhooks(&mut conn, team_id).map_err(TeamError::Internal)?;
+    Ok(webhooks
+        .into_iter()
+        .map(|(webhook, pending, failed)| WebhookResponse {
+            id: webhook.id,
+            events: event_list(&webhook),
+            url: webhook.url,
+            created_by: webhook.created_by,
+            created_at: webhook.created_at.map(format_time).unwrap_or_default(),
+            pending,
+            failed,
+        })
+        .collect())
+}
+
+// Add a webhook to a team, its signing secret is only returned now.
+pub fn create_webhook(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    guild_id: &str,
+    team_name: &str,
+    request: &CreateWebhookRequest,
+) -> Result<CreateWebhookResponse, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let team_id = authorized_team(
+        &mut conn,
+        caller_discord_id,
+        principal,
+        guild_id,
+        team_name,
+        Permission::ManageTeam,
+    )?;
+
+    let webhook = webhook_service::add_webhook(
+        &mut conn,
+        team_id,
+        request.url.trim(),
+        &request.events.join(","),
+        &AuditActor::api(caller_discord_id),
+    )
+    .map_err(TeamError::Invalid)?;
+
+    Ok(CreateWebhookResponse {
+        id: webhook.id,
+        events: event_list(&webhook),
+        url: webhook.url,
+        secret: webhook.secret,
+    })
+}
+
+// Remove a webhook together with its delivery log.
+pub fn delete_webhook(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    webhook_id: i32,
+) -> Result<(), TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let webhook = authorized_webhook(&mut conn, caller_discord_id, principal, webhook_id)?;
+
+    webhook_service::remove_webhook(
+        &mut conn,
+        webhook.team_id,
+        webhook.id,
+        &AuditActor::api(caller_discord_id),
+    )
+    .map_err(TeamError::Internal)
+}
+
+// Latest deliveries of a webhook, newest first.
+pub fn list_deliveries(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    webhook_id: i32,
+) -> Result<Vec<WebhookDeliveryResponse>, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let webhook = authorized_webhook(&mut conn, caller_discord_id, principal, webhook_id)?;
+
+    let deliveries = webhook_service::get_deliveries(&mut conn, webhook.team_id, webhook.id)
+        .map_err(TeamError::Internal)?;
+    Ok(deliveries.into_iter().map(delivery_response).collect())
+}
+
+// Send a delivery again with the same payload, as a new delivery.
+pub fn replay_delivery(
+    pool: &DBPool,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    delivery_id: i32,
+) -> Result<WebhookDeliveryResponse, TeamError> {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let (delivery, _) = webhook_service::find_delivery(&mut conn, delivery_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(|| TeamError::NotFound(format!("Delivery {} not found", delivery_id)))?;
+    let webhook = authorized_webhook(&mut conn, caller_discord_id, principal, delivery.webhook_id)?;
+
+    webhook_service::replay_delivery(
+        &mut conn,
+        webhook.team_id,
+        delivery.id,
+        &AuditActor::api(caller_discord_id),
+    )
+    .map(delivery_response)
+    .map_err(TeamError::Internal)
+}
+
+// Resolve a webhook and check the caller may manage the team it belongs to.
+fn authorized_webhook(
+    conn: &mut PgConnection,
+    caller_discord_id: &str,
+    principal: Option<&ApiKeyPrincipal>,
+    webhook_id: i32,
+) -> Result<Webhook, TeamError> {
+    let webhook = webhook_service::find_webhook(conn, webhook_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(|| TeamError::NotFound(format!("Webhook {} not found", webhook_id)))?;
+    let team = team_service::find_team(conn, webhook.team_id)
+        .map_err(TeamError::Internal)?
+        .ok_or_else(|| TeamError::NotFound(format!("Webhook {} not found", webhook_id)))?;
+
+    if !can_access_team(conn, principal, &team.guild_id, &team.name) {
+        return Err(TeamError::Forbidden(
+            "API key is not allowed to access this team".to_string(),
+        ));
+    }
+    role_service::authorize(conn, team.id, caller_discord_id, Permission::ManageTeam)
+        .map_err(TeamError::Forbidden)?;
+
+    Ok(webhook)
+}
+
+fn delivery_response(delivery: WebhookDelivery) -> WebhookDeliveryResponse {
+    let pending = delivery.status == DeliveryStatus::Pending.as_str();
+    WebhookDeliveryResponse {
+        id: delivery.id,
+        webhook_id: delivery.webhook_id,
+        event: delivery.event_type,
+        status: delivery.status,
+        attempts: delivery.attempts,
+        response_status: delivery.response_status,
+        last_error: delivery.last_error,
+        next_attempt_at: pending.then(|| format_time(delivery.next_attempt_at)),
+        delivered_at: delivery.delivered_at.map(format_time),
+        replay_of: delivery.replay_of,
+        created_at: format_time(delivery.created_at),
+        payload: serde_json::from_str(&delivery.payload).unwrap_or_default(),
+    }
+}
+
+fn event_list(webhook: &Webhook) -> Vec<String> {
+    webhook.event_types.split(',').map(str::to_string).collect()
+}
+
+fn format_time(time: NaiveDateTime) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
@@ -1,12 +1,41 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
+use crate::bot::domain::live::{LiveUpdate, Presence};
+
 // DTO for user login claim
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
     pub exp: usize,
+    pub jti: String,
+}
+
+// claims of the signed OAuth2 `state` parameter
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OAuthState {
+    pub nonce: String,
+    pub exp: usize,
 }
 
+// query string Discord redirects back with
+#[derive(Debug, Deserialize)]
+pub struct OAuthCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+// (guild_id, team name) of the team an API key is restricted to
+pub type TeamScope = (String, String);
+
+// identity of a request authenticated with an API key
+#[derive(Debug, Clone)]
+pub struct ApiKeyPrincipal {
+    pub owner_discord_id: String,
+    pub read_only: bool,
+    pub team: Option<TeamScope>,
+}
 
 // login request
 #[derive(Debug, Deserialize)]
@@ -15,10 +44,343 @@ pub struct AuthRequest {
     pub password: String,
 }
 
+// reasons a login attempt is rejected
+#[derive(Debug)]
+pub enum LoginError {
+    InvalidCredentials,
+    TooManyAttempts {
+        retry_after: u64,
+    },
+    AccountLocked {
+        retry_after: u64,
+        discord_id: String,
+    },
+    Internal(&'static str),
+}
+
 // login response
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
     pub token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+}
+
+// refresh request
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+// logout request
+#[derive(Debug, Deserialize)]
+pub struct LogoutRequest {
+    pub refresh_token: Option<String>,
+}
+
+// API key creation request
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scope: String,
+    pub guild_id: Option<String>,
+    pub team_name: Option<String>,
+}
+
+// API key creation response, the only time the key is shown
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    pub id: i32,
+    pub key: String,
+}
+
+// PATCH /teams/{id} request, only the given fields are changed
+#[derive(Debug, Deserialize)]
+pub struct UpdateTeamRequest {
+    pub name: Option<String>,
+    pub archived: Option<bool>,
+    pub owner_discord_id: Option<String>,
+}
+
+// reasons a team or its members cannot be changed through the API
+#[derive(Debug)]
+pub enum TeamError {
+    NotFound(String),
+    Forbidden(String),
+    Invalid(String),
+    Internal(String),
+}
+
+// a database failure outside of the checks above, e.g. a transaction that could not commit
+impl From<String> for TeamError {
+    fn from(error: String) -> Self {
+        TeamError::Internal(error)
+    }
+}
+
+// POST /guilds/{guild_id}/members/{team_name} request
+#[derive(Debug, Deserialize)]
+pub struct AddMemberRequest {
+    pub discord_id: String,
+    pub username: String,
+}
+
+// PATCH /guilds/{guild_id}/members/{team_name}/{discord_id} request,
+// only the given fields are changed
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemberRequest {
+    pub username: Option<String>,
+    pub position: Option<String>,
+    pub active: Option<bool>,
+}
+
+// POST /guilds/{guild_id}/check-in-codes/{team_name} request
+#[derive(Debug, Deserialize)]
+pub struct StartCheckInCodeRequest {
+    pub title: Option<String>,
+    pub duration_minutes: i64,
+}
+
+// on-site event whose check-ins require its rotating code
+#[derive(Debug, Serialize)]
+pub struct CheckInCodeEventResponse {
+    pub event_id: i32,
+    pub title: String,
+    pub ends_at: String,
+}
+
+// POST /guilds/{guild_id}/events/{team_name} request, times are UTC
+#[derive(Debug, Deserialize)]
+pub struct CreateEventRequest {
+    pub title: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub recurrence_rule: Option<String>,
+}
+
+// GET /events/{id}/attendance query string, the date of the occurrence of a repeating event
+#[derive(Debug, Deserialize)]
+pub struct EventOccurrenceQuery {
+    pub date: Option<String>,
+}
+
+// POST /events/{id}/exceptions request, skips the occurrence on that date (YYYY-MM-DD)
+#[derive(Debug, Deserialize)]
+pub struct SkipOccurrenceRequest {
+    pub date: String,
+}
+
+// POST /guilds/{guild_id}/leave/{team_name} request, days are YYYY-MM-DD and inclusive
+#[derive(Debug, Deserialize)]
+pub struct CreateLeaveRequest {
+    pub starts_on: String,
+    pub ends_on: String,
+    pub leave_type: String,
+    pub reason: String,
+}
+
+// GET /guilds/{guild_id}/leave/{team_name} query string
+#[derive(Debug, Deserialize)]
+pub struct LeaveRequestQuery {
+    pub pending: Option<bool>,
+}
+
+// GET /guilds/{guild_id}/leave-balances/{team_name} query string, defaults to the current year
+#[derive(Debug, Deserialize)]
+pub struct LeaveBalanceQuery {
+    pub year: Option<i32>,
+}
+
+// PUT /guilds/{guild_id}/leave-balances/{team_name}/{discord_id} request
+#[derive(Debug, Deserialize)]
+pub struct SetLeaveAllowanceRequest {
+    pub year: i32,
+    pub leave_type: String,
+    pub days: i32,
+}
+
+// PATCH /attendance/{id} request, times are YYYY-MM-DD HH:MM[:SS] in UTC and an empty
+// check_out_time removes the check-out
+#[derive(Debug, Deserialize)]
+pub struct UpdateAttendanceRequest {
+    pub check_in_time: Option<String>,
+    pub check_out_time: Option<String>,
+    pub status: Option<String>,
+    pub reason: String,
+}
+
+// POST /guilds/{guild_id}/attendance/{team_name}/records request, adds a missing record
+#[derive(Debug, Deserialize)]
+pub struct AddAttendanceRequest {
+    pub discord_id: String,
+    pub date: String,
+    pub check_in_time: String,
+    pub check_out_time: Option<String>,
+    pub status: Option<String>,
+    pub reason: String,
+}
+
+// POST /guilds/{guild_id}/attendance/{team_name}/corrections request, either attendance_id to
+// correct one of the caller's records or date to add a missing one
+#[derive(Debug, Deserialize)]
+pub struct CreateCorrectionRequest {
+    pub attendance_id: Option<i32>,
+    pub date: Option<String>,
+    pub check_in_time: Option<String>,
+    pub check_out_time: Option<String>,
+    pub status: Option<String>,
+    pub reason: String,
+}
+
+// GET /guilds/{guild_id}/attendance/{team_name}/corrections query string
+#[derive(Debug, Deserialize)]
+pub struct CorrectionQuery {
+    pub pending: Option<bool>,
 }
 
+// POST /guilds/{guild_id}/holidays/{team_name} request
+#[derive(Debug, Deserialize)]
+pub struct CreateHolidayRequest {
+    pub date: String,
+    pub name: String,
+}
+
+// GET /guilds/{guild_id}/holidays/{team_name} query string, defaults to the current year
+#[derive(Debug, Deserialize)]
+pub struct HolidayQuery {
+    pub year: Option<i32>,
+}
+
+// POST /guilds/{guild_id}/holidays/{team_name}/import response
+#[derive(Debug, Serialize)]
+pub struct HolidayImportResponse {
+    pub added: usize,
+    pub existing: usize,
+    pub skipped: usize,
+}
+
+// GET /events/{id}/code response, the code to show on screen right now
+#[derive(Debug, Serialize)]
+pub struct CheckInCodeResponse {
+    pub code: String,
+    pub expires_in: i64,
+    pub ends_at: String,
+}
 
+// POST /guilds/{guild_id}/attendance/{team_name}/check-in request
+#[derive(Debug, Deserialize)]
+pub struct CheckInRequest {
+    pub code: String,
+}
+
+// GET /audit query string, `guild_id` and `team` go together, without them the events of every
+// team the caller owns and their own actions are listed
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    pub guild_id: Option<String>,
+    pub team: Option<String>,
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// GET /audit response item, `details` holds the changed values as JSON
+#[derive(Debug, Serialize)]
+pub struct AuditEventResponse {
+    pub id: i32,
+    pub actor_id: String,
+    pub source: String,
+    pub action: String,
+    pub team_id: Option<i32>,
+    pub team_name: Option<String>,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub target: Option<String>,
+    pub details: serde_json::Value,
+    pub created_at: String,
+}
+
+// POST /guilds/{guild_id}/webhooks/{team_name} request, `events` lists event types or is ["all"]
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub events: Vec<String>,
+}
+
+// POST /guilds/{guild_id}/webhooks/{team_name} response, the secret is only ever returned here
+#[derive(Debug, Serialize)]
+pub struct CreateWebhookResponse {
+    pub id: i32,
+    pub url: String,
+    pub events: Vec<String>,
+    pub secret: String,
+}
+
+// GET /guilds/{guild_id}/webhooks/{team_name} response item
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    pub id: i32,
+    pub url: String,
+    pub events: Vec<String>,
+    pub created_by: String,
+    pub created_at: String,
+    pub pending: i64,
+    pub failed: i64,
+}
+
+// GET /webhooks/{id}/deliveries response item, `payload` is the body sent to the receiver
+#[derive(Debug, Serialize)]
+pub struct WebhookDeliveryResponse {
+    pub id: i32,
+    pub webhook_id: i32,
+    pub event: String,
+    pub status: String,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<String>,
+    pub delivered_at: Option<String>,
+    pub replay_of: Option<i32>,
+    pub created_at: String,
+    pub payload: serde_json::Value,
+}
+
+// GET /teams/{id}/presence response, the members with an open check-in, oldest first
+#[derive(Debug, Serialize)]
+pub struct PresenceResponse {
+    pub team_id: i32,
+    pub members: Vec<Presence>,
+    pub at: NaiveDateTime,
+}
+
+// message a client sends over the `/ws` WebSocket, e.g. `{"type":"subscribe","team_id":1}`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsClientMessage {
+    Subscribe { team_id: i32 },
+    Unsubscribe { team_id: i32 },
+    Ping,
+}
+
+// message the `/ws` WebSocket sends to a client, tagged by `type` like the client messages
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsServerMessage {
+    Snapshot {
+        team_id: i32,
+        members: Vec<Presence>,
+        at: NaiveDateTime,
+    },
+    Event(LiveUpdate),
+    Unsubscribed {
+        team_id: i32,
+    },
+    Pong,
+    Error {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        team_id: Option<i32>,
+    },
+}
@@ -4,15 +4,66 @@ use diesel::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::schema::users;
+use crate::schema::{api_keys, refresh_tokens, revoked_tokens, users};
 
 #[derive(Debug, Serialize, Deserialize, Queryable, Insertable, Selectable)]
 #[diesel(table_name = users)]
 pub struct User {
     pub id: i32,
     pub username: String,
+    pub discord_id: String,
     pub password_hash: String,
     pub is_admin: Option<bool>,
     pub created_at: Option<chrono::NaiveDateTime>,
     pub updated_at: Option<chrono::NaiveDateTime>,
+    pub failed_login_attempts: i32,
+    pub last_failed_login_at: Option<chrono::NaiveDateTime>,
+    pub locked_until: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Queryable, Selectable)]
+#[diesel(table_name = refresh_tokens)]
+pub struct RefreshToken {
+    pub id: i32,
+    pub user_id: i32,
+    pub expires_at: chrono::NaiveDateTime,
+    pub revoked_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = refresh_tokens)]
+pub struct NewRefreshToken {
+    pub user_id: i32,
+    pub token_hash: String,
+    pub access_jti: String,
+    pub expires_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = revoked_tokens)]
+pub struct NewRevokedToken {
+    pub jti: String,
+    pub expires_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Queryable, Selectable)]
+#[diesel(table_name = api_keys)]
+pub struct ApiKey {
+    pub id: i32,
+    pub name: String,
+    pub key_prefix: String,
+    pub scope: String,
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+    pub revoked_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = api_keys)]
+pub struct NewApiKey {
+    pub user_id: i32,
+    pub team_id: Option<i32>,
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub scope: String,
 }
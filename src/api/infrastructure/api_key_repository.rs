@@ -0,0 +1,128 @@
+use crate::api::domain::dto::TeamScope;
+use crate::api::domain::model::{ApiKey, NewApiKey};
+use chrono::Utc;
+use diesel::prelude::*;
+
+/// Stores a new (hashed) API key.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_key` - The API key row to insert.
+///
+/// # Returns
+/// Returns the ID of the created API key.
+///
+/// # Errors
+/// Returns an error if the API key cannot be inserted into the database.
+pub fn insert_api_key(conn: &mut PgConnection, new_key: &NewApiKey) -> Result<i32, String> {
+    use crate::schema::api_keys::dsl::{api_keys, id};
+
+    diesel::insert_into(api_keys)
+        .values(new_key)
+        .returning(id)
+        .get_result(conn)
+        .map_err(|e| format!("Failed to create API key: {}", e))
+}
+
+/// Retrieves all API keys of a user together with the server ID and name of the team they are
+/// restricted to.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `owner_id` - The ID of the user owning the keys.
+///
+/// # Errors
+/// Returns an error if the API keys cannot be fetched from the database.
+pub fn get_user_api_keys(
+    conn: &mut PgConnection,
+    owner_id: i32,
+) -> Result<Vec<(ApiKey, Option<TeamScope>)>, String> {
+    use crate::schema::{api_keys, teams};
+
+    api_keys::table
+        .left_join(teams::table)
+        .filter(api_keys::user_id.eq(owner_id))
+        .order(api_keys::id)
+        .select((ApiKey::as_select(), (teams::guild_id, teams::name).nullable()))
+        .load::<(ApiKey, Option<TeamScope>)>(conn)
+        .map_err(|e| format!("Failed to fetch API keys: {}", e))
+}
+
+/// Finds a non-revoked API key by the hash of its value.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `hash` - The SHA-256 hash of the API key.
+///
+/// # Returns
+/// Returns the API key, the server ID and name of the team it is restricted to and the Discord ID
+/// of its owner.
+pub fn find_active_api_key(
+    conn: &mut PgConnection,
+    hash: &str,
+) -> Option<(ApiKey, Option<TeamScope>, String)> {
+    use crate::schema::{api_keys, teams, users};
+
+    api_keys::table
+        .inner_join(users::table)
+        .left_join(teams::table)
+        .filter(api_keys::key_hash.eq(hash))
+        .filter(api_keys::revoked_at.is_null())
+        .select((
+            ApiKey::as_select(),
+            (teams::guild_id, teams::name).nullable(),
+            users::discord_id,
+        ))
+        .first(conn)
+        .ok()
+}
+
+/// Records that an API key has just been used.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `key_id` - The ID of the API key.
+///
+/// # Errors
+/// Returns an error if the API key cannot be updated in the database.
+pub fn touch_api_key(conn: &mut PgConnection, key_id: i32) -> Result<(), String> {
+    use crate::schema::api_keys::dsl::*;
+
+    diesel::update(api_keys.filter(id.eq(key_id)))
+        .set(last_used_at.eq(Utc::now().naive_utc()))
+        .execute(conn)
+        .map_err(|e| format!("Failed to update API key: {}", e))?;
+
+    Ok(())
+}
+
+/// Revokes an API key owned by a user.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `owner_id` - The ID of the user owning the key.
+/// * `key_id` - The ID of the API key to revoke.
+///
+/// # Errors
+/// Returns an error if:
+/// - The key does not exist, belongs to another user or is already revoked.
+/// - The key cannot be updated in the database.
+pub fn revoke_api_key(conn: &mut PgConnection, owner_id: i32, key_id: i32) -> Result<(), String> {
+    use crate::schema::api_keys::dsl::*;
+
+    let revoked = diesel::update(
+        api_keys
+            .filter(id.eq(key_id))
+            .filter(user_id.eq(owner_id))
+            .filter(revoked_at.is_null()),
+    )
+    .set(revoked_at.eq(Utc::now().naive_utc()))
+    .execute(conn)
+    .map_err(|e| format!("Failed to revoke API key: {}", e))?;
+
+    if revoked == 0 {
+        return Err(format!("No active API key with ID {} found!", key_id));
+    }
+
+    Ok(())
+}
@@ -1,4 +1,6 @@
 use crate::{api::domain::model::User, config::database::DBPool};
+use chrono::{NaiveDateTime, Utc};
+use diesel::PgConnection;
 use diesel::RunQueryDsl;
 use diesel::SelectableHelper;
 use diesel::{ExpressionMethods, QueryDsl};
@@ -14,3 +16,75 @@ pub fn find_by_username(pool: &DBPool, username: &str) -> Option<User> {
         .first(&mut conn)
         .ok()
 }
+
+pub fn find_by_id(conn: &mut PgConnection, user_id: i32) -> Option<User> {
+    use crate::schema::users::dsl::{id, users};
+
+    users
+        .filter(id.eq(user_id))
+        .select(User::as_select())
+        .first(conn)
+        .ok()
+}
+
+// Increment the failed login counter and return its new value.
+pub fn record_failed_login(pool: &DBPool, user_id: i32) -> Result<i32, String> {
+    use crate::schema::users::dsl::{failed_login_attempts, id, last_failed_login_at, users};
+
+    let mut conn = pool.get().map_err(|e| format!("Failed to get DB connection: {}", e))?;
+
+    diesel::update(users.filter(id.eq(user_id)))
+        .set((
+            failed_login_attempts.eq(failed_login_attempts + 1),
+            last_failed_login_at.eq(Utc::now().naive_utc()),
+        ))
+        .returning(failed_login_attempts)
+        .get_result(&mut conn)
+        .map_err(|e| format!("Failed to record failed login: {}", e))
+}
+
+// Lock the account until the given time; the counter starts over once the lock expires.
+pub fn lock_account(pool: &DBPool, user_id: i32, until: NaiveDateTime) -> Result<(), String> {
+    use crate::schema::users::dsl::{failed_login_attempts, id, locked_until, users};
+
+    let mut conn = pool.get().map_err(|e| format!("Failed to get DB connection: {}", e))?;
+
+    diesel::update(users.filter(id.eq(user_id)))
+        .set((failed_login_attempts.eq(0), locked_until.eq(until)))
+        .execute(&mut conn)
+        .map_err(|e| format!("Failed to lock account: {}", e))?;
+
+    Ok(())
+}
+
+pub fn reset_failed_logins(pool: &DBPool, user_id: i32) -> Result<(), String> {
+    use crate::schema::users::dsl::{
+        failed_login_attempts, id, last_failed_login_at, locked_until, users,
+    };
+
+    let mut conn = pool.get().map_err(|e| format!("Failed to get DB connection: {}", e))?;
+
+    diesel::update(users.filter(id.eq(user_id)))
+        .set((
+            failed_login_attempts.eq(0),
+            last_failed_login_at.eq(None::<NaiveDateTime>),
+            locked_until.eq(None::<NaiveDateTime>),
+        ))
+        .execute(&mut conn)
+        .map_err(|e| format!("Failed to reset failed logins: {}", e))?;
+
+    Ok(())
+}
+
+pub fn find_by_discord_id(pool: &DBPool, user_discord_id: &str) -> Option<User> {
+    use crate::schema::users::dsl::{discord_id, id, users};
+
+    let mut conn = pool.get().expect("Failed to get DB connection!");
+
+    users
+        .filter(discord_id.eq(user_discord_id))
+        .order(id)
+        .select(User::as_select())
+        .first(&mut conn)
+        .ok()
+}
@@ -0,0 +1,18 @@
+use serenity::{http::Http, model::id::UserId};
+
+// Send a direct message to a Discord user from the API side.
+pub async fn send_dm(http: &Http, discord_id: &str, message: &str) {
+    let Ok(user_id) = discord_id.parse::<u64>() else {
+        log::error!("❌ Invalid Discord ID: {}", discord_id);
+        return;
+    };
+
+    match UserId::new(user_id).create_dm_channel(http).await {
+        Ok(channel) => {
+            if let Err(e) = channel.say(http, message).await {
+                log::error!("❌ Failed to send DM: {:?}", e);
+            }
+        }
+        Err(e) => log::error!("❌ Failed to open DM channel: {:?}", e),
+    }
+}
@@ -0,0 +1,55 @@
+use serde::Deserialize;
+
+use crate::config::settings::DiscordOAuthSettings;
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiscordUser {
+    pub id: String,
+}
+
+// Exchange an authorization code for a Discord access token.
+pub async fn exchange_code(settings: &DiscordOAuthSettings, code: &str) -> Result<String, String> {
+    let response = reqwest::Client::new()
+        .post(&settings.token_url)
+        .form(&[
+            ("client_id", settings.client_id.as_str()),
+            ("client_secret", settings.client_secret.as_str()),
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", settings.redirect_uri.as_str()),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Discord token endpoint: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Discord rejected the authorization code: {}", e))?;
+
+    response
+        .json::<TokenResponse>()
+        .await
+        .map(|token| token.access_token)
+        .map_err(|e| format!("Invalid Discord token response: {}", e))
+}
+
+// Read the Discord user the access token belongs to.
+pub async fn fetch_current_user(
+    settings: &DiscordOAuthSettings,
+    access_token: &str,
+) -> Result<DiscordUser, String> {
+    reqwest::Client::new()
+        .get(format!("{}/users/@me", settings.api_base_url.trim_end_matches('/')))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Discord API: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Discord rejected the access token: {}", e))?
+        .json::<DiscordUser>()
+        .await
+        .map_err(|e| format!("Invalid Discord user response: {}", e))
+}
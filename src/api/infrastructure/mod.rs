@@ -1,2 +1,6 @@
 pub mod auth_repository;
+pub mod token_repository;
+pub mod api_key_repository;
+pub mod discord_notifier;
+pub mod discord_oauth_client;
 
@@ -0,0 +1,182 @@
+use crate::api::domain::model::{NewRefreshToken, NewRevokedToken, RefreshToken};
+use chrono::{NaiveDateTime, Utc};
+use diesel::dsl::exists;
+use diesel::prelude::*;
+
+/// Stores a new (hashed) refresh token.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_token` - The refresh token row to insert.
+///
+/// # Errors
+/// Returns an error if the refresh token cannot be inserted into the database.
+pub fn insert_refresh_token(
+    conn: &mut PgConnection,
+    new_token: &NewRefreshToken,
+) -> Result<(), String> {
+    use crate::schema::refresh_tokens::dsl::refresh_tokens;
+
+    diesel::insert_into(refresh_tokens)
+        .values(new_token)
+        .execute(conn)
+        .map_err(|e| format!("Failed to store refresh token: {}", e))?;
+
+    Ok(())
+}
+
+/// Finds a refresh token by the hash of its value.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `hash` - The SHA-256 hash of the refresh token.
+///
+/// # Returns
+/// Returns the refresh token row, revoked or not, if it exists.
+pub fn find_refresh_token(conn: &mut PgConnection, hash: &str) -> Option<RefreshToken> {
+    use crate::schema::refresh_tokens::dsl::{refresh_tokens, token_hash};
+
+    refresh_tokens
+        .filter(token_hash.eq(hash))
+        .select(RefreshToken::as_select())
+        .first(conn)
+        .ok()
+}
+
+/// Revokes a refresh token that is still valid, so it can be exchanged only once.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `hash` - The SHA-256 hash of the refresh token.
+///
+/// # Returns
+/// Returns the refresh token row, or `None` if it does not exist, is expired or was revoked
+/// already, also by a concurrent request.
+///
+/// # Errors
+/// Returns an error if the refresh token cannot be updated in the database.
+pub fn claim_refresh_token(
+    conn: &mut PgConnection,
+    hash: &str,
+) -> Result<Option<RefreshToken>, String> {
+    use crate::schema::refresh_tokens::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    diesel::update(
+        refresh_tokens
+            .filter(token_hash.eq(hash))
+            .filter(revoked_at.is_null())
+            .filter(expires_at.gt(now)),
+    )
+    .set(revoked_at.eq(now))
+    .returning(RefreshToken::as_returning())
+    .get_result(conn)
+    .optional()
+    .map_err(|e| format!("Failed to revoke refresh token: {}", e))
+}
+
+/// Marks a single refresh token as revoked.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `token_id` - The ID of the refresh token to revoke.
+///
+/// # Errors
+/// Returns an error if the refresh token cannot be updated in the database.
+pub fn revoke_refresh_token(conn: &mut PgConnection, token_id: i32) -> Result<(), String> {
+    use crate::schema::refresh_tokens::dsl::*;
+
+    diesel::update(refresh_tokens.filter(id.eq(token_id)))
+        .set(revoked_at.eq(Utc::now().naive_utc()))
+        .execute(conn)
+        .map_err(|e| format!("Failed to revoke refresh token: {}", e))?;
+
+    Ok(())
+}
+
+/// Revokes every active refresh token of a user.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `owner_id` - The ID of the user whose sessions are being revoked.
+///
+/// # Returns
+/// Returns the access token IDs (`jti`) that were issued alongside the revoked refresh tokens.
+///
+/// # Errors
+/// Returns an error if the refresh tokens cannot be updated in the database.
+pub fn revoke_user_refresh_tokens(
+    conn: &mut PgConnection,
+    owner_id: i32,
+) -> Result<Vec<String>, String> {
+    use crate::schema::refresh_tokens::dsl::*;
+
+    diesel::update(
+        refresh_tokens
+            .filter(user_id.eq(owner_id))
+            .filter(revoked_at.is_null()),
+    )
+    .set(revoked_at.eq(Utc::now().naive_utc()))
+    .returning(access_jti)
+    .get_results::<String>(conn)
+    .map_err(|e| format!("Failed to revoke sessions: {}", e))
+}
+
+/// Adds an access token ID to the revocation list.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `token_jti` - The `jti` claim of the access token.
+/// * `token_expires_at` - When the access token expires; the entry is useless afterwards.
+///
+/// # Errors
+/// Returns an error if the entry cannot be inserted into the database.
+pub fn revoke_jti(
+    conn: &mut PgConnection,
+    token_jti: &str,
+    token_expires_at: NaiveDateTime,
+) -> Result<(), String> {
+    use crate::schema::revoked_tokens::dsl::revoked_tokens;
+
+    diesel::insert_into(revoked_tokens)
+        .values(NewRevokedToken {
+            jti: token_jti.to_string(),
+            expires_at: token_expires_at,
+        })
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map_err(|e| format!("Failed to revoke token: {}", e))?;
+
+    Ok(())
+}
+
+/// Checks whether an access token ID is on the revocation list.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `token_jti` - The `jti` claim of the access token.
+///
+/// # Errors
+/// Returns an error if the revocation list cannot be queried.
+pub fn is_jti_revoked(conn: &mut PgConnection, token_jti: &str) -> Result<bool, String> {
+    use crate::schema::revoked_tokens::dsl::{jti, revoked_tokens};
+
+    diesel::select(exists(revoked_tokens.filter(jti.eq(token_jti))))
+        .get_result(conn)
+        .map_err(|e| format!("Failed to check token revocation: {}", e))
+}
+
+/// Removes revocation entries for access tokens that have already expired.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Errors
+/// Returns an error if the entries cannot be deleted.
+pub fn purge_expired_revocations(conn: &mut PgConnection) -> Result<usize, String> {
+    use crate::schema::revoked_tokens::dsl::{expires_at, revoked_tokens};
+
+    diesel::delete(revoked_tokens.filter(expires_at.lt(Utc::now().naive_utc())))
+        .execute(conn)
+        .map_err(|e| format!("Failed to purge revoked tokens: {}", e))
+}
@@ -1,10 +1,16 @@
-use crate::config::database::establish_connection;
 use crate::config::database::DBPool;
+use serenity::all::Interaction;
 use serenity::all::Message;
 use serenity::all::Ready;
+use serenity::all::{GuildId, GuildMemberUpdateEvent, Member, User, VoiceState};
 use serenity::{async_trait, prelude::*};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-use crate::bot::adapters::handler::{attendance, auth, members, teams};
+use crate::bot::adapters::handler::{
+    absence, api_keys, attendance, audit, auth, corrections, events, holidays, leave, members,
+    presence, reminders, role_sync, roles, rollcall, teams, voice, webhooks,
+};
 
 use crate::config::constant::HELP_MESSAGES;
 
@@ -13,6 +19,8 @@ use crate::config::constant::HELP_MESSAGES;
 /// This struct is responsible for handling events such as messages and connection readiness.
 pub struct Handler {
     db_pool: DBPool,
+    pending_check_outs: Arc<voice::PendingCheckOuts>,
+    background_jobs_started: AtomicBool,
 }
 
 impl Handler {
@@ -21,7 +29,11 @@ impl Handler {
     /// # Arguments
     /// * `db_pool` - A connection pool to the database.
     pub fn new(db_pool: DBPool) -> Self {
-        Self { db_pool }
+        Self {
+            db_pool,
+            pending_check_outs: Arc::new(voice::PendingCheckOuts::default()),
+            background_jobs_started: AtomicBool::new(false),
+        }
     }
 }
 
@@ -33,19 +45,18 @@ impl EventHandler for Handler {
     /// * `ctx` - The context of the event.
     /// * `msg` - The message that triggered the event.
     async fn message(&self, ctx: Context, msg: Message) {
-        // Establish a database connection.
-        let conn = establish_connection();
-
         // Show help message if the command is "!AB help".
         if msg.content.starts_with("!AB help") {
-            if let Err(e) = msg.channel_id.say(&ctx.http, HELP_MESSAGES).await {
-                println!("Error sending message: {e:?}");
+            for message in HELP_MESSAGES {
+                if let Err(e) = msg.channel_id.say(&ctx.http, *message).await {
+                    println!("Error sending message: {e:?}");
+                }
             }
             return;
         }
 
         // Get a database connection from the pool.
-        let mut db_conn = match conn.get() {
+        let mut db_conn = match self.db_pool.get() {
             Ok(conn) => conn,
             Err(_) => {
                 if let Err(e) = msg
@@ -66,25 +77,178 @@ impl EventHandler for Handler {
             attendance::handle_check_out(&ctx, &msg, &mut db_conn).await;
         } else if msg.content.starts_with("!AB register") {
             auth::handle_register(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB revoke_sessions") {
+            auth::handle_revoke_sessions(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB apikey") {
+            api_keys::handle_api_key(&ctx, &msg, &mut db_conn).await;
         } else if msg.content.starts_with("!AB create_team") {
             teams::handle_create_team(&ctx, &msg, &mut db_conn).await;
         } else if msg.content.starts_with("!AB add_member") {
             teams::handle_add_member(&ctx, &msg, &mut db_conn).await;
-        } else if msg.content.starts_with("!AB show_members") {
-            members::handle_show_members(&ctx, &msg, &mut db_conn).await;
         } else if msg.content.starts_with("!AB show_members_attendance") {
             attendance::handle_show_member_attendance(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB show_members") {
+            members::handle_show_members(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB remove_member") {
+            members::handle_remove_member(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB set_position") {
+            members::handle_set_position(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB rename_member") {
+            members::handle_rename_member(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB deactivate_member") {
+            members::handle_deactivate_member(&ctx, &msg, &mut db_conn).await;
         } else if msg.content.starts_with("!AB show_team") {
             teams::handle_show_team(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB rename_team") {
+            teams::handle_rename_team(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB archive_team") {
+            teams::handle_archive_team(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB transfer_team") {
+            teams::handle_transfer_team(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB delete_team") {
+            teams::handle_delete_team(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB grant_role") {
+            roles::handle_grant_role(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB revoke_role") {
+            roles::handle_revoke_role(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB show_roles") {
+            roles::handle_show_roles(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB link_role") {
+            role_sync::handle_link_role(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB unlink_role") {
+            role_sync::handle_unlink_role(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB sync_team") {
+            role_sync::handle_sync_team(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB link_voice") {
+            voice::handle_link_voice(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB unlink_voice") {
+            voice::handle_unlink_voice(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB voice_settings") {
+            voice::handle_voice_settings(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB rollcall") {
+            rollcall::handle_rollcall(&ctx, &msg, &mut db_conn, &self.db_pool).await;
+        } else if msg.content.starts_with("!AB checkin_code") {
+            events::handle_check_in_code(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB event") {
+            events::handle_event(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB leave") {
+            leave::handle_leave(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB holiday") {
+            holidays::handle_holiday(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB schedule") {
+            absence::handle_schedule(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB reminders") {
+            reminders::handle_reminders(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB attendance") {
+            corrections::handle_attendance(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB audit") {
+            audit::handle_audit(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB webhook") {
+            webhooks::handle_webhook(&ctx, &msg, &mut db_conn).await;
+        } else if msg.content.starts_with("!AB whos_in") {
+            presence::handle_whos_in(&ctx, &msg, &mut db_conn).await;
+        }
+    }
+
+    /// Handles button clicks on messages sent by the bot.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of the event.
+    /// * `interaction` - The interaction that triggered the event.
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Component(component) = interaction else {
+            return;
+        };
+
+        let custom_id = component.data.custom_id.as_str();
+        if !custom_id.starts_with(teams::DELETE_TEAM_PREFIX)
+            && !custom_id.starts_with(rollcall::ROLLCALL_PREFIX)
+            && !custom_id.starts_with(leave::LEAVE_PREFIX)
+        {
+            return;
+        }
+
+        let mut db_conn = match self.db_pool.get() {
+            Ok(conn) => conn,
+            Err(e) => {
+                println!("Failed to get DB connection: {e:?}");
+                return;
+            }
+        };
+
+        if custom_id.starts_with(teams::DELETE_TEAM_PREFIX) {
+            teams::handle_delete_team_confirmation(&ctx, &component, &mut db_conn).await;
+        } else if custom_id.starts_with(leave::LEAVE_PREFIX) {
+            leave::handle_leave_review(&ctx, &component, &mut db_conn).await;
+        } else {
+            rollcall::handle_rollcall_answer(&ctx, &component, &mut db_conn).await;
         }
     }
 
+    /// Keeps the members of role bound teams in sync when a server member changes.
+    ///
+    /// # Arguments
+    /// * `_ctx` - The context of the event (unused in this implementation).
+    /// * `event` - The member update event.
+    async fn guild_member_update(
+        &self,
+        _ctx: Context,
+        _old: Option<Member>,
+        _new: Option<Member>,
+        event: GuildMemberUpdateEvent,
+    ) {
+        match self.db_pool.get() {
+            Ok(mut db_conn) => role_sync::handle_member_update(&mut db_conn, &event),
+            Err(e) => println!("Failed to get DB connection: {e:?}"),
+        }
+    }
+
+    /// Deactivates a user in the role bound teams of a server they left.
+    ///
+    /// # Arguments
+    /// * `_ctx` - The context of the event (unused in this implementation).
+    /// * `guild_id` - The ID of the server the user left.
+    /// * `user` - The user who left.
+    async fn guild_member_removal(
+        &self,
+        _ctx: Context,
+        guild_id: GuildId,
+        user: User,
+        _member: Option<Member>,
+    ) {
+        match self.db_pool.get() {
+            Ok(mut db_conn) => role_sync::handle_member_removal(&mut db_conn, guild_id, &user),
+            Err(e) => println!("Failed to get DB connection: {e:?}"),
+        }
+    }
+
+    /// Checks members in and out when they join or leave the voice channels of their teams.
+    ///
+    /// # Arguments
+    /// * `_ctx` - The context of the event (unused in this implementation).
+    /// * `new` - The new voice state of the member.
+    async fn voice_state_update(&self, _ctx: Context, _old: Option<VoiceState>, new: VoiceState) {
+        voice::handle_voice_state_update(&self.db_pool, &self.pending_check_outs, &new).await;
+    }
+
     /// Handles the bot's readiness event.
     ///
     /// # Arguments
-    /// * `_` - The context of the event (unused in this implementation).
+    /// * `ctx` - The context of the event.
     /// * `ready` - The readiness event data.
-    async fn ready(&self, _: Context, ready: Ready) {
+    ///
+    /// # Behavior
+    /// - Resumes the roll-calls that were still open when the bot stopped.
+    /// - Starts the daily absence check, the reminders, the presence boards and the removal of
+    ///   expired audit events, once even if the bot reconnects.
+    async fn ready(&self, ctx: Context, ready: Ready) {
         println!("{} is connected!", ready.user.name);
+        rollcall::resume_rollcalls(ctx.http.clone(), &self.db_pool);
+        if !self.background_jobs_started.swap(true, Ordering::SeqCst) {
+            absence::start_absence_check(ctx.http.clone(), self.db_pool.clone());
+            reminders::start_reminders(ctx.http.clone(), self.db_pool.clone());
+            presence::start_presence_boards(ctx.http.clone(), self.db_pool.clone());
+            audit::start_audit_purge(self.db_pool.clone());
+        }
     }
 }
@@ -0,0 +1,273 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use diesel::PgConnection;
+use serenity::all::{ChannelId, Http};
+use serenity::client::Context;
+use serenity::model::channel::Message;
+
+use crate::bot::adapters::handler::args::{parse_channel, parse_time};
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::adapters::handler::events::authorized_team;
+use crate::bot::application::services::absence_service::{self, AbsenceReport};
+use crate::bot::application::services::team_service;
+use crate::bot::domain::permission::Permission;
+use crate::config::constant::ABSENCE_CHECK_INTERVAL_SECONDS;
+use crate::config::database::DBPool;
+
+/// Handles the `!AB schedule` command.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - `schedule <team>` shows the daily schedule of the team.
+/// - `schedule <team> <HH:MM> <grace_minutes> [#channel]` sets the start (UTC) of the working
+///   day, how long after it members without a check-in are recorded as absent and the channel
+///   the daily no-show report is posted to. Requires the owner role.
+/// - `schedule <team> off` stops the absence check. Requires the owner role.
+pub async fn handle_schedule(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let usage = "Usage: !AB schedule <team_name> [<HH:MM> <grace_minutes> [#channel] | off]";
+
+    match args.len() {
+        3 => show_schedule(ctx, msg, db_conn, args[2]).await,
+        4 if args[3] == "off" => clear_schedule(ctx, msg, db_conn, args[2]).await,
+        5..=6 => {
+            set_schedule(
+                ctx,
+                msg,
+                db_conn,
+                args[2],
+                args[3],
+                args[4],
+                args.get(5).copied(),
+            )
+            .await
+        }
+        _ => send_message(ctx, &msg.channel_id, usage).await,
+    }
+}
+
+/// Shows the daily schedule of a team.
+async fn show_schedule(ctx: &Context, msg: &Message, db_conn: &mut PgConnection, team_name: &str) {
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ViewMembers).await
+    else {
+        return;
+    };
+
+    let team = match team_service::find_team(db_conn, team_id) {
+        Ok(Some(team)) => team,
+        Ok(None) => {
+            send_message(ctx, &msg.channel_id, "Team not found").await;
+            return;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
+    let reply = match team.schedule_start {
+        Some(start) => format!(
+            "🕘 Schedule of '{}'\nStart: {} UTC (Monday to Friday)\nGrace period: {} minutes\nNo-show report: {}",
+            team.name,
+            start.format("%H:%M"),
+            team.schedule_grace_minutes,
+            team.report_channel_id
+                .map(|channel| format!("<#{}>", channel))
+                .unwrap_or_else(|| "not posted".to_string())
+        ),
+        None => format!("'{}' has no schedule, absences are not recorded.", team.name),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Sets the daily schedule of a team.
+async fn set_schedule(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    start: &str,
+    grace_minutes: &str,
+    channel: Option<&str>,
+) {
+    let (Some(start), Ok(grace_minutes)) = (parse_time(start), grace_minutes.parse::<i32>()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "The start must be given as HH:MM (UTC) and the grace period in minutes.",
+        )
+        .await;
+        return;
+    };
+    let channel = match channel.map(parse_channel) {
+        Some(Some(channel)) => Some(channel.to_string()),
+        Some(None) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                "The report channel must be a #channel.",
+            )
+            .await;
+            return;
+        }
+        None => None,
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, team_name, Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    let reply = match absence_service::set_schedule(
+        db_conn,
+        team_id,
+        Some(start),
+        grace_minutes,
+        channel.as_deref(),
+        &message_actor(msg),
+    ) {
+        Ok(_) => format!(
+            "Members of '{}' without a check-in by {} UTC on working days will be recorded as absent.",
+            team_name,
+            (start + chrono::Duration::minutes(grace_minutes.into())).format("%H:%M")
+        ),
+        Err(e) => format!("Failed to set schedule: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Removes the daily schedule of a team, which stops the absence check.
+async fn clear_schedule(ctx: &Context, msg: &Message, db_conn: &mut PgConnection, team_name: &str) {
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, team_name, Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    let reply = match absence_service::set_schedule(db_conn, team_id, None, 0, None, &actor) {
+        Ok(_) => format!("Absences of '{}' are no longer recorded.", team_name),
+        Err(e) => format!("Failed to clear schedule: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Starts the job recording the members who did not check in as absent.
+///
+/// # Arguments
+/// * `http` - The Discord HTTP client used to post the no-show reports.
+/// * `db_pool` - The connection pool to the database.
+///
+/// # Behavior
+/// - Every `ABSENCE_CHECK_INTERVAL_SECONDS`, checks the teams whose start plus grace period
+///   has passed today and posts their no-show report to their report channel, if any.
+pub fn start_absence_check(http: Arc<Http>, db_pool: DBPool) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(ABSENCE_CHECK_INTERVAL_SECONDS));
+        loop {
+            interval.tick().await;
+
+            let reports = match db_pool.get() {
+                Ok(mut db_conn) => check_absences(&mut db_conn),
+                Err(e) => {
+                    println!("Failed to get DB connection: {e:?}");
+                    continue;
+                }
+            };
+            for report in reports {
+                post_report(&http, &report).await;
+            }
+        }
+    });
+}
+
+/// Records the absences of the teams that are due and returns their reports.
+fn check_absences(db_conn: &mut PgConnection) -> Vec<AbsenceReport> {
+    let now = Utc::now().naive_utc();
+    let teams = match absence_service::get_due_teams(db_conn, now) {
+        Ok(teams) => teams,
+        Err(e) => {
+            println!("Error checking absences: {e}");
+            return Vec::new();
+        }
+    };
+
+    let mut reports = Vec::new();
+    for team in teams {
+        match absence_service::check_team_absences(db_conn, &team, now.date()) {
+            Ok(Some(report)) => reports.push(report),
+            Ok(None) => {}
+            Err(e) => println!("Error checking absences of '{}': {e}", team.name),
+        }
+    }
+    reports
+}
+
+/// Posts the no-show report of a team to its report channel.
+async fn post_report(http: &Http, report: &AbsenceReport) {
+    let Some(Ok(channel_id)) = report
+        .channel_id
+        .as_deref()
+        .map(|channel_id| channel_id.parse::<ChannelId>())
+    else {
+        return;
+    };
+
+    if let Err(e) = channel_id.say(http, format_report(report)).await {
+        println!(
+            "Error posting the no-show report of '{}': {e:?}",
+            report.team_name
+        );
+    }
+}
+
+/// Formats the no-show report of a team.
+fn format_report(report: &AbsenceReport) -> String {
+    let mut message = format!(
+        "📋 No-shows of '{}' on {} ({})",
+        report.team_name,
+        report.date,
+        report.date.format("%A")
+    );
+    if report.absent.is_empty() {
+        message.push_str("\n✅ Everyone checked in.");
+    } else {
+        message.push_str(&format!(
+            "\n❌ Absent ({}): {}",
+            report.absent.len(),
+            report.absent.join(", ")
+        ));
+    }
+    if !report.on_leave.is_empty() {
+        message.push_str(&format!(
+            "\n🌴 On leave ({}): {}",
+            report.on_leave.len(),
+            report.on_leave.join(", ")
+        ));
+    }
+
+    message
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
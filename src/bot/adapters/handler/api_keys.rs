@@ -0,0 +1,161 @@
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+use serenity::client::Context;
+use serenity::model::channel::Message;
+use tabled::{settings::Style, Table};
+
+use crate::api::application::api_key_service;
+use crate::bot::adapters::handler::audit::message_actor;
+
+/// Handles the `!AB apikey` commands used to manage personal API keys.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Only works in direct messages, so keys are never posted in a guild channel.
+/// - `create <name> <read|write> [[guild_id/]team_name]` creates a key and sends it once.
+///   The server ID is only needed when the admin has teams with that name in several servers.
+/// - `list` shows the keys of the admin in a formatted table.
+/// - `revoke <id>` revokes a key.
+pub async fn handle_api_key(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    if msg.guild_id.is_some() {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "API keys can only be managed in a direct message with the bot.",
+        )
+        .await;
+        return;
+    }
+
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let usage =
+        "Usage: !AB apikey create <name> <read|write> [[guild_id/]team_name] | list | revoke <id>";
+    if args.len() < 3 {
+        send_message(ctx, &msg.channel_id, usage).await;
+        return;
+    }
+
+    let admin_id = match find_admin_id(db_conn, &msg.author.id.to_string()) {
+        Ok(admin_id) => admin_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
+    match (args[2], args.len()) {
+        ("create", 5..) => {
+            let (guild_id, team_name) = match args.get(5).map(|team| team.split_once('/')) {
+                Some(Some((guild_id, team_name))) => (Some(guild_id), Some(team_name)),
+                Some(None) => (None, args.get(5).copied()),
+                None => (None, None),
+            };
+
+            match api_key_service::create_api_key(
+                db_conn,
+                admin_id,
+                &message_actor(msg),
+                args[3],
+                args[4],
+                guild_id,
+                team_name,
+            ) {
+                Ok((key_id, key)) => {
+                    send_message(
+                        ctx,
+                        &msg.channel_id,
+                        &format!(
+                            "API key #{} created. Store it now, it will not be shown again:\n`{}`",
+                            key_id, key
+                        ),
+                    )
+                    .await;
+                }
+                Err(e) => {
+                    send_message(ctx, &msg.channel_id, &format!("Failed to create API key: {}", e))
+                        .await;
+                }
+            }
+        }
+        ("list", _) => {
+            let keys = match api_key_service::list_api_keys(db_conn, admin_id) {
+                Ok(keys) => keys,
+                Err(e) => {
+                    send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+                    return;
+                }
+            };
+
+            if keys.is_empty() {
+                send_message(ctx, &msg.channel_id, "You have no API keys.").await;
+                return;
+            }
+
+            let table = Table::new(keys).with(Style::rounded()).to_string();
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Your API keys:\n```\n{}\n```", table),
+            )
+            .await;
+        }
+        ("revoke", 4..) => {
+            let Ok(key_id) = args[3].parse::<i32>() else {
+                send_message(ctx, &msg.channel_id, "Usage: !AB apikey revoke <id>").await;
+                return;
+            };
+
+            match api_key_service::revoke_api_key(db_conn, admin_id, key_id, &message_actor(msg)) {
+                Ok(_) => {
+                    send_message(
+                        ctx,
+                        &msg.channel_id,
+                        &format!("API key #{} revoked.", key_id),
+                    )
+                    .await;
+                }
+                Err(e) => {
+                    send_message(ctx, &msg.channel_id, &format!("Failed to revoke API key: {}", e))
+                        .await;
+                }
+            }
+        }
+        _ => send_message(ctx, &msg.channel_id, usage).await,
+    }
+}
+
+/// Fetches the user ID of a registered admin by Discord ID.
+fn find_admin_id(db_conn: &mut PgConnection, dc_user_id: &str) -> Result<i32, String> {
+    use crate::schema::users::dsl::{discord_id, id, users};
+
+    users
+        .filter(discord_id.eq(dc_user_id))
+        .select(id)
+        .first::<i32>(db_conn)
+        .map_err(|e| match e {
+            diesel::NotFound => "User not found!".to_string(),
+            e => {
+                println!("Database error: {:?}", e);
+                "An error occurred while fetching user.".to_string()
+            }
+        })
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &serenity::model::id::ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
@@ -0,0 +1,42 @@
+use chrono::{Duration, NaiveDate, NaiveTime};
+use serenity::all::ChannelId;
+
+/// Parses a duration such as `90s`, `10m` or `1h`, a bare number is read as minutes.
+///
+/// # Arguments
+/// * `arg` - The command argument.
+/// * `max_seconds` - The longest duration accepted.
+///
+/// # Returns
+/// Returns the duration, or `None` if it is malformed, zero or longer than `max_seconds`.
+pub fn parse_duration(arg: &str, max_seconds: i64) -> Option<Duration> {
+    let (value, unit_seconds) = match arg.chars().last()? {
+        's' => (&arg[..arg.len() - 1], 1),
+        'm' => (&arg[..arg.len() - 1], 60),
+        'h' => (&arg[..arg.len() - 1], 60 * 60),
+        _ => (arg, 60),
+    };
+
+    let seconds = value.parse::<i64>().ok()?.checked_mul(unit_seconds)?;
+    (1..=max_seconds)
+        .contains(&seconds)
+        .then(|| Duration::seconds(seconds))
+}
+
+/// Parses a day given as `YYYY-MM-DD`.
+pub fn parse_date(arg: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(arg, "%Y-%m-%d").ok()
+}
+
+/// Parses a time of day given as `HH:MM`.
+pub fn parse_time(arg: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(arg, "%H:%M").ok()
+}
+
+/// Parses a channel mention (`<#id>`) or a raw channel ID.
+pub fn parse_channel(arg: &str) -> Option<ChannelId> {
+    arg.trim_start_matches("<#")
+        .trim_end_matches('>')
+        .parse::<ChannelId>()
+        .ok()
+}
@@ -4,6 +4,8 @@ use serenity::model::channel::Message;
 use tabled::{settings::Style, Table};
 
 use crate::bot::application::services::attendance_service::{self, get_member_attendance};
+use crate::bot::application::services::{role_service, team_service};
+use crate::bot::domain::permission::Permission;
 
 /// Handles the check-in process for a member in a specific team.
 ///
@@ -14,19 +16,22 @@ use crate::bot::application::services::attendance_service::{self, get_member_att
 ///
 /// # Behavior
 /// - Parses the command arguments to extract the team name and status.
-/// - Fetches the member ID and team ID from the database.
-/// - Records the check-in in the database.
+/// - Fetches the team ID of the server and the active member ID in that team from the database.
+/// - Checks that the user may record attendance in the team.
+/// - With a `code:{code}` argument, records the check-in to the on-site event the code belongs to.
+/// - Otherwise records the check-in with the given status.
 /// - Sends a success or error message back to the user.
 pub async fn handle_check_in(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
-    use crate::schema::members::dsl::{discord_id, id as members_id, members};
-    use crate::schema::teams::dsl::{id as teams_id, name, teams};
+    use crate::schema::members::dsl::{
+        discord_id, id as members_id, left_date, members, team_id as member_team_id,
+    };
 
     let args: Vec<&str> = msg.content.split_whitespace().collect();
     if args.len() < 4 {
         send_message(
             ctx,
             &msg.channel_id,
-            "Usage: !AB check-in <team-name> <status>",
+            "Usage: !AB check-in <team-name> <status|code:123456>",
         )
         .await;
         return;
@@ -35,31 +40,66 @@ pub async fn handle_check_in(ctx: &Context, msg: &Message, db_conn: &mut PgConne
     let team_name = args[2];
     let status = args[3];
 
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return;
+    };
+
+    let team_id = match team_service::find_team_id(db_conn, &guild_id, team_name) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
     let user_id_str = msg.author.id.to_string();
-    let user_id = members
+    let Ok(user_id) = members
         .filter(discord_id.eq(&user_id_str))
+        .filter(member_team_id.eq(team_id))
+        .filter(left_date.is_null())
         .select(members_id)
         .first::<i32>(db_conn)
-        .ok();
+    else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "You are not an active member of this team.",
+        )
+        .await;
+        return;
+    };
 
-    let team_id = match teams
-        .filter(name.eq(team_name))
-        .select(teams_id)
-        .first::<i32>(db_conn)
+    if let Err(e) =
+        role_service::authorize(db_conn, team_id, &user_id_str, Permission::RecordAttendance)
     {
-        Ok(id) => id,
-        Err(_) => {
-            send_message(ctx, &msg.channel_id, "Team not found.").await;
-            return;
+        send_message(ctx, &msg.channel_id, &e).await;
+        return;
+    }
+
+    if let Some(code) = status.strip_prefix("code:") {
+        match attendance_service::check_in_with_code(db_conn, user_id, team_id, code) {
+            Ok(event) => {
+                send_message(
+                    ctx,
+                    &msg.channel_id,
+                    &format!("Checked in to '{}' successfully!", event.title),
+                )
+                .await;
+            }
+            Err(e) => {
+                send_message(ctx, &msg.channel_id, &format!("Failed to check in! {}", e)).await;
+            }
         }
-    };
+        return;
+    }
 
-    match attendance_service::check_in(
-        db_conn,
-        user_id.expect("no user id"),
-        team_id,
-        status.to_string(),
-    ) {
+    match attendance_service::check_in(db_conn, user_id, team_id, status.to_string()) {
         Ok(_) => {
             send_message(ctx, &msg.channel_id, "Checked in successfully!").await;
         }
@@ -78,11 +118,14 @@ pub async fn handle_check_in(ctx: &Context, msg: &Message, db_conn: &mut PgConne
 ///
 /// # Behavior
 /// - Parses the command arguments to extract the team name.
-/// - Fetches the member ID from the database.
+/// - Checks that the user may record attendance in the team.
+/// - Fetches the member ID in that team from the database.
 /// - Records the check-out in the database.
 /// - Sends a success or error message back to the user.
 pub async fn handle_check_out(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
-    use crate::schema::members::dsl::{discord_id, id as members_id, members};
+    use crate::schema::members::dsl::{
+        discord_id, id as members_id, members, team_id as member_team_id,
+    };
 
     let args: Vec<&str> = msg.content.split_whitespace().collect();
     if args.len() < 3 {
@@ -90,10 +133,36 @@ pub async fn handle_check_out(ctx: &Context, msg: &Message, db_conn: &mut PgConn
         return;
     }
 
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return;
+    };
+
+    let team_id = match team_service::find_team_id(db_conn, &guild_id, args[2]) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
     // Parse the user ID from the message author
     let user_id_str = msg.author.id.to_string();
+
+    if let Err(e) =
+        role_service::authorize(db_conn, team_id, &user_id_str, Permission::RecordAttendance)
+    {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return;
+    }
     let user_id = match members
         .filter(discord_id.eq(&user_id_str))
+        .filter(member_team_id.eq(team_id))
         .select(members_id)
         .first::<i32>(db_conn)
     {
@@ -124,6 +193,7 @@ pub async fn handle_check_out(ctx: &Context, msg: &Message, db_conn: &mut PgConn
 ///
 /// # Behavior
 /// - Parses the command arguments to extract the team name.
+/// - Checks that the user may view the attendance of the team.
 /// - Fetches the attendance records for the team from the database.
 /// - Displays the attendance records in a formatted table.
 /// - Sends the table as a message back to the user.
@@ -133,7 +203,7 @@ pub async fn handle_show_member_attendance(
     db_conn: &mut PgConnection,
 ) {
     let args: Vec<&str> = msg.content.split_whitespace().collect();
-    if args.len() < 2 {
+    if args.len() < 3 {
         send_message(
             ctx,
             &msg.channel_id,
@@ -145,8 +215,36 @@ pub async fn handle_show_member_attendance(
 
     let team_name = args[2];
 
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return;
+    };
+
+    let team_id = match team_service::find_team_id(db_conn, &guild_id, team_name) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
+    if let Err(e) = role_service::authorize(
+        db_conn,
+        team_id,
+        &msg.author.id.to_string(),
+        Permission::ViewAttendance,
+    ) {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return;
+    }
+
     // Fetch attendance data from the database
-    let attendance_data = match get_member_attendance(db_conn, team_name) {
+    let attendance_data = match get_member_attendance(db_conn, &guild_id, team_name) {
         Ok(data) => data,
         Err(e) => {
             send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
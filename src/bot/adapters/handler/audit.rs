@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use diesel::PgConnection;
+use serenity::all::{ChannelId, ComponentInteraction};
+use serenity::client::Context;
+use serenity::model::channel::Message;
+
+use crate::bot::adapters::handler::events::authorized_team;
+use crate::bot::application::services::audit_service;
+use crate::bot::domain::audit::{AuditActor, AuditFilter};
+use crate::bot::domain::permission::Permission;
+use crate::config::constant::AUDIT_PURGE_INTERVAL_SECONDS;
+use crate::config::database::DBPool;
+use crate::config::settings::AuditSettings;
+
+/// Longest audit log reply, below the 2000 character limit of a Discord message.
+const AUDIT_REPLY_MAX_CHARS: usize = 1900;
+
+/// Handles the `!AB audit` command.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - `audit <team> [action:<action>] [actor:@user] [since:<date>] [until:<date>] [limit:<n>]`
+///   shows the latest administrative actions on the team, newest first. Requires the owner role.
+/// - Stops listing actions once the reply would no longer fit in a message.
+pub async fn handle_audit(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    if args.len() < 3 {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB audit <team_name> [action:<action>] [actor:@user] [since:YYYY-MM-DD] [until:YYYY-MM-DD] [limit:<n>]",
+        )
+        .await;
+        return;
+    }
+
+    let filter = match AuditFilter::parse_args(&args[3..]) {
+        Ok(filter) => filter,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, args[2], Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    let events = match audit_service::get_team_log(db_conn, team_id, &filter) {
+        Ok(events) => events,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+            return;
+        }
+    };
+    if events.is_empty() {
+        send_message(ctx, &msg.channel_id, "No matching actions.").await;
+        return;
+    }
+
+    let total = events.len();
+    let mut reply = format!("Audit log of '{}':", args[2]);
+    for (shown, event) in events.into_iter().enumerate() {
+        let line = format!(
+            "\n`{}` **{}** {} by {} `{}`",
+            event.at, event.action, event.target, event.actor, event.details
+        );
+        if reply.len() + line.len() > AUDIT_REPLY_MAX_CHARS {
+            reply.push_str(&format!(
+                "\n… {} more, narrow the list with filters.",
+                total - shown
+            ));
+            break;
+        }
+        reply.push_str(&line);
+    }
+
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Starts the daily removal of audit events older than `AUDIT_RETENTION_DAYS`.
+///
+/// # Arguments
+/// * `db_pool` - A connection pool to the database.
+///
+/// # Behavior
+/// - Does nothing when the retention is `0`, events are then kept forever.
+/// - Every `AUDIT_PURGE_INTERVAL_SECONDS`, starting right away, removes the expired events.
+pub fn start_audit_purge(db_pool: DBPool) {
+    let Some(retention_days) = AuditSettings::from_env().retention_days else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(AUDIT_PURGE_INTERVAL_SECONDS));
+        loop {
+            interval.tick().await;
+
+            let mut db_conn = match db_pool.get() {
+                Ok(db_conn) => db_conn,
+                Err(e) => {
+                    println!("Failed to get DB connection: {e:?}");
+                    continue;
+                }
+            };
+            match audit_service::purge_expired(&mut db_conn, retention_days) {
+                Ok(0) => {}
+                Ok(removed) => println!("Removed {removed} expired audit event(s)"),
+                Err(e) => println!("Error removing expired audit events: {e}"),
+            }
+        }
+    });
+}
+
+/// Returns the actor of a bot command, recorded in the audit log.
+pub fn message_actor(msg: &Message) -> AuditActor {
+    AuditActor::discord(
+        &msg.author.id.to_string(),
+        msg.guild_id.map(|guild| guild.to_string()),
+        msg.channel_id.to_string(),
+    )
+}
+
+/// Returns the actor of a button click, recorded in the audit log.
+pub fn component_actor(component: &ComponentInteraction) -> AuditActor {
+    AuditActor::discord(
+        &component.user.id.to_string(),
+        component.guild_id.map(|guild| guild.to_string()),
+        component.channel_id.to_string(),
+    )
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
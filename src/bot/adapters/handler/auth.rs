@@ -1,7 +1,11 @@
 use diesel::PgConnection;
 use serenity::client::Context;
 use serenity::model::channel::Message;
+use serde_json::json;
 
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::application::services::{audit_service, user_service};
+use crate::bot::domain::audit::AuditAction;
 use crate::bot::infrastructure::persistence::user_repository::register_admin;
 
 /// Handles the registration of an admin user.
@@ -13,7 +17,7 @@ use crate::bot::infrastructure::persistence::user_repository::register_admin;
 ///
 /// # Behavior
 /// - Parses the command arguments to extract the password.
-/// - Registers the admin user in the database.
+/// - Registers the admin user in the database and records it in the audit log.
 /// - Sends a success or error message back to the user.
 pub async fn handle_register(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
     let args: Vec<&str> = msg.content.split_whitespace().collect();
@@ -31,6 +35,14 @@ pub async fn handle_register(ctx: &Context, msg: &Message, db_conn: &mut PgConne
         password,
     ) {
         Ok(_) => {
+            audit_service::record(
+                db_conn,
+                &message_actor(msg),
+                AuditAction::UserRegister,
+                None,
+                None,
+                json!({ "username": msg.author.name }),
+            );
             send_message(ctx, &msg.channel_id, "Admin registered successfully!").await;
         }
         Err(_) => {
@@ -39,6 +51,32 @@ pub async fn handle_register(ctx: &Context, msg: &Message, db_conn: &mut PgConne
     }
 }
 
+/// Handles revoking every API session of the calling admin.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Fetches the admin user from the database.
+/// - Revokes all refresh tokens and the access tokens issued with them, and records it in the
+///   audit log.
+/// - Sends the number of revoked sessions back to the user.
+pub async fn handle_revoke_sessions(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let author_id = msg.author.id.to_string();
+    match user_service::revoke_sessions(db_conn, &author_id, &message_actor(msg)) {
+        Ok(count) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Revoked {} active session(s).", count),
+            )
+            .await;
+        }
+        Err(e) => send_message(ctx, &msg.channel_id, &e).await,
+    }
+}
 
 /// Sends a message to a specific channel.
 ///
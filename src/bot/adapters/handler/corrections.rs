@@ -0,0 +1,386 @@
+use diesel::PgConnection;
+use serenity::all::{ChannelId, Http, UserId};
+use serenity::client::Context;
+use serenity::model::channel::Message;
+use tabled::{settings::Style, Table};
+
+use crate::bot::adapters::handler::args::parse_date;
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::adapters::handler::events::authorized_team;
+use crate::bot::application::services::correction_service::{self, CorrectionReview};
+use crate::bot::domain::correction::AttendanceChange;
+use crate::bot::domain::model::MemberAttendance;
+use crate::bot::domain::permission::Permission;
+
+/// Handles the `!AB attendance` commands.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - `attendance edit <team> <id> [in:<time>] [out:<time>|out:-] [status:<status>] <reason>`
+///   changes an attendance record, requires the owner or manager role.
+/// - `attendance add <team> @user <YYYY-MM-DD> in:<time> [out:<time>] [status:<status>] <reason>`
+///   adds a missing record, requires the owner or manager role.
+/// - `attendance request <team> <id|YYYY-MM-DD> [in:<time>] [out:<time>|out:-] [status:<status>] <reason>`
+///   asks for a correction of one of the author's records, or for a missing one.
+/// - `attendance corrections <team> [all]` lists the pending (or all) correction requests.
+/// - `attendance approve|reject <team> <id>` reviews a correction request and tells the member
+///   in a DM, requires the owner or manager role.
+/// - `attendance history <id>` shows every change made to a record by hand.
+/// - Times are `HH:MM` on the day of the record or `YYYY-MM-DDTHH:MM`, in UTC.
+pub async fn handle_attendance(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let usage = "Usage: !AB attendance edit <team_name> <id> [in:<time>] [out:<time>|out:-] [status:<status>] <reason> | add <team_name> @user <YYYY-MM-DD> in:<time> [out:<time>] [status:<status>] <reason> | request <team_name> <id|YYYY-MM-DD> [in:<time>] [out:<time>|out:-] [status:<status>] <reason> | corrections <team_name> [all] | approve <team_name> <id> | reject <team_name> <id> | history <id>";
+
+    match (args.get(2).copied(), args.len()) {
+        (Some("edit"), 6..) => {
+            edit_attendance(ctx, msg, db_conn, args[3], args[4], &args[5..]).await
+        }
+        (Some("add"), 8..) => add_attendance(ctx, msg, db_conn, args[3], args[5], &args[6..]).await,
+        (Some("request"), 6..) => {
+            request_correction(ctx, msg, db_conn, args[3], args[4], &args[5..]).await
+        }
+        (Some("corrections"), 4) => list_corrections(ctx, msg, db_conn, args[3], true).await,
+        (Some("corrections"), 5) if args[4] == "all" => {
+            list_corrections(ctx, msg, db_conn, args[3], false).await
+        }
+        (Some("approve"), 5) => review_correction(ctx, msg, db_conn, args[3], args[4], true).await,
+        (Some("reject"), 5) => review_correction(ctx, msg, db_conn, args[3], args[4], false).await,
+        (Some("history"), 4) => show_history(ctx, msg, db_conn, args[3]).await,
+        _ => send_message(ctx, &msg.channel_id, usage).await,
+    }
+}
+
+/// Changes an attendance record of a team.
+async fn edit_attendance(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    attendance_id: &str,
+    rest: &[&str],
+) {
+    let Ok(attendance_id) = attendance_id.parse::<i32>() else {
+        send_message(ctx, &msg.channel_id, "Attendance ID must be a number").await;
+        return;
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let result = correction_service::find_attendance(db_conn, team_id, attendance_id)
+        .and_then(|(record, _)| parse_change(rest, record.date))
+        .and_then(|(change, reason)| {
+            correction_service::edit_attendance(
+                db_conn,
+                team_id,
+                attendance_id,
+                &change,
+                &message_actor(msg),
+                &reason,
+            )
+        });
+    let reply = match result {
+        Ok(record) => format!(
+            "✏️ Attendance #{} updated: {}",
+            record.id,
+            describe(&record)
+        ),
+        Err(e) => format!("Failed to update attendance: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Adds a missing attendance record to a member of a team.
+async fn add_attendance(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    date: &str,
+    rest: &[&str],
+) {
+    let Some(target) = msg.mentions.first() else {
+        send_message(ctx, &msg.channel_id, "Please mention the member.").await;
+        return;
+    };
+    let Some(date) = parse_date(date) else {
+        send_message(ctx, &msg.channel_id, "The day must be given as YYYY-MM-DD.").await;
+        return;
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let result = parse_change(rest, date).and_then(|(change, reason)| {
+        correction_service::add_attendance(
+            db_conn,
+            team_id,
+            &target.id.to_string(),
+            date,
+            &change,
+            &message_actor(msg),
+            &reason,
+        )
+    });
+    let reply = match result {
+        Ok(record) => format!(
+            "➕ Attendance #{} added for {}: {}",
+            record.id,
+            target.name,
+            describe(&record)
+        ),
+        Err(e) => format!("Failed to add attendance: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Asks for a correction of one of the author's attendance records, or for a missing one.
+async fn request_correction(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    target: &str,
+    rest: &[&str],
+) {
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::RecordAttendance).await
+    else {
+        return;
+    };
+
+    let target = match (target.parse::<i32>(), parse_date(target)) {
+        (Ok(attendance_id), _) => {
+            correction_service::find_attendance(db_conn, team_id, attendance_id)
+                .map(|(record, _)| (Some(record.id), record.date))
+        }
+        (_, Some(date)) => Ok((None, date)),
+        _ => Err("Give the ID of the record, or the day (YYYY-MM-DD) of a missing one".to_string()),
+    };
+
+    let result = target.and_then(|(attendance_id, date)| {
+        let (change, reason) = parse_change(rest, date)?;
+        correction_service::request_correction(
+            db_conn,
+            team_id,
+            &msg.author.id.to_string(),
+            attendance_id,
+            date,
+            &change,
+            &reason,
+        )
+    });
+    let reply = match result {
+        Ok(correction) => format!(
+            "📝 Correction request #{} sent, an owner or manager of '{}' will review it.",
+            correction.id, team_name
+        ),
+        Err(e) => format!("Failed to request a correction: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Shows the correction requests of a team.
+async fn list_corrections(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    pending_only: bool,
+) {
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    match correction_service::get_corrections(db_conn, team_id, pending_only) {
+        Ok(corrections) if corrections.is_empty() => {
+            send_message(ctx, &msg.channel_id, "No correction requests.").await;
+        }
+        Ok(corrections) => {
+            let table = Table::new(corrections).with(Style::rounded()).to_string();
+            send_message(ctx, &msg.channel_id, &format!("```\n{}\n```", table)).await;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+        }
+    }
+}
+
+/// Approves or rejects a correction request of a team.
+async fn review_correction(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    correction_id: &str,
+    approve: bool,
+) {
+    let Ok(correction_id) = correction_id.parse::<i32>() else {
+        send_message(ctx, &msg.channel_id, "Correction ID must be a number").await;
+        return;
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let result = match correction_service::find_correction(db_conn, correction_id) {
+        Ok(Some(correction)) if correction.team_id == team_id => {
+            correction_service::review_correction(
+                db_conn,
+                correction_id,
+                &message_actor(msg),
+                approve,
+            )
+        }
+        Ok(_) => Err(format!("Correction request {} not found", correction_id)),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(review) => {
+            notify_member(&ctx.http, &review).await;
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!(
+                    "{} correction request #{}.",
+                    outcome(&review),
+                    correction_id
+                ),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to review correction request: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Shows the changes made by hand to an attendance record.
+async fn show_history(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    attendance_id: &str,
+) {
+    let Ok(attendance_id) = attendance_id.parse::<i32>() else {
+        send_message(ctx, &msg.channel_id, "Attendance ID must be a number").await;
+        return;
+    };
+
+    match correction_service::get_history(db_conn, attendance_id, &msg.author.id.to_string()) {
+        Ok(entries) if entries.is_empty() => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Attendance #{} was never changed by hand.", attendance_id),
+            )
+            .await;
+        }
+        Ok(entries) => {
+            let table = Table::new(entries).with(Style::rounded()).to_string();
+            send_message(ctx, &msg.channel_id, &format!("```\n{}\n```", table)).await;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+        }
+    }
+}
+
+/// Tells a member in a DM whether their correction request was approved or rejected.
+///
+/// # Arguments
+/// * `http` - The Discord HTTP client.
+/// * `review` - The reviewed correction request.
+pub async fn notify_member(http: &Http, review: &CorrectionReview) {
+    let Ok(user_id) = review.discord_id.parse::<UserId>() else {
+        return;
+    };
+    let mut message = format!(
+        "{}: your attendance correction for {} in '{}' (request #{}).",
+        outcome(review),
+        review.correction.attendance_date,
+        review.team_name,
+        review.correction.id
+    );
+    if let Some(record) = &review.attendance {
+        message.push_str(&format!(
+            "\nAttendance #{}: {}",
+            record.id,
+            describe(record)
+        ));
+    }
+
+    match user_id.create_dm_channel(http).await {
+        Ok(channel) => {
+            if let Err(e) = channel.say(http, message).await {
+                println!("Error sending message: {e:?}");
+            }
+        }
+        Err(e) => println!("Error opening DM channel: {e:?}"),
+    }
+}
+
+/// Splits the arguments describing a change from the reason that follows them.
+fn parse_change(
+    args: &[&str],
+    date: chrono::NaiveDate,
+) -> Result<(AttendanceChange, String), String> {
+    let (change, used) = AttendanceChange::parse_args(args, date)?;
+    Ok((change, args[used..].join(" ")))
+}
+
+fn describe(record: &MemberAttendance) -> String {
+    let format_time = |time: Option<chrono::NaiveDateTime>| {
+        time.map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    format!(
+        "{} → {} ({})",
+        format_time(record.check_in_time),
+        format_time(record.check_out_time),
+        record.status.as_deref().unwrap_or("-")
+    )
+}
+
+fn outcome(review: &CorrectionReview) -> &'static str {
+    match review.correction.status.as_str() {
+        "approved" => "✅ Approved",
+        _ => "❌ Rejected",
+    }
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
@@ -0,0 +1,446 @@
+use std::sync::Arc;
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use diesel::PgConnection;
+use serenity::all::{ChannelId, CreateMessage, EditMessage, Http, MessageId};
+use serenity::client::Context;
+use serenity::model::channel::Message;
+use tabled::{settings::Style, Table};
+
+use crate::bot::adapters::handler::args::{parse_date, parse_duration};
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::application::services::{event_service, role_service, team_service};
+use crate::bot::domain::model::Event;
+use crate::bot::domain::permission::Permission;
+use crate::config::constant::EVENT_MAX_HOURS;
+
+/// Longest on-site check-in window that can be opened, in seconds.
+const MAX_CODE_EVENT_SECONDS: i64 = 24 * 60 * 60;
+
+/// Handles the `!AB event` commands used to schedule the meetings of a team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - `create <team> <YYYY-MM-DD> <HH:MM> <duration> <title> [repeat:<RRULE>]` schedules an
+///   event (times are UTC), requires the owner or manager role.
+/// - `list <team>` shows the upcoming and repeating events of the team.
+/// - `cancel <team> <id>` cancels an event, requires the owner or manager role.
+/// - `skip <team> <id> <YYYY-MM-DD>` skips one occurrence of a repeating event, requires the
+///   owner or manager role.
+/// - `report <team> <id> [YYYY-MM-DD]` shows who checked in to an event and who did not show
+///   up, for repeating events at the occurrence on that date (the latest one by default).
+pub async fn handle_event(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let usage = "Usage: !AB event create <team_name> <YYYY-MM-DD> <HH:MM> <duration> <title> [repeat:<RRULE>] | list <team_name> | cancel <team_name> <id> | skip <team_name> <id> <YYYY-MM-DD> | report <team_name> <id> [YYYY-MM-DD]";
+
+    match (args.get(2).copied(), args.len()) {
+        (Some("create"), 8..) => create_event(ctx, msg, db_conn, &args[3..]).await,
+        (Some("list"), 4..) => list_events(ctx, msg, db_conn, args[3]).await,
+        (Some("cancel"), 5..) => cancel_event(ctx, msg, db_conn, args[3], args[4]).await,
+        (Some("skip"), 6..) => skip_occurrence(ctx, msg, db_conn, args[3], args[4], args[5]).await,
+        (Some("report"), 5..) => {
+            report_event(ctx, msg, db_conn, args[3], args[4], args.get(5).copied()).await
+        }
+        _ => send_message(ctx, &msg.channel_id, usage).await,
+    }
+}
+
+/// Schedules an event from the `create` arguments: team, date, time, duration and title.
+async fn create_event(ctx: &Context, msg: &Message, db_conn: &mut PgConnection, args: &[&str]) {
+    let Ok(starts_at) =
+        NaiveDateTime::parse_from_str(&format!("{} {}", args[1], args[2]), "%Y-%m-%d %H:%M")
+    else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "The start must be given as YYYY-MM-DD HH:MM (UTC).",
+        )
+        .await;
+        return;
+    };
+    let Some(duration) = parse_duration(args[3], EVENT_MAX_HOURS * 60 * 60) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            &format!(
+                "The duration must be like 45m or 2h, up to {}h.",
+                EVENT_MAX_HOURS
+            ),
+        )
+        .await;
+        return;
+    };
+
+    let recurrence_rule = args[4..].iter().find_map(|arg| arg.strip_prefix("repeat:"));
+    let title = args[4..]
+        .iter()
+        .filter(|arg| !arg.starts_with("repeat:"))
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, args[0], Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    match event_service::create_event(
+        db_conn,
+        team_id,
+        &title,
+        starts_at,
+        starts_at + duration,
+        recurrence_rule,
+        &message_actor(msg),
+    ) {
+        Ok(event) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!(
+                    "Event #{} '{}' scheduled for <t:{}:F>.",
+                    event.id,
+                    event.title,
+                    event.starts_at.and_utc().timestamp()
+                ),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to create event: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Shows the upcoming and repeating events of a team.
+async fn list_events(ctx: &Context, msg: &Message, db_conn: &mut PgConnection, team_name: &str) {
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ViewMembers).await
+    else {
+        return;
+    };
+
+    let events = match event_service::get_upcoming_events(db_conn, team_id) {
+        Ok(events) => events,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+            return;
+        }
+    };
+
+    if events.is_empty() {
+        send_message(ctx, &msg.channel_id, "No upcoming events.").await;
+        return;
+    }
+
+    let table = Table::new(events).with(Style::rounded()).to_string();
+    send_message(
+        ctx,
+        &msg.channel_id,
+        &format!("Upcoming events (UTC):\n```\n{}\n```", table),
+    )
+    .await;
+}
+
+/// Cancels an event of a team.
+async fn cancel_event(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    event_id: &str,
+) {
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let result = parse_event_id(event_id)
+        .and_then(|event_id| event_service::find_team_event(db_conn, team_id, event_id))
+        .and_then(|event| {
+            event_service::cancel_event(db_conn, event.id, &message_actor(msg)).map(|_| event)
+        });
+
+    let reply = match result {
+        Ok(event) => format!("Event #{} '{}' cancelled.", event.id, event.title),
+        Err(e) => format!("Failed to cancel event: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Skips the occurrence of a repeating event on a date.
+async fn skip_occurrence(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    event_id: &str,
+    date: &str,
+) {
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let result = parse_event_id(event_id)
+        .and_then(|event_id| event_service::find_team_event(db_conn, team_id, event_id))
+        .and_then(|event| {
+            let date = parse_day(date)?;
+            event_service::skip_occurrence(db_conn, &event, date, &message_actor(msg))
+                .map(|start| (event, start))
+        });
+
+    let reply = match result {
+        Ok((event, start)) => format!(
+            "Event #{} '{}' will not take place on <t:{}:F>.",
+            event.id,
+            event.title,
+            start.and_utc().timestamp()
+        ),
+        Err(e) => format!("Failed to skip occurrence: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Shows who checked in to an occurrence of an event and who did not show up.
+async fn report_event(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    event_id: &str,
+    date: Option<&str>,
+) {
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ViewAttendance).await
+    else {
+        return;
+    };
+
+    let report = parse_event_id(event_id)
+        .and_then(|event_id| event_service::find_team_event(db_conn, team_id, event_id))
+        .and_then(|event| {
+            let date = date.map(parse_day).transpose()?;
+            event_service::find_occurrence(db_conn, &event, date)
+        })
+        .and_then(|occurrence| {
+            event_service::get_event_attendance(db_conn, &occurrence).map(|rows| (occurrence, rows))
+        });
+
+    match report {
+        Ok((occurrence, rows)) if rows.is_empty() => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!(
+                    "No attendance for event #{} '{}' on {}.",
+                    occurrence.event.id,
+                    occurrence.event.title,
+                    occurrence.starts_at.date()
+                ),
+            )
+            .await;
+        }
+        Ok((occurrence, rows)) => {
+            let table = Table::new(rows).with(Style::rounded()).to_string();
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!(
+                    "Attendance for event #{} '{}' on {}:\n```\n{}\n```",
+                    occurrence.event.id,
+                    occurrence.event.title,
+                    occurrence.starts_at.date(),
+                    table
+                ),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+        }
+    }
+}
+
+fn parse_day(date: &str) -> Result<NaiveDate, String> {
+    parse_date(date).ok_or_else(|| "The date must be given as YYYY-MM-DD".to_string())
+}
+
+fn parse_event_id(event_id: &str) -> Result<i32, String> {
+    event_id
+        .trim_start_matches('#')
+        .parse::<i32>()
+        .map_err(|_| "Event ID must be a number".to_string())
+}
+
+/// Handles opening an on-site check-in protected by a rotating code.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name, the duration and the title.
+/// - Checks that the author may manage the members of the team.
+/// - Creates an event and posts its check-in code, the message is refreshed every time the
+///   code rotates until the event ends.
+pub async fn handle_check_in_code(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let Some(duration) = args
+        .get(3)
+        .and_then(|arg| parse_duration(arg, MAX_CODE_EVENT_SECONDS))
+    else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB checkin_code <team_name> <duration> [title] (e.g. 2h, up to 24h)",
+        )
+        .await;
+        return;
+    };
+    let title = match args[4..].join(" ") {
+        title if title.is_empty() => "On-site check-in".to_string(),
+        title => title,
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, args[2], Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    let event =
+        match event_service::start_code_event(db_conn, team_id, &title, duration, &actor) {
+            Ok(event) => event,
+            Err(e) => {
+                send_message(ctx, &msg.channel_id, &e).await;
+                return;
+            }
+        };
+
+    let screen = CreateMessage::new().content(format_code(&event, args[2]));
+    match msg.channel_id.send_message(&ctx.http, screen).await {
+        Ok(posted) => refresh_code(
+            ctx.http.clone(),
+            msg.channel_id,
+            posted.id,
+            event,
+            args[2].to_string(),
+        ),
+        Err(e) => println!("Error sending message: {e:?}"),
+    }
+}
+
+/// Edits the current code into the check-in message every time it rotates, until the event ends.
+fn refresh_code(
+    http: Arc<Http>,
+    channel_id: ChannelId,
+    message_id: MessageId,
+    event: Event,
+    team_name: String,
+) {
+    tokio::spawn(async move {
+        loop {
+            let now = Utc::now().naive_utc();
+            let wait = match event_service::current_code(&event, now) {
+                Some((_, seconds_left)) => seconds_left,
+                None => break,
+            };
+            tokio::time::sleep(std::time::Duration::from_secs(wait as u64)).await;
+
+            let edit = EditMessage::new().content(format_code(&event, &team_name));
+            if let Err(e) = channel_id.edit_message(&http, message_id, edit).await {
+                println!(
+                    "Error refreshing check-in code of event {}: {e:?}",
+                    event.id
+                );
+                return;
+            }
+        }
+    });
+}
+
+/// Formats the check-in message of an event, showing the current code while it is running.
+fn format_code(event: &Event, team_name: &str) -> String {
+    match event_service::current_code(event, Utc::now().naive_utc()) {
+        Some((code, _)) => format!(
+            "🔐 **{}** ({})\nCheck-in code: **{}**\nCheck in with `!AB check_in {} code:{}`, the code changes every few seconds. Closes <t:{}:R>.",
+            event.title,
+            team_name,
+            code,
+            team_name,
+            code,
+            event.ends_at.and_utc().timestamp()
+        ),
+        None => format!("🔐 **{}** ({})\nCheck-in is closed.", event.title, team_name),
+    }
+}
+
+/// Resolves a team by name in the current server and checks that the author has a permission.
+///
+/// # Returns
+/// Returns the ID of the team, or `None` after replying with the reason it failed.
+pub(crate) async fn authorized_team(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    permission: Permission,
+) -> Option<i32> {
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return None;
+    };
+
+    let team_id = match team_service::find_team_id(db_conn, &guild_id, team_name) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return None;
+        }
+    };
+
+    if let Err(e) =
+        role_service::authorize(db_conn, team_id, &msg.author.id.to_string(), permission)
+    {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return None;
+    }
+
+    Some(team_id)
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
@@ -0,0 +1,235 @@
+use chrono::{Datelike, Utc};
+use diesel::PgConnection;
+use serenity::all::ChannelId;
+use serenity::client::Context;
+use serenity::model::channel::Message;
+use tabled::{settings::Style, Table};
+
+use crate::bot::adapters::handler::args::parse_date;
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::adapters::handler::events::authorized_team;
+use crate::bot::application::services::holiday_service;
+use crate::bot::domain::permission::Permission;
+use crate::config::constant::HOLIDAY_IMPORT_MAX_BYTES;
+
+/// Handles the `!AB holiday` commands.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - `add <team> <YYYY-MM-DD> <name>` adds a day off to the holiday calendar of the team.
+/// - `list <team> [year]` shows the holidays of a year, the current one by default.
+/// - `remove <team> <YYYY-MM-DD>` removes a day off.
+/// - `import <team>` adds the days of the `.ics` file attached to the message.
+/// - Changing the calendar requires the owner or manager role.
+pub async fn handle_holiday(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let usage = "Usage: !AB holiday add <team_name> <YYYY-MM-DD> <name> | list <team_name> [year] | remove <team_name> <YYYY-MM-DD> | import <team_name> (with an .ics file attached)";
+
+    match (args.get(2).copied(), args.len()) {
+        (Some("add"), 6..) => {
+            add_holiday(ctx, msg, db_conn, args[3], args[4], &args[5..].join(" ")).await
+        }
+        (Some("list"), 4..=5) => {
+            list_holidays(ctx, msg, db_conn, args[3], args.get(4).copied()).await
+        }
+        (Some("remove"), 5) => remove_holiday(ctx, msg, db_conn, args[3], args[4]).await,
+        (Some("import"), 4) => import_holidays(ctx, msg, db_conn, args[3]).await,
+        _ => send_message(ctx, &msg.channel_id, usage).await,
+    }
+}
+
+/// Adds a day off to the holiday calendar of a team.
+async fn add_holiday(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    date: &str,
+    name: &str,
+) {
+    let Some(date) = parse_date(date) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "The date must be given as YYYY-MM-DD.",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    let reply = match holiday_service::add_holiday(db_conn, team_id, date, name, &actor) {
+        Ok(holiday) => format!(
+            "🏖 {} ({}) is now a holiday of '{}': {}",
+            holiday.date, holiday.weekday, team_name, holiday.name
+        ),
+        Err(e) => format!("Failed to add holiday: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Shows the holiday calendar of a team for a year.
+async fn list_holidays(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    year: Option<&str>,
+) {
+    let year = match year.map(|year| year.parse::<i32>()) {
+        Some(Ok(year)) => year,
+        Some(Err(_)) => {
+            send_message(ctx, &msg.channel_id, "The year must be a number.").await;
+            return;
+        }
+        None => Utc::now().year(),
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ViewMembers).await
+    else {
+        return;
+    };
+
+    match holiday_service::get_holidays(db_conn, team_id, year) {
+        Ok(holidays) if holidays.is_empty() => {
+            send_message(ctx, &msg.channel_id, &format!("No holidays in {}.", year)).await;
+        }
+        Ok(holidays) => {
+            let table = Table::new(holidays).with(Style::rounded()).to_string();
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!(
+                    "Holidays of '{}' in {}:\n```\n{}\n```",
+                    team_name, year, table
+                ),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+        }
+    }
+}
+
+/// Removes a day off from the holiday calendar of a team.
+async fn remove_holiday(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    date: &str,
+) {
+    let Some(date) = parse_date(date) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "The date must be given as YYYY-MM-DD.",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    let reply = match holiday_service::remove_holiday(db_conn, team_id, date, &actor) {
+        Ok(_) => format!("{} is no longer a holiday of '{}'.", date, team_name),
+        Err(e) => format!("Failed to remove holiday: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Adds the days of the `.ics` file attached to the message to the holiday calendar of a team.
+async fn import_holidays(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+) {
+    let Some(attachment) = msg
+        .attachments
+        .iter()
+        .find(|attachment| attachment.filename.to_lowercase().ends_with(".ics"))
+    else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Attach the .ics file to import to the message.",
+        )
+        .await;
+        return;
+    };
+    if attachment.size > HOLIDAY_IMPORT_MAX_BYTES {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            &format!(
+                "The file is too large, at most {} KB can be imported.",
+                HOLIDAY_IMPORT_MAX_BYTES / 1024
+            ),
+        )
+        .await;
+        return;
+    }
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let content = match attachment.download().await {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to download {}: {}", attachment.filename, e),
+            )
+            .await;
+            return;
+        }
+    };
+
+    let actor = message_actor(msg);
+    let reply = match holiday_service::import_holidays(db_conn, team_id, &content, &actor) {
+        Ok(import) => format!(
+            "🏖 Imported {} holidays into '{}' ({} already there, {} unreadable events skipped).",
+            import.added, team_name, import.existing, import.skipped
+        ),
+        Err(e) => format!("Failed to import holidays: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
@@ -0,0 +1,486 @@
+use chrono::{Datelike, Utc};
+use diesel::PgConnection;
+use serenity::all::{
+    ButtonStyle, ChannelId, ComponentInteraction, CreateActionRow, CreateButton,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, Http, UserId,
+};
+use serenity::client::Context;
+use serenity::model::channel::Message;
+use tabled::{settings::Style, Table};
+
+use crate::bot::adapters::handler::args::{parse_channel, parse_date};
+use crate::bot::adapters::handler::audit::{component_actor, message_actor};
+use crate::bot::adapters::handler::events::authorized_team;
+use crate::bot::application::services::leave_service::{self, LeaveReview};
+use crate::bot::domain::permission::Permission;
+
+/// Prefix of the custom IDs of the buttons used to approve or reject leave requests.
+pub const LEAVE_PREFIX: &str = "leave:";
+
+/// Handles the `!AB leave` commands.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - `request <team> <YYYY-MM-DD> <YYYY-MM-DD> <type> <reason>` creates a pending request and
+///   posts it with approve and reject buttons to the review channel of the team, or to the
+///   team admin in a DM.
+/// - `list <team> [pending]` shows the leave requests of the team.
+/// - `cancel <team> <id>` withdraws one of the author's own requests.
+/// - `channel <team> [#channel]` sets (or clears) the review channel, requires the owner role.
+/// - `balance <team> [year]` shows the leave taken against the allowances.
+/// - `balance <team> @user <year> <type> <days>` sets an allowance, requires the owner or
+///   manager role.
+pub async fn handle_leave(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let usage = "Usage: !AB leave request <team_name> <YYYY-MM-DD> <YYYY-MM-DD> <vacation|sick|personal|other> <reason> | list <team_name> [pending] | cancel <team_name> <id> | channel <team_name> [#channel] | balance <team_name> [year] | balance <team_name> @user <year> <type> <days>";
+
+    match (args.get(2).copied(), args.len()) {
+        (Some("request"), 8..) => request_leave(ctx, msg, db_conn, &args[3..]).await,
+        (Some("list"), 4..) => {
+            let pending_only = args.get(4) == Some(&"pending");
+            list_requests(ctx, msg, db_conn, args[3], pending_only).await
+        }
+        (Some("cancel"), 5..) => cancel_request(ctx, msg, db_conn, args[3], args[4]).await,
+        (Some("channel"), 4..) => {
+            set_channel(ctx, msg, db_conn, args[3], args.get(4).copied()).await
+        }
+        (Some("balance"), 8..) => set_allowance(ctx, msg, db_conn, &args[3..]).await,
+        (Some("balance"), 4..=5) => {
+            show_balances(ctx, msg, db_conn, args[3], args.get(4).copied()).await
+        }
+        _ => send_message(ctx, &msg.channel_id, usage).await,
+    }
+}
+
+/// Creates a leave request from the `request` arguments and posts it for review.
+async fn request_leave(ctx: &Context, msg: &Message, db_conn: &mut PgConnection, args: &[&str]) {
+    let (Some(starts_on), Some(ends_on)) = (parse_date(args[1]), parse_date(args[2])) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "The first and last day of leave must be given as YYYY-MM-DD.",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, args[0], Permission::RecordAttendance).await
+    else {
+        return;
+    };
+
+    let review = match leave_service::request_leave(
+        db_conn,
+        team_id,
+        &msg.author.id.to_string(),
+        starts_on,
+        ends_on,
+        args[3],
+        &args[4..].join(" "),
+    ) {
+        Ok(review) => review,
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to request leave: {}", e),
+            )
+            .await;
+            return;
+        }
+    };
+
+    let reply = match post_for_review(&ctx.http, &review).await {
+        Ok(_) => format!(
+            "🌴 Leave request #{} submitted, you will get a DM once it is reviewed.",
+            review.request.id
+        ),
+        Err(e) => format!(
+            "🌴 Leave request #{} submitted, but it could not be sent for review: {}",
+            review.request.id, e
+        ),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Shows the leave requests of a team.
+async fn list_requests(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    pending_only: bool,
+) {
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ViewAttendance).await
+    else {
+        return;
+    };
+
+    match leave_service::get_requests(db_conn, team_id, pending_only) {
+        Ok(requests) if requests.is_empty() => {
+            send_message(ctx, &msg.channel_id, "No leave requests.").await;
+        }
+        Ok(requests) => {
+            let table = Table::new(requests).with(Style::rounded()).to_string();
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Leave requests:\n```\n{}\n```", table),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+        }
+    }
+}
+
+/// Withdraws one of the author's own leave requests.
+async fn cancel_request(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    request_id: &str,
+) {
+    let Ok(request_id) = request_id.trim_start_matches('#').parse::<i32>() else {
+        send_message(ctx, &msg.channel_id, "Leave request ID must be a number").await;
+        return;
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::RecordAttendance).await
+    else {
+        return;
+    };
+
+    let reply = match leave_service::cancel_request(
+        db_conn,
+        team_id,
+        request_id,
+        &msg.author.id.to_string(),
+    ) {
+        Ok(request) => format!("Leave request #{} cancelled.", request.id),
+        Err(e) => format!("Failed to cancel leave request: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Sets or clears the channel the leave requests of a team are posted to.
+async fn set_channel(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    channel: Option<&str>,
+) {
+    let channel = match channel.map(parse_channel) {
+        Some(Some(channel)) => Some(channel),
+        Some(None) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                "Usage: !AB leave channel <team_name> [#channel]",
+            )
+            .await;
+            return;
+        }
+        None => None,
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, team_name, Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    let channel_id = channel.map(|channel| channel.to_string());
+    let actor = message_actor(msg);
+    let reply = match leave_service::set_leave_channel(
+        db_conn,
+        team_id,
+        channel_id.as_deref(),
+        &actor,
+    ) {
+        Ok(_) => match channel {
+            Some(channel) => format!(
+                "Leave requests of '{}' will be posted in <#{}>.",
+                team_name, channel
+            ),
+            None => format!(
+                "Leave requests of '{}' will be sent to the team admin.",
+                team_name
+            ),
+        },
+        Err(e) => format!("Failed to set leave channel: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Shows the leave the members of a team took in a year against their allowances.
+async fn show_balances(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    year: Option<&str>,
+) {
+    let year = match year.map(|year| year.parse::<i32>()) {
+        Some(Ok(year)) => year,
+        Some(Err(_)) => {
+            send_message(ctx, &msg.channel_id, "The year must be a number.").await;
+            return;
+        }
+        None => Utc::now().year(),
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ViewAttendance).await
+    else {
+        return;
+    };
+
+    match leave_service::get_balances(db_conn, team_id, year) {
+        Ok(balances) if balances.is_empty() => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("No leave allowances or approved leave in {}.", year),
+            )
+            .await;
+        }
+        Ok(balances) => {
+            let table = Table::new(balances).with(Style::rounded()).to_string();
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Leave in {} (working days):\n```\n{}\n```", year, table),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+        }
+    }
+}
+
+/// Sets the allowance of a member from the `balance` arguments: team, user, year, type, days.
+async fn set_allowance(ctx: &Context, msg: &Message, db_conn: &mut PgConnection, args: &[&str]) {
+    let (Some(target), Ok(year), Ok(days)) = (
+        msg.mentions.first(),
+        args[2].parse::<i32>(),
+        args[4].parse::<i32>(),
+    ) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB leave balance <team_name> @user <year> <vacation|sick|personal|other> <days>",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, args[0], Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let reply = match leave_service::set_allowance(
+        db_conn,
+        team_id,
+        &target.id.to_string(),
+        year,
+        args[3],
+        days,
+        &message_actor(msg),
+    ) {
+        Ok(_) => format!(
+            "<@{}> may take {} {} days in {}.",
+            target.id,
+            days,
+            args[3].to_lowercase(),
+            year
+        ),
+        Err(e) => format!("Failed to set leave allowance: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Handles a click on the approve or reject button of a leave request.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `component` - The button interaction.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Reviews the request if the user may manage the members of the team.
+/// - Replaces the buttons with the outcome and sends it to the member in a DM.
+/// - Replies with the error only to the user who clicked when the review fails.
+pub async fn handle_leave_review(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    db_conn: &mut PgConnection,
+) {
+    let Some((action, Ok(request_id))) = component
+        .data
+        .custom_id
+        .trim_start_matches(LEAVE_PREFIX)
+        .split_once(':')
+        .map(|(action, request_id)| (action, request_id.parse::<i32>()))
+    else {
+        return;
+    };
+    let approve = match action {
+        "approve" => true,
+        "reject" => false,
+        _ => return,
+    };
+
+    let reviewer = component.user.id.to_string();
+    let actor = component_actor(component);
+    let response = match leave_service::review_request(db_conn, request_id, &actor, approve) {
+        Ok(review) => {
+            notify_member(&ctx.http, &review).await;
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(format!(
+                        "{}\n{} by <@{}>",
+                        component.message.content,
+                        outcome(&review),
+                        reviewer
+                    ))
+                    .components(vec![]),
+            )
+        }
+        Err(e) => CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(e)
+                .ephemeral(true),
+        ),
+    };
+
+    if let Err(e) = component.create_response(&ctx.http, response).await {
+        println!("Error responding to interaction: {e:?}");
+    }
+}
+
+/// Posts a pending leave request with approve and reject buttons for the team to review.
+///
+/// # Arguments
+/// * `http` - The Discord HTTP client.
+/// * `review` - The pending leave request.
+///
+/// # Behavior
+/// - Posts in the review channel of the team, or sends a DM to the team admin when the team
+///   has no review channel.
+///
+/// # Errors
+/// Returns an error if there is no one to send the request to or the message cannot be sent.
+pub async fn post_for_review(http: &Http, review: &LeaveReview) -> Result<(), String> {
+    let request = &review.request;
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{}approve:{}", LEAVE_PREFIX, request.id))
+            .label("Approve")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("{}reject:{}", LEAVE_PREFIX, request.id))
+            .label("Reject")
+            .style(ButtonStyle::Danger),
+    ]);
+    let message = CreateMessage::new()
+        .content(format!(
+            "🌴 Leave request #{} in '{}' from <@{}> ({}): {} from {} to {} ({} working days)\nReason: {}",
+            request.id,
+            review.team_name,
+            review.discord_id,
+            review.username,
+            request.leave_type,
+            request.starts_on,
+            request.ends_on,
+            review.days,
+            request.reason
+        ))
+        .components(vec![buttons]);
+
+    let channel_id = match (&review.channel_id, &review.admin_discord_id) {
+        (Some(channel_id), _) => channel_id
+            .parse::<ChannelId>()
+            .map_err(|_| format!("Invalid leave channel {}", channel_id))?,
+        (None, Some(admin_discord_id)) => {
+            admin_discord_id
+                .parse::<UserId>()
+                .map_err(|_| format!("Invalid Discord ID {}", admin_discord_id))?
+                .create_dm_channel(http)
+                .await
+                .map_err(|e| format!("Failed to open DM channel: {e:?}"))?
+                .id
+        }
+        (None, None) => {
+            return Err("The team has no leave channel and no admin".to_string());
+        }
+    };
+
+    channel_id
+        .send_message(http, message)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to send leave request: {e:?}"))
+}
+
+/// Tells a member in a DM whether their leave request was approved or rejected.
+///
+/// # Arguments
+/// * `http` - The Discord HTTP client.
+/// * `review` - The reviewed leave request.
+pub async fn notify_member(http: &Http, review: &LeaveReview) {
+    let Ok(user_id) = review.discord_id.parse::<UserId>() else {
+        return;
+    };
+    let message = format!(
+        "{}: your {} leave in '{}' from {} to {} (request #{}).",
+        outcome(review),
+        review.request.leave_type,
+        review.team_name,
+        review.request.starts_on,
+        review.request.ends_on,
+        review.request.id
+    );
+
+    match user_id.create_dm_channel(http).await {
+        Ok(channel) => {
+            if let Err(e) = channel.say(http, message).await {
+                println!("Error sending message: {e:?}");
+            }
+        }
+        Err(e) => println!("Error opening DM channel: {e:?}"),
+    }
+}
+
+fn outcome(review: &LeaveReview) -> &'static str {
+    match review.request.status.as_str() {
+        "approved" => "✅ Approved",
+        _ => "❌ Rejected",
+    }
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
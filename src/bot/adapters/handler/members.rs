@@ -3,7 +3,10 @@ use serenity::client::Context;
 use serenity::model::channel::Message;
 use tabled::{settings::Style, Table};
 
-use crate::bot::application::services::team_service::get_members_by_team;
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::application::services::team_service::{find_team_id, get_members_by_team};
+use crate::bot::application::services::{member_service, role_service};
+use crate::bot::domain::permission::Permission;
 
 /// Handles displaying the members of a specific team.
 ///
@@ -14,6 +17,7 @@ use crate::bot::application::services::team_service::get_members_by_team;
 ///
 /// # Behavior
 /// - Parses the command arguments to extract the team name.
+/// - Checks that the user may view the members of the team.
 /// - Fetches the members of the specified team from the database.
 /// - Displays the members in a formatted table.
 /// - Sends the table as a message back to the user.
@@ -26,8 +30,36 @@ pub async fn handle_show_members(ctx: &Context, msg: &Message, db_conn: &mut PgC
 
     let team_name = args[2];
 
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return;
+    };
+
+    let team_id = match find_team_id(db_conn, &guild_id, team_name) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
+    if let Err(e) = role_service::authorize(
+        db_conn,
+        team_id,
+        &msg.author.id.to_string(),
+        Permission::ViewMembers,
+    ) {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return;
+    }
+
     // Fetch members from the database
-    let members = match get_members_by_team(db_conn, team_name) {
+    let members = match get_members_by_team(db_conn, &guild_id, team_name) {
         Ok(members) => members,
         Err(e) => {
             send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
@@ -53,6 +85,264 @@ pub async fn handle_show_members(ctx: &Context, msg: &Message, db_conn: &mut PgC
     .await;
 }
 
+/// Handles removing a member from a team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name and the mentioned user.
+/// - Checks that the author may manage the members of the team.
+/// - Deletes the member together with their attendance records.
+/// - Sends a success or error message back to the user.
+pub async fn handle_remove_member(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let Some(target) = msg.mentions.first().filter(|_| args.len() >= 4) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB remove_member <team_name> @user",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) = managed_team(ctx, msg, db_conn, args[2]).await else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    match member_service::remove_member(db_conn, team_id, &target.id.to_string(), &actor) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("{} removed from team '{}'.", target.name, args[2]),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to remove member: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Handles updating the position of a member.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name, the mentioned user and the position.
+/// - Checks that the author may manage the members of the team.
+/// - Updates the position of the member.
+/// - Sends a success or error message back to the user.
+pub async fn handle_set_position(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let Some(target) = msg.mentions.first().filter(|_| args.len() >= 5) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB set_position <team_name> @user <position>",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) = managed_team(ctx, msg, db_conn, args[2]).await else {
+        return;
+    };
+
+    let position = args[4..].join(" ");
+    let actor = message_actor(msg);
+    match member_service::set_position(
+        db_conn,
+        team_id,
+        &target.id.to_string(),
+        &position,
+        &actor,
+    ) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!(
+                    "{} is now '{}' in team '{}'.",
+                    target.name, position, args[2]
+                ),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to update position: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Handles renaming a member.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name, the mentioned user and the new name.
+/// - Checks that the author may manage the members of the team.
+/// - Updates the name the member is listed with.
+/// - Sends a success or error message back to the user.
+pub async fn handle_rename_member(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let Some(target) = msg.mentions.first().filter(|_| args.len() >= 5) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB rename_member <team_name> @user <new_name>",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) = managed_team(ctx, msg, db_conn, args[2]).await else {
+        return;
+    };
+
+    let new_name = args[4..].join(" ");
+    let actor = message_actor(msg);
+    match member_service::rename_member(
+        db_conn,
+        team_id,
+        &target.id.to_string(),
+        &new_name,
+        &actor,
+    ) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("{} is now listed as '{}'.", target.name, new_name),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to rename member: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Handles deactivating a member who left the team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name and the mentioned user.
+/// - Checks that the author may manage the members of the team.
+/// - Records today as the date the member left, keeping their attendance history.
+/// - Sends a success or error message back to the user.
+pub async fn handle_deactivate_member(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let Some(target) = msg.mentions.first().filter(|_| args.len() >= 4) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB deactivate_member <team_name> @user",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) = managed_team(ctx, msg, db_conn, args[2]).await else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    match member_service::deactivate_member(db_conn, team_id, &target.id.to_string(), &actor) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!(
+                    "{} left team '{}'. Use add_member to bring them back.",
+                    target.name, args[2]
+                ),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to deactivate member: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Resolves a team by name in the current server and checks that the author may manage its
+/// members.
+///
+/// # Returns
+/// Returns the ID of the team, or `None` after replying with the reason it failed.
+async fn managed_team(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+) -> Option<i32> {
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return None;
+    };
+
+    let team_id = match find_team_id(db_conn, &guild_id, team_name) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return None;
+        }
+    };
+
+    if let Err(e) = role_service::authorize(
+        db_conn,
+        team_id,
+        &msg.author.id.to_string(),
+        Permission::ManageMembers,
+    ) {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return None;
+    }
+
+    Some(team_id)
+}
+
 /// Sends a message to a specific channel.
 ///
 /// # Arguments
@@ -2,3 +2,18 @@ pub mod teams;
 pub mod attendance;
 pub mod auth;
 pub mod members;
+pub mod api_keys;
+pub mod roles;
+pub mod role_sync;
+pub mod voice;
+pub mod rollcall;
+pub mod args;
+pub mod events;
+pub mod leave;
+pub mod holidays;
+pub mod absence;
+pub mod reminders;
+pub mod corrections;
+pub mod audit;
+pub mod webhooks;
+pub mod presence;
@@ -0,0 +1,360 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, Utc};
+use diesel::PgConnection;
+use serenity::all::{ChannelId, EditMessage, Http, MessageId};
+use serenity::client::Context;
+use serenity::model::channel::Message;
+use tabled::{settings::Style, Table};
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+use crate::bot::adapters::handler::args::parse_channel;
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::adapters::handler::events::authorized_team;
+use crate::bot::application::services::{live_service, team_service};
+use crate::bot::domain::live::Presence;
+use crate::bot::domain::model::Team;
+use crate::bot::domain::permission::Permission;
+use crate::bot::infrastructure::event_bus;
+use crate::config::constant::PRESENCE_BOARD_DEBOUNCE_SECONDS;
+use crate::config::database::DBPool;
+
+/// Longest presence board, below the 2000 character limit of a Discord message.
+const PRESENCE_BOARD_MAX_CHARS: usize = 1900;
+
+/// Handles the `!AB whos_in` command.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - `whos_in <team>` shows the members who are checked in and have not checked out yet.
+///   Requires the owner, manager or viewer role.
+/// - `whos_in <team> board <#channel>` posts a presence board in the channel and pins it. The bot
+///   edits it after every check-in and check-out. Requires the owner role.
+/// - `whos_in <team> board off` removes the presence board. Requires the owner role.
+pub async fn handle_whos_in(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let usage = "Usage: !AB whos_in <team_name> [board <#channel|off>]";
+
+    match (args.len(), args.get(3).copied()) {
+        (3, _) => show_presence(ctx, msg, db_conn, args[2]).await,
+        (5, Some("board")) if args[4] == "off" => remove_board(ctx, msg, db_conn, args[2]).await,
+        (5, Some("board")) => set_board(ctx, msg, db_conn, args[2], args[4]).await,
+        _ => send_message(ctx, &msg.channel_id, usage).await,
+    }
+}
+
+/// Shows who is in a team right now.
+async fn show_presence(ctx: &Context, msg: &Message, db_conn: &mut PgConnection, team_name: &str) {
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ViewAttendance).await
+    else {
+        return;
+    };
+
+    let reply = match live_service::get_presence(db_conn, team_id) {
+        Ok(presence) => format_board(team_name, &presence, Utc::now().naive_utc()),
+        Err(e) => format!("Error: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Posts the presence board of a team in a channel, replacing the previous one.
+async fn set_board(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    channel: &str,
+) {
+    let Some(channel_id) = parse_channel(channel) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "The board channel must be a #channel.",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, team_name, Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    let (team, presence) = match load_board(db_conn, team_id) {
+        Ok(board) => board,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+            return;
+        }
+    };
+
+    let board = format_board(&team.name, &presence, Utc::now().naive_utc());
+    let posted = match channel_id.say(&ctx.http, board).await {
+        Ok(posted) => posted,
+        Err(e) => {
+            let reply = format!(
+                "Could not post the presence board in <#{}>: {}",
+                channel_id, e
+            );
+            send_message(ctx, &msg.channel_id, &reply).await;
+            return;
+        }
+    };
+
+    let actor = message_actor(msg);
+    let channel = channel_id.to_string();
+    let message = posted.id.to_string();
+    if let Err(e) =
+        live_service::set_presence_board(db_conn, team_id, Some(&channel), Some(&message), &actor)
+    {
+        delete_board_message(&ctx.http, &channel, &message).await;
+        send_message(
+            ctx,
+            &msg.channel_id,
+            &format!("Failed to set presence board: {}", e),
+        )
+        .await;
+        return;
+    }
+    if let (Some(old_channel), Some(old_message)) =
+        (&team.presence_channel_id, &team.presence_message_id)
+    {
+        delete_board_message(&ctx.http, old_channel, old_message).await;
+    }
+
+    let reply = match posted.pin(&ctx.http).await {
+        Ok(_) => format!(
+            "📌 Presence board of '{}' pinned in <#{}>, it is updated on every check-in and check-out.",
+            team_name, channel_id
+        ),
+        Err(e) => {
+            println!("Error pinning presence board: {e:?}");
+            format!(
+                "Presence board of '{}' posted in <#{}>, it is updated on every check-in and check-out. It could not be pinned, pin it by hand or allow the bot to manage messages.",
+                team_name, channel_id
+            )
+        }
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Removes the presence board of a team and deletes its message.
+async fn remove_board(ctx: &Context, msg: &Message, db_conn: &mut PgConnection, team_name: &str) {
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, team_name, Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    let team = match team_service::find_team(db_conn, team_id) {
+        Ok(Some(team)) => team,
+        Ok(None) => {
+            send_message(ctx, &msg.channel_id, "Team not found").await;
+            return;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
+    let actor = message_actor(msg);
+    let reply = match live_service::set_presence_board(db_conn, team_id, None, None, &actor) {
+        Ok(_) => {
+            if let (Some(channel), Some(message)) =
+                (&team.presence_channel_id, &team.presence_message_id)
+            {
+                delete_board_message(&ctx.http, channel, message).await;
+            }
+            format!("Presence board of '{}' removed.", team_name)
+        }
+        Err(e) => format!("Failed to remove presence board: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Starts the job keeping the presence boards up to date.
+///
+/// # Arguments
+/// * `http` - The Discord HTTP client used to edit the boards.
+/// * `db_pool` - The connection pool to the database.
+///
+/// # Behavior
+/// - Refreshes every board right away, check-ins made while the bot was offline are not missed.
+/// - After an attendance change, waits `PRESENCE_BOARD_DEBOUNCE_SECONDS` so a burst of changes,
+///   e.g. a roll-call closing, ends up in a single edit of each board.
+/// - Refreshes every board when it fell too far behind the changes.
+/// - Posts a board again when its message was deleted.
+pub fn start_presence_boards(http: Arc<Http>, db_pool: DBPool) {
+    tokio::spawn(async move {
+        let mut updates = event_bus::subscribe();
+        refresh_boards(&http, &db_pool, None).await;
+
+        loop {
+            let mut teams = BTreeSet::new();
+            let mut all = false;
+            match updates.recv().await {
+                Ok(update) => {
+                    teams.insert(update.team_id);
+                }
+                Err(RecvError::Lagged(_)) => all = true,
+                Err(RecvError::Closed) => return,
+            }
+
+            tokio::time::sleep(Duration::from_secs(PRESENCE_BOARD_DEBOUNCE_SECONDS)).await;
+            loop {
+                match updates.try_recv() {
+                    Ok(update) => {
+                        teams.insert(update.team_id);
+                    }
+                    Err(TryRecvError::Lagged(_)) => all = true,
+                    Err(_) => break,
+                }
+            }
+
+            refresh_boards(&http, &db_pool, (!all).then_some(&teams)).await;
+        }
+    });
+}
+
+/// Edits the presence boards of some teams, or of every team when `teams` is `None`.
+async fn refresh_boards(http: &Http, db_pool: &DBPool, teams: Option<&BTreeSet<i32>>) {
+    let boards = match db_pool.get() {
+        Ok(mut db_conn) => match live_service::get_presence_boards(&mut db_conn) {
+            Ok(boards) => boards,
+            Err(e) => {
+                println!("Error refreshing presence boards: {e}");
+                return;
+            }
+        },
+        Err(e) => {
+            println!("Failed to get DB connection: {e:?}");
+            return;
+        }
+    };
+
+    for team in boards {
+        if teams.is_some_and(|teams| !teams.contains(&team.id)) {
+            continue;
+        }
+        if let Err(e) = refresh_board(http, db_pool, &team).await {
+            println!("Error refreshing presence board of team {}: {e}", team.id);
+        }
+    }
+}
+
+/// Edits the presence board of a team, posting it again if its message is gone.
+async fn refresh_board(http: &Http, db_pool: &DBPool, team: &Team) -> Result<(), String> {
+    let Some(channel) = &team.presence_channel_id else {
+        return Ok(());
+    };
+    let channel_id = channel
+        .parse::<ChannelId>()
+        .map_err(|_| format!("Invalid presence channel {}", channel))?;
+
+    let presence = {
+        let mut db_conn = db_pool
+            .get()
+            .map_err(|e| format!("Failed to get DB connection: {e:?}"))?;
+        live_service::get_presence(&mut db_conn, team.id)?
+    };
+    let board = format_board(&team.name, &presence, Utc::now().naive_utc());
+
+    if let Some(message_id) = team
+        .presence_message_id
+        .as_ref()
+        .and_then(|message| message.parse::<MessageId>().ok())
+    {
+        let edit = EditMessage::new().content(&board);
+        match channel_id.edit_message(http, message_id, edit).await {
+            Ok(_) => return Ok(()),
+            Err(e) if !is_not_found(&e) => return Err(e.to_string()),
+            Err(_) => {}
+        }
+    }
+
+    let posted = channel_id
+        .say(http, board)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Err(e) = posted.pin(http).await {
+        println!("Error pinning presence board: {e:?}");
+    }
+    let mut db_conn = db_pool
+        .get()
+        .map_err(|e| format!("Failed to get DB connection: {e:?}"))?;
+    live_service::set_presence_message(&mut db_conn, team.id, channel, &posted.id.to_string())
+}
+
+/// Loads a team together with who is in it.
+fn load_board(db_conn: &mut PgConnection, team_id: i32) -> Result<(Team, Vec<Presence>), String> {
+    let team = team_service::find_team(db_conn, team_id)?.ok_or("Team not found")?;
+    let presence = live_service::get_presence(db_conn, team_id)?;
+    Ok((team, presence))
+}
+
+/// Formats who is in a team, dropping the members that do not fit in a message.
+fn format_board(team_name: &str, presence: &[Presence], now: NaiveDateTime) -> String {
+    let title = format!("🟢 **Who's in: {}** ({})", team_name, presence.len());
+    let updated = format!("Updated <t:{}:R>", now.and_utc().timestamp());
+    if presence.is_empty() {
+        return format!("{}\nNobody is checked in.\n{}", title, updated);
+    }
+
+    let mut shown = presence.len();
+    loop {
+        let table = Table::new(live_service::get_presence_table(&presence[..shown], now))
+            .with(Style::rounded())
+            .to_string();
+        let more = match presence.len() - shown {
+            0 => String::new(),
+            hidden => format!("… and {} more\n", hidden),
+        };
+        let board = format!("{}\n```\n{}\n```\n{}{}", title, table, more, updated);
+        if board.len() <= PRESENCE_BOARD_MAX_CHARS || shown == 1 {
+            return board;
+        }
+        shown -= 1;
+    }
+}
+
+/// Deletes the message of a presence board, logging why if it could not be deleted.
+async fn delete_board_message(http: &Http, channel: &str, message: &str) {
+    let (Ok(channel_id), Ok(message_id)) =
+        (channel.parse::<ChannelId>(), message.parse::<MessageId>())
+    else {
+        return;
+    };
+    if let Err(e) = channel_id.delete_message(http, message_id).await {
+        if !is_not_found(&e) {
+            println!("Error deleting presence board: {e:?}");
+        }
+    }
+}
+
+/// Checks whether Discord answered that a message or channel does not exist.
+fn is_not_found(error: &serenity::Error) -> bool {
+    matches!(error, serenity::Error::Http(e) if e.status_code().is_some_and(|status| status.as_u16() == 404))
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
@@ -0,0 +1,302 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use diesel::PgConnection;
+use serenity::all::{ChannelId, Http, UserId};
+use serenity::client::Context;
+use serenity::model::channel::Message;
+use tabled::{settings::Style, Table};
+
+use crate::bot::adapters::handler::args::parse_time;
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::adapters::handler::events::authorized_team;
+use crate::bot::application::services::reminder_service::{self, Reminder};
+use crate::bot::domain::permission::Permission;
+use crate::bot::domain::reminder::{ReminderKind, ReminderTrigger};
+use crate::config::constant::REMINDER_CHECK_INTERVAL_SECONDS;
+use crate::config::database::DBPool;
+
+/// Handles the `!AB reminders` commands.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - `reminders` shows the reminder settings of the author.
+/// - `reminders on|off` turns the reminders of the author on or off, in every team.
+/// - `reminders quiet <HH:MM> <HH:MM>|off` sets or clears the quiet hours (UTC) of the author.
+/// - `reminders add <team> <check_in|check_out> <before:<minutes>|at:<HH:MM>>` adds a rule
+///   to the team, requires the owner or manager role.
+/// - `reminders list <team>` shows the rules of the team.
+/// - `reminders remove <team> <id>` removes a rule, requires the owner or manager role.
+pub async fn handle_reminders(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let usage = "Usage: !AB reminders [on | off | quiet <HH:MM> <HH:MM> | quiet off] | add <team_name> <check_in|check_out> <before:<minutes>|at:<HH:MM>> | list <team_name> | remove <team_name> <id>";
+
+    match (args.get(2).copied(), args.len()) {
+        (None, _) => show_preference(ctx, msg, db_conn).await,
+        (Some("on"), 3) => set_opted_out(ctx, msg, db_conn, false).await,
+        (Some("off"), 3) => set_opted_out(ctx, msg, db_conn, true).await,
+        (Some("quiet"), 4) if args[3] == "off" => set_quiet_hours(ctx, msg, db_conn, None).await,
+        (Some("quiet"), 5) => {
+            let (Some(start), Some(end)) = (parse_time(args[3]), parse_time(args[4])) else {
+                send_message(
+                    ctx,
+                    &msg.channel_id,
+                    "Quiet hours must be given as HH:MM HH:MM (UTC).",
+                )
+                .await;
+                return;
+            };
+            set_quiet_hours(ctx, msg, db_conn, Some((start, end))).await
+        }
+        (Some("add"), 6) => add_rule(ctx, msg, db_conn, args[3], args[4], args[5]).await,
+        (Some("list"), 4) => list_rules(ctx, msg, db_conn, args[3]).await,
+        (Some("remove"), 5) => remove_rule(ctx, msg, db_conn, args[3], args[4]).await,
+        _ => send_message(ctx, &msg.channel_id, usage).await,
+    }
+}
+
+/// Shows the reminder settings of the author of the message.
+async fn show_preference(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let reply = match reminder_service::get_preference(db_conn, &msg.author.id.to_string()) {
+        Ok(preference) => {
+            let opted_out = preference
+                .as_ref()
+                .is_some_and(|preference| preference.opted_out);
+            let quiet_hours = preference
+                .and_then(|preference| preference.quiet_start.zip(preference.quiet_end))
+                .map(|(start, end)| {
+                    format!("{} to {} UTC", start.format("%H:%M"), end.format("%H:%M"))
+                })
+                .unwrap_or_else(|| "none".to_string());
+            format!(
+                "⏰ Reminders: {}\nQuiet hours: {}",
+                if opted_out { "off" } else { "on" },
+                quiet_hours
+            )
+        }
+        Err(e) => format!("Error: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Turns the reminders of the author of the message on or off.
+async fn set_opted_out(ctx: &Context, msg: &Message, db_conn: &mut PgConnection, opted_out: bool) {
+    let reply =
+        match reminder_service::set_opted_out(db_conn, &msg.author.id.to_string(), opted_out) {
+            Ok(_) if opted_out => "You will no longer get reminders.".to_string(),
+            Ok(_) => "You will get reminders again.".to_string(),
+            Err(e) => format!("Failed to update reminders: {}", e),
+        };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Sets or clears the quiet hours of the author of the message.
+async fn set_quiet_hours(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    hours: Option<(chrono::NaiveTime, chrono::NaiveTime)>,
+) {
+    let reply = match reminder_service::set_quiet_hours(db_conn, &msg.author.id.to_string(), hours)
+    {
+        Ok(_) => match hours {
+            Some((start, end)) => format!(
+                "No reminders will be sent to you from {} to {} UTC.",
+                start.format("%H:%M"),
+                end.format("%H:%M")
+            ),
+            None => "Your quiet hours were cleared.".to_string(),
+        },
+        Err(e) => format!("Failed to update quiet hours: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Adds a reminder rule to a team.
+async fn add_rule(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    kind: &str,
+    trigger: &str,
+) {
+    let (Some(kind), Some(trigger)) = (ReminderKind::parse(kind), ReminderTrigger::parse(trigger))
+    else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "A reminder is `check_in` or `check_out` and fires `before:<minutes>` the schedule start or `at:<HH:MM>` (UTC).",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let reply = match reminder_service::add_rule(
+        db_conn,
+        team_id,
+        kind,
+        trigger,
+        &message_actor(msg),
+    ) {
+        Ok(rule) => format!(
+            "⏰ Reminder #{} added: {} {} on working days.",
+            rule.id, rule.kind, rule.when
+        ),
+        Err(e) => format!("Failed to add reminder: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Shows the reminder rules of a team.
+async fn list_rules(ctx: &Context, msg: &Message, db_conn: &mut PgConnection, team_name: &str) {
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ViewMembers).await
+    else {
+        return;
+    };
+
+    match reminder_service::get_rules(db_conn, team_id) {
+        Ok(rules) if rules.is_empty() => {
+            send_message(ctx, &msg.channel_id, "No reminders.").await;
+        }
+        Ok(rules) => {
+            let table = Table::new(rules).with(Style::rounded()).to_string();
+            send_message(ctx, &msg.channel_id, &format!("```\n{}\n```", table)).await;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+        }
+    }
+}
+
+/// Removes a reminder rule from a team.
+async fn remove_rule(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    rule_id: &str,
+) {
+    let Ok(rule_id) = rule_id.parse::<i32>() else {
+        send_message(ctx, &msg.channel_id, "Reminder ID must be a number").await;
+        return;
+    };
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, team_name, Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    let reply = match reminder_service::remove_rule(db_conn, team_id, rule_id, &actor) {
+        Ok(_) => format!("Reminder #{} removed.", rule_id),
+        Err(e) => format!("Failed to remove reminder: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Starts the job sending the reminders of every team.
+///
+/// # Arguments
+/// * `http` - The Discord HTTP client used to send the DMs.
+/// * `db_pool` - The connection pool to the database.
+///
+/// # Behavior
+/// - Every `REMINDER_CHECK_INTERVAL_SECONDS`, sends the reminders of the rules that are due
+///   in a DM.
+pub fn start_reminders(http: Arc<Http>, db_pool: DBPool) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(REMINDER_CHECK_INTERVAL_SECONDS));
+        loop {
+            interval.tick().await;
+
+            let reminders = match db_pool.get() {
+                Ok(mut db_conn) => due_reminders(&mut db_conn),
+                Err(e) => {
+                    println!("Failed to get DB connection: {e:?}");
+                    continue;
+                }
+            };
+            for reminder in reminders {
+                send_reminder(&http, &reminder).await;
+            }
+        }
+    });
+}
+
+/// Collects the reminders of the rules that are due.
+fn due_reminders(db_conn: &mut PgConnection) -> Vec<Reminder> {
+    let now = Utc::now().naive_utc();
+    let rules = match reminder_service::get_due_rules(db_conn, now) {
+        Ok(rules) => rules,
+        Err(e) => {
+            println!("Error checking reminders: {e}");
+            return Vec::new();
+        }
+    };
+
+    let mut reminders = Vec::new();
+    for (rule, team) in rules {
+        match reminder_service::rule_reminders(db_conn, &rule, &team, now) {
+            Ok(rule_reminders) => reminders.extend(rule_reminders),
+            Err(e) => println!("Error sending reminder #{}: {e}", rule.id),
+        }
+    }
+    reminders
+}
+
+/// Sends a reminder to a member in a DM.
+async fn send_reminder(http: &Http, reminder: &Reminder) {
+    let Ok(user_id) = reminder.discord_id.parse::<UserId>() else {
+        return;
+    };
+    let message = match reminder.kind {
+        ReminderKind::CheckIn => format!(
+            "⏰ You have not checked in to '{}' today: `!AB check_in {} {{status}}`",
+            reminder.team_name, reminder.team_name
+        ),
+        ReminderKind::CheckOut => format!(
+            "⏰ You are still checked in to '{}': `!AB check_out {}`",
+            reminder.team_name, reminder.team_name
+        ),
+    };
+
+    match user_id.create_dm_channel(http).await {
+        Ok(channel) => {
+            if let Err(e) = channel.say(http, message).await {
+                println!("Error sending message: {e:?}");
+            }
+        }
+        Err(e) => println!("Error opening DM channel: {e:?}"),
+    }
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
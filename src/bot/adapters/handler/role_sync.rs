@@ -0,0 +1,353 @@
+use diesel::PgConnection;
+use serenity::all::{GuildId, GuildMemberUpdateEvent, RoleId, User};
+use serenity::client::Context;
+use serenity::model::channel::Message;
+
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::application::services::{member_service, role_service, team_service};
+use crate::bot::domain::audit::AuditActor;
+use crate::bot::domain::permission::Permission;
+
+/// Number of server members fetched per request while syncing a team.
+const MEMBERS_PAGE_SIZE: u64 = 1000;
+
+/// Handles binding a team to a Discord role.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name and the mentioned role.
+/// - Checks that the author owns the team.
+/// - Binds the team to the role, so members follow the holders of the role from now on.
+/// - Sends a success or error message back to the user.
+pub async fn handle_link_role(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let Some(role) = msg.mention_roles.first().filter(|_| args.len() >= 4) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB link_role <team_name> @role",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, args[2], Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    match team_service::link_discord_role(db_conn, team_id, Some(&role.to_string()), &actor) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!(
+                    "Team '{}' now follows <@&{}>. Run `!AB sync_team {}` to import the current role holders.",
+                    args[2], role, args[2]
+                ),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Failed to link role: {}", e)).await;
+        }
+    }
+}
+
+/// Handles removing the Discord role binding of a team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name.
+/// - Checks that the author owns the team.
+/// - Removes the binding, the current members are kept.
+/// - Sends a success or error message back to the user.
+pub async fn handle_unlink_role(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    if args.len() < 3 {
+        send_message(ctx, &msg.channel_id, "Usage: !AB unlink_role <team_name>").await;
+        return;
+    }
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, args[2], Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    match team_service::link_discord_role(db_conn, team_id, None, &message_actor(msg)) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Team '{}' no longer follows a Discord role.", args[2]),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to unlink role: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Handles reconciling the members of a team with the holders of its Discord role.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name.
+/// - Checks that the author may manage the members of the team.
+/// - Fetches the server members holding the role of the team.
+/// - Adds the missing role holders and deactivates members without the role.
+/// - Reports who was added and who was deactivated.
+pub async fn handle_sync_team(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    if args.len() < 3 {
+        send_message(ctx, &msg.channel_id, "Usage: !AB sync_team <team_name>").await;
+        return;
+    }
+
+    let Some(team_id) =
+        authorized_team(ctx, msg, db_conn, args[2], Permission::ManageMembers).await
+    else {
+        return;
+    };
+
+    let role = match team_service::find_team(db_conn, team_id) {
+        Ok(Some(team)) => team
+            .discord_role_id
+            .and_then(|role| role.parse::<RoleId>().ok()),
+        Ok(None) => None,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+    let (Some(role), Some(guild)) = (role, msg.guild_id) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            &format!(
+                "Team '{}' is not linked to a Discord role, use `!AB link_role` first.",
+                args[2]
+            ),
+        )
+        .await;
+        return;
+    };
+
+    let holders = match role_holders(ctx, guild, role).await {
+        Ok(holders) => holders,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
+    let sync = match member_service::sync_members(db_conn, team_id, &holders, &message_actor(msg)) {
+        Ok(sync) => sync,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Failed to sync team: {}", e)).await;
+            return;
+        }
+    };
+
+    let mut report = vec![format!("Team '{}' synced with <@&{}>.", args[2], role)];
+    report.push(match sync.added.is_empty() {
+        true => "➕ Added: none".to_string(),
+        false => format!("➕ Added: {}", sync.added.join(", ")),
+    });
+    report.push(match sync.deactivated.is_empty() {
+        true => "➖ Deactivated: none".to_string(),
+        false => format!("➖ Deactivated: {}", sync.deactivated.join(", ")),
+    });
+    send_message(ctx, &msg.channel_id, &report.join("\n")).await;
+}
+
+/// Handles a change of the roles or nickname of a server member.
+///
+/// # Arguments
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+/// * `event` - The member update event.
+///
+/// # Behavior
+/// - Adds the user to every role bound team whose role they now have.
+/// - Records the changes in the audit log as made by the bot itself.
+/// - Deactivates the user in every role bound team whose role they lost.
+/// - Logs an error for every team that cannot be synced.
+pub fn handle_member_update(db_conn: &mut PgConnection, event: &GuildMemberUpdateEvent) {
+    if event.user.bot {
+        return;
+    }
+
+    let name = event
+        .nick
+        .as_ref()
+        .or(event.user.global_name.as_ref())
+        .unwrap_or(&event.user.name);
+
+    let actor = AuditActor::system(Some(event.guild_id.to_string()));
+    for_each_bound_team(db_conn, event.guild_id, |db_conn, team_id, role| {
+        let has_role = event.roles.iter().any(|held| held.to_string() == role);
+        member_service::sync_member(
+            db_conn,
+            team_id,
+            &event.user.id.to_string(),
+            name,
+            has_role,
+            &actor,
+        )
+    });
+}
+
+/// Handles a user leaving the server.
+///
+/// # Arguments
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild_id` - The ID of the server the user left.
+/// * `user` - The user who left.
+///
+/// # Behavior
+/// - Deactivates the user in every role bound team of the server.
+/// - Records the changes in the audit log as made by the bot itself.
+pub fn handle_member_removal(db_conn: &mut PgConnection, guild_id: GuildId, user: &User) {
+    let actor = AuditActor::system(Some(guild_id.to_string()));
+    for_each_bound_team(db_conn, guild_id, |db_conn, team_id, _| {
+        member_service::sync_member(
+            db_conn,
+            team_id,
+            &user.id.to_string(),
+            &user.name,
+            false,
+            &actor,
+        )
+    });
+}
+
+/// Runs a sync step for every role bound team of a server, logging the failures.
+fn for_each_bound_team(
+    db_conn: &mut PgConnection,
+    guild_id: GuildId,
+    mut sync: impl FnMut(&mut PgConnection, i32, &str) -> Result<(), String>,
+) {
+    let teams = match team_service::get_role_bound_teams(db_conn, &guild_id.to_string()) {
+        Ok(teams) => teams,
+        Err(e) => {
+            println!("Error fetching role bound teams: {e}");
+            return;
+        }
+    };
+
+    for (team_id, role) in teams {
+        if let Err(e) = sync(db_conn, team_id, &role) {
+            println!("Error syncing team {team_id} with role {role}: {e}");
+        }
+    }
+}
+
+/// Fetches the Discord ID and display name of every server member with a role.
+///
+/// # Errors
+/// Returns an error if the members of the server cannot be fetched.
+async fn role_holders(
+    ctx: &Context,
+    guild: GuildId,
+    role: RoleId,
+) -> Result<Vec<(String, String)>, String> {
+    let mut holders = Vec::new();
+    let mut after = None;
+
+    loop {
+        let page = guild
+            .members(&ctx.http, Some(MEMBERS_PAGE_SIZE), after)
+            .await
+            .map_err(|e| format!("Failed to fetch server members: {}", e))?;
+
+        holders.extend(
+            page.iter()
+                .filter(|member| !member.user.bot && member.roles.contains(&role))
+                .map(|member| {
+                    (
+                        member.user.id.to_string(),
+                        member.display_name().to_string(),
+                    )
+                }),
+        );
+
+        match page.last() {
+            Some(last) if page.len() as u64 == MEMBERS_PAGE_SIZE => after = Some(last.user.id),
+            _ => return Ok(holders),
+        }
+    }
+}
+
+/// Resolves a team by name in the current server and checks that the author has a permission.
+///
+/// # Returns
+/// Returns the ID of the team, or `None` after replying with the reason it failed.
+async fn authorized_team(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    permission: Permission,
+) -> Option<i32> {
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return None;
+    };
+
+    let team_id = match team_service::find_team_id(db_conn, &guild_id, team_name) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return None;
+        }
+    };
+
+    if let Err(e) =
+        role_service::authorize(db_conn, team_id, &msg.author.id.to_string(), permission)
+    {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return None;
+    }
+
+    Some(team_id)
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &serenity::model::id::ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
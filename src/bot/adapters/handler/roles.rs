@@ -0,0 +1,232 @@
+use diesel::PgConnection;
+use serenity::client::Context;
+use serenity::model::channel::Message;
+use tabled::{settings::Style, Table};
+
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::application::services::{role_service, team_service};
+use crate::bot::domain::permission::{Permission, Role};
+
+/// Handles granting a team role to a user.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name, the mentioned user and the role.
+/// - Checks that the author may manage roles in the team.
+/// - Grants the role, replacing the current role of the user.
+/// - Sends a success or error message back to the user.
+pub async fn handle_grant_role(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let (Some(target), Some(role)) = (
+        msg.mentions.first(),
+        args.get(4).and_then(|role| Role::parse(role)),
+    ) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB grant_role <team_name> @user <owner|manager|viewer|member>",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, args[2]).await else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    match role_service::grant_role(db_conn, team_id, &target.id.to_string(), role, &actor) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("{} is now {} of team '{}'.", target.name, role, args[2]),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to grant role: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Handles revoking the team role of a user.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name and the mentioned user.
+/// - Checks that the author may manage roles in the team.
+/// - Revokes the role of the user.
+/// - Sends a success or error message back to the user.
+pub async fn handle_revoke_role(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let Some(target) = msg.mentions.first().filter(|_| args.len() >= 4) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB revoke_role <team_name> @user",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, args[2]).await else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    match role_service::revoke_role(db_conn, team_id, &target.id.to_string(), &actor) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!(
+                    "{} no longer has a role in team '{}'.",
+                    target.name, args[2]
+                ),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to revoke role: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Handles displaying the roles of a team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name.
+/// - Checks that the author may view the members of the team.
+/// - Displays the role assignments in a formatted table.
+pub async fn handle_show_roles(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    if args.len() < 3 {
+        send_message(ctx, &msg.channel_id, "Usage: !AB show_roles <team_name>").await;
+        return;
+    }
+
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return;
+    };
+
+    let team_id = match team_service::find_team_id(db_conn, &guild_id, args[2]) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
+    if let Err(e) = role_service::authorize(
+        db_conn,
+        team_id,
+        &msg.author.id.to_string(),
+        Permission::ViewMembers,
+    ) {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return;
+    }
+
+    let roles = match role_service::get_team_roles(db_conn, team_id) {
+        Ok(roles) => roles,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+            return;
+        }
+    };
+
+    let table = Table::new(roles).with(Style::rounded()).to_string();
+    send_message(
+        ctx,
+        &msg.channel_id,
+        &format!("Roles of team '{}':\n```\n{}\n```", args[2], table),
+    )
+    .await;
+}
+
+/// Resolves a team by name in the current server and checks that the author may manage its roles.
+///
+/// # Returns
+/// Returns the ID of the team, or `None` after replying with the reason it failed.
+async fn authorized_team(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+) -> Option<i32> {
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return None;
+    };
+
+    let team_id = match team_service::find_team_id(db_conn, &guild_id, team_name) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return None;
+        }
+    };
+
+    if let Err(e) = role_service::authorize(
+        db_conn,
+        team_id,
+        &msg.author.id.to_string(),
+        Permission::ManageRoles,
+    ) {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return None;
+    }
+
+    Some(team_id)
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &serenity::model::id::ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
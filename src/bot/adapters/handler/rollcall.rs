@@ -0,0 +1,289 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use diesel::PgConnection;
+use serenity::all::{
+    ButtonStyle, ChannelId, ComponentInteraction, CreateActionRow, CreateButton,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditMessage, Http,
+    MessageId,
+};
+use serenity::client::Context;
+use serenity::model::channel::Message;
+
+use crate::bot::adapters::handler::args::parse_duration;
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::application::services::rollcall_service::{self, RollcallTally};
+use crate::bot::application::services::{role_service, team_service};
+use crate::bot::domain::model::Rollcall;
+use crate::bot::domain::permission::Permission;
+use crate::config::database::DBPool;
+
+/// Prefix of the custom IDs of the roll-call "Present" buttons.
+pub const ROLLCALL_PREFIX: &str = "rollcall:";
+
+/// Longest roll-call that can be started, in seconds.
+const MAX_ROLLCALL_SECONDS: i64 = 24 * 60 * 60;
+
+/// Handles starting a roll-call for a team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+/// * `db_pool` - The connection pool used to close the roll-call once it expires.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name and the duration.
+/// - Checks that the author may manage the members of the team.
+/// - Posts a message with a "Present" button, answers are handled by
+///   [`handle_rollcall_answer`].
+/// - Closes the roll-call when the duration has passed.
+pub async fn handle_rollcall(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    db_pool: &DBPool,
+) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let Some(duration) = args
+        .get(3)
+        .and_then(|arg| parse_duration(arg, MAX_ROLLCALL_SECONDS))
+    else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB rollcall <team_name> <duration> (e.g. 90s, 10m, 1h, up to 24h)",
+        )
+        .await;
+        return;
+    };
+
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return;
+    };
+
+    let team_id = match team_service::find_team_id(db_conn, &guild_id, args[2]) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
+    let author_id = msg.author.id.to_string();
+    if let Err(e) = role_service::authorize(db_conn, team_id, &author_id, Permission::ManageMembers)
+    {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return;
+    }
+
+    let rollcall = match rollcall_service::start_rollcall(
+        db_conn,
+        team_id,
+        &msg.channel_id.to_string(),
+        &message_actor(msg),
+        duration,
+    ) {
+        Ok(rollcall) => rollcall,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
+    let closes_at = rollcall.closes_at.and_utc().timestamp();
+    let button = CreateActionRow::Buttons(vec![CreateButton::new(format!(
+        "{}present:{}",
+        ROLLCALL_PREFIX, rollcall.id
+    ))
+    .label("Present")
+    .style(ButtonStyle::Success)]);
+    let announcement = CreateMessage::new()
+        .content(format!(
+            "📋 Roll-call for '{}', click **Present** before <t:{}:t> (<t:{}:R>).",
+            args[2], closes_at, closes_at
+        ))
+        .components(vec![button]);
+
+    let posted = match msg.channel_id.send_message(&ctx.http, announcement).await {
+        Ok(posted) => posted,
+        Err(e) => {
+            println!("Error sending message: {e:?}");
+            if let Err(e) = rollcall_service::discard_rollcall(db_conn, rollcall.id) {
+                println!("Error discarding roll-call {}: {e}", rollcall.id);
+            }
+            return;
+        }
+    };
+
+    let message_id = posted.id.to_string();
+    if let Err(e) = rollcall_service::attach_message(db_conn, rollcall.id, &message_id) {
+        println!("Error saving roll-call {}: {e}", rollcall.id);
+    }
+
+    schedule_close(
+        ctx.http.clone(),
+        db_pool.clone(),
+        Rollcall {
+            message_id: Some(message_id),
+            ..rollcall
+        },
+    );
+}
+
+/// Handles a click on the "Present" button of a roll-call.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `component` - The button interaction.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Records the user as present if they are an active member of the team and the
+///   roll-call is still open.
+/// - Replies with the outcome, only visible to the user who clicked.
+pub async fn handle_rollcall_answer(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    db_conn: &mut PgConnection,
+) {
+    let Some(Ok(rollcall_id)) = component
+        .data
+        .custom_id
+        .trim_start_matches(ROLLCALL_PREFIX)
+        .strip_prefix("present:")
+        .map(|rollcall_id| rollcall_id.parse::<i32>())
+    else {
+        return;
+    };
+
+    let reply = match rollcall_service::mark_present(
+        db_conn,
+        rollcall_id,
+        &component.user.id.to_string(),
+    ) {
+        Ok(_) => "✅ You are marked present.".to_string(),
+        Err(e) => e,
+    };
+
+    let response = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(reply)
+            .ephemeral(true),
+    );
+    if let Err(e) = component.create_response(&ctx.http, response).await {
+        println!("Error responding to interaction: {e:?}");
+    }
+}
+
+/// Schedules the roll-calls that were still open when the bot stopped.
+///
+/// # Arguments
+/// * `http` - The Discord HTTP client used to edit the roll-call messages.
+/// * `db_pool` - The connection pool to the database.
+///
+/// # Behavior
+/// - Closes the overdue roll-calls right away and the others when they expire.
+pub fn resume_rollcalls(http: Arc<Http>, db_pool: &DBPool) {
+    let rollcalls = db_pool
+        .get()
+        .map_err(|e| format!("Failed to get DB connection: {e:?}"))
+        .and_then(|mut db_conn| rollcall_service::get_open_rollcalls(&mut db_conn));
+
+    match rollcalls {
+        Ok(rollcalls) => {
+            for rollcall in rollcalls {
+                schedule_close(http.clone(), db_pool.clone(), rollcall);
+            }
+        }
+        Err(e) => println!("Error resuming roll-calls: {e}"),
+    }
+}
+
+/// Closes a roll-call once it expires and edits the tally into its message.
+fn schedule_close(http: Arc<Http>, db_pool: DBPool, rollcall: Rollcall) {
+    tokio::spawn(async move {
+        let remaining = (rollcall.closes_at - Utc::now().naive_utc())
+            .to_std()
+            .unwrap_or_default();
+        tokio::time::sleep(remaining).await;
+
+        let tally = db_pool
+            .get()
+            .map_err(|e| format!("Failed to get DB connection: {e:?}"))
+            .and_then(|mut db_conn| rollcall_service::close_rollcall(&mut db_conn, rollcall.id));
+        let tally = match tally {
+            Ok(Some(tally)) => tally,
+            Ok(None) => return,
+            Err(e) => {
+                println!("Error closing roll-call {}: {e}", rollcall.id);
+                return;
+            }
+        };
+
+        let (Ok(channel_id), Some(Ok(message_id))) = (
+            rollcall.channel_id.parse::<ChannelId>(),
+            rollcall
+                .message_id
+                .as_deref()
+                .map(|message_id| message_id.parse::<MessageId>()),
+        ) else {
+            return;
+        };
+
+        let edit = EditMessage::new()
+            .content(format_tally(&tally))
+            .components(vec![]);
+        if let Err(e) = channel_id.edit_message(&http, message_id, edit).await {
+            println!("Error editing roll-call {}: {e:?}", rollcall.id);
+        }
+    });
+}
+
+/// Formats the tally of a closed roll-call.
+fn format_tally(tally: &RollcallTally) -> String {
+    let names = |names: &[String]| match names.is_empty() {
+        true => "none".to_string(),
+        false => names.join(", "),
+    };
+
+    let mut tally_message = format!(
+        "📋 Roll-call for '{}' closed.\n✅ Present ({}): {}\n❌ Absent ({}): {}",
+        tally.team_name,
+        tally.present.len(),
+        names(&tally.present),
+        tally.absent.len(),
+        names(&tally.absent)
+    );
+    if !tally.excused.is_empty() {
+        tally_message.push_str(&format!(
+            "\n🌴 Excused ({}): {}",
+            tally.excused.len(),
+            names(&tally.excused)
+        ));
+    }
+
+    tally_message
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
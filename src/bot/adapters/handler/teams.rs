@@ -1,11 +1,18 @@
-use diesel::query_dsl::methods::{FilterDsl, SelectDsl};
+use diesel::query_dsl::methods::FilterDsl;
 use diesel::{ExpressionMethods as _, PgConnection, RunQueryDsl};
+use serenity::all::{
+    ButtonStyle, ComponentInteraction, CreateActionRow, CreateButton, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, GuildId,
+};
 use serenity::client::Context;
 use serenity::model::channel::Message;
 use tabled::{settings::Style, Table};
 
+use crate::bot::adapters::handler::audit::{component_actor, message_actor};
+use crate::bot::application::services::role_service;
 use crate::bot::application::services::team_service::{self, show_team};
 use crate::bot::domain::model::User;
+use crate::bot::domain::permission::Permission;
 
 /// Handles the creation of a new team.
 ///
@@ -15,9 +22,10 @@ use crate::bot::domain::model::User;
 /// * `db_conn` - A mutable reference to the PostgreSQL connection.
 ///
 /// # Behavior
+/// - Only works in a server, since team names are unique per server.
 /// - Parses the command arguments to extract the team name.
 /// - Fetches the admin user from the database.
-/// - Registers the team in the database.
+/// - Registers the team in the server with the admin as its owner.
 /// - Sends a success or error message back to the user.
 pub async fn handle_create_team(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
     let args: Vec<&str> = msg.content.split_whitespace().collect();
@@ -26,12 +34,22 @@ pub async fn handle_create_team(ctx: &Context, msg: &Message, db_conn: &mut PgCo
         return;
     }
 
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return;
+    };
+
     let team_name = args[2];
 
     use crate::schema::users::dsl::*;
     let dc_user_id = msg.author.id.to_string();
     let admin = match users
-        .filter(discord_id.eq(dc_user_id))
+        .filter(discord_id.eq(&dc_user_id))
         .first::<User>(db_conn)
     {
         Ok(admin) => admin,
@@ -51,7 +69,8 @@ pub async fn handle_create_team(ctx: &Context, msg: &Message, db_conn: &mut PgCo
         }
     };
 
-    match team_service::register_team(db_conn, team_name, admin.id) {
+    let actor = message_actor(msg);
+    match team_service::register_team(db_conn, &guild_id, team_name, admin.id, &actor) {
         Ok(_) => {
             send_message(
                 ctx,
@@ -66,7 +85,7 @@ pub async fn handle_create_team(ctx: &Context, msg: &Message, db_conn: &mut PgCo
     }
 }
 
-/// Handles adding a member to a team.
+/// Handles adding one or more members to a team.
 ///
 /// # Arguments
 /// * `ctx` - The context of the event.
@@ -74,69 +93,140 @@ pub async fn handle_create_team(ctx: &Context, msg: &Message, db_conn: &mut PgCo
 /// * `db_conn` - A mutable reference to the PostgreSQL connection.
 ///
 /// # Behavior
-/// - Parses the command arguments to extract the team name, user ID, and username.
-/// - Fetches the team ID from the database.
-/// - Adds the member to the team in the database.
-/// - Sends a success or error message back to the user.
+/// - Parses the command arguments to extract the team name and an optional `as <name>`.
+/// - Resolves the users from the mentions of the message, ignoring role and channel mentions.
+/// - Fetches the team ID of the server from the database.
+/// - Checks that the user may manage the members of the team.
+/// - Checks that every mentioned user is in the server and defaults their name to their
+///   server nickname, unless a name was given for a single mentioned user.
+/// - Adds the members to the team and reports the outcome for each of them.
 pub async fn handle_add_member(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let usage = "Usage: !AB add_member <team_name> @user [@user ...] [as <full name>]";
     let args: Vec<&str> = msg.content.split_whitespace().collect();
-    if args.len() < 6 {
+
+    // Replies also list the author of the referenced message, only keep users typed in the command
+    let users: Vec<_> = msg
+        .mentions
+        .iter()
+        .filter(|user| {
+            msg.content.contains(&format!("<@{}>", user.id))
+                || msg.content.contains(&format!("<@!{}>", user.id))
+        })
+        .collect();
+    if args.len() < 4 || users.is_empty() {
+        send_message(ctx, &msg.channel_id, usage).await;
+        return;
+    }
+
+    let full_name = display_name_argument(&args);
+    if full_name.as_deref() == Some("") {
+        send_message(ctx, &msg.channel_id, usage).await;
+        return;
+    }
+    if full_name.is_some() && users.len() > 1 {
         send_message(
             ctx,
             &msg.channel_id,
-            "Usage: !AB add_member <team_name> @user as User_full_name",
+            "A name can only be given when adding a single member.",
         )
         .await;
         return;
     }
 
-    let user_id = args[3]
-        .replace("<@!", "")
-        .replace("<@", "")
-        .replace(">", "");
-
-    let username = args[5].to_string();
+    let Some(guild) = msg.guild_id else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return;
+    };
 
-    use crate::schema::teams::dsl::*;
-    let team_name = args[2];
-    let team_id = match teams
-        .filter(name.eq(team_name))
-        .select(id)
-        .first::<i32>(db_conn)
-    {
-        Ok(ids) => ids,
-        Err(diesel::NotFound) => {
-            send_message(ctx, &msg.channel_id, "Team not found!").await;
-            return;
-        }
+    let team_id = match team_service::find_team_id(db_conn, &guild.to_string(), args[2]) {
+        Ok(team_id) => team_id,
         Err(e) => {
-            println!("Database error: {:?}", e);
-            send_message(
-                ctx,
-                &msg.channel_id,
-                "An error occurred while fetching team.",
-            )
-            .await;
+            send_message(ctx, &msg.channel_id, &e).await;
             return;
         }
     };
 
-    match team_service::add_member(db_conn, &user_id, username, team_id) {
-        Ok(_) => {
-            send_message(
-                ctx,
-                &msg.channel_id,
-                &format!("Member {} assigned successfully", &user_id),
-            )
-            .await;
-        }
-        Err(_) => {
-            send_message(ctx, &msg.channel_id, "Failed to assign member.").await;
-        }
+    if let Err(e) = role_service::authorize(
+        db_conn,
+        team_id,
+        &msg.author.id.to_string(),
+        Permission::ManageMembers,
+    ) {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return;
+    }
+
+    let actor = message_actor(msg);
+    let mut report = Vec::with_capacity(users.len());
+    for user in users {
+        let outcome = match guild_display_name(ctx, guild, user).await {
+            Ok(nickname) => {
+                let username = full_name.clone().unwrap_or(nickname);
+                team_service::add_member(
+                    db_conn,
+                    &user.id.to_string(),
+                    username.clone(),
+                    team_id,
+                    &actor,
+                )
+                .map(|_| username)
+            }
+            Err(e) => Err(e),
+        };
+
+        report.push(match outcome {
+            Ok(username) => format!(
+                "✅ {} added to team '{}' as {}",
+                user.name, args[2], username
+            ),
+            Err(e) => format!("❌ {}: {}", user.name, e),
+        });
     }
+
+    send_message(ctx, &msg.channel_id, &report.join("\n")).await;
+}
+
+/// Reads the display name of an `add_member` command.
+///
+/// # Returns
+/// Returns everything after an `as` that directly follows the last mention, so team names
+/// containing the word are not mistaken for it.
+fn display_name_argument(args: &[&str]) -> Option<String> {
+    let last_mention = args.iter().rposition(|arg| arg.starts_with("<@"))?;
+    args.get(last_mention + 1)
+        .filter(|arg| arg.eq_ignore_ascii_case("as"))
+        .map(|_| args[last_mention + 2..].join(" "))
 }
 
-/// Handles displaying the teams created by the admin.
+/// Looks up a mentioned user in the server.
+///
+/// # Returns
+/// Returns the name the user is displayed with in the server.
+///
+/// # Errors
+/// Returns an error if the user is a bot or is not a member of the server.
+async fn guild_display_name(
+    ctx: &Context,
+    guild: GuildId,
+    user: &serenity::model::user::User,
+) -> Result<String, String> {
+    if user.bot {
+        return Err("Bots cannot be team members".to_string());
+    }
+
+    guild
+        .member(&ctx.http, user.id)
+        .await
+        .map(|member| member.display_name().to_string())
+        .map_err(|_| "User is not a member of this server".to_string())
+}
+
+/// Handles displaying the teams created by the admin in the current server.
 ///
 /// # Arguments
 /// * `ctx` - The context of the event.
@@ -144,13 +234,23 @@ pub async fn handle_add_member(ctx: &Context, msg: &Message, db_conn: &mut PgCon
 /// * `db_conn` - A mutable reference to the PostgreSQL connection.
 ///
 /// # Behavior
-/// - Fetches the teams created by the admin from the database.
+/// - Fetches the teams created by the admin in the server from the database.
 /// - Displays the teams in a formatted table.
 /// - Sends the table as a message back to the user.
 pub async fn handle_show_team(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return;
+    };
+
     let admin_discord_id = msg.author.id.to_string();
 
-    let teams = match show_team(db_conn, &admin_discord_id) {
+    let teams = match show_team(db_conn, &guild_id, &admin_discord_id) {
         Ok(teams) => teams,
         Err(e) => {
             send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
@@ -176,6 +276,316 @@ pub async fn handle_show_team(ctx: &Context, msg: &Message, db_conn: &mut PgConn
     .await;
 }
 
+/// Handles renaming a team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the current and the new team name.
+/// - Checks that the author owns the team.
+/// - Renames the team and sends a success or error message back to the user.
+pub async fn handle_rename_team(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    if args.len() < 4 {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB rename_team <team_name> <new_name>",
+        )
+        .await;
+        return;
+    }
+
+    let Some(team_id) = owned_team(ctx, msg, db_conn, args[2]).await else {
+        return;
+    };
+
+    match team_service::rename_team(db_conn, team_id, args[3], &message_actor(msg)) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Team '{}' renamed to '{}'.", args[2], args[3]),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to rename team: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Handles archiving a team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name.
+/// - Checks that the author owns the team.
+/// - Archives the team, which hides it from `show_team` and makes it read-only.
+/// - Sends a success or error message back to the user.
+pub async fn handle_archive_team(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    if args.len() < 3 {
+        send_message(ctx, &msg.channel_id, "Usage: !AB archive_team <team_name>").await;
+        return;
+    }
+
+    let Some(team_id) = owned_team(ctx, msg, db_conn, args[2]).await else {
+        return;
+    };
+
+    match team_service::archive_team(db_conn, team_id, true, &message_actor(msg)) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Team '{}' archived, it is now read-only.", args[2]),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to archive team: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Handles transferring the ownership of a team to another admin.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name and the mentioned user.
+/// - Checks that the author owns the team.
+/// - Makes the mentioned admin the owner and demotes the previous creator to manager.
+/// - Sends a success or error message back to the user.
+pub async fn handle_transfer_team(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let Some(target) = msg.mentions.first().filter(|_| args.len() >= 4) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB transfer_team <team_name> @user",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) = owned_team(ctx, msg, db_conn, args[2]).await else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    match team_service::transfer_team(db_conn, team_id, &target.id.to_string(), &actor) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Team '{}' now belongs to {}.", args[2], target.name),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to transfer team: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Handles deleting a team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name.
+/// - Checks that the author owns the team.
+/// - Asks for confirmation with buttons, the team is deleted by
+///   [`handle_delete_team_confirmation`] once confirmed.
+pub async fn handle_delete_team(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    if args.len() < 3 {
+        send_message(ctx, &msg.channel_id, "Usage: !AB delete_team <team_name>").await;
+        return;
+    }
+
+    let Some(team_id) = owned_team(ctx, msg, db_conn, args[2]).await else {
+        return;
+    };
+
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{}confirm:{}", DELETE_TEAM_PREFIX, team_id))
+            .label("Delete")
+            .style(ButtonStyle::Danger),
+        CreateButton::new(format!("{}cancel:{}", DELETE_TEAM_PREFIX, team_id))
+            .label("Cancel")
+            .style(ButtonStyle::Secondary),
+    ]);
+    let confirmation = CreateMessage::new()
+        .content(format!(
+            "Delete team '{}' with all its members, attendance records and API keys? This cannot be undone.",
+            args[2]
+        ))
+        .components(vec![buttons]);
+
+    if let Err(e) = msg.channel_id.send_message(&ctx.http, confirmation).await {
+        println!("Error sending message: {e:?}");
+    }
+}
+
+/// Prefix of the custom IDs of the `delete_team` confirmation buttons.
+pub const DELETE_TEAM_PREFIX: &str = "delete_team:";
+
+/// Handles a click on one of the `delete_team` confirmation buttons.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `component` - The button interaction.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Checks that the user clicking the button owns the team.
+/// - Deletes the team when confirmed, or leaves it untouched when cancelled.
+/// - Replaces the confirmation message with the outcome.
+pub async fn handle_delete_team_confirmation(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    db_conn: &mut PgConnection,
+) {
+    let action = component
+        .data
+        .custom_id
+        .trim_start_matches(DELETE_TEAM_PREFIX);
+    let Some((confirmed, Ok(team_id))) = action
+        .split_once(':')
+        .map(|(choice, team_id)| (choice == "confirm", team_id.parse::<i32>()))
+    else {
+        return;
+    };
+
+    let outcome = match role_service::authorize(
+        db_conn,
+        team_id,
+        &component.user.id.to_string(),
+        Permission::ManageTeam,
+    ) {
+        Err(e) => {
+            respond(
+                ctx,
+                component,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(e)
+                        .ephemeral(true),
+                ),
+            )
+            .await;
+            return;
+        }
+        Ok(_) if !confirmed => "Team deletion cancelled.".to_string(),
+        Ok(_) => match team_service::find_team(db_conn, team_id) {
+            Ok(Some(team)) => match team_service::delete_team(
+                db_conn,
+                team_id,
+                &component_actor(component),
+            ) {
+                Ok(_) => format!("Team '{}' deleted.", team.name),
+                Err(e) => format!("Failed to delete team: {}", e),
+            },
+            Ok(None) => "Team was already deleted.".to_string(),
+            Err(e) => format!("Failed to delete team: {}", e),
+        },
+    };
+
+    respond(
+        ctx,
+        component,
+        CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new()
+                .content(outcome)
+                .components(vec![]),
+        ),
+    )
+    .await;
+}
+
+/// Resolves a team by name in the current server and checks that the author owns it.
+///
+/// # Returns
+/// Returns the ID of the team, or `None` after replying with the reason it failed.
+async fn owned_team(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+) -> Option<i32> {
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return None;
+    };
+
+    let team_id = match team_service::find_team_id(db_conn, &guild_id, team_name) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return None;
+        }
+    };
+
+    if let Err(e) = role_service::authorize(
+        db_conn,
+        team_id,
+        &msg.author.id.to_string(),
+        Permission::ManageTeam,
+    ) {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return None;
+    }
+
+    Some(team_id)
+}
+
+/// Responds to a component interaction, logging an error if it fails.
+async fn respond(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    response: CreateInteractionResponse,
+) {
+    if let Err(e) = component.create_response(&ctx.http, response).await {
+        println!("Error responding to interaction: {e:?}");
+    }
+}
+
 /// Sends a message to a specific channel.
 ///
 /// # Arguments
@@ -191,3 +601,29 @@ async fn send_message(ctx: &Context, channel_id: &serenity::model::id::ChannelId
         println!("Error sending message: {e:?}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_name_follows_the_last_mention() {
+        let args = ["!AB", "add_member", "dev", "<@1>", "as", "Jane", "Doe"];
+        assert_eq!(display_name_argument(&args).as_deref(), Some("Jane Doe"));
+
+        let args = ["!AB", "add_member", "dev", "<@!1>", "<@2>"];
+        assert_eq!(display_name_argument(&args), None);
+
+        let args = ["!AB", "add_member", "dev", "<@1>", "as"];
+        assert_eq!(display_name_argument(&args).as_deref(), Some(""));
+    }
+
+    #[test]
+    fn team_named_as_is_not_the_keyword() {
+        let args = ["!AB", "add_member", "as", "<@1>"];
+        assert_eq!(display_name_argument(&args), None);
+
+        let args = ["!AB", "add_member", "as", "<@1>", "as", "Jane"];
+        assert_eq!(display_name_argument(&args).as_deref(), Some("Jane"));
+    }
+}
@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use diesel::PgConnection;
+use serenity::all::{Channel, ChannelId, ChannelType, VoiceState};
+use serenity::client::Context;
+use serenity::model::channel::Message;
+
+use crate::bot::adapters::handler::args::parse_channel;
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::application::services::{role_service, team_service, voice_service};
+use crate::bot::domain::permission::Permission;
+use crate::config::database::DBPool;
+
+/// Keeps track of the members who left a voice channel and are waiting to be checked out.
+///
+/// Every scheduled check-out gets a ticket, rejoining before the debounce expires cancels
+/// it so a short reconnect does not split the session.
+#[derive(Default)]
+pub struct PendingCheckOuts {
+    next_ticket: AtomicU64,
+    pending: Mutex<HashMap<(i32, String), u64>>,
+}
+
+impl PendingCheckOuts {
+    /// Schedules a check-out and returns its ticket, replacing any earlier one.
+    fn schedule(&self, team_id: i32, discord_id: &str) -> u64 {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        self.lock()
+            .insert((team_id, discord_id.to_string()), ticket);
+        ticket
+    }
+
+    /// Cancels a scheduled check-out, returns whether one was pending.
+    fn cancel(&self, team_id: i32, discord_id: &str) -> bool {
+        self.lock()
+            .remove(&(team_id, discord_id.to_string()))
+            .is_some()
+    }
+
+    /// Returns whether a check-out is scheduled.
+    fn is_pending(&self, team_id: i32, discord_id: &str) -> bool {
+        self.lock().contains_key(&(team_id, discord_id.to_string()))
+    }
+
+    /// Claims a scheduled check-out, returns `false` if it was cancelled or replaced since.
+    fn claim(&self, team_id: i32, discord_id: &str, ticket: u64) -> bool {
+        let mut pending = self.lock();
+        let key = (team_id, discord_id.to_string());
+        if pending.get(&key) != Some(&ticket) {
+            return false;
+        }
+        pending.remove(&key);
+        true
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(i32, String), u64>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Handles linking a voice channel to a team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name and the voice channel.
+/// - Checks that the author owns the team and that the channel is a voice channel of the server.
+/// - Links the channel, members joining it are checked in from now on.
+/// - Sends a success or error message back to the user.
+pub async fn handle_link_voice(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let Some(channel) = args.get(3).and_then(|arg| parse_channel(arg)) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB link_voice <team_name> <#voice-channel>",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, args[2], Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    if let Err(e) = ensure_voice_channel(ctx, msg, channel).await {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return;
+    }
+
+    let actor = message_actor(msg);
+    match voice_service::link_channel(db_conn, team_id, &channel.to_string(), &actor) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!(
+                    "Members of '{}' joining <#{}> are now checked in automatically.",
+                    args[2], channel
+                ),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to link voice channel: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Handles unlinking a voice channel from a team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Parses the command arguments to extract the team name and the voice channel.
+/// - Checks that the author owns the team.
+/// - Unlinks the channel.
+/// - Sends a success or error message back to the user.
+pub async fn handle_unlink_voice(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let Some(channel) = args.get(3).and_then(|arg| parse_channel(arg)) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB unlink_voice <team_name> <#voice-channel>",
+        )
+        .await;
+        return;
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, args[2], Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    match voice_service::unlink_channel(db_conn, team_id, &channel.to_string(), &actor) {
+        Ok(_) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("<#{}> is no longer linked to '{}'.", channel, args[2]),
+            )
+            .await;
+        }
+        Err(e) => {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                &format!("Failed to unlink voice channel: {}", e),
+            )
+            .await;
+        }
+    }
+}
+
+/// Handles showing and changing the voice attendance settings of a team.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - Without settings, shows the linked voice channels and the current settings.
+/// - With a minimum duration and a debounce, checks that the author owns the team and
+///   updates the settings.
+/// - Sends the result or an error message back to the user.
+pub async fn handle_voice_settings(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    if args.len() != 3 && args.len() != 5 {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "Usage: !AB voice_settings <team_name> [<min_minutes> <debounce_seconds>]",
+        )
+        .await;
+        return;
+    }
+
+    if args.len() == 5 {
+        let (Ok(min_minutes), Ok(debounce_seconds)) =
+            (args[3].parse::<i32>(), args[4].parse::<i32>())
+        else {
+            send_message(
+                ctx,
+                &msg.channel_id,
+                "Minimum duration and debounce must be numbers.",
+            )
+            .await;
+            return;
+        };
+
+        let Some(team_id) =
+            authorized_team(ctx, msg, db_conn, args[2], Permission::ManageTeam).await
+        else {
+            return;
+        };
+
+        let reply = match voice_service::set_voice_settings(
+            db_conn,
+            team_id,
+            min_minutes,
+            debounce_seconds,
+            &message_actor(msg),
+        ) {
+            Ok(_) => format!("Voice settings of '{}' updated.", args[2]),
+            Err(e) => format!("Failed to update voice settings: {}", e),
+        };
+        send_message(ctx, &msg.channel_id, &reply).await;
+        return;
+    }
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, args[2], Permission::ViewMembers).await
+    else {
+        return;
+    };
+
+    let team = match team_service::find_team(db_conn, team_id) {
+        Ok(Some(team)) => team,
+        Ok(None) => {
+            send_message(ctx, &msg.channel_id, "Team not found").await;
+            return;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+    let channels = match voice_service::get_channels(db_conn, team_id) {
+        Ok(channels) if channels.is_empty() => "none".to_string(),
+        Ok(channels) => channels
+            .iter()
+            .map(|channel| format!("<#{}>", channel))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return;
+        }
+    };
+
+    send_message(
+        ctx,
+        &msg.channel_id,
+        &format!(
+            "🎙 Voice attendance of '{}'\nChannels: {}\nMinimum duration: {} minutes\nDebounce: {} seconds",
+            team.name, channels, team.voice_min_minutes, team.voice_debounce_seconds
+        ),
+    )
+    .await;
+}
+
+/// Handles a member joining, moving between or leaving voice channels.
+///
+/// # Arguments
+/// * `db_pool` - The connection pool to the database.
+/// * `pending` - The check-outs waiting for their debounce to expire.
+/// * `state` - The new voice state of the member.
+///
+/// # Behavior
+/// - Checks the member in for every team linked to the channel they are in, cancelling a
+///   pending check-out when they reconnect within the debounce.
+/// - Schedules a check-out for every other voice team of the server in which the member
+///   has a voice session open, the check-out runs once the debounce expired.
+/// - Logs an error for every team that cannot be updated.
+pub async fn handle_voice_state_update(
+    db_pool: &DBPool,
+    pending: &Arc<PendingCheckOuts>,
+    state: &VoiceState,
+) {
+    let Some(guild_id) = state.guild_id else {
+        return;
+    };
+    if state.member.as_ref().is_some_and(|member| member.user.bot) {
+        return;
+    }
+
+    let mut db_conn = match db_pool.get() {
+        Ok(conn) => conn,
+        Err(e) => {
+            println!("Failed to get DB connection: {e:?}");
+            return;
+        }
+    };
+
+    let voice_teams = match voice_service::get_voice_teams(&mut db_conn, &guild_id.to_string()) {
+        Ok(voice_teams) => voice_teams,
+        Err(e) => {
+            println!("Error fetching voice teams: {e}");
+            return;
+        }
+    };
+
+    let discord_id = state.user_id.to_string();
+    let channel = state.channel_id.map(|channel| channel.to_string());
+
+    for voice in voice_teams {
+        if channel
+            .as_ref()
+            .is_some_and(|channel| voice.channels.contains(channel))
+        {
+            if pending.cancel(voice.team_id, &discord_id) {
+                continue;
+            }
+            if let Err(e) = voice_service::start_session(&mut db_conn, voice.team_id, &discord_id) {
+                println!(
+                    "Error checking in {discord_id} to team {}: {e}",
+                    voice.team_id
+                );
+            }
+            continue;
+        }
+
+        if pending.is_pending(voice.team_id, &discord_id) {
+            continue;
+        }
+        match voice_service::has_open_session(&mut db_conn, voice.team_id, &discord_id) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                println!(
+                    "Error checking out {discord_id} of team {}: {e}",
+                    voice.team_id
+                );
+                continue;
+            }
+        }
+
+        let ticket = pending.schedule(voice.team_id, &discord_id);
+        tokio::spawn(check_out_after_debounce(
+            db_pool.clone(),
+            Arc::clone(pending),
+            voice,
+            discord_id.clone(),
+            ticket,
+        ));
+    }
+}
+
+/// Checks a member out once the debounce expired, unless they rejoined in the meantime.
+async fn check_out_after_debounce(
+    db_pool: DBPool,
+    pending: Arc<PendingCheckOuts>,
+    voice: voice_service::VoiceTeam,
+    discord_id: String,
+    ticket: u64,
+) {
+    tokio::time::sleep(Duration::from_secs(voice.debounce_seconds.max(0) as u64)).await;
+
+    if !pending.claim(voice.team_id, &discord_id, ticket) {
+        return;
+    }
+
+    let result = db_pool
+        .get()
+        .map_err(|e| format!("Failed to get DB connection: {e:?}"))
+        .and_then(|mut db_conn| {
+            voice_service::end_session(&mut db_conn, voice.team_id, &discord_id, voice.min_minutes)
+        });
+    if let Err(e) = result {
+        println!(
+            "Error checking out {discord_id} of team {}: {e}",
+            voice.team_id
+        );
+    }
+}
+
+/// Checks that a channel is a voice or stage channel of the server the message was sent in.
+///
+/// # Errors
+/// Returns an error if the channel cannot be fetched or is not a voice channel of the server.
+async fn ensure_voice_channel(
+    ctx: &Context,
+    msg: &Message,
+    channel: ChannelId,
+) -> Result<(), String> {
+    match channel.to_channel(&ctx.http).await {
+        Ok(Channel::Guild(channel))
+            if Some(channel.guild_id) == msg.guild_id
+                && matches!(channel.kind, ChannelType::Voice | ChannelType::Stage) =>
+        {
+            Ok(())
+        }
+        Ok(_) => Err("That is not a voice channel of this server.".to_string()),
+        Err(e) => Err(format!("Failed to fetch channel: {}", e)),
+    }
+}
+
+/// Resolves a team by name in the current server and checks that the author has a permission.
+///
+/// # Returns
+/// Returns the ID of the team, or `None` after replying with the reason it failed.
+async fn authorized_team(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    permission: Permission,
+) -> Option<i32> {
+    let Some(guild_id) = msg.guild_id.map(|guild| guild.to_string()) else {
+        send_message(
+            ctx,
+            &msg.channel_id,
+            "This command can only be used in a server.",
+        )
+        .await;
+        return None;
+    };
+
+    let team_id = match team_service::find_team_id(db_conn, &guild_id, team_name) {
+        Ok(team_id) => team_id,
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &e).await;
+            return None;
+        }
+    };
+
+    if let Err(e) =
+        role_service::authorize(db_conn, team_id, &msg.author.id.to_string(), permission)
+    {
+        send_message(ctx, &msg.channel_id, &e).await;
+        return None;
+    }
+
+    Some(team_id)
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
@@ -0,0 +1,299 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use diesel::PgConnection;
+use serenity::all::ChannelId;
+use serenity::client::Context;
+use serenity::model::channel::Message;
+use tabled::{settings::Style, Table};
+
+use crate::bot::adapters::handler::audit::message_actor;
+use crate::bot::adapters::handler::events::authorized_team;
+use crate::bot::application::services::webhook_service;
+use crate::bot::domain::model::{Webhook, WebhookDelivery};
+use crate::bot::domain::permission::Permission;
+use crate::bot::domain::webhook::{self, DeliveryStatus, WEBHOOK_EVENTS};
+use crate::bot::infrastructure::webhook_client;
+use crate::config::constant::WEBHOOK_DELIVERY_INTERVAL_SECONDS;
+use crate::config::database::DBPool;
+
+/// Handles the `!AB webhook` commands. Every command requires the owner role.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `msg` - The message that triggered the event.
+/// * `db_conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Behavior
+/// - `webhook add <team> <url> <events|all>` adds a webhook subscribed to a comma separated
+///   list of events. Its signing secret is sent to the author in a DM, never in the channel.
+/// - `webhook list <team>` shows the webhooks of the team with their pending and failed
+///   deliveries.
+/// - `webhook remove <team> <id>` removes a webhook together with its delivery log.
+/// - `webhook deliveries <team> <id>` shows the latest deliveries of a webhook.
+/// - `webhook replay <team> <delivery_id>` sends a delivery again.
+pub async fn handle_webhook(ctx: &Context, msg: &Message, db_conn: &mut PgConnection) {
+    let args: Vec<&str> = msg.content.split_whitespace().collect();
+    let usage = "Usage: !AB webhook add <team_name> <url> <events|all> | list <team_name> | remove <team_name> <id> | deliveries <team_name> <id> | replay <team_name> <delivery_id>";
+
+    match (args.get(2).copied(), args.len()) {
+        (Some("add"), 6) => add_webhook(ctx, msg, db_conn, args[3], args[4], args[5]).await,
+        (Some("list"), 4) => list_webhooks(ctx, msg, db_conn, args[3]).await,
+        (Some("remove"), 5) => remove_webhook(ctx, msg, db_conn, args[3], args[4]).await,
+        (Some("deliveries"), 5) => show_deliveries(ctx, msg, db_conn, args[3], args[4]).await,
+        (Some("replay"), 5) => replay_delivery(ctx, msg, db_conn, args[3], args[4]).await,
+        _ => send_message(ctx, &msg.channel_id, usage).await,
+    }
+}
+
+/// Adds a webhook to a team and sends its secret to the author in a DM.
+async fn add_webhook(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    url: &str,
+    events: &str,
+) {
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, team_name, Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    let webhook =
+        match webhook_service::add_webhook(db_conn, team_id, url, events, &message_actor(msg)) {
+            Ok(webhook) => webhook,
+            Err(e) => {
+                let reply = format!(
+                    "Failed to add webhook: {}\nEvents: {} or all",
+                    e,
+                    webhook::join(&WEBHOOK_EVENTS)
+                );
+                send_message(ctx, &msg.channel_id, &reply).await;
+                return;
+            }
+        };
+
+    let secret = format!(
+        "🔑 Secret of webhook #{} of '{}': `{}`\nCheck the `X-Attendance-Signature` header of each delivery with it. It will not be shown again.",
+        webhook.id, team_name, webhook.secret
+    );
+    let reply = match msg.author.create_dm_channel(&ctx.http).await {
+        Ok(channel) => match channel.say(&ctx.http, secret).await {
+            Ok(_) => format!(
+                "🔗 Webhook #{} added for {}. Its secret was sent to you in a DM.",
+                webhook.id, webhook.event_types
+            ),
+            Err(e) => secret_not_sent(db_conn, team_id, &webhook, msg, e),
+        },
+        Err(e) => secret_not_sent(db_conn, team_id, &webhook, msg, e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Removes a webhook whose secret could not be sent, it could not be verified without it.
+fn secret_not_sent(
+    db_conn: &mut PgConnection,
+    team_id: i32,
+    webhook: &Webhook,
+    msg: &Message,
+    error: serenity::Error,
+) -> String {
+    println!("Error sending webhook secret: {error:?}");
+    if let Err(e) =
+        webhook_service::remove_webhook(db_conn, team_id, webhook.id, &message_actor(msg))
+    {
+        println!("Error removing webhook #{}: {e}", webhook.id);
+    }
+    "Could not send you the secret of the webhook in a DM, so it was not added. Allow DMs from server members and try again.".to_string()
+}
+
+/// Shows the webhooks of a team.
+async fn list_webhooks(ctx: &Context, msg: &Message, db_conn: &mut PgConnection, team_name: &str) {
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, team_name, Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    match webhook_service::get_webhooks(db_conn, team_id) {
+        Ok(webhooks) if webhooks.is_empty() => {
+            send_message(ctx, &msg.channel_id, "No webhooks.").await;
+        }
+        Ok(webhooks) => {
+            let table = Table::new(webhooks).with(Style::rounded()).to_string();
+            send_message(ctx, &msg.channel_id, &format!("```\n{}\n```", table)).await;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+        }
+    }
+}
+
+/// Removes a webhook from a team.
+async fn remove_webhook(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    webhook_id: &str,
+) {
+    let Ok(webhook_id) = webhook_id.parse::<i32>() else {
+        send_message(ctx, &msg.channel_id, "Webhook ID must be a number").await;
+        return;
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, team_name, Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    let reply = match webhook_service::remove_webhook(db_conn, team_id, webhook_id, &actor) {
+        Ok(_) => format!("Webhook #{} removed.", webhook_id),
+        Err(e) => format!("Failed to remove webhook: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Shows the latest deliveries of a webhook of a team.
+async fn show_deliveries(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    webhook_id: &str,
+) {
+    let Ok(webhook_id) = webhook_id.parse::<i32>() else {
+        send_message(ctx, &msg.channel_id, "Webhook ID must be a number").await;
+        return;
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, team_name, Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    match webhook_service::get_delivery_log(db_conn, team_id, webhook_id) {
+        Ok(deliveries) if deliveries.is_empty() => {
+            send_message(ctx, &msg.channel_id, "No deliveries yet.").await;
+        }
+        Ok(deliveries) => {
+            let table = Table::new(deliveries).with(Style::rounded()).to_string();
+            send_message(ctx, &msg.channel_id, &format!("```\n{}\n```", table)).await;
+        }
+        Err(e) => {
+            send_message(ctx, &msg.channel_id, &format!("Error: {}", e)).await;
+        }
+    }
+}
+
+/// Sends a delivery of a webhook of a team again.
+async fn replay_delivery(
+    ctx: &Context,
+    msg: &Message,
+    db_conn: &mut PgConnection,
+    team_name: &str,
+    delivery_id: &str,
+) {
+    let Ok(delivery_id) = delivery_id.parse::<i32>() else {
+        send_message(ctx, &msg.channel_id, "Delivery ID must be a number").await;
+        return;
+    };
+
+    let Some(team_id) = authorized_team(ctx, msg, db_conn, team_name, Permission::ManageTeam).await
+    else {
+        return;
+    };
+
+    let actor = message_actor(msg);
+    let reply = match webhook_service::replay_delivery(db_conn, team_id, delivery_id, &actor) {
+        Ok(replay) => format!(
+            "🔁 Delivery #{} queued again as #{}.",
+            delivery_id, replay.id
+        ),
+        Err(e) => format!("Failed to replay delivery: {}", e),
+    };
+    send_message(ctx, &msg.channel_id, &reply).await;
+}
+
+/// Starts the job sending the webhook deliveries of every team.
+///
+/// # Arguments
+/// * `db_pool` - The connection pool to the database.
+///
+/// # Behavior
+/// - Every `WEBHOOK_DELIVERY_INTERVAL_SECONDS`, posts the deliveries that are due one after the
+///   other, without holding a database connection while waiting for a receiver.
+/// - A delivery that fails is retried with exponential backoff until it runs out of attempts.
+pub fn start_webhook_deliveries(db_pool: DBPool) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(WEBHOOK_DELIVERY_INTERVAL_SECONDS));
+        loop {
+            interval.tick().await;
+
+            let deliveries = match db_pool.get() {
+                Ok(mut db_conn) => match webhook_service::get_due_deliveries(&mut db_conn) {
+                    Ok(deliveries) => deliveries,
+                    Err(e) => {
+                        println!("Error checking webhook deliveries: {e}");
+                        continue;
+                    }
+                },
+                Err(e) => {
+                    println!("Failed to get DB connection: {e:?}");
+                    continue;
+                }
+            };
+
+            for (delivery, webhook) in deliveries {
+                let outcome = send_delivery(&delivery, &webhook).await;
+                let status = match db_pool.get() {
+                    Ok(mut db_conn) => {
+                        webhook_service::record_attempt(&mut db_conn, &delivery, outcome)
+                    }
+                    Err(e) => Err(format!("Failed to get DB connection: {e:?}")),
+                };
+                match status {
+                    Ok(DeliveryStatus::Failed) => println!(
+                        "Webhook delivery #{} to {} failed for good",
+                        delivery.id, webhook.url
+                    ),
+                    Ok(_) => {}
+                    Err(e) => println!("Error recording webhook delivery #{}: {e}", delivery.id),
+                }
+            }
+        }
+    });
+}
+
+/// Posts a delivery to its webhook, signed with the secret of the webhook.
+async fn send_delivery(delivery: &WebhookDelivery, webhook: &Webhook) -> Result<u16, String> {
+    let timestamp = Utc::now().timestamp();
+    let headers = [
+        ("X-Attendance-Event", delivery.event_type.clone()),
+        ("X-Attendance-Delivery", delivery.id.to_string()),
+        ("X-Attendance-Timestamp", timestamp.to_string()),
+        (
+            "X-Attendance-Signature",
+            webhook::sign(&webhook.secret, timestamp, &delivery.payload),
+        ),
+    ];
+    webhook_client::post(&webhook.url, &headers, delivery.payload.clone()).await
+}
+
+/// Sends a message to a specific channel.
+///
+/// # Arguments
+/// * `ctx` - The context of the event.
+/// * `channel_id` - The ID of the channel to send the message to.
+/// * `message` - The message to send.
+///
+/// # Behavior
+/// - Attempts to send the message to the specified channel.
+/// - Logs an error if the message fails to send.
+async fn send_message(ctx: &Context, channel_id: &ChannelId, message: &str) {
+    if let Err(e) = channel_id.say(&ctx.http, message).await {
+        println!("Error sending message: {e:?}");
+    }
+}
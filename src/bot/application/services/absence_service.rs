@@ -0,0 +1,202 @@
+use crate::bot::{
+    application::services::{
+        audit_service, holiday_service, leave_service, live_service, webhook_service,
+    },
+    domain::{
+        audit::{self, AuditAction, AuditActor},
+        leave::is_working_day,
+        live::LiveEvent,
+        model::{Member, SystemAbsence, Team},
+        webhook::WebhookEvent,
+    },
+    infrastructure::persistence::{attendance_repository, member_repository, team_repository},
+};
+use crate::config::constant::SCHEDULE_MAX_GRACE_MINUTES;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+
+/// Represents the outcome of the daily absence check of a team.
+///
+/// # Fields
+/// * `team_name` - The name of the team.
+/// * `channel_id` - The ID of the channel the no-show report is posted to, if any.
+/// * `date` - The day that was checked.
+/// * `absent` - The names of the members who did not check in, recorded as absent.
+/// * `on_leave` - The names of the members who did not check in while on approved leave.
+#[derive(Debug)]
+pub struct AbsenceReport {
+    pub team_name: String,
+    pub channel_id: Option<String>,
+    pub date: NaiveDate,
+    pub absent: Vec<String>,
+    pub on_leave: Vec<String>,
+}
+
+/// Sets the daily schedule the absences of a team are checked against.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `start` - The time (UTC) the working day starts, or `None` to stop the absence check.
+/// * `grace_minutes` - How long after the start members are recorded as absent.
+/// * `channel_id` - The ID of the channel the daily no-show report is posted to, if any.
+/// * `actor` - Who changes the schedule, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the grace period is out of range or the team cannot be updated in the
+/// database.
+pub fn set_schedule(
+    conn: &mut PgConnection,
+    team_id: i32,
+    start: Option<NaiveTime>,
+    grace_minutes: i32,
+    channel_id: Option<&str>,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    if !(0..=SCHEDULE_MAX_GRACE_MINUTES).contains(&grace_minutes) {
+        return Err(format!(
+            "The grace period must be between 0 and {} minutes",
+            SCHEDULE_MAX_GRACE_MINUTES
+        ));
+    }
+
+    let team = team_repository::find_team(conn, team_id)?.ok_or("Team not found")?;
+    team_repository::set_schedule(conn, team_id, start, grace_minutes, channel_id)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::ScheduleUpdate,
+        Some(team_id),
+        None,
+        audit::diff(&[
+            ("start", json!(team.schedule_start), json!(start)),
+            (
+                "grace_minutes",
+                json!(team.schedule_grace_minutes),
+                json!(grace_minutes),
+            ),
+            (
+                "report_channel_id",
+                json!(team.report_channel_id),
+                json!(channel_id),
+            ),
+        ]),
+    );
+    Ok(())
+}
+
+/// Retrieves the teams whose absences are due to be checked.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `now` - The current time (UTC).
+///
+/// # Returns
+/// Returns the active teams with a schedule whose start plus grace period has passed today
+/// and whose absences were not checked yet today.
+///
+/// # Errors
+/// Returns an error if the teams cannot be fetched from the database.
+pub fn get_due_teams(conn: &mut PgConnection, now: NaiveDateTime) -> Result<Vec<Team>, String> {
+    Ok(
+        team_repository::get_unchecked_scheduled_teams(conn, now.date())?
+            .into_iter()
+            .filter(|team| {
+                team.schedule_start.is_some_and(|start| {
+                    now >= now.date().and_time(start)
+                        + Duration::minutes(team.schedule_grace_minutes.into())
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Records the members of a team who did not check in on a day as absent.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The team.
+/// * `date` - The day.
+///
+/// # Returns
+/// Returns the no-show report of the team, or `None` if the day was already checked, is a
+/// weekend day or is a holiday of the team.
+///
+/// # Behavior
+/// - Members with any attendance record on the day, members on approved leave and members
+///   who joined later are left out.
+/// - The absences are flagged as system-generated, a late check-in replaces them.
+/// - Publishes the absences on the live feed and notifies the `absence_detected` webhooks of
+///   the team.
+///
+/// # Errors
+/// Returns an error if the attendance records cannot be fetched from or inserted into the
+/// database.
+pub fn check_team_absences(
+    conn: &mut PgConnection,
+    team: &Team,
+    date: NaiveDate,
+) -> Result<Option<AbsenceReport>, String> {
+    if !team_repository::mark_absences_checked(conn, team.id, date)? {
+        return Ok(None);
+    }
+    if !is_working_day(date) || holiday_service::is_holiday(conn, team.id, date)? {
+        return Ok(None);
+    }
+
+    let recorded = attendance_repository::get_recorded_members(conn, team.id, date)?;
+    let on_leave = leave_service::members_on_leave(conn, team.id, date)?;
+    let mut report = AbsenceReport {
+        team_name: team.name.clone(),
+        channel_id: team.report_channel_id.clone(),
+        date,
+        absent: Vec::new(),
+        on_leave: Vec::new(),
+    };
+    let mut absences = Vec::new();
+    let mut absentees = Vec::new();
+    let mut absent_members: Vec<Member> = Vec::new();
+
+    for member in member_repository::get_active_members(conn, team.id)? {
+        if recorded.contains(&member.id) || member.join_date.is_some_and(|joined| joined > date) {
+            continue;
+        }
+
+        if on_leave.contains(&member.id) {
+            report.on_leave.push(member.username);
+            continue;
+        }
+
+        absences.push(SystemAbsence {
+            member_id: member.id,
+            team_id: team.id,
+            date,
+            status: "Absent".to_string(),
+            system_generated: true,
+        });
+        absentees.push(json!({ "discord_id": member.discord_id, "username": member.username }));
+        report.absent.push(member.username.clone());
+        absent_members.push(member);
+    }
+
+    if !absences.is_empty() {
+        attendance_repository::record_absences(conn, &absences)?;
+        webhook_service::emit(
+            conn,
+            team.id,
+            WebhookEvent::AbsenceDetected,
+            json!({ "date": date, "source": "schedule", "members": absentees }),
+        );
+
+        let now = Utc::now().naive_utc();
+        for member in &absent_members {
+            live_service::publish(member, LiveEvent::StatusChange, Some("Absent"), date, now);
+        }
+    }
+    report.absent.sort();
+    report.on_leave.sort();
+
+    Ok(Some(report))
+}
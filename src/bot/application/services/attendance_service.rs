@@ -1,7 +1,19 @@
 use crate::bot::{
-    domain::table::MemberAttendanceTable, infrastructure::persistence::attendance_repository,
+    application::services::{
+        audit_service, event_service, holiday_service, live_service, webhook_service,
+    },
+    domain::{
+        audit::{AuditAction, AuditActor},
+        live::LiveEvent,
+        model::{Event, MemberAttendance},
+        table::MemberAttendanceTable,
+    },
+    infrastructure::persistence::{attendance_repository, member_repository, team_repository},
 };
+use crate::config::constant::VOICE_CHECK_IN_STATUS;
+use chrono::Utc;
 use diesel::PgConnection;
+use serde_json::{json, Value};
 
 /// Records a check-in for a member in a specific team.
 ///
@@ -11,10 +23,16 @@ use diesel::PgConnection;
 /// * `team_id` - The ID of the team the member is checking into.
 /// * `status` - The status of the check-in (e.g., "Present", "Late").
 ///
+/// # Behavior
+/// - Links the check-in to the scheduled event of the team running at that time, if any.
+/// - Replaces the absence recorded by the daily check. A voice session only replaces it once it
+///   is kept, when the member checks out.
+/// - Publishes the check-in on the live feed and notifies the `check_in` webhooks of the team.
+///
 /// # Errors
 /// Returns an error if:
 /// - The member does not exist.
-/// - The team does not exist.
+/// - The team does not exist or is archived.
 /// - The check-in record cannot be inserted into the database.
 pub fn check_in(
     conn: &mut PgConnection,
@@ -22,7 +40,81 @@ pub fn check_in(
     team_id: i32,
     status: String,
 ) -> Result<(), String> {
-    attendance_repository::check_in(conn, member_id, team_id, status)
+    let now = Utc::now().naive_utc();
+    let event = event_service::find_open_event(conn, team_id, now)?;
+    let event_id = event.map(|event| event.id);
+    attendance_repository::check_in(conn, member_id, team_id, status.clone(), event_id)?;
+    if status != VOICE_CHECK_IN_STATUS {
+        attendance_repository::delete_system_absence(conn, member_id, team_id, now.date())?;
+    }
+
+    notify(
+        conn,
+        member_id,
+        LiveEvent::CheckIn,
+        Some(&status),
+        json!({ "status": status, "checked_in_at": now, "event_id": event_id }),
+    );
+    Ok(())
+}
+
+/// Records a check-in to an on-site event, verified with its rotating check-in code.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `member_id` - The ID of the member checking in.
+/// * `team_id` - The ID of the team the member is checking into.
+/// * `code` - The check-in code typed by the member.
+///
+/// # Returns
+/// Returns the event the member checked in to.
+///
+/// # Behavior
+/// - Publishes the check-in on the live feed and notifies the `check_in` webhooks of the team.
+///
+/// # Errors
+/// Returns an error if:
+/// - No running event of the team accepts the code.
+/// - The member already checked in to the event.
+/// - The check-in record cannot be inserted into the database.
+pub fn check_in_with_code(
+    conn: &mut PgConnection,
+    member_id: i32,
+    team_id: i32,
+    code: &str,
+) -> Result<Event, String> {
+    let event = event_service::verify_code(conn, team_id, code)?;
+
+    if attendance_repository::has_event_check_in(conn, member_id, event.id)? {
+        return Err(format!("You already checked in to '{}'.", event.title));
+    }
+
+    attendance_repository::check_in(
+        conn,
+        member_id,
+        team_id,
+        "Present".to_string(),
+        Some(event.id),
+    )?;
+    attendance_repository::delete_system_absence(
+        conn,
+        member_id,
+        team_id,
+        Utc::now().date_naive(),
+    )?;
+
+    notify(
+        conn,
+        member_id,
+        LiveEvent::CheckIn,
+        Some("Present"),
+        json!({
+            "status": "Present",
+            "checked_in_at": Utc::now().naive_utc(),
+            "event_id": event.id,
+        }),
+    );
+    Ok(event)
 }
 
 /// Records a check-out for a member.
@@ -31,22 +123,90 @@ pub fn check_in(
 /// * `conn` - A mutable reference to the PostgreSQL connection.
 /// * `member_id` - The ID of the member checking out.
 ///
+/// # Behavior
+/// - Replaces the absence recorded by the daily check on the day of the check-in.
+/// - Publishes the check-out on the live feed and notifies the `check_out` webhooks of the team.
+///
 /// # Errors
 /// Returns an error if:
 /// - No active check-in is found for the member.
 /// - The check-out record cannot be updated in the database.
 pub fn check_out(conn: &mut PgConnection, member_id: i32) -> Result<(), String> {
-    attendance_repository::check_out(conn, member_id)
+    let open = attendance_repository::find_open_check_in(conn, member_id)?;
+    attendance_repository::check_out(conn, member_id)?;
+    if let Some((Some(team_id), date)) = open.map(|open| (open.team_id, open.date)) {
+        attendance_repository::delete_system_absence(conn, member_id, team_id, date)?;
+    }
+
+    notify(
+        conn,
+        member_id,
+        LiveEvent::CheckOut,
+        None,
+        json!({ "checked_out_at": Utc::now().naive_utc() }),
+    );
+    Ok(())
+}
+
+/// Discards a check-in that is not kept, e.g. a voice session that was too short.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `attendance` - The open attendance record to discard.
+/// * `actor` - Who discarded it.
+/// * `reason` - Why it was discarded, recorded in the audit log.
+///
+/// # Behavior
+/// - Publishes a check-out on the live feed and notifies the `check_out` webhooks of the team,
+///   with `discarded` set, so the member is no longer shown as checked in.
+///
+/// # Errors
+/// Returns an error if the attendance record cannot be deleted from the database.
+pub fn discard_check_in(
+    conn: &mut PgConnection,
+    attendance: &MemberAttendance,
+    actor: &AuditActor,
+    reason: &str,
+) -> Result<(), String> {
+    attendance_repository::delete_attendance(conn, attendance.id)?;
+
+    let target = attendance.id.to_string();
+    let details = json!({
+        "check_in_time": attendance.check_in_time,
+        "status": attendance.status,
+        "reason": reason,
+    });
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::AttendanceDiscard,
+        attendance.team_id,
+        Some(&target),
+        details,
+    );
+
+    if let Some(member_id) = attendance.member_id {
+        notify(
+            conn,
+            member_id,
+            LiveEvent::CheckOut,
+            None,
+            json!({ "checked_out_at": Utc::now().naive_utc(), "discarded": true }),
+        );
+    }
+    Ok(())
 }
 
 /// Retrieves attendance records for all members of a specific team.
 ///
 /// # Arguments
 /// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild_id` - The ID of the Discord server the team belongs to.
 /// * `team_name` - The name of the team whose attendance records are being fetched.
 ///
 /// # Returns
 /// Returns a `Vec<MemberAttendanceTable>` containing the attendance records for the team.
+/// Absences on a holiday of the team are shown as "Holiday".
 ///
 /// # Errors
 /// Returns an error if:
@@ -54,7 +214,32 @@ pub fn check_out(conn: &mut PgConnection, member_id: i32) -> Result<(), String>
 /// - The attendance records cannot be fetched from the database.
 pub fn get_member_attendance(
     conn: &mut PgConnection,
+    guild_id: &str,
     team_name: &str,
 ) -> Result<Vec<MemberAttendanceTable>, String> {
-    attendance_repository::get_member_attendance_by_team(conn, team_name)
+    let team_id = team_repository::find_team_id(conn, guild_id, team_name)?;
+    let holidays = holiday_service::holiday_dates(conn, team_id)?;
+    attendance_repository::get_member_attendance_by_team(conn, guild_id, team_name, &holidays)
+}
+
+/// Publishes an attendance change on the live feed and notifies the webhooks of the team of a
+/// member, looked up by ID.
+fn notify(
+    conn: &mut PgConnection,
+    member_id: i32,
+    event: LiveEvent,
+    status: Option<&str>,
+    data: Value,
+) {
+    let now = Utc::now().naive_utc();
+    match member_repository::find_member_by_id(conn, member_id) {
+        Ok(Some(member)) => {
+            live_service::publish(&member, event, status, now.date(), now);
+            if let Some(webhook_event) = event.webhook_event() {
+                webhook_service::emit_member(conn, &member, webhook_event, data);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => println!("Error publishing {}: {}", event.as_str(), e),
+    }
 }
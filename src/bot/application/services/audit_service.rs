@@ -0,0 +1,154 @@
+use crate::bot::{
+    domain::{
+        audit::{AuditAction, AuditActor, AuditFilter},
+        model::{AuditEvent, NewAuditEvent, Team},
+        table::AuditEventTable,
+    },
+    infrastructure::persistence::{audit_repository, team_repository},
+};
+use chrono::{Duration, Utc};
+use diesel::PgConnection;
+use serde_json::Value;
+
+/// Records an administrative action in the audit log.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `actor` - Who made the change and where from.
+/// * `action` - What was done.
+/// * `team_id` - The ID of the team the action applies to, if any.
+/// * `target` - What the action applies to within the team, e.g. the Discord ID of a member.
+/// * `details` - A JSON object with the changed values.
+///
+/// # Behavior
+/// - The action already happened, so a failure to record it is logged instead of returned.
+pub fn record(
+    conn: &mut PgConnection,
+    actor: &AuditActor,
+    action: AuditAction,
+    team_id: Option<i32>,
+    target: Option<&str>,
+    details: Value,
+) {
+    let team = match team_id.map(|team_id| team_repository::find_team(conn, team_id)) {
+        Some(Ok(team)) => team,
+        Some(Err(e)) => {
+            println!("Error recording {}: {}", action, e);
+            None
+        }
+        None => None,
+    };
+
+    record_team(conn, actor, action, team.as_ref(), target, details);
+}
+
+/// Records an administrative action on a team that may no longer exist, e.g. its deletion.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `actor` - Who made the change and where from.
+/// * `action` - What was done.
+/// * `team` - The team as it was before the action, if any.
+/// * `target` - What the action applies to within the team.
+/// * `details` - A JSON object with the changed values.
+///
+/// # Behavior
+/// - Keeps the name and server of the team, so its events still read well once it is deleted.
+/// - A failure to record the action is logged instead of returned.
+pub fn record_team(
+    conn: &mut PgConnection,
+    actor: &AuditActor,
+    action: AuditAction,
+    team: Option<&Team>,
+    target: Option<&str>,
+    details: Value,
+) {
+    let event = NewAuditEvent {
+        actor_id: actor.discord_id.clone(),
+        source: actor.source.as_str().to_string(),
+        action: action.as_str().to_string(),
+        team_id: team.map(|team| team.id),
+        team_name: team.map(|team| team.name.clone()),
+        guild_id: team
+            .map(|team| team.guild_id.clone())
+            .or_else(|| actor.guild_id.clone()),
+        channel_id: actor.channel_id.clone(),
+        target: target.map(str::to_string),
+        details: details.to_string(),
+    };
+
+    if let Err(e) = audit_repository::insert_event(conn, &event) {
+        println!("Error recording {}: {}", action, e);
+    }
+}
+
+/// Retrieves the audit log of a team in a tabular format.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `filter` - The filters of the query.
+///
+/// # Returns
+/// Returns the matching events, newest first.
+///
+/// # Errors
+/// Returns an error if the events cannot be fetched from the database.
+pub fn get_team_log(
+    conn: &mut PgConnection,
+    team_id: i32,
+    filter: &AuditFilter,
+) -> Result<Vec<AuditEventTable>, String> {
+    let events = audit_repository::get_team_events(conn, team_id, filter)?;
+
+    Ok(events
+        .into_iter()
+        .map(|event| AuditEventTable {
+            at: event.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            actor: format!("{} ({})", event.actor_id, event.source),
+            action: event.action,
+            target: event.target.unwrap_or_else(|| "-".to_string()),
+            details: event.details,
+        })
+        .collect())
+}
+
+/// Retrieves the audit events of a team, which may have been deleted since.
+///
+/// # Errors
+/// Returns an error if the events cannot be fetched from the database.
+pub fn get_team_events(
+    conn: &mut PgConnection,
+    team_id: i32,
+    filter: &AuditFilter,
+) -> Result<Vec<AuditEvent>, String> {
+    audit_repository::get_team_events(conn, team_id, filter)
+}
+
+/// Retrieves the audit events of the teams a user owns and every action they made themselves.
+///
+/// # Errors
+/// Returns an error if the events cannot be fetched from the database.
+pub fn get_owner_events(
+    conn: &mut PgConnection,
+    discord_id: &str,
+    filter: &AuditFilter,
+) -> Result<Vec<AuditEvent>, String> {
+    audit_repository::get_owner_events(conn, discord_id, filter)
+}
+
+/// Removes the audit events older than the retention period.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `retention_days` - How many days events are kept.
+///
+/// # Returns
+/// Returns the number of removed events.
+///
+/// # Errors
+/// Returns an error if the events cannot be removed from the database.
+pub fn purge_expired(conn: &mut PgConnection, retention_days: i64) -> Result<usize, String> {
+    let cutoff = Utc::now().naive_utc() - Duration::days(retention_days);
+    audit_repository::delete_events_before(conn, cutoff)
+}
@@ -0,0 +1,676 @@
+use crate::bot::{
+    application::services::{audit_service, live_service, role_service},
+    domain::{
+        audit::{self, AuditAction, AuditActor},
+        correction::{AttendanceChange, AttendanceValues, CorrectionStatus},
+        live::LiveEvent,
+        model::{
+            AttendanceCorrection, ManualAttendance, Member, MemberAttendance, NewAttendanceAudit,
+            NewAttendanceCorrection,
+        },
+        permission::{Permission, Role},
+        table::{AttendanceAuditTable, AttendanceCorrectionTable},
+    },
+    infrastructure::persistence::{correction_repository, member_repository, team_repository},
+};
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+
+/// Represents a reviewed correction request together with what is needed to notify the member.
+///
+/// # Fields
+/// * `correction` - The correction request.
+/// * `team_name` - The name of the team.
+/// * `discord_id` - The Discord ID of the member who made the request.
+/// * `attendance` - The attendance record the approved change was applied to, if approved.
+#[derive(Debug)]
+pub struct CorrectionReview {
+    pub correction: AttendanceCorrection,
+    pub team_name: String,
+    pub discord_id: String,
+    pub attendance: Option<MemberAttendance>,
+}
+
+/// Finds an attendance record of a team together with its member.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `attendance_id` - The ID of the attendance record.
+///
+/// # Errors
+/// Returns an error if the record does not exist in the team or cannot be fetched from the
+/// database.
+pub fn find_attendance(
+    conn: &mut PgConnection,
+    team_id: i32,
+    attendance_id: i32,
+) -> Result<(MemberAttendance, Member), String> {
+    correction_repository::find_attendance(conn, attendance_id)?
+        .filter(|(record, _)| record.team_id == Some(team_id))
+        .ok_or_else(|| format!("Attendance record {} not found", attendance_id))
+}
+
+/// Finds an attendance record by ID, whatever its team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `attendance_id` - The ID of the attendance record.
+///
+/// # Returns
+/// Returns the attendance record, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the attendance record cannot be fetched from the database.
+pub fn find_record(
+    conn: &mut PgConnection,
+    attendance_id: i32,
+) -> Result<Option<MemberAttendance>, String> {
+    Ok(correction_repository::find_attendance(conn, attendance_id)?.map(|(record, _)| record))
+}
+
+/// Changes an attendance record of a team and records the change in its audit trail.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `attendance_id` - The ID of the attendance record.
+/// * `change` - The values to change.
+/// * `actor` - The user making the change, recorded in the audit trail and the audit log.
+/// * `reason` - Why the record is changed.
+///
+/// # Returns
+/// Returns the updated attendance record.
+///
+/// # Errors
+/// Returns an error if:
+/// - The actor may not manage the members of the team, or changes their own record without
+///   being the owner.
+/// - The record does not exist in the team.
+/// - The change is empty, the resulting record is inconsistent or the reason is invalid.
+/// - The record cannot be updated in the database.
+pub fn edit_attendance(
+    conn: &mut PgConnection,
+    team_id: i32,
+    attendance_id: i32,
+    change: &AttendanceChange,
+    actor: &AuditActor,
+    reason: &str,
+) -> Result<MemberAttendance, String> {
+    let (record, member) = find_attendance(conn, team_id, attendance_id)?;
+    authorize_change(conn, team_id, &member, &actor.discord_id)?;
+    let updated = apply_change(conn, &record, change, &actor.discord_id, reason, None)?;
+
+    let details = attendance_details(&values_of(&record), &updated, reason);
+    record_attendance(conn, actor, AuditAction::AttendanceEdit, &updated, details);
+    publish_status(&member, record.status.as_deref(), &updated);
+    Ok(updated)
+}
+
+/// Adds a missing attendance record to a member and records it in its audit trail.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member.
+/// * `date` - The day of the attendance record.
+/// * `change` - The values of the record, the status defaults to "Present".
+/// * `actor` - The user adding the record, recorded in the audit trail and the audit log.
+/// * `reason` - Why the record is added.
+///
+/// # Returns
+/// Returns the inserted attendance record.
+///
+/// # Errors
+/// Returns an error if:
+/// - The user is not a member of the team.
+/// - The actor may not manage the members of the team, or adds their own record without
+///   being the owner.
+/// - The record has no check-in, is inconsistent or the reason is invalid.
+/// - The record cannot be inserted into the database.
+pub fn add_attendance(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    date: NaiveDate,
+    change: &AttendanceChange,
+    actor: &AuditActor,
+    reason: &str,
+) -> Result<MemberAttendance, String> {
+    let member = member_repository::find_member(conn, team_id, discord_id)?
+        .ok_or("This user is not a member of the team.")?;
+    authorize_change(conn, team_id, &member, &actor.discord_id)?;
+    let added = insert_record(
+        conn,
+        team_id,
+        member.id,
+        date,
+        change,
+        &actor.discord_id,
+        reason,
+        None,
+    )?;
+
+    let details = attendance_details(&AttendanceValues::default(), &added, reason);
+    record_attendance(conn, actor, AuditAction::AttendanceAdd, &added, details);
+    publish_status(&member, None, &added);
+    Ok(added)
+}
+
+/// Creates a pending correction request for one of the member's attendance records, or for a
+/// record that is missing.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member.
+/// * `attendance_id` - The ID of the record to change, or `None` to add the missing record.
+/// * `date` - The day of the missing record, ignored when `attendance_id` is given.
+/// * `change` - The requested values.
+/// * `reason` - Why the record is wrong.
+///
+/// # Returns
+/// Returns the new correction request.
+///
+/// # Errors
+/// Returns an error if:
+/// - The user is not an active member of the team.
+/// - The record does not exist or belongs to someone else.
+/// - The change is empty, the resulting record is inconsistent or the reason is invalid.
+/// - The member already has a pending request for the record or the day.
+/// - The request cannot be inserted into the database.
+pub fn request_correction(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    attendance_id: Option<i32>,
+    date: NaiveDate,
+    change: &AttendanceChange,
+    reason: &str,
+) -> Result<AttendanceCorrection, String> {
+    let member = member_repository::find_member(conn, team_id, discord_id)?
+        .filter(|member| member.left_date.is_none())
+        .ok_or("You are not an active member of this team.")?;
+    let reason = check_reason(reason)?;
+    if change.is_empty() {
+        return Err("The correction does not change anything".to_string());
+    }
+
+    let date = match attendance_id {
+        Some(attendance_id) => {
+            let (record, owner) = find_attendance(conn, team_id, attendance_id)?;
+            if owner.id != member.id {
+                return Err("You can only correct your own attendance records.".to_string());
+            }
+            change.apply(&values_of(&record)).validate()?;
+            record.date
+        }
+        None => {
+            if date > Utc::now().date_naive() {
+                return Err("You cannot add attendance for a day in the future".to_string());
+            }
+            check_new_record(&change.apply(&AttendanceValues::default()))?;
+            date
+        }
+    };
+
+    if let Some(pending) =
+        correction_repository::find_pending_correction(conn, member.id, attendance_id, date)?
+    {
+        return Err(format!(
+            "You already have correction request #{} waiting for review",
+            pending.id
+        ));
+    }
+
+    correction_repository::create_correction(
+        conn,
+        &NewAttendanceCorrection {
+            team_id,
+            member_id: member.id,
+            attendance_id,
+            attendance_date: date,
+            check_in_time: change.check_in_time,
+            check_out_time: change.check_out_time,
+            clear_check_out: change.clear_check_out,
+            attendance_status: change.status.clone(),
+            reason,
+        },
+    )
+}
+
+/// Approves or rejects a pending correction request, applying the change if approved.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `correction_id` - The ID of the correction request.
+/// * `actor` - The user reviewing the request, recorded in the audit log.
+/// * `approve` - Whether the request is approved or rejected.
+///
+/// # Returns
+/// Returns the reviewed request, ready to be sent to the member.
+///
+/// # Behavior
+/// - The change is recorded in the audit trail with the reviewer as actor and the reason of
+///   the member.
+/// - Only the owner of a team may review their own requests.
+///
+/// # Errors
+/// Returns an error if:
+/// - The request does not exist or was already reviewed.
+/// - The reviewer may not manage the members of the team.
+/// - The change no longer applies, e.g. the record changed since the request was made.
+/// - The request or the record cannot be updated in the database.
+pub fn review_correction(
+    conn: &mut PgConnection,
+    correction_id: i32,
+    actor: &AuditActor,
+    approve: bool,
+) -> Result<CorrectionReview, String> {
+    let reviewer = actor.discord_id.as_str();
+    let (correction, member) = correction_repository::find_correction(conn, correction_id)?
+        .ok_or_else(|| format!("Correction request {} not found", correction_id))?;
+
+    let role = role_service::authorize(
+        conn,
+        correction.team_id,
+        reviewer,
+        Permission::ManageMembers,
+    )?;
+    if member.discord_id == reviewer && role != Role::Owner {
+        return Err("You cannot review your own correction request.".to_string());
+    }
+    if correction.status != CorrectionStatus::Pending.as_str() {
+        return Err(format!(
+            "This correction request is already {}.",
+            correction.status
+        ));
+    }
+
+    let change = AttendanceChange {
+        check_in_time: correction.check_in_time,
+        check_out_time: correction.check_out_time,
+        clear_check_out: correction.clear_check_out,
+        status: correction.attendance_status.clone(),
+    };
+    let record = match (approve, correction.attendance_id) {
+        (true, Some(attendance_id)) => {
+            let (record, _) = find_attendance(conn, correction.team_id, attendance_id)?;
+            change.apply(&values_of(&record)).validate()?;
+            Some(record)
+        }
+        (true, None) => {
+            check_new_record(&change.apply(&AttendanceValues::default()))?;
+            None
+        }
+        (false, _) => None,
+    };
+
+    let new_status = if approve {
+        CorrectionStatus::Approved
+    } else {
+        CorrectionStatus::Rejected
+    };
+    if !correction_repository::set_status(
+        conn,
+        correction.id,
+        CorrectionStatus::Pending.as_str(),
+        new_status.as_str(),
+        reviewer,
+    )? {
+        return Err("This correction request was already reviewed.".to_string());
+    }
+
+    let status_before = record.as_ref().and_then(|record| record.status.clone());
+    let attendance = match (approve, record) {
+        (true, Some(record)) => Some(apply_change(
+            conn,
+            &record,
+            &change,
+            reviewer,
+            &correction.reason,
+            Some(correction.id),
+        )?),
+        (true, None) => Some(insert_record(
+            conn,
+            correction.team_id,
+            member.id,
+            correction.attendance_date,
+            &change,
+            reviewer,
+            &correction.reason,
+            Some(correction.id),
+        )?),
+        (false, _) => None,
+    };
+    if let Some(attendance) = &attendance {
+        publish_status(&member, status_before.as_deref(), attendance);
+    }
+
+    let target = correction.id.to_string();
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::CorrectionReview,
+        Some(correction.team_id),
+        Some(&target),
+        json!({
+            "status": new_status.as_str(),
+            "attendance_id": attendance.as_ref().map(|record| record.id),
+        }),
+    );
+
+    let team_name = team_repository::find_team(conn, correction.team_id)?
+        .map(|team| team.name)
+        .unwrap_or_default();
+    Ok(CorrectionReview {
+        correction: AttendanceCorrection {
+            status: new_status.to_string(),
+            reviewed_by: Some(reviewer.to_string()),
+            ..correction
+        },
+        team_name,
+        discord_id: member.discord_id,
+        attendance,
+    })
+}
+
+/// Finds a correction request by ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `correction_id` - The ID of the correction request.
+///
+/// # Returns
+/// Returns the correction request, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the correction request cannot be fetched from the database.
+pub fn find_correction(
+    conn: &mut PgConnection,
+    correction_id: i32,
+) -> Result<Option<AttendanceCorrection>, String> {
+    Ok(correction_repository::find_correction(conn, correction_id)?
+        .map(|(correction, _)| correction))
+}
+
+/// Retrieves the correction requests of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `pending_only` - Whether only the requests waiting for review are returned.
+///
+/// # Returns
+/// Returns a `Vec<AttendanceCorrectionTable>`, newest first.
+///
+/// # Errors
+/// Returns an error if the correction requests cannot be fetched from the database.
+pub fn get_corrections(
+    conn: &mut PgConnection,
+    team_id: i32,
+    pending_only: bool,
+) -> Result<Vec<AttendanceCorrectionTable>, String> {
+    let status = pending_only.then_some(CorrectionStatus::Pending.as_str());
+
+    Ok(
+        correction_repository::get_corrections(conn, team_id, status)?
+            .into_iter()
+            .map(|(correction, member)| AttendanceCorrectionTable {
+                id: correction.id,
+                username: member.username,
+                record: correction
+                    .attendance_id
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| "new".to_string()),
+                date: correction.attendance_date.to_string(),
+                change: format_change(&correction),
+                status: correction.status,
+                reason: correction.reason,
+            })
+            .collect(),
+    )
+}
+
+/// Retrieves the audit trail of an attendance record.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `attendance_id` - The ID of the attendance record.
+/// * `viewer` - The Discord ID of the user asking for it.
+///
+/// # Returns
+/// Returns a `Vec<AttendanceAuditTable>`, oldest change first.
+///
+/// # Errors
+/// Returns an error if:
+/// - The record does not exist.
+/// - The viewer may not view the attendance of the team and the record is not theirs.
+/// - The audit entries cannot be fetched from the database.
+pub fn get_history(
+    conn: &mut PgConnection,
+    attendance_id: i32,
+    viewer: &str,
+) -> Result<Vec<AttendanceAuditTable>, String> {
+    let (record, member) = correction_repository::find_attendance(conn, attendance_id)?
+        .ok_or_else(|| format!("Attendance record {} not found", attendance_id))?;
+    if member.discord_id != viewer {
+        let team_id = record
+            .team_id
+            .ok_or_else(|| format!("Attendance record {} not found", attendance_id))?;
+        role_service::authorize(conn, team_id, viewer, Permission::ViewAttendance)?;
+    }
+
+    Ok(correction_repository::get_audit(conn, attendance_id)?
+        .into_iter()
+        .map(|entry| AttendanceAuditTable {
+            at: entry
+                .created_at
+                .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| "N/A".to_string()),
+            action: match entry.correction_id {
+                Some(correction_id) => format!("{} (#{})", entry.action, correction_id),
+                None => entry.action,
+            },
+            actor: entry.actor_id,
+            before: format_values(
+                entry.before_check_in_time,
+                entry.before_check_out_time,
+                entry.before_status.as_deref(),
+            ),
+            after: format_values(
+                entry.after_check_in_time,
+                entry.after_check_out_time,
+                entry.after_status.as_deref(),
+            ),
+            reason: entry.reason,
+        })
+        .collect())
+}
+
+/// Checks that the actor may change the attendance records of a member.
+fn authorize_change(
+    conn: &mut PgConnection,
+    team_id: i32,
+    member: &Member,
+    actor: &str,
+) -> Result<(), String> {
+    let role = role_service::authorize(conn, team_id, actor, Permission::ManageMembers)?;
+    if member.discord_id == actor && role != Role::Owner {
+        return Err("You cannot change your own attendance records.".to_string());
+    }
+    Ok(())
+}
+
+fn apply_change(
+    conn: &mut PgConnection,
+    record: &MemberAttendance,
+    change: &AttendanceChange,
+    actor: &str,
+    reason: &str,
+    correction_id: Option<i32>,
+) -> Result<MemberAttendance, String> {
+    let reason = check_reason(reason)?;
+    if change.is_empty() {
+        return Err("The change does not change anything".to_string());
+    }
+    let before = values_of(record);
+    let after = change.apply(&before);
+    after.validate()?;
+
+    correction_repository::update_attendance(
+        conn,
+        record.id,
+        &after,
+        &NewAttendanceAudit {
+            attendance_id: record.id,
+            correction_id,
+            action: "update".to_string(),
+            actor_id: actor.to_string(),
+            reason,
+            before_check_in_time: before.check_in_time,
+            before_check_out_time: before.check_out_time,
+            before_status: before.status,
+            after_check_in_time: after.check_in_time,
+            after_check_out_time: after.check_out_time,
+            after_status: after.status.clone(),
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_record(
+    conn: &mut PgConnection,
+    team_id: i32,
+    member_id: i32,
+    date: NaiveDate,
+    change: &AttendanceChange,
+    actor: &str,
+    reason: &str,
+    correction_id: Option<i32>,
+) -> Result<MemberAttendance, String> {
+    let reason = check_reason(reason)?;
+    let values = change.apply(&AttendanceValues::default());
+    check_new_record(&values)?;
+    let status = values.status.unwrap_or_else(|| "Present".to_string());
+
+    correction_repository::insert_attendance(
+        conn,
+        &ManualAttendance {
+            member_id,
+            team_id,
+            date,
+            check_in_time: values.check_in_time,
+            check_out_time: values.check_out_time,
+            status: status.clone(),
+        },
+        NewAttendanceAudit {
+            attendance_id: 0,
+            correction_id,
+            action: "insert".to_string(),
+            actor_id: actor.to_string(),
+            reason,
+            before_check_in_time: None,
+            before_check_out_time: None,
+            before_status: None,
+            after_check_in_time: values.check_in_time,
+            after_check_out_time: values.check_out_time,
+            after_status: Some(status),
+        },
+    )
+}
+
+/// Checks that the values of a record added by hand are complete and consistent.
+fn check_new_record(values: &AttendanceValues) -> Result<(), String> {
+    if values.check_in_time.is_none() {
+        return Err("A new attendance record needs a check-in time (`in:`)".to_string());
+    }
+    values.validate()
+}
+
+fn check_reason(reason: &str) -> Result<String, String> {
+    let reason = reason.trim();
+    if reason.is_empty() || reason.chars().count() > 255 {
+        return Err("The reason must be between 1 and 255 characters".to_string());
+    }
+    Ok(reason.to_string())
+}
+
+/// Records a change to an attendance record in the audit log, the record ID as its target.
+fn record_attendance(
+    conn: &mut PgConnection,
+    actor: &AuditActor,
+    action: AuditAction,
+    record: &MemberAttendance,
+    details: serde_json::Value,
+) {
+    let target = record.id.to_string();
+    audit_service::record(conn, actor, action, record.team_id, Some(&target), details);
+}
+
+/// Publishes the status of an attendance record on the live feed of the team, if it changed.
+fn publish_status(member: &Member, before: Option<&str>, after: &MemberAttendance) {
+    if before != after.status.as_deref() {
+        let now = Utc::now().naive_utc();
+        let status = after.status.as_deref();
+        live_service::publish(member, LiveEvent::StatusChange, status, after.date, now);
+    }
+}
+
+/// Returns the changed values of an attendance record and the reason, recorded in the audit log.
+fn attendance_details(
+    before: &AttendanceValues,
+    after: &MemberAttendance,
+    reason: &str,
+) -> serde_json::Value {
+    let mut details = audit::diff(&[
+        ("check_in_time", json!(before.check_in_time), json!(after.check_in_time)),
+        ("check_out_time", json!(before.check_out_time), json!(after.check_out_time)),
+        ("status", json!(before.status), json!(after.status)),
+    ]);
+    details["reason"] = json!(reason.trim());
+    details
+}
+
+fn values_of(record: &MemberAttendance) -> AttendanceValues {
+    AttendanceValues {
+        check_in_time: record.check_in_time,
+        check_out_time: record.check_out_time,
+        status: record.status.clone(),
+    }
+}
+
+fn format_change(correction: &AttendanceCorrection) -> String {
+    let mut parts = Vec::new();
+    if let Some(check_in) = correction.check_in_time {
+        parts.push(format!("in {}", check_in.format("%Y-%m-%d %H:%M")));
+    }
+    if let Some(check_out) = correction.check_out_time {
+        parts.push(format!("out {}", check_out.format("%Y-%m-%d %H:%M")));
+    }
+    if correction.clear_check_out {
+        parts.push("no check-out".to_string());
+    }
+    if let Some(status) = &correction.attendance_status {
+        parts.push(format!("status {}", status));
+    }
+    parts.join(", ")
+}
+
+fn format_values(
+    check_in: Option<NaiveDateTime>,
+    check_out: Option<NaiveDateTime>,
+    status: Option<&str>,
+) -> String {
+    let format_time = |time: Option<NaiveDateTime>| {
+        time.map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    format!(
+        "{} → {} ({})",
+        format_time(check_in),
+        format_time(check_out),
+        status.unwrap_or("-")
+    )
+}
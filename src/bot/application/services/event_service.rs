@@ -0,0 +1,652 @@
+use crate::bot::{
+    application::services::{audit_service, holiday_service, leave_service},
+    domain::{
+        audit::{AuditAction, AuditActor},
+        check_in_code,
+        model::{Event, EventException, NewEvent, NewEventException},
+        recurrence::RecurrenceRule,
+        table::{EventAttendanceTable, EventTable},
+    },
+    infrastructure::persistence::{event_repository, member_repository},
+};
+use crate::config::constant::{EVENT_CHECK_IN_GRACE_MINUTES, EVENT_MAX_HOURS};
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+use std::collections::HashSet;
+
+/// An occurrence of an event, the event itself when it does not repeat.
+///
+/// # Fields
+/// * `event` - The event.
+/// * `starts_at` - The start of the occurrence.
+/// * `ends_at` - The end of the occurrence.
+#[derive(Debug, Clone)]
+pub struct Occurrence {
+    pub event: Event,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+}
+
+/// Starts an on-site event whose check-ins require the rotating check-in code.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `title` - The title of the event.
+/// * `duration` - How long members can check in.
+/// * `actor` - The user starting the event, recorded in the audit log.
+///
+/// # Returns
+/// Returns the new event.
+///
+/// # Errors
+/// Returns an error if the title is invalid or the event cannot be inserted into the database.
+pub fn start_code_event(
+    conn: &mut PgConnection,
+    team_id: i32,
+    title: &str,
+    duration: Duration,
+    actor: &AuditActor,
+) -> Result<Event, String> {
+    let title = validate_title(title)?;
+
+    let now = Utc::now().naive_utc();
+    let event = event_repository::create_event(
+        conn,
+        &NewEvent {
+            team_id,
+            title: title.to_string(),
+            starts_at: now,
+            ends_at: now + duration,
+            code_secret: Some(check_in_code::generate_secret()),
+            created_by: actor.discord_id.clone(),
+            recurrence_rule: None,
+        },
+    )?;
+
+    record_event(conn, actor, AuditAction::EventCreate, &event, event_details(&event));
+    Ok(event)
+}
+
+/// Schedules an event for a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `title` - The title of the event.
+/// * `starts_at` - The timestamp when the (first occurrence of the) event starts.
+/// * `ends_at` - The timestamp when the (first occurrence of the) event ends.
+/// * `recurrence_rule` - The iCalendar recurrence rule of the event, if it repeats.
+/// * `actor` - The user scheduling the event, recorded in the audit log.
+///
+/// # Returns
+/// Returns the new event.
+///
+/// # Errors
+/// Returns an error if:
+/// - The title is invalid.
+/// - The event does not end after it starts or lasts longer than a day.
+/// - The recurrence rule is not a supported iCalendar rule or never matches.
+/// - The event cannot be inserted into the database.
+pub fn create_event(
+    conn: &mut PgConnection,
+    team_id: i32,
+    title: &str,
+    starts_at: NaiveDateTime,
+    ends_at: NaiveDateTime,
+    recurrence_rule: Option<&str>,
+    actor: &AuditActor,
+) -> Result<Event, String> {
+    let title = validate_title(title)?;
+    if ends_at <= starts_at {
+        return Err("An event must end after it starts".to_string());
+    }
+    if ends_at - starts_at > Duration::hours(EVENT_MAX_HOURS) {
+        return Err(format!(
+            "An event cannot last longer than {} hours",
+            EVENT_MAX_HOURS
+        ));
+    }
+
+    let recurrence_rule = recurrence_rule.map(|rule| rule.trim().to_uppercase());
+    if let Some(rule) = &recurrence_rule {
+        if rule.len() > 255 {
+            return Err("The recurrence rule cannot be longer than 255 characters".to_string());
+        }
+        let parsed = rule
+            .parse::<RecurrenceRule>()
+            .map_err(|e| format!("Invalid recurrence rule: {}", e))?;
+        if parsed.next_occurrence(starts_at, &[], starts_at).is_none() {
+            return Err("The recurrence rule never matches".to_string());
+        }
+    }
+
+    let event = event_repository::create_event(
+        conn,
+        &NewEvent {
+            team_id,
+            title: title.to_string(),
+            starts_at,
+            ends_at,
+            code_secret: None,
+            created_by: actor.discord_id.clone(),
+            recurrence_rule,
+        },
+    )?;
+
+    record_event(conn, actor, AuditAction::EventCreate, &event, event_details(&event));
+    Ok(event)
+}
+
+/// Retrieves the events of a team that have not ended yet, with their next occurrence.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+///
+/// # Returns
+/// Returns a `Vec<EventTable>` ordered by start time. Repeating events show their running or
+/// next occurrence and are left out once the rule has ended.
+///
+/// # Errors
+/// Returns an error if the events cannot be fetched from the database.
+pub fn get_upcoming_events(
+    conn: &mut PgConnection,
+    team_id: i32,
+) -> Result<Vec<EventTable>, String> {
+    let now = Utc::now().naive_utc();
+    let events = event_repository::get_upcoming_events(conn, team_id, now)?;
+    let exceptions = get_exceptions(conn, &events)?;
+    let holidays = holiday_service::holiday_dates(conn, team_id)?;
+
+    let mut occurrences = Vec::new();
+    for event in events {
+        let duration = event.ends_at - event.starts_at;
+        let starts_at = match parse_rule(&event)? {
+            Some(rule) => {
+                let exdates = exdates(&exceptions, &holidays, &event);
+                match rule.next_occurrence(event.starts_at, &exdates, now - duration) {
+                    Some(starts_at) => starts_at,
+                    None => continue,
+                }
+            }
+            None => event.starts_at,
+        };
+        occurrences.push(Occurrence {
+            event,
+            starts_at,
+            ends_at: starts_at + duration,
+        });
+    }
+    occurrences.sort_by_key(|occurrence| occurrence.starts_at);
+
+    Ok(occurrences.iter().map(occurrence_table).collect())
+}
+
+/// Formats an event for display.
+///
+/// # Arguments
+/// * `event` - The event.
+///
+/// # Returns
+/// Returns the `EventTable` row of the event, showing its first occurrence.
+pub fn event_table(event: &Event) -> EventTable {
+    occurrence_table(&Occurrence {
+        event: event.clone(),
+        starts_at: event.starts_at,
+        ends_at: event.ends_at,
+    })
+}
+
+/// Formats an occurrence of an event for display.
+///
+/// # Arguments
+/// * `occurrence` - The occurrence.
+///
+/// # Returns
+/// Returns the `EventTable` row of the occurrence.
+pub fn occurrence_table(occurrence: &Occurrence) -> EventTable {
+    EventTable {
+        id: occurrence.event.id,
+        title: occurrence.event.title.clone(),
+        starts_at: occurrence.starts_at.format("%Y-%m-%d %H:%M").to_string(),
+        ends_at: occurrence.ends_at.format("%Y-%m-%d %H:%M").to_string(),
+        repeats: occurrence
+            .event
+            .recurrence_rule
+            .clone()
+            .unwrap_or_else(|| "-".to_string()),
+    }
+}
+
+/// Expands the events of a team into the occurrences overlapping a window of time.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `from` - The start of the window.
+/// * `to` - The end of the window.
+///
+/// # Returns
+/// Returns the occurrences that end after `from` and start before `to`, oldest first.
+/// Cancelled events, skipped occurrences and the occurrences of repeating events on a holiday
+/// of the team are left out.
+///
+/// # Errors
+/// Returns an error if the events cannot be fetched from the database or an event has an
+/// invalid recurrence rule.
+pub fn get_occurrences(
+    conn: &mut PgConnection,
+    team_id: i32,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<Vec<Occurrence>, String> {
+    let events = event_repository::get_events_in_window(conn, team_id, from, to)?;
+    let exceptions = get_exceptions(conn, &events)?;
+    let holidays = holiday_service::holiday_dates(conn, team_id)?;
+
+    let mut occurrences = Vec::new();
+    for event in events {
+        let duration = event.ends_at - event.starts_at;
+        let starts = match parse_rule(&event)? {
+            Some(rule) => rule.occurrences_between(
+                event.starts_at,
+                &exdates(&exceptions, &holidays, &event),
+                from - duration,
+                to,
+            ),
+            None => vec![event.starts_at],
+        };
+        for starts_at in starts {
+            if starts_at + duration > from && starts_at < to {
+                occurrences.push(Occurrence {
+                    event: event.clone(),
+                    starts_at,
+                    ends_at: starts_at + duration,
+                });
+            }
+        }
+    }
+    occurrences.sort_by_key(|occurrence| occurrence.starts_at);
+
+    Ok(occurrences)
+}
+
+/// Finds an occurrence of an event.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `event` - The event.
+/// * `on` - The date of the occurrence, or `None` for the latest occurrence that started
+///   (the first one if none has started yet).
+///
+/// # Errors
+/// Returns an error if the event does not take place on that date or has no occurrences.
+pub fn find_occurrence(
+    conn: &mut PgConnection,
+    event: &Event,
+    on: Option<NaiveDate>,
+) -> Result<Occurrence, String> {
+    let duration = event.ends_at - event.starts_at;
+    let starts_at = match (parse_rule(event)?, on) {
+        (None, Some(on)) if event.starts_at.date() != on => None,
+        (None, _) => Some(event.starts_at),
+        (Some(rule), on) => {
+            let exceptions = get_exceptions(conn, std::slice::from_ref(event))?;
+            let holidays = holiday_service::holiday_dates(conn, event.team_id)?;
+            let exdates = exdates(&exceptions, &holidays, event);
+            match on {
+                Some(on) => {
+                    let day = on.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+                    rule.occurrences_between(
+                        event.starts_at,
+                        &exdates,
+                        day,
+                        day + Duration::days(1),
+                    )
+                    .first()
+                    .copied()
+                }
+                None => rule
+                    .occurrences_between(
+                        event.starts_at,
+                        &exdates,
+                        event.starts_at,
+                        Utc::now().naive_utc(),
+                    )
+                    .last()
+                    .copied()
+                    .or_else(|| rule.next_occurrence(event.starts_at, &exdates, event.starts_at)),
+            }
+        }
+    };
+
+    let starts_at = starts_at.ok_or_else(|| match on {
+        Some(on) => format!("Event {} does not take place on {}", event.id, on),
+        None => format!("Event {} has no occurrences", event.id),
+    })?;
+
+    Ok(Occurrence {
+        event: event.clone(),
+        starts_at,
+        ends_at: starts_at + duration,
+    })
+}
+
+/// Skips the occurrence of a repeating event on a date (an iCalendar `EXDATE`).
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `event` - The repeating event.
+/// * `on` - The date of the occurrence to skip.
+/// * `actor` - Who skips the occurrence, recorded in the audit log.
+///
+/// # Returns
+/// Returns the start of the skipped occurrence.
+///
+/// # Errors
+/// Returns an error if the event does not repeat, does not take place on that date or the
+/// exception cannot be inserted into the database.
+pub fn skip_occurrence(
+    conn: &mut PgConnection,
+    event: &Event,
+    on: NaiveDate,
+    actor: &AuditActor,
+) -> Result<NaiveDateTime, String> {
+    if event.recurrence_rule.is_none() {
+        return Err(
+            "Only repeating events can skip an occurrence, cancel the event instead".to_string(),
+        );
+    }
+
+    let occurrence = find_occurrence(conn, event, Some(on))?;
+    event_repository::add_exception(
+        conn,
+        &NewEventException {
+            event_id: event.id,
+            starts_at: occurrence.starts_at,
+        },
+    )?;
+
+    record_event(
+        conn,
+        actor,
+        AuditAction::EventSkip,
+        event,
+        json!({ "starts_at": occurrence.starts_at }),
+    );
+    Ok(occurrence.starts_at)
+}
+
+/// Finds an event of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `event_id` - The ID of the event.
+///
+/// # Errors
+/// Returns an error if the event does not exist, belongs to another team or cannot be fetched.
+pub fn find_team_event(
+    conn: &mut PgConnection,
+    team_id: i32,
+    event_id: i32,
+) -> Result<Event, String> {
+    event_repository::find_event(conn, event_id)?
+        .filter(|event| event.team_id == team_id)
+        .ok_or_else(|| format!("Event {} not found", event_id))
+}
+
+/// Cancels an event, members can no longer check in to it.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `event_id` - The ID of the event.
+/// * `actor` - Who cancels the event, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the event does not exist, is already cancelled or cannot be updated.
+pub fn cancel_event(
+    conn: &mut PgConnection,
+    event_id: i32,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let event = event_repository::find_event(conn, event_id)?
+        .ok_or_else(|| format!("Event {} not found", event_id))?;
+    if !event_repository::cancel_event(conn, event_id)? {
+        return Err("This event is already cancelled".to_string());
+    }
+
+    record_event(conn, actor, AuditAction::EventCancel, &event, json!({ "title": event.title }));
+    Ok(())
+}
+
+/// Finds the scheduled event of a team a check-in at a point in time belongs to.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `at` - The point in time of the check-in.
+///
+/// # Returns
+/// Returns the event that is running or starts within the check-in grace period, or `None`.
+///
+/// # Errors
+/// Returns an error if the events cannot be fetched from the database.
+pub fn find_open_event(
+    conn: &mut PgConnection,
+    team_id: i32,
+    at: NaiveDateTime,
+) -> Result<Option<Event>, String> {
+    let opens_at = at + Duration::minutes(EVENT_CHECK_IN_GRACE_MINUTES);
+    Ok(get_occurrences(conn, team_id, at, opens_at)?
+        .into_iter()
+        .find(|occurrence| occurrence.event.code_secret.is_none())
+        .map(|occurrence| occurrence.event))
+}
+
+/// Retrieves the attendance of the members of a team at an occurrence of an event.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `occurrence` - The occurrence.
+///
+/// # Returns
+/// Returns a row for every member who checked in, followed by a "No-show" row for every
+/// active member who did not ("Excused" for the members on approved leave that day). For
+/// repeating events only the check-ins made from the check-in grace period before the
+/// occurrence until its end are counted.
+///
+/// # Errors
+/// Returns an error if the members or their attendance cannot be fetched from the database.
+pub fn get_event_attendance(
+    conn: &mut PgConnection,
+    occurrence: &Occurrence,
+) -> Result<Vec<EventAttendanceTable>, String> {
+    let event = &occurrence.event;
+    let opens_at = occurrence.starts_at - Duration::minutes(EVENT_CHECK_IN_GRACE_MINUTES);
+    let mut attended = event_repository::get_event_attendance(conn, event.id)?;
+    if event.recurrence_rule.is_some() {
+        attended.retain(|(attendance, _)| match attendance.check_in_time {
+            Some(time) => time >= opens_at && time <= occurrence.ends_at,
+            None => attendance.date == occurrence.starts_at.date(),
+        });
+    }
+    let format_time = |time: Option<NaiveDateTime>| {
+        time.map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "N/A".to_string())
+    };
+
+    let on_leave =
+        leave_service::members_on_leave(conn, event.team_id, occurrence.starts_at.date())?;
+    let mut rows = Vec::new();
+    for member in member_repository::get_active_members(conn, event.team_id)? {
+        if !attended
+            .iter()
+            .any(|(_, attendee)| attendee.id == member.id)
+        {
+            rows.push(EventAttendanceTable {
+                username: member.username,
+                status: if on_leave.contains(&member.id) {
+                    "Excused"
+                } else {
+                    "No-show"
+                }
+                .to_string(),
+                check_in_time: "N/A".to_string(),
+                check_out_time: "N/A".to_string(),
+            });
+        }
+    }
+
+    let mut attendance: Vec<EventAttendanceTable> = attended
+        .into_iter()
+        .map(|(attendance, member)| EventAttendanceTable {
+            username: member.username,
+            status: attendance.status.unwrap_or_else(|| "N/A".to_string()),
+            check_in_time: format_time(attendance.check_in_time),
+            check_out_time: format_time(attendance.check_out_time),
+        })
+        .collect();
+    attendance.append(&mut rows);
+
+    Ok(attendance)
+}
+
+/// Finds an event by its ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `event_id` - The ID of the event.
+///
+/// # Errors
+/// Returns an error if the event cannot be fetched from the database.
+pub fn find_event(conn: &mut PgConnection, event_id: i32) -> Result<Option<Event>, String> {
+    event_repository::find_event(conn, event_id)
+}
+
+/// Returns the check-in code of an event at a point in time.
+///
+/// # Arguments
+/// * `event` - The event.
+/// * `at` - The point in time.
+///
+/// # Returns
+/// Returns the code together with the number of seconds until it rotates, or `None` if the
+/// event does not use check-in codes or is not running.
+pub fn current_code(event: &Event, at: NaiveDateTime) -> Option<(String, i64)> {
+    let secret = event.code_secret.as_deref()?;
+    if at < event.starts_at || at >= event.ends_at {
+        return None;
+    }
+
+    let unix_time = at.and_utc().timestamp();
+    Some((
+        check_in_code::code_at(secret, unix_time),
+        check_in_code::seconds_until_rotation(unix_time),
+    ))
+}
+
+/// Finds the running event of a team a check-in code belongs to.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `code` - The code typed by the member.
+///
+/// # Errors
+/// Returns an error if no running event of the team accepts the code.
+pub fn verify_code(conn: &mut PgConnection, team_id: i32, code: &str) -> Result<Event, String> {
+    let now = Utc::now().naive_utc();
+    let unix_time = now.and_utc().timestamp();
+
+    event_repository::get_running_code_events(conn, team_id, now)?
+        .into_iter()
+        .find(|event| {
+            event
+                .code_secret
+                .as_deref()
+                .is_some_and(|secret| check_in_code::verify(secret, code, unix_time))
+        })
+        .ok_or_else(|| "Invalid or expired check-in code.".to_string())
+}
+
+/// Trims an event title and checks its length.
+fn validate_title(title: &str) -> Result<&str, String> {
+    let title = title.trim();
+    if title.is_empty() || title.chars().count() > 100 {
+        return Err("Event title must be between 1 and 100 characters".to_string());
+    }
+
+    Ok(title)
+}
+
+/// Records an action on an event in the audit log, the event ID as its target.
+fn record_event(
+    conn: &mut PgConnection,
+    actor: &AuditActor,
+    action: AuditAction,
+    event: &Event,
+    details: serde_json::Value,
+) {
+    let target = event.id.to_string();
+    audit_service::record(conn, actor, action, Some(event.team_id), Some(&target), details);
+}
+
+/// Returns the schedule of a new event, recorded in the audit log.
+fn event_details(event: &Event) -> serde_json::Value {
+    json!({
+        "title": event.title,
+        "starts_at": event.starts_at,
+        "ends_at": event.ends_at,
+        "recurrence_rule": event.recurrence_rule,
+    })
+}
+
+fn parse_rule(event: &Event) -> Result<Option<RecurrenceRule>, String> {
+    event
+        .recurrence_rule
+        .as_deref()
+        .map(|rule| {
+            rule.parse::<RecurrenceRule>()
+                .map_err(|e| format!("Event {} has an invalid recurrence rule: {}", event.id, e))
+        })
+        .transpose()
+}
+
+fn get_exceptions(
+    conn: &mut PgConnection,
+    events: &[Event],
+) -> Result<Vec<EventException>, String> {
+    let repeating: Vec<i32> = events
+        .iter()
+        .filter(|event| event.recurrence_rule.is_some())
+        .map(|event| event.id)
+        .collect();
+    if repeating.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    event_repository::get_exceptions(conn, &repeating)
+}
+
+/// The starts of the occurrences of a repeating event that do not take place: the skipped
+/// ones and those on a holiday of the team. Every occurrence starts at the time of day of
+/// the first one.
+fn exdates(
+    exceptions: &[EventException],
+    holidays: &HashSet<NaiveDate>,
+    event: &Event,
+) -> Vec<NaiveDateTime> {
+    exceptions
+        .iter()
+        .filter(|exception| exception.event_id == event.id)
+        .map(|exception| exception.starts_at)
+        .chain(
+            holidays
+                .iter()
+                .map(|holiday| holiday.and_time(event.starts_at.time())),
+        )
+        .collect()
+}
@@ -0,0 +1,244 @@
+use crate::bot::{
+    application::services::audit_service,
+    domain::{
+        audit::{AuditAction, AuditActor},
+        ics,
+        model::NewHoliday,
+        table::HolidayTable,
+    },
+    infrastructure::persistence::holiday_repository,
+};
+use crate::config::constant::HOLIDAY_IMPORT_MAX_DAYS;
+use chrono::{Datelike, NaiveDate, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+use std::collections::HashSet;
+
+/// Longest name of a holiday, longer names are cut.
+const HOLIDAY_NAME_MAX_CHARS: usize = 100;
+
+/// The outcome of a holiday import.
+///
+/// # Fields
+/// * `added` - The number of days added to the calendar.
+/// * `existing` - The number of days that already were holidays.
+/// * `skipped` - The number of events of the file that could not be read.
+#[derive(Debug)]
+pub struct HolidayImport {
+    pub added: usize,
+    pub existing: usize,
+    pub skipped: usize,
+}
+
+/// Adds a day off to the holiday calendar of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `date` - The day off.
+/// * `name` - The name of the holiday.
+/// * `actor` - Who adds the holiday, recorded in the audit log.
+///
+/// # Returns
+/// Returns the `HolidayTable` row of the new day off.
+///
+/// # Errors
+/// Returns an error if the name is empty, the day already is a holiday or it cannot be
+/// inserted into the database.
+pub fn add_holiday(
+    conn: &mut PgConnection,
+    team_id: i32,
+    date: NaiveDate,
+    name: &str,
+    actor: &AuditActor,
+) -> Result<HolidayTable, String> {
+    let name: String = name.trim().chars().take(HOLIDAY_NAME_MAX_CHARS).collect();
+    if name.is_empty() {
+        return Err("The holiday needs a name".to_string());
+    }
+
+    let added = holiday_repository::add_holidays(
+        conn,
+        &[NewHoliday {
+            team_id,
+            holiday_date: date,
+            name: name.clone(),
+        }],
+    )?;
+    if added == 0 {
+        return Err(format!("{} already is a holiday", date));
+    }
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::HolidayAdd,
+        Some(team_id),
+        Some(&date.to_string()),
+        json!({ "name": name }),
+    );
+    Ok(holiday_table(date, &name))
+}
+
+/// Adds the days of an iCalendar (`.ics`) file to the holiday calendar of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `content` - The content of the file.
+/// * `actor` - Who imports the file, recorded in the audit log.
+///
+/// # Returns
+/// Returns how many days were added. Repeating events are expanded until the end of next
+/// year, and days before the start of last year are left out.
+///
+/// # Errors
+/// Returns an error if the file is not an iCalendar file, it holds too many days or they
+/// cannot be inserted into the database.
+pub fn import_holidays(
+    conn: &mut PgConnection,
+    team_id: i32,
+    content: &str,
+    actor: &AuditActor,
+) -> Result<HolidayImport, String> {
+    let year = Utc::now().year();
+    let (first_day, last_day) = NaiveDate::from_ymd_opt(year - 1, 1, 1)
+        .zip(NaiveDate::from_ymd_opt(year + 1, 12, 31))
+        .ok_or("Invalid import window")?;
+
+    let import = ics::parse_holidays(content, last_day)?;
+    let mut seen = HashSet::new();
+    let new_holidays: Vec<NewHoliday> = import
+        .holidays
+        .into_iter()
+        .filter(|holiday| holiday.date >= first_day && seen.insert(holiday.date))
+        .map(|holiday| NewHoliday {
+            team_id,
+            holiday_date: holiday.date,
+            name: holiday.name.chars().take(HOLIDAY_NAME_MAX_CHARS).collect(),
+        })
+        .collect();
+    if new_holidays.len() > HOLIDAY_IMPORT_MAX_DAYS {
+        return Err(format!(
+            "The file holds {} days, at most {} can be imported at once",
+            new_holidays.len(),
+            HOLIDAY_IMPORT_MAX_DAYS
+        ));
+    }
+
+    let added = holiday_repository::add_holidays(conn, &new_holidays)?;
+    let import = HolidayImport {
+        added,
+        existing: new_holidays.len() - added,
+        skipped: import.skipped,
+    };
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::HolidayImport,
+        Some(team_id),
+        None,
+        json!({
+            "added": import.added,
+            "existing": import.existing,
+            "skipped": import.skipped,
+        }),
+    );
+    Ok(import)
+}
+
+/// Retrieves the holiday calendar of a team for a year.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `year` - The calendar year.
+///
+/// # Returns
+/// Returns a `Vec<HolidayTable>` ordered by date.
+///
+/// # Errors
+/// Returns an error if the year is invalid or the holidays cannot be fetched from the database.
+pub fn get_holidays(
+    conn: &mut PgConnection,
+    team_id: i32,
+    year: i32,
+) -> Result<Vec<HolidayTable>, String> {
+    let (first_day, last_day) = NaiveDate::from_ymd_opt(year, 1, 1)
+        .zip(NaiveDate::from_ymd_opt(year, 12, 31))
+        .ok_or_else(|| format!("Invalid year {}", year))?;
+
+    Ok(
+        holiday_repository::get_holidays(conn, team_id, first_day, last_day)?
+            .iter()
+            .map(|holiday| holiday_table(holiday.holiday_date, &holiday.name))
+            .collect(),
+    )
+}
+
+/// Removes a day off from the holiday calendar of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `date` - The day off.
+/// * `actor` - Who removes the holiday, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the day is not a holiday or it cannot be deleted from the database.
+pub fn remove_holiday(
+    conn: &mut PgConnection,
+    team_id: i32,
+    date: NaiveDate,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    if !holiday_repository::remove_holiday(conn, team_id, date)? {
+        return Err(format!("{} is not a holiday", date));
+    }
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::HolidayRemove,
+        Some(team_id),
+        Some(&date.to_string()),
+        json!({}),
+    );
+    Ok(())
+}
+
+/// Retrieves the dates of the days off of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the holidays cannot be fetched from the database.
+pub fn holiday_dates(conn: &mut PgConnection, team_id: i32) -> Result<HashSet<NaiveDate>, String> {
+    Ok(holiday_repository::get_holiday_dates(conn, team_id)?
+        .into_iter()
+        .collect())
+}
+
+/// Checks whether a day is a day off for a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `date` - The day.
+///
+/// # Errors
+/// Returns an error if the holidays cannot be fetched from the database.
+pub fn is_holiday(conn: &mut PgConnection, team_id: i32, date: NaiveDate) -> Result<bool, String> {
+    Ok(!holiday_repository::get_holidays(conn, team_id, date, date)?.is_empty())
+}
+
+fn holiday_table(date: NaiveDate, name: &str) -> HolidayTable {
+    HolidayTable {
+        date: date.to_string(),
+        weekday: date.format("%A").to_string(),
+        name: name.to_string(),
+    }
+}
@@ -0,0 +1,680 @@
+use crate::bot::{
+    application::services::{audit_service, holiday_service, role_service},
+    domain::{
+        audit::{self, AuditAction, AuditActor},
+        leave::{working_days, LeaveStatus, LeaveType},
+        model::{LeaveRequest, Member, NewLeaveBalance, NewLeaveRequest},
+        permission::{Permission, Role},
+        table::{LeaveBalanceTable, LeaveRequestTable},
+    },
+    infrastructure::persistence::{
+        attendance_repository, leave_repository, member_repository, team_repository,
+        user_repository,
+    },
+};
+use crate::config::{constant::LEAVE_MAX_DAYS, database};
+use chrono::{Datelike, NaiveDate, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+
+/// Represents a leave request together with what is needed to notify the people involved.
+///
+/// # Fields
+/// * `request` - The leave request.
+/// * `team_name` - The name of the team.
+/// * `username` - The name of the member taking leave.
+/// * `discord_id` - The Discord ID of the member taking leave.
+/// * `days` - The number of working days the leave covers.
+/// * `channel_id` - The ID of the channel the team reviews leave requests in, if any.
+/// * `admin_discord_id` - The Discord ID of the team admin, who gets a DM when there is
+///   no review channel.
+#[derive(Debug, Clone)]
+pub struct LeaveReview {
+    pub request: LeaveRequest,
+    pub team_name: String,
+    pub username: String,
+    pub discord_id: String,
+    pub days: i64,
+    pub channel_id: Option<String>,
+    pub admin_discord_id: Option<String>,
+}
+
+/// Creates a pending leave request for a member.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member taking leave.
+/// * `starts_on` - The first day of leave.
+/// * `ends_on` - The last day of leave.
+/// * `leave_type` - The kind of leave, as typed by the member.
+/// * `reason` - Why the member takes leave.
+///
+/// # Returns
+/// Returns the new request, ready to be posted for review.
+///
+/// # Errors
+/// Returns an error if:
+/// - The user is not an active member of the team.
+/// - The leave type, the days or the reason are invalid.
+/// - The member already has pending or approved leave on one of the days.
+/// - The request exceeds the allowance of the member.
+/// - The request cannot be inserted into the database.
+pub fn request_leave(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    starts_on: NaiveDate,
+    ends_on: NaiveDate,
+    leave_type: &str,
+    reason: &str,
+) -> Result<LeaveReview, String> {
+    let member = member_repository::find_member(conn, team_id, discord_id)?
+        .filter(|member| member.left_date.is_none())
+        .ok_or("You are not an active member of this team.")?;
+    let leave_type = LeaveType::parse(leave_type)
+        .ok_or("The leave type must be one of: vacation, sick, personal, other")?;
+
+    if ends_on < starts_on {
+        return Err("The leave cannot end before it starts".to_string());
+    }
+    if (ends_on - starts_on).num_days() >= LEAVE_MAX_DAYS {
+        return Err(format!(
+            "A leave request cannot cover more than {} days",
+            LEAVE_MAX_DAYS
+        ));
+    }
+    let holidays = holiday_service::holiday_dates(conn, team_id)?;
+    if working_days(starts_on, ends_on, &holidays) == 0 {
+        return Err("The leave does not cover any working day".to_string());
+    }
+
+    let reason = reason.trim();
+    if reason.is_empty() || reason.chars().count() > 255 {
+        return Err("The reason must be between 1 and 255 characters".to_string());
+    }
+
+    // the checks and the insert run under a lock on the member, so concurrent requests cannot
+    // both pass them
+    let request = database::transaction(conn, |conn| {
+        member_repository::lock_member(conn, member.id)?;
+
+        let overlapping = leave_repository::get_member_requests_between(
+            conn,
+            member.id,
+            starts_on,
+            ends_on,
+            &[
+                LeaveStatus::Pending.as_str(),
+                LeaveStatus::Approved.as_str(),
+            ],
+        )?;
+        if let Some(existing) = overlapping.first() {
+            return Err(format!(
+                "You already have leave request #{} from {} to {}",
+                existing.id, existing.starts_on, existing.ends_on
+            ));
+        }
+
+        check_allowance(conn, member.id, leave_type, starts_on, ends_on, &holidays)?;
+
+        leave_repository::create_request(
+            conn,
+            &NewLeaveRequest {
+                team_id,
+                member_id: member.id,
+                leave_type: leave_type.to_string(),
+                starts_on,
+                ends_on,
+                reason: reason.to_string(),
+            },
+        )
+    })?;
+
+    leave_review(conn, request, member, &holidays)
+}
+
+/// Approves or rejects a pending leave request.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `request_id` - The ID of the leave request.
+/// * `actor` - The user reviewing the request, recorded in the audit log.
+/// * `approve` - Whether the request is approved or rejected.
+///
+/// # Returns
+/// Returns the reviewed request, ready to be sent to the member.
+///
+/// # Behavior
+/// - Approving a request marks the absences already recorded on its days as excused.
+/// - Only the owner of a team may review their own requests.
+///
+/// # Errors
+/// Returns an error if:
+/// - The request does not exist or was already reviewed.
+/// - The reviewer may not manage the members of the team.
+/// - Approving the request would exceed the allowance of the member.
+/// - The request cannot be updated in the database.
+pub fn review_request(
+    conn: &mut PgConnection,
+    request_id: i32,
+    actor: &AuditActor,
+    approve: bool,
+) -> Result<LeaveReview, String> {
+    let reviewer = actor.discord_id.as_str();
+    let (request, member) = leave_repository::find_request(conn, request_id)?
+        .ok_or_else(|| format!("Leave request {} not found", request_id))?;
+
+    let role = role_service::authorize(conn, request.team_id, reviewer, Permission::ManageMembers)?;
+    if member.discord_id == reviewer && role != Role::Owner {
+        return Err("You cannot review your own leave request.".to_string());
+    }
+    if request.status != LeaveStatus::Pending.as_str() {
+        return Err(format!("This leave request is already {}.", request.status));
+    }
+
+    let holidays = holiday_service::holiday_dates(conn, request.team_id)?;
+    // the allowance check and the review run under a lock on the member, so concurrent
+    // approvals cannot both pass the check
+    let new_status = database::transaction(conn, |conn| {
+        member_repository::lock_member(conn, member.id)?;
+
+        let new_status = if approve {
+            let leave_type = LeaveType::parse(&request.leave_type).unwrap_or(LeaveType::Other);
+            check_allowance(
+                conn,
+                member.id,
+                leave_type,
+                request.starts_on,
+                request.ends_on,
+                &holidays,
+            )?;
+            LeaveStatus::Approved
+        } else {
+            LeaveStatus::Rejected
+        };
+
+        if !leave_repository::set_status(
+            conn,
+            request.id,
+            &[LeaveStatus::Pending.as_str()],
+            new_status.as_str(),
+            Some(reviewer),
+        )? {
+            return Err("This leave request was already reviewed.".to_string());
+        }
+        if approve {
+            attendance_repository::excuse_absences(
+                conn,
+                member.id,
+                request.starts_on,
+                request.ends_on,
+            )?;
+        }
+
+        audit_service::record(
+            conn,
+            actor,
+            AuditAction::LeaveReview,
+            Some(request.team_id),
+            Some(&member.discord_id),
+            json!({
+                "request_id": request.id,
+                "status": { "from": request.status, "to": new_status.as_str() },
+            }),
+        );
+        Ok(new_status)
+    })?;
+
+    leave_review(
+        conn,
+        LeaveRequest {
+            status: new_status.to_string(),
+            reviewed_by: Some(reviewer.to_string()),
+            ..request
+        },
+        member,
+        &holidays,
+    )
+}
+
+/// Withdraws a leave request.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `request_id` - The ID of the leave request.
+/// * `discord_id` - The Discord ID of the member withdrawing the request.
+///
+/// # Returns
+/// Returns the cancelled request.
+///
+/// # Errors
+/// Returns an error if:
+/// - The request does not exist in the team or belongs to someone else.
+/// - The request was rejected, cancelled or the approved leave has already started.
+/// - The request cannot be updated in the database.
+pub fn cancel_request(
+    conn: &mut PgConnection,
+    team_id: i32,
+    request_id: i32,
+    discord_id: &str,
+) -> Result<LeaveRequest, String> {
+    let (request, member) = leave_repository::find_request(conn, request_id)?
+        .filter(|(request, _)| request.team_id == team_id)
+        .ok_or_else(|| format!("Leave request {} not found", request_id))?;
+    if member.discord_id != discord_id {
+        return Err("You can only cancel your own leave requests.".to_string());
+    }
+
+    let today = Utc::now().date_naive();
+    let cancellable = match LeaveStatus::parse(&request.status) {
+        Some(LeaveStatus::Pending) => true,
+        Some(LeaveStatus::Approved) => request.starts_on > today,
+        _ => false,
+    };
+    if !cancellable
+        || !leave_repository::set_status(
+            conn,
+            request.id,
+            &[
+                LeaveStatus::Pending.as_str(),
+                LeaveStatus::Approved.as_str(),
+            ],
+            LeaveStatus::Cancelled.as_str(),
+            None,
+        )?
+    {
+        return Err(format!(
+            "Leave request {} is {} and cannot be cancelled anymore.",
+            request.id, request.status
+        ));
+    }
+
+    Ok(LeaveRequest {
+        status: LeaveStatus::Cancelled.to_string(),
+        ..request
+    })
+}
+
+/// Retrieves the leave requests of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `pending_only` - Whether only the requests waiting for review are returned.
+///
+/// # Returns
+/// Returns a `Vec<LeaveRequestTable>` ordered by the first day of leave.
+///
+/// # Errors
+/// Returns an error if the leave requests cannot be fetched from the database.
+pub fn get_requests(
+    conn: &mut PgConnection,
+    team_id: i32,
+    pending_only: bool,
+) -> Result<Vec<LeaveRequestTable>, String> {
+    let status = pending_only.then_some(LeaveStatus::Pending.as_str());
+    let holidays = holiday_service::holiday_dates(conn, team_id)?;
+
+    Ok(leave_repository::get_requests(conn, team_id, status)?
+        .into_iter()
+        .map(|(request, member)| {
+            let days = working_days(request.starts_on, request.ends_on, &holidays);
+            leave_request_table(&request, &member.username, days)
+        })
+        .collect())
+}
+
+/// Finds a leave request by ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `request_id` - The ID of the leave request.
+///
+/// # Returns
+/// Returns the leave request, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the leave request cannot be fetched from the database.
+pub fn find_request(
+    conn: &mut PgConnection,
+    request_id: i32,
+) -> Result<Option<LeaveRequest>, String> {
+    Ok(leave_repository::find_request(conn, request_id)?.map(|(request, _)| request))
+}
+
+/// Builds the table row of a leave request.
+///
+/// # Arguments
+/// * `request` - The leave request.
+/// * `username` - The name of the member taking leave.
+/// * `days` - The number of working days the leave covers.
+///
+/// # Returns
+/// Returns the `LeaveRequestTable` row of the request.
+pub fn leave_request_table(request: &LeaveRequest, username: &str, days: i64) -> LeaveRequestTable {
+    LeaveRequestTable {
+        id: request.id,
+        username: username.to_string(),
+        leave_type: request.leave_type.clone(),
+        from: request.starts_on.to_string(),
+        to: request.ends_on.to_string(),
+        days,
+        status: request.status.clone(),
+        reason: request.reason.clone(),
+    }
+}
+
+/// Sets how many working days of a leave type a member may take in a year.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member.
+/// * `year` - The calendar year.
+/// * `leave_type` - The kind of leave, as typed by the manager.
+/// * `days` - The number of working days.
+/// * `actor` - Who sets the allowance, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the user is not a member of the team, the arguments are invalid or the
+/// allowance cannot be saved in the database.
+pub fn set_allowance(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    year: i32,
+    leave_type: &str,
+    days: i32,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let member = member_repository::find_member(conn, team_id, discord_id)?
+        .ok_or("The user is not a member of this team.")?;
+    let leave_type = LeaveType::parse(leave_type)
+        .ok_or("The leave type must be one of: vacation, sick, personal, other")?;
+    if !(2000..=2100).contains(&year) {
+        return Err("The year must be between 2000 and 2100".to_string());
+    }
+    if !(0..=366).contains(&days) {
+        return Err("The allowance must be between 0 and 366 days".to_string());
+    }
+
+    let previous = leave_repository::find_balance(conn, member.id, year, leave_type.as_str())?
+        .map(|balance| balance.allowance_days);
+    leave_repository::set_balance(
+        conn,
+        &NewLeaveBalance {
+            member_id: member.id,
+            year,
+            leave_type: leave_type.to_string(),
+            allowance_days: days,
+        },
+    )?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::LeaveAllowance,
+        Some(team_id),
+        Some(discord_id),
+        json!({
+            "year": year,
+            "leave_type": leave_type.as_str(),
+            "allowance_days": { "from": previous, "to": days },
+        }),
+    );
+    Ok(())
+}
+
+/// Retrieves the leave the members of a team took in a year against their allowances.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `year` - The calendar year.
+///
+/// # Returns
+/// Returns a row for every allowance and for every leave type a member used without one,
+/// ordered by member and leave type.
+///
+/// # Errors
+/// Returns an error if the allowances or the leave requests cannot be fetched from the database.
+pub fn get_balances(
+    conn: &mut PgConnection,
+    team_id: i32,
+    year: i32,
+) -> Result<Vec<LeaveBalanceTable>, String> {
+    let (first_day, last_day) = year_bounds(year)?;
+    let holidays = holiday_service::holiday_dates(conn, team_id)?;
+
+    let mut used: HashMap<(i32, String), (String, i64)> = HashMap::new();
+    for (request, member) in
+        leave_repository::get_requests(conn, team_id, Some(LeaveStatus::Approved.as_str()))?
+    {
+        if request.ends_on < first_day || request.starts_on > last_day {
+            continue;
+        }
+        let days = working_days(
+            request.starts_on.max(first_day),
+            request.ends_on.min(last_day),
+            &holidays,
+        );
+        used.entry((member.id, request.leave_type))
+            .or_insert((member.username, 0))
+            .1 += days;
+    }
+
+    let mut rows = Vec::new();
+    for (balance, member) in leave_repository::get_balances(conn, team_id, year)? {
+        let used_days = used
+            .remove(&(member.id, balance.leave_type.clone()))
+            .map(|(_, days)| days)
+            .unwrap_or(0);
+        rows.push(LeaveBalanceTable {
+            username: member.username,
+            leave_type: balance.leave_type,
+            allowance: balance.allowance_days.to_string(),
+            used: used_days,
+            remaining: (balance.allowance_days as i64 - used_days)
+                .max(0)
+                .to_string(),
+        });
+    }
+    for ((_, leave_type), (username, used_days)) in used {
+        rows.push(LeaveBalanceTable {
+            username,
+            leave_type,
+            allowance: "-".to_string(),
+            used: used_days,
+            remaining: "-".to_string(),
+        });
+    }
+    rows.sort_by(|a, b| (&a.username, &a.leave_type).cmp(&(&b.username, &b.leave_type)));
+
+    Ok(rows)
+}
+
+/// Retrieves the members of a team who are on approved leave on a day.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `date` - The day.
+///
+/// # Returns
+/// Returns the IDs of the members.
+///
+/// # Errors
+/// Returns an error if the leave requests cannot be fetched from the database.
+pub fn members_on_leave(
+    conn: &mut PgConnection,
+    team_id: i32,
+    date: NaiveDate,
+) -> Result<Vec<i32>, String> {
+    Ok(
+        leave_repository::get_approved_between(conn, team_id, date, date)?
+            .into_iter()
+            .map(|request| request.member_id)
+            .collect(),
+    )
+}
+
+/// Sets the channel leave requests of a team are posted to for review.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `channel_id` - The ID of the channel, or `None` to send them to the team admin.
+/// * `actor` - Who sets the channel, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn set_leave_channel(
+    conn: &mut PgConnection,
+    team_id: i32,
+    channel_id: Option<&str>,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let team = team_repository::find_team(conn, team_id)?.ok_or("Team not found")?;
+    team_repository::set_leave_channel(conn, team_id, channel_id)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::LeaveChannel,
+        Some(team_id),
+        None,
+        audit::diff(&[(
+            "leave_channel_id",
+            json!(team.leave_channel_id),
+            json!(channel_id),
+        )]),
+    );
+    Ok(())
+}
+
+/// Checks that a member has enough allowance left for leave on some days.
+fn check_allowance(
+    conn: &mut PgConnection,
+    member_id: i32,
+    leave_type: LeaveType,
+    starts_on: NaiveDate,
+    ends_on: NaiveDate,
+    holidays: &HashSet<NaiveDate>,
+) -> Result<(), String> {
+    for year in starts_on.year()..=ends_on.year() {
+        let Some(balance) =
+            leave_repository::find_balance(conn, member_id, year, leave_type.as_str())?
+        else {
+            continue;
+        };
+
+        let (first_day, last_day) = year_bounds(year)?;
+        let used: i64 = leave_repository::get_member_requests_between(
+            conn,
+            member_id,
+            first_day,
+            last_day,
+            &[LeaveStatus::Approved.as_str()],
+        )?
+        .iter()
+        .filter(|request| request.leave_type == leave_type.as_str())
+        .map(|request| {
+            working_days(
+                request.starts_on.max(first_day),
+                request.ends_on.min(last_day),
+                holidays,
+            )
+        })
+        .sum();
+        let requested = working_days(starts_on.max(first_day), ends_on.min(last_day), holidays);
+
+        if used + requested > balance.allowance_days as i64 {
+            return Err(format!(
+                "Only {} of {} {} days are left in {}, {} requested",
+                (balance.allowance_days as i64 - used).max(0),
+                balance.allowance_days,
+                leave_type,
+                year,
+                requested
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn year_bounds(year: i32) -> Result<(NaiveDate, NaiveDate), String> {
+    NaiveDate::from_ymd_opt(year, 1, 1)
+        .zip(NaiveDate::from_ymd_opt(year, 12, 31))
+        .ok_or_else(|| format!("Invalid year {}", year))
+}
+
+fn leave_review(
+    conn: &mut PgConnection,
+    request: LeaveRequest,
+    member: Member,
+    holidays: &HashSet<NaiveDate>,
+) -> Result<LeaveReview, String> {
+    let team = team_repository::find_team(conn, request.team_id)?
+        .ok_or_else(|| format!("Team {} not found", request.team_id))?;
+    let admin_discord_id = match team.admin_id {
+        Some(admin_id) => Some(user_repository::find_discord_id(conn, admin_id)?),
+        None => None,
+    };
+
+    Ok(LeaveReview {
+        days: working_days(request.starts_on, request.ends_on, holidays),
+        request,
+        team_name: team.name,
+        username: member.username,
+        discord_id: member.discord_id,
+        channel_id: team.leave_channel_id,
+        admin_discord_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::database::testing::with_member;
+
+    #[test]
+    #[ignore = "needs a database"]
+    fn approvals_do_not_overdraw_the_allowance() {
+        with_member(|conn, team_id, member_id| {
+            let reviewer = AuditActor::api("2");
+            role_service::grant_role(conn, team_id, "2", Role::Owner, &reviewer).unwrap();
+            leave_repository::set_balance(
+                conn,
+                &NewLeaveBalance {
+                    member_id,
+                    year: 2030,
+                    leave_type: LeaveType::Vacation.to_string(),
+                    allowance_days: 1,
+                },
+            )
+            .unwrap();
+
+            let monday = NaiveDate::from_ymd_opt(2030, 1, 7).unwrap();
+            let tuesday = monday.succ_opt().unwrap();
+            let first = request_leave(conn, team_id, "1", monday, monday, "vacation", "Trip")
+                .unwrap()
+                .request;
+            let second = request_leave(conn, team_id, "1", tuesday, tuesday, "vacation", "Trip")
+                .unwrap()
+                .request;
+
+            review_request(conn, first.id, &reviewer, true).unwrap();
+            let overdrawn = review_request(conn, second.id, &reviewer, true).unwrap_err();
+            assert!(overdrawn.starts_with("Only 0 of 1"), "{}", overdrawn);
+
+            let (second, _) = leave_repository::find_request(conn, second.id)
+                .unwrap()
+                .unwrap();
+            assert_eq!(second.status, LeaveStatus::Pending.as_str());
+        });
+    }
+}
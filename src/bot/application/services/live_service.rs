@@ -0,0 +1,140 @@
+use crate::bot::{
+    application::services::audit_service,
+    domain::{
+        audit::{self, AuditAction, AuditActor},
+        live::{LiveEvent, LiveUpdate, Presence},
+        model::{Member, Team},
+        table::PresenceTable,
+    },
+    infrastructure::{
+        event_bus,
+        persistence::{attendance_repository, team_repository},
+    },
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::PgConnection;
+use serde_json::json;
+
+/// Publishes an attendance change of a member on the live feed of their team.
+///
+/// # Arguments
+/// * `member` - The member whose attendance changed.
+/// * `event` - What happened.
+/// * `status` - The status of the attendance record, if it has one.
+/// * `date` - The day of the attendance record.
+/// * `at` - When the change happened.
+pub fn publish(
+    member: &Member,
+    event: LiveEvent,
+    status: Option<&str>,
+    date: NaiveDate,
+    at: NaiveDateTime,
+) {
+    if let Some(update) = LiveUpdate::new(member, event, status, date, at) {
+        event_bus::publish(update);
+    }
+}
+
+/// Retrieves who is in a team right now.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+///
+/// # Returns
+/// Returns the current members who are checked in and have not checked out yet, oldest check-in
+/// first.
+///
+/// # Errors
+/// Returns an error if the attendance records cannot be fetched from the database.
+pub fn get_presence(conn: &mut PgConnection, team_id: i32) -> Result<Vec<Presence>, String> {
+    let check_ins = attendance_repository::get_open_check_ins(conn, team_id)?;
+    Ok(Presence::from_check_ins(check_ins))
+}
+
+/// Formats who is in a team for display.
+///
+/// # Arguments
+/// * `presence` - The members who are in, as returned by `get_presence`.
+/// * `now` - The current time, check-ins of an earlier day show the day too.
+pub fn get_presence_table(presence: &[Presence], now: NaiveDateTime) -> Vec<PresenceTable> {
+    presence
+        .iter()
+        .map(|member| PresenceTable {
+            member: member.username.clone(),
+            status: member.status.clone().unwrap_or_else(|| "-".to_string()),
+            since: if member.since.date() == now.date() {
+                member.since.format("%H:%M").to_string()
+            } else {
+                member.since.format("%Y-%m-%d %H:%M").to_string()
+            },
+            duration: member.duration(now),
+        })
+        .collect()
+}
+
+/// Sets the channel the presence board of a team is kept in.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `channel_id` - The ID of the channel, or `None` to remove the board.
+/// * `message_id` - The ID of the message of the board posted in the channel.
+/// * `actor` - Who sets the channel, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the team does not exist or cannot be updated in the database.
+pub fn set_presence_board(
+    conn: &mut PgConnection,
+    team_id: i32,
+    channel_id: Option<&str>,
+    message_id: Option<&str>,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let team = team_repository::find_team(conn, team_id)?.ok_or("Team not found")?;
+    team_repository::set_presence_board(conn, team_id, channel_id, message_id)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::PresenceBoard,
+        Some(team_id),
+        None,
+        audit::diff(&[(
+            "presence_channel_id",
+            json!(team.presence_channel_id),
+            json!(channel_id),
+        )]),
+    );
+    Ok(())
+}
+
+/// Records the message of a presence board that was posted again, e.g. after it was deleted.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `channel_id` - The ID of the channel the message was posted in.
+/// * `message_id` - The ID of the new message.
+///
+/// # Behavior
+/// - Does nothing if the board was moved or removed since.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn set_presence_message(
+    conn: &mut PgConnection,
+    team_id: i32,
+    channel_id: &str,
+    message_id: &str,
+) -> Result<(), String> {
+    team_repository::set_presence_message(conn, team_id, channel_id, message_id)
+}
+
+/// Retrieves the active teams that keep a presence board.
+///
+/// # Errors
+/// Returns an error if the teams cannot be fetched from the database.
+pub fn get_presence_boards(conn: &mut PgConnection) -> Result<Vec<Team>, String> {
+    team_repository::get_presence_board_teams(conn)
+}
@@ -0,0 +1,305 @@
+use crate::bot::{
+    application::services::{audit_service, team_service},
+    domain::{
+        audit::{self, AuditAction, AuditActor},
+        model::Member,
+        permission::Role,
+    },
+    infrastructure::persistence::{member_repository, role_repository},
+};
+use chrono::Utc;
+use diesel::PgConnection;
+use serde_json::json;
+
+/// Removes a member from a team, deleting their attendance records.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member.
+/// * `actor` - Who removes the member, recorded in the audit log.
+///
+/// # Behavior
+/// - Deletes the member and their attendance records.
+/// - Revokes their member role, a higher role in the team is kept.
+///
+/// # Errors
+/// Returns an error if the user is not a member of the team or cannot be removed.
+pub fn remove_member(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let member = find_member(conn, team_id, discord_id)?;
+    member_repository::delete_member(conn, member.id)?;
+
+    if role_repository::get_role(conn, team_id, discord_id)?.as_deref()
+        == Some(Role::Member.as_str())
+    {
+        role_repository::remove_role(conn, team_id, discord_id)?;
+    }
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::MemberRemove,
+        Some(team_id),
+        Some(discord_id),
+        json!({ "username": member.username }),
+    );
+    Ok(())
+}
+
+/// Updates the position of a member in a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member.
+/// * `position` - The new position of the member.
+/// * `actor` - Who changes the position, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if:
+/// - The position is empty or longer than 50 characters.
+/// - The user is not a member of the team.
+/// - The member cannot be updated in the database.
+pub fn set_position(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    position: &str,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    if position.is_empty() || position.chars().count() > 50 {
+        return Err("Position must be between 1 and 50 characters".to_string());
+    }
+
+    let member = find_member(conn, team_id, discord_id)?;
+    member_repository::set_position(conn, member.id, position)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::MemberUpdate,
+        Some(team_id),
+        Some(discord_id),
+        audit::diff(&[("position", json!(member.position), json!(position))]),
+    );
+    Ok(())
+}
+
+/// Updates the display name of a member in a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member.
+/// * `username` - The new name of the member.
+/// * `actor` - Who renames the member, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if:
+/// - The name is empty or longer than 100 characters.
+/// - The user is not a member of the team.
+/// - The member cannot be updated in the database.
+pub fn rename_member(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    username: &str,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    if username.is_empty() || username.chars().count() > 100 {
+        return Err("Name must be between 1 and 100 characters".to_string());
+    }
+
+    let member = find_member(conn, team_id, discord_id)?;
+    member_repository::set_username(conn, member.id, username)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::MemberUpdate,
+        Some(team_id),
+        Some(discord_id),
+        audit::diff(&[("username", json!(member.username), json!(username))]),
+    );
+    Ok(())
+}
+
+/// Marks a member as having left the team. Their attendance history is kept,
+/// but they can no longer check in until they are added again.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member.
+/// * `actor` - Who deactivates the member, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if:
+/// - The user is not a member of the team or already left it.
+/// - The member cannot be updated in the database.
+pub fn deactivate_member(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let member = find_member(conn, team_id, discord_id)?;
+    if member.left_date.is_some() {
+        return Err("Member already left this team".to_string());
+    }
+
+    let left_date = Utc::now().date_naive();
+    member_repository::set_left_date(conn, member.id, Some(left_date))?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::MemberDeactivate,
+        Some(team_id),
+        Some(discord_id),
+        audit::diff(&[("left_date", json!(null), json!(left_date))]),
+    );
+    Ok(())
+}
+
+/// Marks a member who left the team as active again.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member.
+/// * `actor` - Who reactivates the member, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if:
+/// - The user is not a member of the team or is still active.
+/// - The member cannot be updated in the database.
+pub fn reactivate_member(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let member = find_member(conn, team_id, discord_id)?;
+    if member.left_date.is_none() {
+        return Err("User is already a member of this team".to_string());
+    }
+
+    member_repository::set_left_date(conn, member.id, None)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::MemberReactivate,
+        Some(team_id),
+        Some(discord_id),
+        audit::diff(&[("left_date", json!(member.left_date), json!(null))]),
+    );
+    Ok(())
+}
+
+/// Represents the changes made while syncing a team with its Discord role.
+///
+/// # Fields
+/// * `added` - The names of the users added to or reactivated in the team.
+/// * `deactivated` - The names of the members deactivated because they lost the role.
+#[derive(Debug, Default)]
+pub struct MemberSync {
+    pub added: Vec<String>,
+    pub deactivated: Vec<String>,
+}
+
+/// Reconciles the active members of a team with the holders of its Discord role.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `role_holders` - The Discord ID and display name of every user with the role.
+/// * `actor` - Who started the sync, recorded in the audit log for every change.
+///
+/// # Behavior
+/// - Adds or reactivates every role holder who is not an active member.
+/// - Deactivates every active member who no longer has the role.
+///
+/// # Errors
+/// Returns an error if the members cannot be fetched or updated in the database.
+pub fn sync_members(
+    conn: &mut PgConnection,
+    team_id: i32,
+    role_holders: &[(String, String)],
+    actor: &AuditActor,
+) -> Result<MemberSync, String> {
+    let active = member_repository::get_active_members(conn, team_id)?;
+    let mut sync = MemberSync::default();
+
+    for (discord_id, name) in role_holders {
+        if !active.iter().any(|member| &member.discord_id == discord_id) {
+            team_service::add_member(conn, discord_id, name.clone(), team_id, actor)?;
+            sync.added.push(name.clone());
+        }
+    }
+
+    for member in active {
+        if !role_holders.iter().any(|(discord_id, _)| *discord_id == member.discord_id) {
+            deactivate_member(conn, team_id, &member.discord_id, actor)?;
+            sync.deactivated.push(member.username);
+        }
+    }
+
+    Ok(sync)
+}
+
+/// Adds or deactivates a single user after their Discord roles changed.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the user.
+/// * `name` - The display name of the user in the server.
+/// * `has_role` - Whether the user now has the Discord role of the team.
+/// * `actor` - Who triggered the change, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the member cannot be added or updated in the database.
+pub fn sync_member(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    name: &str,
+    has_role: bool,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let is_active = member_repository::find_member(conn, team_id, discord_id)?
+        .is_some_and(|member| member.left_date.is_none());
+
+    match (has_role, is_active) {
+        (true, false) => {
+            team_service::add_member(conn, discord_id, name.to_string(), team_id, actor)
+        }
+        (false, true) => deactivate_member(conn, team_id, discord_id, actor),
+        _ => Ok(()),
+    }
+}
+
+/// Finds the member of a team with a specific Discord ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member.
+///
+/// # Errors
+/// Returns an error if the user is not a member of the team or cannot be fetched.
+pub fn find_member(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+) -> Result<Member, String> {
+    member_repository::find_member(conn, team_id, discord_id)?
+        .ok_or_else(|| "User is not a member of this team".to_string())
+}
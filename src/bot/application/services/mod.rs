@@ -1,2 +1,16 @@
 pub mod team_service;
 pub mod attendance_service;
+pub mod role_service;
+pub mod member_service;
+pub mod voice_service;
+pub mod rollcall_service;
+pub mod event_service;
+pub mod leave_service;
+pub mod holiday_service;
+pub mod absence_service;
+pub mod reminder_service;
+pub mod correction_service;
+pub mod audit_service;
+pub mod webhook_service;
+pub mod live_service;
+pub mod user_service;
@@ -0,0 +1,331 @@
+use crate::bot::{
+    application::services::{audit_service, holiday_service, leave_service},
+    domain::{
+        audit::{AuditAction, AuditActor},
+        leave::is_working_day,
+        model::{NewReminderRule, ReminderPreference, ReminderRule, Team},
+        reminder::{in_quiet_hours, ReminderKind, ReminderTrigger},
+        table::ReminderRuleTable,
+    },
+    infrastructure::persistence::{attendance_repository, member_repository, reminder_repository},
+};
+use crate::config::constant::{
+    REMINDER_MAX_DELAY_MINUTES, REMINDER_MAX_RULES, SCHEDULE_MAX_GRACE_MINUTES,
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use diesel::PgConnection;
+use serde_json::json;
+
+/// Represents a reminder to send to a member in a DM.
+///
+/// # Fields
+/// * `discord_id` - The Discord ID of the member.
+/// * `team_name` - The name of the team the reminder is for.
+/// * `kind` - What the member is reminded to do.
+#[derive(Debug)]
+pub struct Reminder {
+    pub discord_id: String,
+    pub team_name: String,
+    pub kind: ReminderKind,
+}
+
+/// Adds a reminder rule to a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `kind` - What the members are reminded to do.
+/// * `trigger` - When the rule fires.
+/// * `actor` - The user adding the rule, recorded in the audit log.
+///
+/// # Returns
+/// Returns the `ReminderRuleTable` row of the new rule.
+///
+/// # Errors
+/// Returns an error if the team has too many rules, the rule fires too long before the
+/// schedule start or it cannot be inserted into the database.
+pub fn add_rule(
+    conn: &mut PgConnection,
+    team_id: i32,
+    kind: ReminderKind,
+    trigger: ReminderTrigger,
+    actor: &AuditActor,
+) -> Result<ReminderRuleTable, String> {
+    if let ReminderTrigger::BeforeStart(minutes) = trigger {
+        if minutes > SCHEDULE_MAX_GRACE_MINUTES {
+            return Err(format!(
+                "A reminder can fire at most {} minutes before the schedule start",
+                SCHEDULE_MAX_GRACE_MINUTES
+            ));
+        }
+    }
+    if reminder_repository::get_rules(conn, team_id)?.len() >= REMINDER_MAX_RULES {
+        return Err(format!(
+            "A team can have at most {} reminders",
+            REMINDER_MAX_RULES
+        ));
+    }
+
+    let (minutes_before_start, remind_at) = match trigger {
+        ReminderTrigger::BeforeStart(minutes) => (Some(minutes), None),
+        ReminderTrigger::At(time) => (None, Some(time)),
+    };
+    let rule = reminder_repository::create_rule(
+        conn,
+        &NewReminderRule {
+            team_id,
+            kind: kind.as_str().to_string(),
+            minutes_before_start,
+            remind_at,
+            created_by: actor.discord_id.clone(),
+        },
+    )?;
+
+    let table = reminder_rule_table(&rule);
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::ReminderAdd,
+        Some(team_id),
+        Some(&rule.id.to_string()),
+        json!({ "kind": table.kind, "when": table.when }),
+    );
+    Ok(table)
+}
+
+/// Retrieves the reminder rules of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the rules cannot be fetched from the database.
+pub fn get_rules(conn: &mut PgConnection, team_id: i32) -> Result<Vec<ReminderRuleTable>, String> {
+    Ok(reminder_repository::get_rules(conn, team_id)?
+        .iter()
+        .map(reminder_rule_table)
+        .collect())
+}
+
+/// Removes a reminder rule from a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `rule_id` - The ID of the rule.
+/// * `actor` - Who removes the rule, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the team has no such rule or it cannot be deleted from the database.
+pub fn remove_rule(
+    conn: &mut PgConnection,
+    team_id: i32,
+    rule_id: i32,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    if !reminder_repository::delete_rule(conn, team_id, rule_id)? {
+        return Err(format!("Reminder #{} not found", rule_id));
+    }
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::ReminderRemove,
+        Some(team_id),
+        Some(&rule_id.to_string()),
+        json!({}),
+    );
+    Ok(())
+}
+
+/// Retrieves the reminder settings of a user.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `discord_id` - The Discord ID of the user.
+///
+/// # Returns
+/// Returns `None` if the user never changed them.
+///
+/// # Errors
+/// Returns an error if the settings cannot be fetched from the database.
+pub fn get_preference(
+    conn: &mut PgConnection,
+    discord_id: &str,
+) -> Result<Option<ReminderPreference>, String> {
+    Ok(
+        reminder_repository::get_preferences(conn, &[discord_id.to_string()])?
+            .into_iter()
+            .next(),
+    )
+}
+
+/// Turns the reminders of a user on or off, in every team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `discord_id` - The Discord ID of the user.
+/// * `opted_out` - Whether the user opts out of reminders.
+///
+/// # Errors
+/// Returns an error if the settings cannot be saved to the database.
+pub fn set_opted_out(
+    conn: &mut PgConnection,
+    discord_id: &str,
+    opted_out: bool,
+) -> Result<(), String> {
+    reminder_repository::set_opted_out(conn, discord_id, opted_out)
+}
+
+/// Sets or clears the quiet hours of a user, during which no reminder is sent to them.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `discord_id` - The Discord ID of the user.
+/// * `hours` - The start and end (UTC) of the quiet hours, or `None` to clear them.
+///
+/// # Errors
+/// Returns an error if the quiet hours start and end at the same time or the settings cannot
+/// be saved to the database.
+pub fn set_quiet_hours(
+    conn: &mut PgConnection,
+    discord_id: &str,
+    hours: Option<(NaiveTime, NaiveTime)>,
+) -> Result<(), String> {
+    if hours.is_some_and(|(start, end)| start == end) {
+        return Err("The quiet hours must start and end at different times".to_string());
+    }
+
+    reminder_repository::set_quiet_hours(conn, discord_id, hours)
+}
+
+/// Retrieves the reminder rules that are due and were not sent yet today.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `now` - The current time (UTC).
+///
+/// # Returns
+/// Returns each rule together with its team.
+///
+/// # Errors
+/// Returns an error if the rules cannot be fetched from the database.
+pub fn get_due_rules(
+    conn: &mut PgConnection,
+    now: NaiveDateTime,
+) -> Result<Vec<(ReminderRule, Team)>, String> {
+    Ok(reminder_repository::get_unsent_rules(conn, now.date())?
+        .into_iter()
+        .filter(|(rule, team)| fires_at(rule, team, now.date()).is_some_and(|at| at <= now))
+        .collect())
+}
+
+/// Marks a due reminder rule as sent and returns the reminders to send for it.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `rule` - The rule.
+/// * `team` - The team of the rule.
+/// * `now` - The current time (UTC).
+///
+/// # Returns
+/// Returns nothing if the rule was already sent today, fired more than
+/// `REMINDER_MAX_DELAY_MINUTES` ago, or today is a weekend day or a holiday of the team.
+///
+/// # Behavior
+/// - Check-in reminders go to the members without an attendance record for the day,
+///   check-out reminders to the members who are still checked in.
+/// - Members on approved leave, members who opted out and members in their quiet hours are
+///   left out.
+///
+/// # Errors
+/// Returns an error if the rule cannot be updated or the members cannot be fetched from the
+/// database.
+pub fn rule_reminders(
+    conn: &mut PgConnection,
+    rule: &ReminderRule,
+    team: &Team,
+    now: NaiveDateTime,
+) -> Result<Vec<Reminder>, String> {
+    let today = now.date();
+    if !reminder_repository::mark_sent(conn, rule.id, today)? {
+        return Ok(Vec::new());
+    }
+    let (Some(kind), Some(at)) = (ReminderKind::parse(&rule.kind), fires_at(rule, team, today))
+    else {
+        return Ok(Vec::new());
+    };
+    if now - at > Duration::minutes(REMINDER_MAX_DELAY_MINUTES)
+        || !is_working_day(today)
+        || holiday_service::is_holiday(conn, team.id, today)?
+    {
+        return Ok(Vec::new());
+    }
+
+    let targets = match kind {
+        ReminderKind::CheckIn => attendance_repository::get_recorded_members(conn, team.id, today)?,
+        ReminderKind::CheckOut => attendance_repository::get_checked_in_members(conn, team.id)?,
+    };
+    let on_leave = leave_service::members_on_leave(conn, team.id, today)?;
+    let members: Vec<_> = member_repository::get_active_members(conn, team.id)?
+        .into_iter()
+        .filter(|member| match kind {
+            ReminderKind::CheckIn => !targets.contains(&member.id),
+            ReminderKind::CheckOut => targets.contains(&member.id),
+        })
+        .filter(|member| !on_leave.contains(&member.id))
+        .filter(|member| member.join_date.is_none_or(|joined| joined <= today))
+        .collect();
+
+    let discord_ids: Vec<String> = members
+        .iter()
+        .map(|member| member.discord_id.clone())
+        .collect();
+    let muted: Vec<String> = reminder_repository::get_preferences(conn, &discord_ids)?
+        .into_iter()
+        .filter(|preference| {
+            preference.opted_out
+                || preference
+                    .quiet_start
+                    .zip(preference.quiet_end)
+                    .is_some_and(|(start, end)| in_quiet_hours(now.time(), start, end))
+        })
+        .map(|preference| preference.discord_id)
+        .collect();
+
+    Ok(members
+        .into_iter()
+        .filter(|member| !muted.contains(&member.discord_id))
+        .map(|member| Reminder {
+            discord_id: member.discord_id,
+            team_name: team.name.clone(),
+            kind,
+        })
+        .collect())
+}
+
+/// Returns the trigger of a rule as stored in the database.
+fn trigger(rule: &ReminderRule) -> Option<ReminderTrigger> {
+    match (rule.minutes_before_start, rule.remind_at) {
+        (Some(minutes), _) => Some(ReminderTrigger::BeforeStart(minutes)),
+        (None, Some(time)) => Some(ReminderTrigger::At(time)),
+        (None, None) => None,
+    }
+}
+
+/// Returns when a rule fires on a day, or `None` if it fires before the schedule start of a
+/// team without a schedule.
+fn fires_at(rule: &ReminderRule, team: &Team, date: NaiveDate) -> Option<NaiveDateTime> {
+    trigger(rule)?.fires_at(date, team.schedule_start)
+}
+
+fn reminder_rule_table(rule: &ReminderRule) -> ReminderRuleTable {
+    ReminderRuleTable {
+        id: rule.id,
+        kind: rule.kind.clone(),
+        when: trigger(rule)
+            .map(|trigger| trigger.to_string())
+            .unwrap_or_default(),
+    }
+}
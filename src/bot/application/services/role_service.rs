@@ -0,0 +1,207 @@
+use crate::bot::{
+    application::services::audit_service,
+    domain::{
+        audit::{self, AuditAction, AuditActor},
+        model::NewTeamRole,
+        permission::{Permission, Role},
+        table::TeamRoleTable,
+    },
+    infrastructure::persistence::{role_repository, team_repository},
+};
+use diesel::PgConnection;
+use serde_json::json;
+
+/// Checks that a user may perform an action in a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the user.
+/// * `permission` - The permission required by the action.
+///
+/// # Returns
+/// Returns the role of the user in the team.
+///
+/// # Errors
+/// Returns an error if:
+/// - The user has no role in the team.
+/// - The role of the user does not grant the permission.
+/// - The team is archived and the action would change it.
+/// - The role cannot be fetched from the database.
+pub fn authorize(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    permission: Permission,
+) -> Result<Role, String> {
+    let role = role_repository::get_role(conn, team_id, discord_id)?
+        .and_then(|role| Role::parse(&role))
+        .ok_or_else(|| "You have no role in this team.".to_string())?;
+
+    if !role.allows(permission) {
+        return Err(format!("Your role '{}' does not allow this action.", role));
+    }
+
+    if !permission.allowed_when_archived() {
+        let archived = team_repository::find_team(conn, team_id)?
+            .is_some_and(|team| team.archived_at.is_some());
+        if archived {
+            return Err("This team is archived and read-only.".to_string());
+        }
+    }
+
+    Ok(role)
+}
+
+/// Grants a role to a user in a team, replacing their current role.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the user receiving the role.
+/// * `role` - The role to grant.
+/// * `actor` - Who grants the role, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if:
+/// - The change would leave the team without an owner.
+/// - The role cannot be stored in the database.
+pub fn grant_role(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    role: Role,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    if role != Role::Owner {
+        ensure_not_last_owner(conn, team_id, discord_id)?;
+    }
+
+    let previous = role_repository::get_role(conn, team_id, discord_id)?;
+    role_repository::set_role(
+        conn,
+        &NewTeamRole {
+            team_id,
+            discord_id: discord_id.to_string(),
+            role: role.as_str().to_string(),
+            granted_by: Some(actor.discord_id.clone()),
+        },
+    )?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::RoleGrant,
+        Some(team_id),
+        Some(discord_id),
+        audit::diff(&[("role", json!(previous), json!(role.as_str()))]),
+    );
+    Ok(())
+}
+
+/// Gives a user the member role in a team unless they already have a role.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member.
+///
+/// # Errors
+/// Returns an error if the role cannot be stored in the database.
+pub fn ensure_member_role(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+) -> Result<(), String> {
+    role_repository::add_role_if_missing(
+        conn,
+        &NewTeamRole {
+            team_id,
+            discord_id: discord_id.to_string(),
+            role: Role::Member.as_str().to_string(),
+            granted_by: None,
+        },
+    )
+}
+
+/// Revokes the role of a user in a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the user.
+/// * `actor` - Who revokes the role, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if:
+/// - The user is the last owner of the team.
+/// - The user has no role in the team.
+/// - The role cannot be deleted from the database.
+pub fn revoke_role(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    ensure_not_last_owner(conn, team_id, discord_id)?;
+    let previous = role_repository::get_role(conn, team_id, discord_id)?;
+    role_repository::remove_role(conn, team_id, discord_id)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::RoleRevoke,
+        Some(team_id),
+        Some(discord_id),
+        audit::diff(&[("role", json!(previous), json!(null))]),
+    );
+    Ok(())
+}
+
+/// Retrieves all role assignments of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the roles cannot be fetched from the database.
+pub fn get_team_roles(conn: &mut PgConnection, team_id: i32) -> Result<Vec<TeamRoleTable>, String> {
+    role_repository::get_team_roles(conn, team_id)
+}
+
+/// Retrieves the role a user holds in a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the user.
+///
+/// # Returns
+/// The name of the role, or `None` if the user holds no role in the team.
+///
+/// # Errors
+/// Returns an error if the role cannot be fetched from the database.
+pub fn get_role(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+) -> Result<Option<String>, String> {
+    role_repository::get_role(conn, team_id, discord_id)
+}
+
+/// Fails if the user is the only owner of the team.
+fn ensure_not_last_owner(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+) -> Result<(), String> {
+    let current = role_repository::get_role(conn, team_id, discord_id)?;
+    if current.as_deref() == Some(Role::Owner.as_str())
+        && role_repository::count_owners(conn, team_id)? <= 1
+    {
+        return Err("A team needs at least one owner.".to_string());
+    }
+
+    Ok(())
+}
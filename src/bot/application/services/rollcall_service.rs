@@ -0,0 +1,331 @@
+use crate::{
+    bot::{
+        application::services::{
+            audit_service, holiday_service, leave_service, live_service, role_service,
+            webhook_service,
+        },
+        domain::{
+            audit::{AuditAction, AuditActor},
+            live::LiveEvent,
+            model::{NewRollcall, Rollcall, RollcallAttendance},
+            permission::Permission,
+            webhook::WebhookEvent,
+        },
+        infrastructure::persistence::{member_repository, rollcall_repository, team_repository},
+    },
+    config::database,
+};
+use chrono::{Duration, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+
+/// Represents the outcome of a closed roll-call.
+///
+/// # Fields
+/// * `team_name` - The name of the team the roll-call was for.
+/// * `present` - The names of the members who answered.
+/// * `absent` - The names of the members who did not answer.
+/// * `excused` - The names of the members who did not answer while on approved leave.
+#[derive(Debug, Default)]
+pub struct RollcallTally {
+    pub team_name: String,
+    pub present: Vec<String>,
+    pub absent: Vec<String>,
+    pub excused: Vec<String>,
+}
+
+/// Starts a roll-call for a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `channel_id` - The ID of the channel the roll-call is posted in.
+/// * `actor` - The user starting the roll-call, recorded in the audit log.
+/// * `duration` - How long members can answer.
+///
+/// # Returns
+/// Returns the new roll-call.
+///
+/// # Errors
+/// Returns an error if the roll-call cannot be inserted into the database.
+pub fn start_rollcall(
+    conn: &mut PgConnection,
+    team_id: i32,
+    channel_id: &str,
+    actor: &AuditActor,
+    duration: Duration,
+) -> Result<Rollcall, String> {
+    let rollcall = rollcall_repository::create_rollcall(
+        conn,
+        &NewRollcall {
+            team_id,
+            channel_id: channel_id.to_string(),
+            started_by: actor.discord_id.clone(),
+            closes_at: Utc::now().naive_utc() + duration,
+        },
+    )?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::RollcallStart,
+        Some(team_id),
+        Some(&rollcall.id.to_string()),
+        json!({ "closes_at": rollcall.closes_at }),
+    );
+    Ok(rollcall)
+}
+
+/// Stores the ID of the message a roll-call was posted as.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `rollcall_id` - The ID of the roll-call.
+/// * `message_id` - The ID of the Discord message.
+///
+/// # Errors
+/// Returns an error if the roll-call cannot be updated in the database.
+pub fn attach_message(
+    conn: &mut PgConnection,
+    rollcall_id: i32,
+    message_id: &str,
+) -> Result<(), String> {
+    rollcall_repository::set_message(conn, rollcall_id, message_id)
+}
+
+/// Deletes a roll-call that could not be posted.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `rollcall_id` - The ID of the roll-call.
+///
+/// # Errors
+/// Returns an error if the roll-call cannot be deleted from the database.
+pub fn discard_rollcall(conn: &mut PgConnection, rollcall_id: i32) -> Result<(), String> {
+    rollcall_repository::delete_rollcall(conn, rollcall_id)
+}
+
+/// Retrieves the posted roll-calls that have not been closed yet.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Errors
+/// Returns an error if the roll-calls cannot be fetched from the database.
+pub fn get_open_rollcalls(conn: &mut PgConnection) -> Result<Vec<Rollcall>, String> {
+    rollcall_repository::get_open_rollcalls(conn)
+}
+
+/// Marks a user as present in a roll-call.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `rollcall_id` - The ID of the roll-call.
+/// * `discord_id` - The Discord ID of the user who answered.
+///
+/// # Behavior
+/// - Publishes the check-in on the live feed and notifies the `check_in` webhooks of the team.
+///
+/// # Errors
+/// Returns an error if:
+/// - The roll-call does not exist or is closed.
+/// - The user is not an active member of the team or may not record attendance.
+/// - The user already answered the roll-call.
+/// - The answer cannot be recorded in the database.
+pub fn mark_present(
+    conn: &mut PgConnection,
+    rollcall_id: i32,
+    discord_id: &str,
+) -> Result<(), String> {
+    let now = Utc::now().naive_utc();
+    let rollcall = rollcall_repository::find_rollcall(conn, rollcall_id)?
+        .filter(|rollcall| rollcall.closed_at.is_none() && rollcall.closes_at > now)
+        .ok_or("This roll-call is closed.")?;
+
+    let member = member_repository::find_member(conn, rollcall.team_id, discord_id)?
+        .filter(|member| member.left_date.is_none())
+        .ok_or("You are not an active member of this team.")?;
+
+    role_service::authorize(
+        conn,
+        rollcall.team_id,
+        discord_id,
+        Permission::RecordAttendance,
+    )?;
+
+    rollcall_repository::record_answer(
+        conn,
+        &RollcallAttendance {
+            member_id: member.id,
+            team_id: rollcall.team_id,
+            date: now.date(),
+            check_in_time: Some(now),
+            check_out_time: Some(now),
+            status: "Present".to_string(),
+            rollcall_id,
+        },
+    )?;
+
+    webhook_service::emit_member(
+        conn,
+        &member,
+        WebhookEvent::CheckIn,
+        json!({ "status": "Present", "checked_in_at": now, "rollcall_id": rollcall_id }),
+    );
+    live_service::publish(&member, LiveEvent::CheckIn, Some("Present"), now.date(), now);
+    Ok(())
+}
+
+/// Closes a roll-call, marking every active member who did not answer as absent.
+///
+/// Members on approved leave, or everyone on a holiday of the team, are marked as excused
+/// instead.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `rollcall_id` - The ID of the roll-call.
+///
+/// # Returns
+/// Returns the tally of the roll-call, or `None` if it was already closed.
+///
+/// # Errors
+/// Returns an error if the roll-call, its team or its answers cannot be read or updated.
+pub fn close_rollcall(
+    conn: &mut PgConnection,
+    rollcall_id: i32,
+) -> Result<Option<RollcallTally>, String> {
+    let Some(rollcall) = rollcall_repository::find_rollcall(conn, rollcall_id)? else {
+        return Ok(None);
+    };
+    let now = Utc::now().naive_utc();
+
+    // closing and recording the members who did not answer succeed or fail together
+    let closed = database::transaction(conn, |conn| {
+        if !rollcall_repository::close_rollcall(conn, rollcall_id)? {
+            return Ok(None);
+        }
+
+        let answers = rollcall_repository::get_answers(conn, rollcall_id)?;
+        let on_leave = leave_service::members_on_leave(conn, rollcall.team_id, now.date())?;
+        let holiday = holiday_service::is_holiday(conn, rollcall.team_id, now.date())?;
+        let mut unanswered = Vec::new();
+
+        for member in member_repository::get_active_members(conn, rollcall.team_id)? {
+            if answers
+                .iter()
+                .any(|(member_id, _, _)| *member_id == member.id)
+            {
+                continue;
+            }
+
+            let excused = holiday || on_leave.contains(&member.id);
+            rollcall_repository::record_answer(
+                conn,
+                &RollcallAttendance {
+                    member_id: member.id,
+                    team_id: rollcall.team_id,
+                    date: now.date(),
+                    check_in_time: None,
+                    check_out_time: None,
+                    status: if excused { "Excused" } else { "Absent" }.to_string(),
+                    rollcall_id,
+                },
+            )?;
+            unanswered.push((member, excused));
+        }
+
+        Ok::<_, String>(Some((answers, unanswered)))
+    })?;
+    let Some((answers, unanswered)) = closed else {
+        return Ok(None);
+    };
+
+    let mut tally = RollcallTally {
+        team_name: team_repository::find_team(conn, rollcall.team_id)?
+            .map(|team| team.name)
+            .unwrap_or_default(),
+        present: answers.iter().map(|(_, name, _)| name.clone()).collect(),
+        absent: Vec::new(),
+        excused: Vec::new(),
+    };
+    let mut absentees = Vec::new();
+
+    for (member, excused) in unanswered {
+        let status = if excused { "Excused" } else { "Absent" };
+        live_service::publish(&member, LiveEvent::StatusChange, Some(status), now.date(), now);
+        if excused {
+            tally.excused.push(member.username);
+        } else {
+            absentees.push(json!({ "discord_id": member.discord_id, "username": member.username }));
+            tally.absent.push(member.username);
+        }
+    }
+    tally.absent.sort();
+    tally.excused.sort();
+
+    if !absentees.is_empty() {
+        webhook_service::emit(
+            conn,
+            rollcall.team_id,
+            WebhookEvent::AbsenceDetected,
+            json!({
+                "date": now.date(),
+                "source": "rollcall",
+                "rollcall_id": rollcall_id,
+                "members": absentees,
+            }),
+        );
+    }
+
+    Ok(Some(tally))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::database::testing::with_member;
+    use crate::schema::{member_attendance, rollcalls};
+    use diesel::prelude::*;
+
+    fn statuses(conn: &mut PgConnection, rollcall_id: i32) -> Vec<Option<String>> {
+        member_attendance::table
+            .filter(member_attendance::rollcall_id.eq(rollcall_id))
+            .select(member_attendance::status)
+            .load(conn)
+            .unwrap()
+    }
+
+    #[test]
+    #[ignore = "needs a database"]
+    fn closing_marks_members_who_did_not_answer_as_absent() {
+        with_member(|conn, team_id, _| {
+            let actor = AuditActor::system(None);
+            let rollcall =
+                start_rollcall(conn, team_id, "1", &actor, Duration::minutes(5)).unwrap();
+
+            let tally = close_rollcall(conn, rollcall.id).unwrap().unwrap();
+            assert_eq!(tally.absent, vec!["member".to_string()]);
+            assert_eq!(statuses(conn, rollcall.id), vec![Some("Absent".to_string())]);
+            let closed: bool = rollcalls::table
+                .find(rollcall.id)
+                .select(rollcalls::closed_at.is_not_null())
+                .get_result(conn)
+                .unwrap();
+            assert!(closed);
+        });
+    }
+
+    #[test]
+    #[ignore = "needs a database"]
+    fn closing_twice_records_absences_once() {
+        with_member(|conn, team_id, _| {
+            let actor = AuditActor::system(None);
+            let rollcall =
+                start_rollcall(conn, team_id, "1", &actor, Duration::minutes(5)).unwrap();
+
+            close_rollcall(conn, rollcall.id).unwrap();
+            assert!(close_rollcall(conn, rollcall.id).unwrap().is_none());
+            assert_eq!(statuses(conn, rollcall.id).len(), 1);
+        });
+    }
+}
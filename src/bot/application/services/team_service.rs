@@ -1,20 +1,52 @@
 use crate::bot::{
-    domain::table::{MemberTable, TeamTable},
-    infrastructure::persistence::team_repository::{self},
+    application::services::{audit_service, member_service, role_service, webhook_service},
+    domain::{
+        audit::{self, AuditAction, AuditActor},
+        model::Team,
+        permission::Role,
+        table::{MemberTable, TeamTable},
+        webhook::WebhookEvent,
+    },
+    infrastructure::persistence::{member_repository, team_repository, user_repository},
 };
+use crate::config::database;
 use diesel::PgConnection;
+use serde_json::json;
 
-/// Registers a new team in the database.
+/// Registers a new team in the database and makes its creator the owner.
 ///
 /// # Arguments
 /// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild_id` - The ID of the Discord server the team belongs to.
 /// * `name` - The name of the team to register.
 /// * `admin_id` - The ID of the admin creating the team.
+/// * `actor` - The admin creating the team, recorded in the audit log.
+///
+/// # Behavior
+/// - The team is only kept if the owner role is granted too.
 ///
 /// # Errors
-/// Returns an error if the team cannot be created in the database.
-pub fn register_team(conn: &mut PgConnection, name: &str, admin_id: i32) -> Result<(), String> {
-    team_repository::create_team(conn, name, admin_id)
+/// Returns an error if the team or the owner role cannot be created in the database.
+pub fn register_team(
+    conn: &mut PgConnection,
+    guild_id: &str,
+    name: &str,
+    admin_id: i32,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    database::transaction(conn, |conn| {
+        let team_id = team_repository::create_team(conn, guild_id, name, admin_id)?;
+        audit_service::record(
+            conn,
+            actor,
+            AuditAction::TeamCreate,
+            Some(team_id),
+            None,
+            json!({ "name": name }),
+        );
+
+        role_service::grant_role(conn, team_id, &actor.discord_id, Role::Owner, actor)
+    })
 }
 
 /// Adds a new member to a team in the database.
@@ -24,22 +56,62 @@ pub fn register_team(conn: &mut PgConnection, name: &str, admin_id: i32) -> Resu
 /// * `discord_id` - The Discord ID of the member.
 /// * `username` - The username of the member.
 /// * `team_id` - The ID of the team to which the member is being added.
+/// * `actor` - Who adds the member, recorded in the audit log.
+///
+/// # Behavior
+/// - Adds the user as a new member, or reactivates them if they left the team before.
+/// - Gives them the member role unless they already have a role in the team.
+/// - Notifies the `member_added` webhooks of the team.
 ///
 /// # Errors
-/// Returns an error if the member cannot be added to the team.
+/// Returns an error if:
+/// - The user is already an active member of the team.
+/// - The member or their member role cannot be added to the team.
 pub fn add_member(
     conn: &mut PgConnection,
     discord_id: &str,
     username: String,
     team_id: i32,
+    actor: &AuditActor,
 ) -> Result<(), String> {
-    team_repository::assign_member(conn, discord_id, username, team_id)
+    let (reactivated, member) = database::transaction(conn, |conn| {
+        let reactivated = member_repository::find_member(conn, team_id, discord_id)?.is_some();
+        if reactivated {
+            member_service::reactivate_member(conn, team_id, discord_id, actor)?;
+        } else {
+            let details = json!({ "username": username });
+            team_repository::assign_member(conn, discord_id, username, team_id)?;
+            audit_service::record(
+                conn,
+                actor,
+                AuditAction::MemberAdd,
+                Some(team_id),
+                Some(discord_id),
+                details,
+            );
+        }
+
+        role_service::ensure_member_role(conn, team_id, discord_id)?;
+        let member = member_repository::find_member(conn, team_id, discord_id)?;
+        Ok::<_, String>((reactivated, member))
+    })?;
+
+    if let Some(member) = member {
+        webhook_service::emit_member(
+            conn,
+            &member,
+            WebhookEvent::MemberAdded,
+            json!({ "reactivated": reactivated, "join_date": member.join_date }),
+        );
+    }
+    Ok(())
 }
 
-/// Retrieves all teams created by a specific admin.
+/// Retrieves all teams created by a specific admin in a Discord server.
 ///
 /// # Arguments
 /// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild_id` - The ID of the Discord server.
 /// * `admin_discord_id` - The Discord ID of the admin whose teams are being fetched.
 ///
 /// # Returns
@@ -49,15 +121,17 @@ pub fn add_member(
 /// Returns an error if the teams cannot be fetched from the database.
 pub fn show_team(
     conn: &mut PgConnection,
+    guild_id: &str,
     admin_discord_id: &str,
 ) -> Result<Vec<TeamTable>, String> {
-    team_repository::get_admin_teams(conn, admin_discord_id)
+    team_repository::get_admin_teams(conn, guild_id, admin_discord_id)
 }
 
 /// Retrieves all members of a specific team.
 ///
 /// # Arguments
 /// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild_id` - The ID of the Discord server the team belongs to.
 /// * `team_name` - The name of the team whose members are being fetched.
 ///
 /// # Returns
@@ -67,7 +141,270 @@ pub fn show_team(
 /// Returns an error if the members cannot be fetched from the database.
 pub fn get_members_by_team(
     conn: &mut PgConnection,
+    guild_id: &str,
     team_name: &str,
 ) -> Result<Vec<MemberTable>, String> {
-    team_repository::get_members(conn, team_name)
+    team_repository::get_members(conn, guild_id, team_name)
+}
+
+/// Finds a team by name among the teams created by a specific admin.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild_id` - The ID of the Discord server the team belongs to, if known.
+/// * `team_name` - The name of the team.
+/// * `admin_id` - The ID of the admin who created the team.
+///
+/// # Errors
+/// Returns an error if:
+/// - The team does not exist or belongs to another admin.
+/// - No server is given and the name matches teams in several servers.
+pub fn find_admin_team(
+    conn: &mut PgConnection,
+    guild_id: Option<&str>,
+    team_name: &str,
+    admin_id: i32,
+) -> Result<i32, String> {
+    team_repository::find_admin_team(conn, guild_id, team_name, admin_id)
+}
+
+/// Finds a team by name in a Discord server.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild_id` - The ID of the Discord server the team belongs to.
+/// * `team_name` - The name of the team.
+///
+/// # Errors
+/// Returns an error if the team does not exist or cannot be fetched.
+pub fn find_team_id(
+    conn: &mut PgConnection,
+    guild_id: &str,
+    team_name: &str,
+) -> Result<i32, String> {
+    team_repository::find_team_id(conn, guild_id, team_name)
+}
+
+/// Finds a team by ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+///
+/// # Returns
+/// Returns the team, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the team cannot be fetched from the database.
+pub fn find_team(conn: &mut PgConnection, team_id: i32) -> Result<Option<Team>, String> {
+    team_repository::find_team(conn, team_id)
+}
+
+/// Renames a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `new_name` - The new name of the team.
+/// * `actor` - Who renames the team, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if:
+/// - The new name is empty or longer than 50 characters.
+/// - Another team in the same server already has this name.
+/// - The team cannot be updated in the database.
+pub fn rename_team(
+    conn: &mut PgConnection,
+    team_id: i32,
+    new_name: &str,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    if new_name.is_empty() || new_name.chars().count() > 50 {
+        return Err("Team name must be between 1 and 50 characters".to_string());
+    }
+
+    let team = find_team(conn, team_id)?.ok_or("Team not found")?;
+    team_repository::rename_team(conn, team_id, new_name)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::TeamRename,
+        Some(team_id),
+        None,
+        audit::diff(&[("name", json!(team.name), json!(new_name))]),
+    );
+    Ok(())
+}
+
+/// Archives or restores a team. Archived teams are read-only and hidden from `show_team`.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `archived` - Whether the team should be archived.
+/// * `actor` - Who archives or restores the team, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn archive_team(
+    conn: &mut PgConnection,
+    team_id: i32,
+    archived: bool,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let team = find_team(conn, team_id)?.ok_or("Team not found")?;
+    team_repository::set_archived(conn, team_id, archived)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::TeamArchive,
+        Some(team_id),
+        None,
+        audit::diff(&[(
+            "archived",
+            json!(team.archived_at.is_some()),
+            json!(archived),
+        )]),
+    );
+    Ok(())
+}
+
+/// Hands a team over to another registered admin.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `new_owner_discord_id` - The Discord ID of the admin taking over the team.
+/// * `actor` - Who transfers the team, recorded in the audit log.
+///
+/// # Behavior
+/// - Makes the new admin an owner of the team and its creator.
+/// - Demotes the previous creator of the team to manager.
+/// - Changes nothing if any step fails.
+///
+/// # Errors
+/// Returns an error if:
+/// - The team does not exist.
+/// - The new owner is not a registered admin or already owns the team.
+/// - The team or the roles cannot be updated in the database.
+pub fn transfer_team(
+    conn: &mut PgConnection,
+    team_id: i32,
+    new_owner_discord_id: &str,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    database::transaction(conn, |conn| {
+        let team = find_team(conn, team_id)?.ok_or("Team not found")?;
+        let new_admin_id = user_repository::find_user_id(conn, new_owner_discord_id)?;
+        if team.admin_id == Some(new_admin_id) {
+            return Err("This user already owns the team".to_string());
+        }
+
+        role_service::grant_role(conn, team_id, new_owner_discord_id, Role::Owner, actor)?;
+        team_repository::set_admin(conn, team_id, new_admin_id)?;
+
+        let previous_owner = match team.admin_id {
+            Some(previous_admin_id) => {
+                let previous_owner = user_repository::find_discord_id(conn, previous_admin_id)?;
+                role_service::grant_role(conn, team_id, &previous_owner, Role::Manager, actor)?;
+                Some(previous_owner)
+            }
+            None => None,
+        };
+
+        audit_service::record(
+            conn,
+            actor,
+            AuditAction::TeamTransfer,
+            Some(team_id),
+            Some(new_owner_discord_id),
+            audit::diff(&[("owner", json!(previous_owner), json!(new_owner_discord_id))]),
+        );
+        Ok(())
+    })
+}
+
+/// Binds a team to a Discord role so its members follow the holders of the role.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `role_id` - The ID of the Discord role, or `None` to stop syncing.
+/// * `actor` - Who links or unlinks the role, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn link_discord_role(
+    conn: &mut PgConnection,
+    team_id: i32,
+    role_id: Option<&str>,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let team = find_team(conn, team_id)?.ok_or("Team not found")?;
+    team_repository::set_discord_role(conn, team_id, role_id)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::TeamLinkRole,
+        Some(team_id),
+        None,
+        audit::diff(&[(
+            "discord_role_id",
+            json!(team.discord_role_id),
+            json!(role_id),
+        )]),
+    );
+    Ok(())
+}
+
+/// Retrieves the active teams of a Discord server that are bound to a Discord role.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild_id` - The ID of the Discord server.
+///
+/// # Returns
+/// Returns the ID of each team together with the ID of its Discord role.
+///
+/// # Errors
+/// Returns an error if the teams cannot be fetched from the database.
+pub fn get_role_bound_teams(
+    conn: &mut PgConnection,
+    guild_id: &str,
+) -> Result<Vec<(i32, String)>, String> {
+    team_repository::get_role_bound_teams(conn, guild_id)
+}
+
+/// Deletes a team together with its members, attendance records, roles and API keys.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `actor` - Who deletes the team, recorded in the audit log.
+///
+/// # Behavior
+/// - The audit events of the team are kept.
+///
+/// # Errors
+/// Returns an error if the team cannot be deleted from the database.
+pub fn delete_team(
+    conn: &mut PgConnection,
+    team_id: i32,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let team = find_team(conn, team_id)?.ok_or("Team not found")?;
+    team_repository::delete_team(conn, team_id)?;
+
+    audit_service::record_team(
+        conn,
+        actor,
+        AuditAction::TeamDelete,
+        Some(&team),
+        None,
+        json!({ "name": team.name }),
+    );
+    Ok(())
 }
@@ -0,0 +1,44 @@
+use crate::api::application::auth_service;
+use crate::bot::{
+    application::services::audit_service,
+    domain::audit::{AuditAction, AuditActor},
+    infrastructure::persistence::user_repository,
+};
+use diesel::PgConnection;
+use serde_json::json;
+
+/// Revokes every API session of a registered user.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `discord_id` - The Discord ID of the user.
+/// * `actor` - Who revoked the sessions.
+///
+/// # Returns
+/// Returns the number of sessions that were revoked.
+///
+/// # Behavior
+/// - Revokes the refresh tokens of the user and the access tokens issued with them.
+/// - Records the revocation in the audit log.
+///
+/// # Errors
+/// Returns an error if the user is not registered or the sessions cannot be revoked.
+pub fn revoke_sessions(
+    conn: &mut PgConnection,
+    discord_id: &str,
+    actor: &AuditActor,
+) -> Result<usize, String> {
+    let user_id = user_repository::find_user_id(conn, discord_id)?;
+    let count = auth_service::revoke_all_sessions(conn, user_id)
+        .map_err(|e| format!("Failed to revoke sessions: {}", e))?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::UserRevokeSessions,
+        None,
+        None,
+        json!({ "revoked": count }),
+    );
+    Ok(count)
+}
@@ -0,0 +1,363 @@
+use crate::bot::{
+    application::services::{attendance_service, audit_service, role_service},
+    domain::{
+        audit::{self, AuditAction, AuditActor},
+        permission::Permission,
+    },
+    infrastructure::persistence::{
+        attendance_repository, member_repository, team_repository, voice_repository,
+    },
+};
+use crate::config::constant::VOICE_CHECK_IN_STATUS;
+use chrono::{Duration, Utc};
+use diesel::PgConnection;
+use serde_json::json;
+
+/// Represents a team that tracks attendance in voice channels.
+///
+/// # Fields
+/// * `team_id` - The ID of the team.
+/// * `channels` - The IDs of the voice channels linked to the team.
+/// * `min_minutes` - The minimum length of a voice session to be recorded.
+/// * `debounce_seconds` - How long a member may leave before being checked out.
+#[derive(Debug, Clone)]
+pub struct VoiceTeam {
+    pub team_id: i32,
+    pub channels: Vec<String>,
+    pub min_minutes: i32,
+    pub debounce_seconds: i32,
+}
+
+/// Links a voice channel to a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `channel_id` - The ID of the Discord voice channel.
+/// * `actor` - Who links the channel, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the channel is already linked or cannot be linked.
+pub fn link_channel(
+    conn: &mut PgConnection,
+    team_id: i32,
+    channel_id: &str,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    voice_repository::link_channel(conn, team_id, channel_id)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::VoiceLink,
+        Some(team_id),
+        Some(channel_id),
+        json!({}),
+    );
+    Ok(())
+}
+
+/// Removes the link between a voice channel and a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `channel_id` - The ID of the Discord voice channel.
+/// * `actor` - Who unlinks the channel, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the channel is not linked or cannot be unlinked.
+pub fn unlink_channel(
+    conn: &mut PgConnection,
+    team_id: i32,
+    channel_id: &str,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    voice_repository::unlink_channel(conn, team_id, channel_id)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::VoiceUnlink,
+        Some(team_id),
+        Some(channel_id),
+        json!({}),
+    );
+    Ok(())
+}
+
+/// Retrieves the voice channels linked to a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the channels cannot be fetched from the database.
+pub fn get_channels(conn: &mut PgConnection, team_id: i32) -> Result<Vec<String>, String> {
+    voice_repository::get_channels(conn, team_id)
+}
+
+/// Updates the voice attendance settings of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `min_minutes` - The minimum length of a voice session to be recorded (0 - 1440).
+/// * `debounce_seconds` - How long a member may leave before being checked out (0 - 3600).
+/// * `actor` - Who changes the settings, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if a setting is out of range or the team cannot be updated.
+pub fn set_voice_settings(
+    conn: &mut PgConnection,
+    team_id: i32,
+    min_minutes: i32,
+    debounce_seconds: i32,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    if !(0..=1440).contains(&min_minutes) {
+        return Err("Minimum duration must be between 0 and 1440 minutes".to_string());
+    }
+    if !(0..=3600).contains(&debounce_seconds) {
+        return Err("Debounce must be between 0 and 3600 seconds".to_string());
+    }
+
+    let team = team_repository::find_team(conn, team_id)?.ok_or("Team not found")?;
+    team_repository::set_voice_settings(conn, team_id, min_minutes, debounce_seconds)?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::VoiceSettings,
+        Some(team_id),
+        None,
+        audit::diff(&[
+            (
+                "min_minutes",
+                json!(team.voice_min_minutes),
+                json!(min_minutes),
+            ),
+            (
+                "debounce_seconds",
+                json!(team.voice_debounce_seconds),
+                json!(debounce_seconds),
+            ),
+        ]),
+    );
+    Ok(())
+}
+
+/// Retrieves the active teams of a Discord server that have voice channels linked.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild_id` - The ID of the Discord server.
+///
+/// # Errors
+/// Returns an error if the teams cannot be fetched from the database.
+pub fn get_voice_teams(conn: &mut PgConnection, guild_id: &str) -> Result<Vec<VoiceTeam>, String> {
+    let mut voice_teams: Vec<VoiceTeam> = Vec::new();
+
+    for (team, channel_id) in voice_repository::get_voice_links(conn, guild_id)? {
+        match voice_teams
+            .iter_mut()
+            .find(|voice| voice.team_id == team.id)
+        {
+            Some(voice) => voice.channels.push(channel_id),
+            None => voice_teams.push(VoiceTeam {
+                team_id: team.id,
+                channels: vec![channel_id],
+                min_minutes: team.voice_min_minutes,
+                debounce_seconds: team.voice_debounce_seconds,
+            }),
+        }
+    }
+
+    Ok(voice_teams)
+}
+
+/// Checks a member in after they joined a voice channel of their team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the user who joined.
+///
+/// # Returns
+/// Returns `true` if the member was checked in, or `false` if the user is not an active
+/// member of the team or is already checked in.
+///
+/// # Errors
+/// Returns an error if the user may not record attendance or the check-in fails.
+pub fn start_session(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+) -> Result<bool, String> {
+    let Some(member) = member_repository::find_member(conn, team_id, discord_id)?
+        .filter(|member| member.left_date.is_none())
+    else {
+        return Ok(false);
+    };
+
+    role_service::authorize(conn, team_id, discord_id, Permission::RecordAttendance)?;
+
+    if attendance_repository::find_open_check_in(conn, member.id)?.is_some() {
+        return Ok(false);
+    }
+
+    attendance_service::check_in(conn, member.id, team_id, VOICE_CHECK_IN_STATUS.to_string())?;
+    Ok(true)
+}
+
+/// Checks whether a user has a voice session open in a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the user.
+///
+/// # Errors
+/// Returns an error if the member or their check-in cannot be fetched from the database.
+pub fn has_open_session(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+) -> Result<bool, String> {
+    let Some(member) = member_repository::find_member(conn, team_id, discord_id)? else {
+        return Ok(false);
+    };
+
+    Ok(attendance_repository::find_open_check_in(conn, member.id)?
+        .is_some_and(|open| open.status.as_deref() == Some(VOICE_CHECK_IN_STATUS)))
+}
+
+/// Checks a member out after they left the voice channels of their team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the user who left.
+/// * `min_minutes` - The minimum length of a voice session to be recorded.
+///
+/// # Behavior
+/// - Does nothing unless the member has a voice session open, manual check-ins are kept.
+/// - Discards the session if it is shorter than `min_minutes`, which is published as a
+///   check-out.
+/// - Otherwise records the check-out.
+///
+/// # Errors
+/// Returns an error if the session cannot be closed or discarded.
+pub fn end_session(
+    conn: &mut PgConnection,
+    team_id: i32,
+    discord_id: &str,
+    min_minutes: i32,
+) -> Result<(), String> {
+    let Some(member) = member_repository::find_member(conn, team_id, discord_id)? else {
+        return Ok(());
+    };
+    let Some(open) = attendance_repository::find_open_check_in(conn, member.id)?
+        .filter(|open| open.status.as_deref() == Some(VOICE_CHECK_IN_STATUS))
+    else {
+        return Ok(());
+    };
+
+    let too_short = open.check_in_time.is_some_and(|check_in_time| {
+        Utc::now().naive_utc() - check_in_time < Duration::minutes(min_minutes as i64)
+    });
+    if too_short {
+        let reason = format!("Voice session shorter than {} minutes", min_minutes);
+        let actor = AuditActor::system(None);
+        return attendance_service::discard_check_in(conn, &open, &actor, &reason);
+    }
+
+    attendance_service::check_out(conn, member.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::domain::{audit::AuditFilter, live::LiveEvent, model::SystemAbsence};
+    use crate::bot::infrastructure::{event_bus, persistence::audit_repository};
+    use crate::config::database;
+    use crate::schema::member_attendance;
+    use diesel::prelude::*;
+
+    /// Runs `test` with a member who has already been marked absent today.
+    fn with_absent_member(test: impl FnOnce(&mut PgConnection, i32, i32)) {
+        database::testing::with_member(|conn, team_id, member_id| {
+            let absence = SystemAbsence {
+                member_id,
+                team_id,
+                date: Utc::now().date_naive(),
+                status: "Absent".to_string(),
+                system_generated: true,
+            };
+            attendance_repository::record_absences(conn, &[absence]).unwrap();
+
+            test(conn, team_id, member_id);
+        });
+    }
+
+    fn has_absence(conn: &mut PgConnection, member_id: i32) -> bool {
+        diesel::select(diesel::dsl::exists(
+            member_attendance::table
+                .filter(member_attendance::member_id.eq(member_id))
+                .filter(member_attendance::system_generated.eq(true)),
+        ))
+        .get_result(conn)
+        .unwrap()
+    }
+
+    #[test]
+    #[ignore = "needs a database"]
+    fn discarded_session_keeps_the_recorded_absence() {
+        with_absent_member(|conn, team_id, member_id| {
+            let status = VOICE_CHECK_IN_STATUS.to_string();
+            attendance_service::check_in(conn, member_id, team_id, status).unwrap();
+            assert!(has_absence(conn, member_id));
+
+            end_session(conn, team_id, "1", 60).unwrap();
+            assert!(has_absence(conn, member_id));
+            assert!(attendance_repository::find_open_check_in(conn, member_id)
+                .unwrap()
+                .is_none());
+        });
+    }
+
+    #[test]
+    #[ignore = "needs a database"]
+    fn discarded_session_is_published_as_a_check_out() {
+        with_absent_member(|conn, team_id, member_id| {
+            let status = VOICE_CHECK_IN_STATUS.to_string();
+            attendance_service::check_in(conn, member_id, team_id, status).unwrap();
+            let mut updates = event_bus::subscribe();
+
+            end_session(conn, team_id, "1", 60).unwrap();
+            let update = std::iter::from_fn(|| updates.try_recv().ok())
+                .find(|update| update.team_id == team_id)
+                .expect("No update published");
+            assert_eq!(update.event, LiveEvent::CheckOut);
+            let filter = AuditFilter {
+                action: Some("attendance.discard".to_string()),
+                ..AuditFilter::default()
+            };
+            let events = audit_repository::get_team_events(conn, team_id, &filter).unwrap();
+            assert_eq!(events.len(), 1);
+        });
+    }
+
+    #[test]
+    #[ignore = "needs a database"]
+    fn kept_session_replaces_the_recorded_absence() {
+        with_absent_member(|conn, team_id, member_id| {
+            let status = VOICE_CHECK_IN_STATUS.to_string();
+            attendance_service::check_in(conn, member_id, team_id, status).unwrap();
+
+            end_session(conn, team_id, "1", 0).unwrap();
+            assert!(!has_absence(conn, member_id));
+        });
+    }
+}
@@ -0,0 +1,532 @@
+use crate::bot::{
+    application::services::audit_service,
+    domain::{
+        audit::{AuditAction, AuditActor},
+        model::{Member, NewWebhook, NewWebhookDelivery, Webhook, WebhookDelivery},
+        table::{WebhookDeliveryTable, WebhookTable},
+        webhook::{self, DeliveryStatus, WebhookEvent},
+    },
+    infrastructure::persistence::{team_repository, webhook_repository},
+};
+use crate::config::constant::{
+    WEBHOOK_DELIVERY_BATCH_SIZE, WEBHOOK_DELIVERY_LOG_LIMIT, WEBHOOK_MAX_ATTEMPTS,
+    WEBHOOK_MAX_PER_TEAM, WEBHOOK_RETRY_BASE_SECONDS, WEBHOOK_RETRY_MAX_SECONDS,
+};
+use chrono::Utc;
+use diesel::PgConnection;
+use serde_json::{json, Value};
+
+/// Longest URL shown in the list of webhooks, so the table fits in a Discord message.
+const WEBHOOK_TABLE_URL_CHARS: usize = 60;
+
+/// Longest error shown in the delivery log, so the table fits in a Discord message.
+const WEBHOOK_TABLE_ERROR_CHARS: usize = 30;
+
+/// Adds a webhook to a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `url` - Where the events are posted.
+/// * `events` - The events to subscribe to, comma separated, or `all`.
+/// * `actor` - The user adding the webhook, recorded in the audit log.
+///
+/// # Returns
+/// Returns the new webhook, its secret is only shown to the user now.
+///
+/// # Errors
+/// Returns an error if:
+/// - The URL is not an http(s) URL or an event type is unknown.
+/// - The team already has `WEBHOOK_MAX_PER_TEAM` webhooks.
+/// - The webhook cannot be inserted into the database.
+pub fn add_webhook(
+    conn: &mut PgConnection,
+    team_id: i32,
+    url: &str,
+    events: &str,
+    actor: &AuditActor,
+) -> Result<Webhook, String> {
+    webhook::validate_url(url)?;
+    let events = WebhookEvent::parse_list(events)?;
+    if webhook_repository::get_webhooks(conn, team_id)?.len() >= WEBHOOK_MAX_PER_TEAM {
+        return Err(format!(
+            "A team can have at most {} webhooks",
+            WEBHOOK_MAX_PER_TEAM
+        ));
+    }
+
+    let webhook = webhook_repository::create_webhook(
+        conn,
+        &NewWebhook {
+            team_id,
+            url: url.to_string(),
+            secret: webhook::generate_secret(),
+            event_types: webhook::join(&events),
+            created_by: actor.discord_id.clone(),
+        },
+    )?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::WebhookAdd,
+        Some(team_id),
+        Some(&webhook.id.to_string()),
+        json!({ "url": webhook.url, "events": webhook.event_types }),
+    );
+    Ok(webhook)
+}
+
+/// Removes a webhook of a team together with its delivery log.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `webhook_id` - The ID of the webhook.
+/// * `actor` - The user removing the webhook, recorded in the audit log.
+///
+/// # Errors
+/// Returns an error if the team has no such webhook or it cannot be deleted.
+pub fn remove_webhook(
+    conn: &mut PgConnection,
+    team_id: i32,
+    webhook_id: i32,
+    actor: &AuditActor,
+) -> Result<(), String> {
+    let webhook = find_team_webhook(conn, team_id, webhook_id)?;
+    if !webhook_repository::delete_webhook(conn, team_id, webhook_id)? {
+        return Err(format!("Webhook {} not found", webhook_id));
+    }
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::WebhookRemove,
+        Some(team_id),
+        Some(&webhook_id.to_string()),
+        json!({ "url": webhook.url }),
+    );
+    Ok(())
+}
+
+/// Finds a webhook by ID, whatever its team.
+///
+/// # Errors
+/// Returns an error if the webhook cannot be fetched from the database.
+pub fn find_webhook(conn: &mut PgConnection, webhook_id: i32) -> Result<Option<Webhook>, String> {
+    webhook_repository::find_webhook(conn, webhook_id)
+}
+
+/// Finds a delivery by ID together with its webhook, whatever its team.
+///
+/// # Errors
+/// Returns an error if the delivery cannot be fetched from the database.
+pub fn find_delivery(
+    conn: &mut PgConnection,
+    delivery_id: i32,
+) -> Result<Option<(WebhookDelivery, Webhook)>, String> {
+    webhook_repository::find_delivery(conn, delivery_id)
+}
+
+/// Retrieves the webhooks of a team together with their pending and failed deliveries.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+///
+/// # Returns
+/// Returns `(webhook, pending, failed)` for each webhook, oldest first.
+///
+/// # Errors
+/// Returns an error if the webhooks cannot be fetched from the database.
+pub fn get_team_webhooks(
+    conn: &mut PgConnection,
+    team_id: i32,
+) -> Result<Vec<(Webhook, i64, i64)>, String> {
+    let webhooks = webhook_repository::get_webhooks(conn, team_id)?;
+    let ids: Vec<i32> = webhooks.iter().map(|webhook| webhook.id).collect();
+    let counts = webhook_repository::count_deliveries(conn, &ids)?;
+    let count = |webhook_id: i32, status: DeliveryStatus| {
+        counts
+            .iter()
+            .find(|(id, name, _)| *id == webhook_id && name == status.as_str())
+            .map_or(0, |(_, _, count)| *count)
+    };
+
+    Ok(webhooks
+        .into_iter()
+        .map(|webhook| {
+            let pending = count(webhook.id, DeliveryStatus::Pending);
+            let failed = count(webhook.id, DeliveryStatus::Failed);
+            (webhook, pending, failed)
+        })
+        .collect())
+}
+
+/// Retrieves the webhooks of a team in a tabular format.
+///
+/// # Errors
+/// Returns an error if the webhooks cannot be fetched from the database.
+pub fn get_webhooks(conn: &mut PgConnection, team_id: i32) -> Result<Vec<WebhookTable>, String> {
+    Ok(get_team_webhooks(conn, team_id)?
+        .into_iter()
+        .map(|(webhook, pending, failed)| WebhookTable {
+            id: webhook.id,
+            url: shorten(&webhook.url, WEBHOOK_TABLE_URL_CHARS),
+            events: webhook.event_types,
+            pending,
+            failed,
+        })
+        .collect())
+}
+
+/// Retrieves the latest deliveries of a webhook of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `webhook_id` - The ID of the webhook.
+///
+/// # Returns
+/// Returns the last `WEBHOOK_DELIVERY_LOG_LIMIT` deliveries, newest first.
+///
+/// # Errors
+/// Returns an error if the team has no such webhook or the deliveries cannot be fetched.
+pub fn get_deliveries(
+    conn: &mut PgConnection,
+    team_id: i32,
+    webhook_id: i32,
+) -> Result<Vec<WebhookDelivery>, String> {
+    find_team_webhook(conn, team_id, webhook_id)?;
+    webhook_repository::get_deliveries(conn, webhook_id, WEBHOOK_DELIVERY_LOG_LIMIT)
+}
+
+/// Retrieves the latest deliveries of a webhook of a team in a tabular format.
+///
+/// # Errors
+/// Returns an error if the team has no such webhook or the deliveries cannot be fetched.
+pub fn get_delivery_log(
+    conn: &mut PgConnection,
+    team_id: i32,
+    webhook_id: i32,
+) -> Result<Vec<WebhookDeliveryTable>, String> {
+    Ok(get_deliveries(conn, team_id, webhook_id)?
+        .into_iter()
+        .map(|delivery| WebhookDeliveryTable {
+            id: delivery.id,
+            event: delivery.event_type,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            response: match (delivery.response_status, delivery.last_error) {
+                (Some(status), _) => status.to_string(),
+                (None, Some(error)) => shorten(&error, WEBHOOK_TABLE_ERROR_CHARS),
+                (None, None) => "-".to_string(),
+            },
+            created_at: delivery.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        })
+        .collect())
+}
+
+/// Sends an earlier delivery again, with the same payload.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `delivery_id` - The ID of the delivery to replay.
+/// * `actor` - The user replaying the delivery, recorded in the audit log.
+///
+/// # Returns
+/// Returns the new pending delivery, sent within seconds.
+///
+/// # Errors
+/// Returns an error if the team has no such delivery or the new one cannot be queued.
+pub fn replay_delivery(
+    conn: &mut PgConnection,
+    team_id: i32,
+    delivery_id: i32,
+    actor: &AuditActor,
+) -> Result<WebhookDelivery, String> {
+    let (delivery, _) = webhook_repository::find_delivery(conn, delivery_id)?
+        .filter(|(_, webhook)| webhook.team_id == team_id)
+        .ok_or_else(|| format!("Delivery {} not found", delivery_id))?;
+
+    let replay = webhook_repository::create_deliveries(
+        conn,
+        &[NewWebhookDelivery {
+            webhook_id: delivery.webhook_id,
+            event_type: delivery.event_type,
+            payload: delivery.payload,
+            replay_of: Some(delivery.id),
+        }],
+    )?
+    .pop()
+    .ok_or("Failed to queue the replay")?;
+
+    audit_service::record(
+        conn,
+        actor,
+        AuditAction::WebhookReplay,
+        Some(team_id),
+        Some(&delivery.id.to_string()),
+        json!({ "replay": replay.id }),
+    );
+    Ok(replay)
+}
+
+/// Queues an event for every webhook of the team subscribed to it.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team_id` - The ID of the team.
+/// * `event` - What happened.
+/// * `data` - The details of the event, sent as the `data` field of the payload.
+///
+/// # Behavior
+/// - The event already happened, so a failure to queue it is logged instead of returned.
+pub fn emit(conn: &mut PgConnection, team_id: i32, event: WebhookEvent, data: Value) {
+    if let Err(e) = queue_event(conn, team_id, event, data) {
+        println!("Error queuing {} webhook: {}", event, e);
+    }
+}
+
+/// Queues an event about a member for every webhook of their team subscribed to it.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `member` - The member the event is about.
+/// * `event` - What happened.
+/// * `data` - The details of the event, added to the Discord ID and name of the member.
+pub fn emit_member(conn: &mut PgConnection, member: &Member, event: WebhookEvent, data: Value) {
+    let Some(team_id) = member.team_id else {
+        return;
+    };
+
+    let mut data = data;
+    data["discord_id"] = json!(member.discord_id);
+    data["username"] = json!(member.username);
+    emit(conn, team_id, event, data);
+}
+
+/// Retrieves the pending deliveries whose next attempt is due.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Returns
+/// Returns at most `WEBHOOK_DELIVERY_BATCH_SIZE` deliveries with their webhook, oldest first.
+///
+/// # Errors
+/// Returns an error if the deliveries cannot be fetched from the database.
+pub fn get_due_deliveries(
+    conn: &mut PgConnection,
+) -> Result<Vec<(WebhookDelivery, Webhook)>, String> {
+    webhook_repository::get_due_deliveries(
+        conn,
+        Utc::now().naive_utc(),
+        WEBHOOK_DELIVERY_BATCH_SIZE,
+    )
+}
+
+/// Records the outcome of an attempt to send a delivery.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `delivery` - The delivery.
+/// * `outcome` - The HTTP status the receiver answered with, or why it could not be reached.
+///
+/// # Returns
+/// Returns the new status of the delivery.
+///
+/// # Behavior
+/// - A 2xx status delivers it, anything else is retried with exponential backoff.
+/// - After `WEBHOOK_MAX_ATTEMPTS` failed attempts the delivery is given up.
+///
+/// # Errors
+/// Returns an error if the delivery cannot be updated in the database.
+pub fn record_attempt(
+    conn: &mut PgConnection,
+    delivery: &WebhookDelivery,
+    outcome: Result<u16, String>,
+) -> Result<DeliveryStatus, String> {
+    let now = Utc::now().naive_utc();
+    let (response, error) = match outcome {
+        Ok(status) if (200..300).contains(&status) => {
+            webhook_repository::mark_delivered(conn, delivery.id, status.into(), now)?;
+            return Ok(DeliveryStatus::Delivered);
+        }
+        Ok(status) => (Some(status.into()), format!("HTTP {}", status)),
+        Err(e) => (None, e),
+    };
+
+    let attempts = delivery.attempts + 1;
+    let retry_at = (attempts < WEBHOOK_MAX_ATTEMPTS).then(|| {
+        now + webhook::retry_delay(
+            attempts,
+            WEBHOOK_RETRY_BASE_SECONDS,
+            WEBHOOK_RETRY_MAX_SECONDS,
+        )
+    });
+    webhook_repository::mark_failed(conn, delivery.id, response, &error, retry_at)?;
+
+    Ok(match retry_at {
+        Some(_) => DeliveryStatus::Pending,
+        None => DeliveryStatus::Failed,
+    })
+}
+
+/// Cuts a text shown in a table down to `max` characters.
+fn shorten(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(max - 1).collect();
+    short.push('…');
+    short
+}
+
+/// Finds a webhook of a team.
+fn find_team_webhook(
+    conn: &mut PgConnection,
+    team_id: i32,
+    webhook_id: i32,
+) -> Result<Webhook, String> {
+    webhook_repository::find_webhook(conn, webhook_id)?
+        .filter(|webhook| webhook.team_id == team_id)
+        .ok_or_else(|| format!("Webhook {} not found", webhook_id))
+}
+
+fn queue_event(
+    conn: &mut PgConnection,
+    team_id: i32,
+    event: WebhookEvent,
+    data: Value,
+) -> Result<(), String> {
+    let webhooks: Vec<Webhook> = webhook_repository::get_webhooks(conn, team_id)?
+        .into_iter()
+        .filter(|webhook| {
+            webhook
+                .event_types
+                .split(',')
+                .any(|name| WebhookEvent::parse(name) == Some(event))
+        })
+        .collect();
+    if webhooks.is_empty() {
+        return Ok(());
+    }
+
+    let team = team_repository::find_team(conn, team_id)?
+        .ok_or_else(|| format!("Team {} not found", team_id))?;
+    let payload = json!({
+        "event": event.as_str(),
+        "occurred_at": Utc::now().naive_utc(),
+        "team": { "id": team.id, "name": team.name, "guild_id": team.guild_id },
+        "data": data,
+    })
+    .to_string();
+
+    let deliveries: Vec<NewWebhookDelivery> = webhooks
+        .into_iter()
+        .map(|webhook| NewWebhookDelivery {
+            webhook_id: webhook.id,
+            event_type: event.as_str().to_string(),
+            payload: payload.clone(),
+            replay_of: None,
+        })
+        .collect();
+    webhook_repository::create_deliveries(conn, &deliveries).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::database::testing::with_member;
+    use crate::schema::webhook_deliveries;
+    use chrono::Duration;
+    use diesel::prelude::*;
+
+    /// Runs `test` with a queued delivery that already failed `attempts` times.
+    fn with_delivery(attempts: i32, test: impl FnOnce(&mut PgConnection, WebhookDelivery)) {
+        with_member(|conn, team_id, _| {
+            let new_webhook = NewWebhook {
+                team_id,
+                url: "http://127.0.0.1:9/hook".to_string(),
+                secret: webhook::generate_secret(),
+                event_types: "check_in".to_string(),
+                created_by: "1".to_string(),
+            };
+            let webhook = webhook_repository::create_webhook(conn, &new_webhook).unwrap();
+            let delivery = NewWebhookDelivery {
+                webhook_id: webhook.id,
+                event_type: "check_in".to_string(),
+                payload: "{}".to_string(),
+                replay_of: None,
+            };
+            let delivery = webhook_repository::create_deliveries(conn, &[delivery]).unwrap();
+            let delivery = diesel::update(webhook_deliveries::table.find(delivery[0].id))
+                .set(webhook_deliveries::attempts.eq(attempts))
+                .get_result::<WebhookDelivery>(conn)
+                .unwrap();
+
+            test(conn, delivery);
+        });
+    }
+
+    fn reload(conn: &mut PgConnection, delivery: &WebhookDelivery) -> WebhookDelivery {
+        webhook_repository::find_delivery(conn, delivery.id)
+            .unwrap()
+            .map(|(delivery, _)| delivery)
+            .unwrap()
+    }
+
+    #[test]
+    #[ignore = "needs a database"]
+    fn marks_2xx_answers_delivered() {
+        with_delivery(0, |conn, delivery| {
+            let status = record_attempt(conn, &delivery, Ok(204)).unwrap();
+            assert_eq!(status, DeliveryStatus::Delivered);
+
+            let delivery = reload(conn, &delivery);
+            assert_eq!(delivery.status, DeliveryStatus::Delivered.as_str());
+            assert_eq!(delivery.response_status, Some(204));
+            assert!(delivery.delivered_at.is_some());
+        });
+    }
+
+    #[test]
+    #[ignore = "needs a database"]
+    fn retries_failed_attempts_with_backoff() {
+        with_delivery(2, |conn, delivery| {
+            let before = Utc::now().naive_utc();
+            let status = record_attempt(conn, &delivery, Ok(500)).unwrap();
+            assert_eq!(status, DeliveryStatus::Pending);
+
+            let delivery = reload(conn, &delivery);
+            assert_eq!(delivery.attempts, 3);
+            assert_eq!(delivery.response_status, Some(500));
+            assert_eq!(delivery.last_error.as_deref(), Some("HTTP 500"));
+            let delay = delivery.next_attempt_at - before;
+            assert!(delay >= Duration::seconds(120) && delay < Duration::seconds(125));
+        });
+    }
+
+    #[test]
+    #[ignore = "needs a database"]
+    fn gives_up_after_the_last_attempt() {
+        with_delivery(WEBHOOK_MAX_ATTEMPTS - 1, |conn, delivery| {
+            let outcome = Err("Connection failed".to_string());
+            let status = record_attempt(conn, &delivery, outcome).unwrap();
+            assert_eq!(status, DeliveryStatus::Failed);
+
+            let delivery = reload(conn, &delivery);
+            assert_eq!(delivery.status, DeliveryStatus::Failed.as_str());
+            assert_eq!(delivery.attempts, WEBHOOK_MAX_ATTEMPTS);
+            assert_eq!(delivery.last_error.as_deref(), Some("Connection failed"));
+            assert!(
+                webhook_repository::get_due_deliveries(conn, delivery.next_attempt_at, 50)
+                    .unwrap()
+                    .iter()
+                    .all(|(due, _)| due.id != delivery.id)
+            );
+        });
+    }
+}
@@ -0,0 +1,367 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// Represents where an administrative action was made from.
+///
+/// # Variants
+/// * `Discord` - A bot command or a button in Discord.
+/// * `Api` - The HTTP API, with a session or an API key.
+/// * `System` - The bot itself, e.g. when it syncs a team with its Discord role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditSource {
+    Discord,
+    Api,
+    System,
+}
+
+/// Represents who makes a change and where from, passed to every service that changes a team.
+///
+/// # Fields
+/// * `discord_id` - The Discord ID of the user, or "system" for the bot itself.
+/// * `source` - Where the change was made from.
+/// * `guild_id` - The ID of the Discord server the command was sent in, if any.
+/// * `channel_id` - The ID of the Discord channel the command was sent in, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditActor {
+    pub discord_id: String,
+    pub source: AuditSource,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+}
+
+/// Represents an administrative action recorded in the audit log.
+///
+/// Actions are named `<subject>.<verb>`, so a filter on the subject matches all of its verbs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    UserRegister,
+    UserRevokeSessions,
+    ApiKeyCreate,
+    ApiKeyRevoke,
+    TeamCreate,
+    TeamRename,
+    TeamArchive,
+    TeamTransfer,
+    TeamLinkRole,
+    TeamDelete,
+    MemberAdd,
+    MemberUpdate,
+    MemberDeactivate,
+    MemberReactivate,
+    MemberRemove,
+    RoleGrant,
+    RoleRevoke,
+    VoiceLink,
+    VoiceUnlink,
+    VoiceSettings,
+    ScheduleUpdate,
+    HolidayAdd,
+    HolidayImport,
+    HolidayRemove,
+    LeaveReview,
+    LeaveAllowance,
+    LeaveChannel,
+    PresenceBoard,
+    ReminderAdd,
+    ReminderRemove,
+    EventCreate,
+    EventSkip,
+    EventCancel,
+    RollcallStart,
+    AttendanceEdit,
+    AttendanceAdd,
+    AttendanceDiscard,
+    CorrectionReview,
+    WebhookAdd,
+    WebhookRemove,
+    WebhookReplay,
+}
+
+/// Represents the filters of an audit log query.
+///
+/// # Fields
+/// * `actor_id` - Only the actions of this Discord user.
+/// * `action` - Only this action, or every action of a subject such as `member`.
+/// * `since` - Only the actions made on or after this day.
+/// * `until` - Only the actions made on or before this day.
+/// * `limit` - How many actions to return at most, newest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditFilter {
+    pub actor_id: Option<String>,
+    pub action: Option<String>,
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    pub limit: i64,
+}
+
+/// The number of actions shown when no limit is given.
+pub const DEFAULT_AUDIT_LIMIT: i64 = 20;
+
+/// The largest number of actions returned by one query.
+pub const MAX_AUDIT_LIMIT: i64 = 500;
+
+impl AuditSource {
+    /// Returns the name of the source as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditSource::Discord => "discord",
+            AuditSource::Api => "api",
+            AuditSource::System => "system",
+        }
+    }
+}
+
+impl AuditActor {
+    /// Creates the actor of a bot command.
+    ///
+    /// # Arguments
+    /// * `discord_id` - The Discord ID of the author of the command.
+    /// * `guild_id` - The ID of the server the command was sent in, `None` in a DM.
+    /// * `channel_id` - The ID of the channel the command was sent in.
+    pub fn discord(discord_id: &str, guild_id: Option<String>, channel_id: String) -> Self {
+        AuditActor {
+            discord_id: discord_id.to_string(),
+            source: AuditSource::Discord,
+            guild_id,
+            channel_id: Some(channel_id),
+        }
+    }
+
+    /// Creates the actor of an API request made by a Discord user.
+    pub fn api(discord_id: &str) -> Self {
+        AuditActor {
+            discord_id: discord_id.to_string(),
+            source: AuditSource::Api,
+            guild_id: None,
+            channel_id: None,
+        }
+    }
+
+    /// Creates the actor of a change the bot makes on its own.
+    ///
+    /// # Arguments
+    /// * `guild_id` - The ID of the server the change was triggered by, if any.
+    pub fn system(guild_id: Option<String>) -> Self {
+        AuditActor {
+            discord_id: "system".to_string(),
+            source: AuditSource::System,
+            guild_id,
+            channel_id: None,
+        }
+    }
+}
+
+impl AuditAction {
+    /// Returns the name of the action as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::UserRegister => "user.register",
+            AuditAction::UserRevokeSessions => "user.revoke_sessions",
+            AuditAction::ApiKeyCreate => "api_key.create",
+            AuditAction::ApiKeyRevoke => "api_key.revoke",
+            AuditAction::TeamCreate => "team.create",
+            AuditAction::TeamRename => "team.rename",
+            AuditAction::TeamArchive => "team.archive",
+            AuditAction::TeamTransfer => "team.transfer",
+            AuditAction::TeamLinkRole => "team.link_role",
+            AuditAction::TeamDelete => "team.delete",
+            AuditAction::MemberAdd => "member.add",
+            AuditAction::MemberUpdate => "member.update",
+            AuditAction::MemberDeactivate => "member.deactivate",
+            AuditAction::MemberReactivate => "member.reactivate",
+            AuditAction::MemberRemove => "member.remove",
+            AuditAction::RoleGrant => "role.grant",
+            AuditAction::RoleRevoke => "role.revoke",
+            AuditAction::VoiceLink => "voice.link",
+            AuditAction::VoiceUnlink => "voice.unlink",
+            AuditAction::VoiceSettings => "voice.settings",
+            AuditAction::ScheduleUpdate => "schedule.update",
+            AuditAction::HolidayAdd => "holiday.add",
+            AuditAction::HolidayImport => "holiday.import",
+            AuditAction::HolidayRemove => "holiday.remove",
+            AuditAction::LeaveReview => "leave.review",
+            AuditAction::LeaveAllowance => "leave.allowance",
+            AuditAction::LeaveChannel => "leave.channel",
+            AuditAction::PresenceBoard => "presence.board",
+            AuditAction::ReminderAdd => "reminder.add",
+            AuditAction::ReminderRemove => "reminder.remove",
+            AuditAction::EventCreate => "event.create",
+            AuditAction::EventSkip => "event.skip",
+            AuditAction::EventCancel => "event.cancel",
+            AuditAction::RollcallStart => "rollcall.start",
+            AuditAction::AttendanceEdit => "attendance.edit",
+            AuditAction::AttendanceAdd => "attendance.add",
+            AuditAction::AttendanceDiscard => "attendance.discard",
+            AuditAction::CorrectionReview => "correction.review",
+            AuditAction::WebhookAdd => "webhook.add",
+            AuditAction::WebhookRemove => "webhook.remove",
+            AuditAction::WebhookReplay => "webhook.replay",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Default for AuditFilter {
+    fn default() -> Self {
+        AuditFilter {
+            actor_id: None,
+            action: None,
+            since: None,
+            until: None,
+            limit: DEFAULT_AUDIT_LIMIT,
+        }
+    }
+}
+
+impl AuditFilter {
+    /// Parses the `action:<action>`, `actor:<discord_id>`, `since:<date>`, `until:<date>` and
+    /// `limit:<n>` arguments of a command.
+    ///
+    /// # Arguments
+    /// * `args` - The arguments, in any order. Mentions are accepted as the actor.
+    ///
+    /// # Errors
+    /// Returns an error if an argument is unknown or its value is invalid.
+    pub fn parse_args(args: &[&str]) -> Result<Self, String> {
+        let mut filter = AuditFilter::default();
+
+        for arg in args {
+            let Some((key, value)) = arg.split_once(':') else {
+                return Err(format!("Unknown filter '{}'", arg));
+            };
+            match key {
+                "action" => filter.action = Some(value.to_lowercase()),
+                "actor" => {
+                    let discord_id = value.trim_start_matches("<@").trim_start_matches('!');
+                    filter.actor_id = Some(discord_id.trim_end_matches('>').to_string());
+                }
+                "since" => filter.since = Some(parse_day(value)?),
+                "until" => filter.until = Some(parse_day(value)?),
+                "limit" => {
+                    filter.limit = value
+                        .parse()
+                        .map_err(|_| format!("'{}' is not a number", value))?;
+                }
+                _ => return Err(format!("Unknown filter '{}'", arg)),
+            }
+        }
+
+        filter.validate()?;
+        Ok(filter)
+    }
+
+    /// Checks that the filters can be combined.
+    ///
+    /// # Errors
+    /// Returns an error if the limit is not between 1 and `MAX_AUDIT_LIMIT`, or the period ends
+    /// before it starts.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_AUDIT_LIMIT).contains(&self.limit) {
+            return Err(format!(
+                "The limit must be between 1 and {}",
+                MAX_AUDIT_LIMIT
+            ));
+        }
+        match (self.since, self.until) {
+            (Some(since), Some(until)) if until < since => {
+                Err("`until` cannot come before `since`".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the first moment the filters accept, if they start on a day.
+    pub fn since_time(&self) -> Option<NaiveDateTime> {
+        self.since.and_then(|day| day.and_hms_opt(0, 0, 0))
+    }
+
+    /// Returns the first moment after the last day the filters accept, if they end on a day.
+    pub fn until_time(&self) -> Option<NaiveDateTime> {
+        self.until
+            .and_then(|day| day.succ_opt())
+            .and_then(|day| day.and_hms_opt(0, 0, 0))
+    }
+}
+
+/// Builds the details of an action from the values of fields before and after it.
+///
+/// # Arguments
+/// * `fields` - The name of each field with its value before and after the action.
+///
+/// # Returns
+/// Returns a JSON object with `{"from": ..., "to": ...}` for each field whose value changed.
+pub fn diff(fields: &[(&str, Value, Value)]) -> Value {
+    let changes: Map<String, Value> = fields
+        .iter()
+        .filter(|(_, from, to)| from != to)
+        .map(|(field, from, to)| (field.to_string(), json!({ "from": from, "to": to })))
+        .collect();
+
+    Value::Object(changes)
+}
+
+/// Parses a day typed in a command as `YYYY-MM-DD`.
+fn parse_day(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("'{}' is not a date (YYYY-MM-DD)", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_filter_arguments() {
+        let filter = AuditFilter::parse_args(&[
+            "action:Member",
+            "actor:<@!200>",
+            "since:2025-03-01",
+            "until:2025-03-31",
+            "limit:50",
+        ])
+        .unwrap();
+
+        assert_eq!(filter.action.as_deref(), Some("member"));
+        assert_eq!(filter.actor_id.as_deref(), Some("200"));
+        assert_eq!(
+            filter.since_time(),
+            NaiveDate::from_ymd_opt(2025, 3, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+        );
+        assert_eq!(
+            filter.until_time(),
+            NaiveDate::from_ymd_opt(2025, 4, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+        );
+        assert_eq!(filter.limit, 50);
+
+        assert_eq!(
+            AuditFilter::parse_args(&[]).unwrap(),
+            AuditFilter::default()
+        );
+        assert!(AuditFilter::parse_args(&["limit:0"]).is_err());
+        assert!(AuditFilter::parse_args(&["since:2025-03-02", "until:2025-03-01"]).is_err());
+        assert!(AuditFilter::parse_args(&["everything"]).is_err());
+    }
+
+    #[test]
+    fn diff_keeps_only_the_changed_fields() {
+        let details = diff(&[
+            ("name", json!("alpha"), json!("beta")),
+            ("archived", json!(false), json!(false)),
+        ]);
+
+        assert_eq!(
+            details,
+            json!({ "name": { "from": "alpha", "to": "beta" } })
+        );
+    }
+}
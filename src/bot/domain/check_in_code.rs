@@ -0,0 +1,128 @@
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+/// How long a check-in code stays on screen before the next one is shown, in seconds.
+pub const CODE_PERIOD_SECONDS: i64 = 30;
+
+/// Number of digits of a check-in code.
+const CODE_DIGITS: u32 = 6;
+
+/// Generates a new random secret for an event, hex encoded.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Computes the check-in code shown at a point in time, TOTP-style (RFC 6238 with HMAC-SHA256).
+///
+/// # Arguments
+/// * `secret` - The secret of the event.
+/// * `unix_time` - The point in time, in seconds since the Unix epoch.
+pub fn code_at(secret: &str, unix_time: i64) -> String {
+    code_for_step(secret, unix_time.div_euclid(CODE_PERIOD_SECONDS))
+}
+
+/// Returns the number of seconds until the code shown at a point in time is replaced.
+pub fn seconds_until_rotation(unix_time: i64) -> i64 {
+    CODE_PERIOD_SECONDS - unix_time.rem_euclid(CODE_PERIOD_SECONDS)
+}
+
+/// Checks a code typed by a member.
+///
+/// The code currently shown and the one shown right before are accepted, so a member
+/// typing while the screen refreshes is not rejected.
+pub fn verify(secret: &str, code: &str, unix_time: i64) -> bool {
+    let step = unix_time.div_euclid(CODE_PERIOD_SECONDS);
+    [step, step - 1]
+        .iter()
+        .any(|step| code_for_step(secret, *step) == code.trim())
+}
+
+fn code_for_step(secret: &str, step: i64) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // Dynamic truncation as described in RFC 4226
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(CODE_DIGITS),
+        width = CODE_DIGITS as usize
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The SHA256 seed of the RFC 6238 test vectors.
+    const RFC_SECRET: &str = "12345678901234567890123456789012";
+
+    #[test]
+    fn matches_the_rfc_6238_vectors() {
+        // The last six digits of the eight digit HMAC-SHA256 codes of RFC 6238, Appendix B
+        assert_eq!(code_at(RFC_SECRET, 59), "119246");
+        assert_eq!(code_at(RFC_SECRET, 1_111_111_109), "084774");
+        assert_eq!(code_at(RFC_SECRET, 1_234_567_890), "819424");
+        assert_eq!(code_at(RFC_SECRET, 2_000_000_000), "698825");
+    }
+
+    #[test]
+    fn accepts_the_current_and_previous_code() {
+        let now = 1_700_000_015;
+        assert!(verify(RFC_SECRET, &code_at(RFC_SECRET, now), now));
+        assert!(verify(
+            RFC_SECRET,
+            &format!(" {} ", code_at(RFC_SECRET, now)),
+            now
+        ));
+        assert!(verify(
+            RFC_SECRET,
+            &code_at(RFC_SECRET, now - CODE_PERIOD_SECONDS),
+            now
+        ));
+    }
+
+    #[test]
+    fn rejects_older_and_future_codes() {
+        let now = 1_700_000_015;
+        assert!(!verify(
+            RFC_SECRET,
+            &code_at(RFC_SECRET, now - 2 * CODE_PERIOD_SECONDS),
+            now
+        ));
+        assert!(!verify(
+            RFC_SECRET,
+            &code_at(RFC_SECRET, now + CODE_PERIOD_SECONDS),
+            now
+        ));
+        assert!(!verify("another secret", &code_at(RFC_SECRET, now), now));
+    }
+
+    #[test]
+    fn rotates_on_period_boundaries() {
+        assert_eq!(seconds_until_rotation(1_700_000_020), 20);
+        assert_eq!(seconds_until_rotation(1_700_000_009), 1);
+        assert_eq!(seconds_until_rotation(1_700_000_010), 30);
+        assert_eq!(
+            code_at(RFC_SECRET, 1_700_000_010),
+            code_at(RFC_SECRET, 1_700_000_039)
+        );
+        assert_ne!(
+            code_at(RFC_SECRET, 1_700_000_039),
+            code_at(RFC_SECRET, 1_700_000_040)
+        );
+        assert_eq!(generate_secret().len(), 40);
+    }
+}
@@ -0,0 +1,250 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use std::fmt;
+
+/// Represents the state of an attendance correction request.
+///
+/// # Variants
+/// * `Pending` - Waiting for a manager to review it.
+/// * `Approved` - Accepted, the change was applied to the attendance record.
+/// * `Rejected` - Refused by a manager.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorrectionStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// Represents the values of an attendance record that can be corrected.
+///
+/// # Fields
+/// * `check_in_time` - The timestamp when the member checked in.
+/// * `check_out_time` - The timestamp when the member checked out.
+/// * `status` - The status of the attendance (e.g., "Present", "Late").
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttendanceValues {
+    pub check_in_time: Option<NaiveDateTime>,
+    pub check_out_time: Option<NaiveDateTime>,
+    pub status: Option<String>,
+}
+
+/// Represents a change to the values of an attendance record, `None` keeps the current value.
+///
+/// # Fields
+/// * `check_in_time` - The new check-in timestamp.
+/// * `check_out_time` - The new check-out timestamp.
+/// * `clear_check_out` - Whether the check-out is removed, the member is still checked in.
+/// * `status` - The new status.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttendanceChange {
+    pub check_in_time: Option<NaiveDateTime>,
+    pub check_out_time: Option<NaiveDateTime>,
+    pub clear_check_out: bool,
+    pub status: Option<String>,
+}
+
+impl CorrectionStatus {
+    /// Returns the name of the status as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CorrectionStatus::Pending => "pending",
+            CorrectionStatus::Approved => "approved",
+            CorrectionStatus::Rejected => "rejected",
+        }
+    }
+}
+
+impl fmt::Display for CorrectionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl AttendanceChange {
+    /// Parses the leading `in:<time>`, `out:<time>|out:-` and `status:<status>` arguments of a
+    /// command.
+    ///
+    /// # Arguments
+    /// * `args` - The arguments, the first ones may describe the change.
+    /// * `date` - The day of the attendance record, `HH:MM` times fall on it.
+    ///
+    /// # Returns
+    /// Returns the change and how many arguments describe it. Times are either `HH:MM` or
+    /// `YYYY-MM-DDTHH:MM` (UTC).
+    ///
+    /// # Errors
+    /// Returns an error if a time or a status is invalid or given twice.
+    pub fn parse_args(args: &[&str], date: NaiveDate) -> Result<(Self, usize), String> {
+        let mut change = AttendanceChange::default();
+        let mut used = 0;
+
+        for arg in args {
+            let Some((key, value)) = arg.split_once(':') else {
+                break;
+            };
+            match key {
+                "in" if change.check_in_time.is_none() => {
+                    change.check_in_time = Some(parse_timestamp(value, date)?);
+                }
+                "out" if change.check_out_time.is_none() && !change.clear_check_out => {
+                    if value == "-" {
+                        change.clear_check_out = true;
+                    } else {
+                        change.check_out_time = Some(parse_timestamp(value, date)?);
+                    }
+                }
+                "status" if change.status.is_none() => {
+                    change.status = Some(value.to_string());
+                }
+                "in" | "out" | "status" => return Err(format!("`{}:` is given twice", key)),
+                _ => break,
+            }
+            used += 1;
+        }
+
+        Ok((change, used))
+    }
+
+    /// Checks whether the change leaves every value as it is.
+    pub fn is_empty(&self) -> bool {
+        self.check_in_time.is_none()
+            && self.check_out_time.is_none()
+            && !self.clear_check_out
+            && self.status.is_none()
+    }
+
+    /// Returns the values of a record once the change is applied to them.
+    pub fn apply(&self, values: &AttendanceValues) -> AttendanceValues {
+        AttendanceValues {
+            check_in_time: self.check_in_time.or(values.check_in_time),
+            check_out_time: if self.clear_check_out {
+                None
+            } else {
+                self.check_out_time.or(values.check_out_time)
+            },
+            status: self.status.clone().or_else(|| values.status.clone()),
+        }
+    }
+}
+
+impl AttendanceValues {
+    /// Checks that the values make a consistent attendance record.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - There is a check-out without a check-in, or it comes before the check-in.
+    /// - The status is empty or longer than 20 characters.
+    pub fn validate(&self) -> Result<(), String> {
+        match (self.check_in_time, self.check_out_time) {
+            (None, Some(_)) => return Err("A check-out needs a check-in".to_string()),
+            (Some(check_in), Some(check_out)) if check_out < check_in => {
+                return Err("The check-out cannot come before the check-in".to_string());
+            }
+            _ => {}
+        }
+        match self.status.as_deref() {
+            Some(status) if status.trim().is_empty() || status.chars().count() > 20 => {
+                Err("The status must be between 1 and 20 characters".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Parses a time typed in a command, `HH:MM` on a day or `YYYY-MM-DDTHH:MM`.
+fn parse_timestamp(value: &str, date: NaiveDate) -> Result<NaiveDateTime, String> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .map(|time| date.and_time(time))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+        .map_err(|_| format!("'{}' is not a time (HH:MM or YYYY-MM-DDTHH:MM)", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, day)
+            .unwrap()
+            .and_hms_opt(h, m, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_the_leading_change_arguments() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let args = [
+            "in:09:05",
+            "out:2025-03-04T01:30",
+            "forgot",
+            "to",
+            "check in",
+        ];
+        let (change, used) = AttendanceChange::parse_args(&args, date).unwrap();
+
+        assert_eq!(used, 2);
+        assert_eq!(change.check_in_time, Some(at(3, 9, 5)));
+        assert_eq!(change.check_out_time, Some(at(4, 1, 30)));
+        assert_eq!(change.status, None);
+
+        let (change, used) = AttendanceChange::parse_args(&["out:-", "status:Late"], date).unwrap();
+        assert_eq!(used, 2);
+        assert!(change.clear_check_out);
+        assert_eq!(change.status.as_deref(), Some("Late"));
+
+        assert!(AttendanceChange::parse_args(&["in:25:00"], date).is_err());
+        assert!(AttendanceChange::parse_args(&["in:09:00", "in:10:00"], date).is_err());
+        assert!(AttendanceChange::parse_args(&["reason"], date)
+            .unwrap()
+            .0
+            .is_empty());
+    }
+
+    #[test]
+    fn applies_a_change_over_the_current_values() {
+        let values = AttendanceValues {
+            check_in_time: Some(at(3, 9, 0)),
+            check_out_time: Some(at(3, 17, 0)),
+            status: Some("Present".to_string()),
+        };
+
+        let change = AttendanceChange {
+            status: Some("Late".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            change.apply(&values),
+            AttendanceValues {
+                status: Some("Late".to_string()),
+                ..values.clone()
+            }
+        );
+
+        let change = AttendanceChange {
+            clear_check_out: true,
+            ..Default::default()
+        };
+        assert_eq!(change.apply(&values).check_out_time, None);
+    }
+
+    #[test]
+    fn rejects_inconsistent_values() {
+        let values = AttendanceValues {
+            check_in_time: Some(at(3, 9, 0)),
+            check_out_time: Some(at(3, 8, 0)),
+            status: Some("Present".to_string()),
+        };
+        assert!(values.validate().is_err());
+        assert!(AttendanceValues {
+            check_in_time: None,
+            ..values.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(AttendanceValues {
+            check_out_time: None,
+            ..values
+        }
+        .validate()
+        .is_ok());
+    }
+}
@@ -0,0 +1,297 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+use crate::bot::domain::recurrence::RecurrenceRule;
+
+/// Longest all-day event read from a file, longer ones are skipped as unreadable.
+const MAX_EVENT_DAYS: i64 = 366;
+
+/// A day off read from an iCalendar (RFC 5545) file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcsHoliday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+/// The holidays read from an iCalendar file.
+///
+/// # Fields
+/// * `holidays` - One entry per day, in the order of the file.
+/// * `skipped` - The number of events that were left out because they could not be read.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct IcsImport {
+    pub holidays: Vec<IcsHoliday>,
+    pub skipped: usize,
+}
+
+/// Reads the days covered by the events of an iCalendar file.
+///
+/// # Arguments
+/// * `content` - The content of the `.ics` file.
+/// * `until` - Repeating events (`RRULE`) are expanded up to this day, included.
+///
+/// # Returns
+/// Returns every day covered by an event. All-day events cover the days from `DTSTART` up to
+/// the day before `DTEND`, timed events only the day they start on. Cancelled events and
+/// the occurrences listed in `EXDATE` are left out.
+///
+/// # Errors
+/// Returns an error if the content is not an iCalendar file.
+pub fn parse_holidays(content: &str, until: NaiveDate) -> Result<IcsImport, String> {
+    let lines = unfold(content.trim_start_matches('\u{feff}'));
+    if lines.first().map(|line| line.trim()) != Some("BEGIN:VCALENDAR") {
+        return Err("The file is not an iCalendar (.ics) file".to_string());
+    }
+
+    let mut import = IcsImport::default();
+    let mut event: Option<Vec<(String, String, String)>> = None;
+    for line in lines {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+        match (name.as_str(), value.as_str(), event.as_mut()) {
+            ("BEGIN", "VEVENT", _) => event = Some(Vec::new()),
+            ("END", "VEVENT", Some(_)) => {
+                let properties = event.take().unwrap_or_default();
+                match event_days(&properties, until) {
+                    Some(holidays) => import.holidays.extend(holidays),
+                    None => import.skipped += 1,
+                }
+            }
+            (_, _, Some(properties)) => properties.push((name, params, value)),
+            _ => {}
+        }
+    }
+
+    Ok(import)
+}
+
+/// Returns the days covered by one `VEVENT`, or `None` if it cannot be read.
+fn event_days(
+    properties: &[(String, String, String)],
+    until: NaiveDate,
+) -> Option<Vec<IcsHoliday>> {
+    let property = |name: &str| {
+        properties
+            .iter()
+            .find(|(key, _, _)| key == name)
+            .map(|(_, params, value)| (params.as_str(), value.as_str()))
+    };
+
+    if property("STATUS").map(|(_, status)| status) == Some("CANCELLED") {
+        return Some(Vec::new());
+    }
+
+    let name = property("SUMMARY")
+        .map(|(_, summary)| unescape(summary))
+        .filter(|summary| !summary.trim().is_empty())
+        .unwrap_or_else(|| "Holiday".to_string());
+    let (start_params, start) = property("DTSTART")?;
+    let dtstart = parse_value(start_params, start)?;
+    let all_day = is_date(start_params, start);
+    let days = match property("DTEND").map(|(params, end)| parse_value(params, end)) {
+        Some(Some(dtend)) if all_day => (dtend.date() - dtstart.date()).num_days().max(1),
+        Some(None) => return None,
+        _ => 1,
+    };
+    if days > MAX_EVENT_DAYS {
+        return None;
+    }
+
+    let starts = match property("RRULE") {
+        Some((_, rule)) => {
+            let rule = rule.parse::<RecurrenceRule>().ok()?;
+            let exdates: Vec<NaiveDateTime> = properties
+                .iter()
+                .filter(|(key, _, _)| key == "EXDATE")
+                .flat_map(|(_, params, value)| {
+                    value
+                        .split(',')
+                        .filter_map(|value| parse_value(params, value))
+                })
+                .collect();
+            let to = until.succ_opt()?.and_hms_opt(0, 0, 0)?;
+            rule.occurrences_between(dtstart, &exdates, dtstart, to)
+        }
+        None => vec![dtstart],
+    };
+
+    Some(
+        starts
+            .into_iter()
+            .flat_map(|start| {
+                (0..days)
+                    .filter_map(move |day| start.date().checked_add_signed(Duration::days(day)))
+            })
+            .filter(|date| *date <= until)
+            .map(|date| IcsHoliday {
+                date,
+                name: name.clone(),
+            })
+            .collect(),
+    )
+}
+
+/// Joins the lines folded with a leading space or tab back together.
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.trim_end_matches('\r').to_string()),
+        }
+    }
+    lines
+}
+
+/// Splits `NAME;PARAM=VALUE:value` into its upper-cased name, its parameters and its value.
+fn split_property(line: &str) -> Option<(String, String, String)> {
+    let (key, value) = line.split_once(':')?;
+    let (name, params) = key.split_once(';').unwrap_or((key, ""));
+    Some((
+        name.trim().to_uppercase(),
+        params.to_uppercase(),
+        value.trim().to_string(),
+    ))
+}
+
+fn is_date(params: &str, value: &str) -> bool {
+    (params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME")) || value.len() == 8
+}
+
+/// Reads a `DATE` (`20250101`) or `DATE-TIME` (`20250101T090000[Z]`) value, ignoring `TZID`.
+fn parse_value(params: &str, value: &str) -> Option<NaiveDateTime> {
+    if is_date(params, value) {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0);
+    }
+    NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S").ok()
+}
+
+/// Resolves the escaped characters of a `TEXT` value.
+fn unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => text.push(' '),
+            Some(escaped) => text.push(escaped),
+            None => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn calendar(events: &str) -> String {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Test//EN\r\n{}END:VCALENDAR\r\n",
+            events
+        )
+    }
+
+    fn dates(import: &IcsImport) -> Vec<NaiveDate> {
+        import.holidays.iter().map(|holiday| holiday.date).collect()
+    }
+
+    #[test]
+    fn reads_all_day_events() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250101\r\nDTEND;VALUE=DATE:20250102\r\nSUMMARY:New Year's Day\r\nEND:VEVENT\r\n",
+        );
+        let import = parse_holidays(&ics, date(2030, 1, 1)).unwrap();
+        assert_eq!(
+            import.holidays,
+            vec![IcsHoliday {
+                date: date(2025, 1, 1),
+                name: "New Year's Day".to_string()
+            }]
+        );
+        assert_eq!(import.skipped, 0);
+    }
+
+    #[test]
+    fn dtend_of_all_day_events_is_exclusive() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251224\r\nDTEND;VALUE=DATE:20251227\r\nSUMMARY:Christmas\r\nEND:VEVENT\r\n",
+        );
+        let import = parse_holidays(&ics, date(2030, 1, 1)).unwrap();
+        assert_eq!(
+            dates(&import),
+            vec![date(2025, 12, 24), date(2025, 12, 25), date(2025, 12, 26)]
+        );
+    }
+
+    #[test]
+    fn timed_events_cover_their_first_day() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nDTSTART:20250501T080000Z\r\nDTEND:20250502T170000Z\r\nSUMMARY:Labour Day\r\nEND:VEVENT\r\n",
+        );
+        let import = parse_holidays(&ics, date(2030, 1, 1)).unwrap();
+        assert_eq!(dates(&import), vec![date(2025, 5, 1)]);
+    }
+
+    #[test]
+    fn unfolds_lines_and_unescapes_text() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250714\r\nSUMMARY:Bastille\r\n  Day\\, France\r\nEND:VEVENT\r\n",
+        );
+        let import = parse_holidays(&ics, date(2030, 1, 1)).unwrap();
+        assert_eq!(import.holidays[0].name, "Bastille Day, France");
+    }
+
+    #[test]
+    fn expands_yearly_rules_up_to_the_limit() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20241225\r\nRRULE:FREQ=YEARLY\r\nEXDATE;VALUE=DATE:20251225\r\nSUMMARY:Christmas Day\r\nEND:VEVENT\r\n",
+        );
+        let import = parse_holidays(&ics, date(2026, 12, 31)).unwrap();
+        assert_eq!(dates(&import), vec![date(2024, 12, 25), date(2026, 12, 25)]);
+    }
+
+    #[test]
+    fn skips_cancelled_and_unreadable_events() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250101\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART:someday\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nSUMMARY:No start\r\nEND:VEVENT\r\n",
+        );
+        let import = parse_holidays(&ics, date(2030, 1, 1)).unwrap();
+        assert!(import.holidays.is_empty());
+        assert_eq!(import.skipped, 2);
+    }
+
+    #[test]
+    fn skips_rules_with_an_oversized_interval() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250101\r\nRRULE:FREQ=YEARLY;INTERVAL=4294967295\r\nSUMMARY:Never\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250203\r\nRRULE:FREQ=WEEKLY;INTERVAL=1300000;BYMONTH=2;BYDAY=MO\r\nSUMMARY:Never either\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250501\r\nSUMMARY:Labour Day\r\nEND:VEVENT\r\n",
+        );
+        let import = parse_holidays(&ics, date(2030, 1, 1)).unwrap();
+        assert_eq!(dates(&import), vec![date(2025, 5, 1)]);
+        assert_eq!(import.skipped, 2);
+    }
+
+    #[test]
+    fn skips_rules_with_a_multibyte_weekday() {
+        let ics = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250106\r\nRRULE:FREQ=WEEKLY;BYDAY=€\r\nSUMMARY:Never\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250501\r\nSUMMARY:Labour Day\r\nEND:VEVENT\r\n",
+        );
+        let import = parse_holidays(&ics, date(2030, 1, 1)).unwrap();
+        assert_eq!(dates(&import), vec![date(2025, 5, 1)]);
+        assert_eq!(import.skipped, 1);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(parse_holidays("name,date\nNew Year,2025-01-01\n", date(2030, 1, 1)).is_err());
+    }
+}
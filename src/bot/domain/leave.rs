@@ -0,0 +1,110 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Represents the kind of leave a member requests.
+///
+/// # Variants
+/// * `Vacation` - Paid time off planned ahead.
+/// * `Sick` - Sick leave, which may be requested after the fact.
+/// * `Personal` - Personal or family matters.
+/// * `Other` - Anything else, the reason tells what.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaveType {
+    Vacation,
+    Sick,
+    Personal,
+    Other,
+}
+
+/// Represents the state of a leave request.
+///
+/// # Variants
+/// * `Pending` - Waiting for a manager to review it.
+/// * `Approved` - Accepted, the member is excused on the days it covers.
+/// * `Rejected` - Refused by a manager.
+/// * `Cancelled` - Withdrawn by the member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaveStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Cancelled,
+}
+
+impl LeaveType {
+    /// Parses a leave type as stored in the database or typed in a command.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "vacation" => Some(LeaveType::Vacation),
+            "sick" => Some(LeaveType::Sick),
+            "personal" => Some(LeaveType::Personal),
+            "other" => Some(LeaveType::Other),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the leave type as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaveType::Vacation => "vacation",
+            LeaveType::Sick => "sick",
+            LeaveType::Personal => "personal",
+            LeaveType::Other => "other",
+        }
+    }
+}
+
+impl LeaveStatus {
+    /// Parses a leave status as stored in the database.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(LeaveStatus::Pending),
+            "approved" => Some(LeaveStatus::Approved),
+            "rejected" => Some(LeaveStatus::Rejected),
+            "cancelled" => Some(LeaveStatus::Cancelled),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the status as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaveStatus::Pending => "pending",
+            LeaveStatus::Approved => "approved",
+            LeaveStatus::Rejected => "rejected",
+            LeaveStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for LeaveType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for LeaveStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Counts the working days (Monday to Friday, holidays excluded) between two dates, both
+/// included.
+pub fn working_days(from: NaiveDate, to: NaiveDate, holidays: &HashSet<NaiveDate>) -> i64 {
+    let mut days = 0;
+    let mut date = from;
+    while date <= to {
+        if is_working_day(date) && !holidays.contains(&date) {
+            days += 1;
+        }
+        date += Duration::days(1);
+    }
+    days
+}
+
+/// Checks whether a day is a working day (Monday to Friday).
+pub fn is_working_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
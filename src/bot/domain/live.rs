@@ -0,0 +1,254 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Serialize;
+
+use crate::bot::domain::{
+    model::{Member, MemberAttendance},
+    webhook::WebhookEvent,
+};
+
+/// Represents an attendance change shown on the live feed of a team.
+///
+/// # Variants
+/// * `CheckIn` - A member checked in, by command, code, voice channel or roll-call.
+/// * `CheckOut` - A member checked out.
+/// * `StatusChange` - The status of an attendance record was set by hand or by an absence check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveEvent {
+    CheckIn,
+    CheckOut,
+    StatusChange,
+}
+
+/// Represents an attendance change of a member, published on the live feed of their team.
+///
+/// # Fields
+/// * `event` - What happened.
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the member.
+/// * `username` - The name of the member.
+/// * `status` - The status of the attendance record, if it has one.
+/// * `date` - The day of the attendance record.
+/// * `at` - When the change happened.
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveUpdate {
+    pub event: LiveEvent,
+    pub team_id: i32,
+    pub discord_id: String,
+    pub username: String,
+    pub status: Option<String>,
+    pub date: NaiveDate,
+    pub at: NaiveDateTime,
+}
+
+/// Represents a member who is checked in and has not checked out yet.
+///
+/// # Fields
+/// * `discord_id` - The Discord ID of the member.
+/// * `username` - The name of the member.
+/// * `status` - The status of the open attendance record, if it has one.
+/// * `since` - When the member checked in.
+/// * `on_break` - Whether the member is on a break. Breaks are not tracked yet, so it is always
+///   `false`; it is part of the presence so clients do not have to change once they are.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Presence {
+    pub discord_id: String,
+    pub username: String,
+    pub status: Option<String>,
+    pub since: NaiveDateTime,
+    pub on_break: bool,
+}
+
+impl LiveEvent {
+    /// Returns the name of the event as sent to live feed clients.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LiveEvent::CheckIn => "check_in",
+            LiveEvent::CheckOut => "check_out",
+            LiveEvent::StatusChange => "status_change",
+        }
+    }
+
+    /// Returns the webhook event sent along, status changes are only shown on the live feed.
+    pub fn webhook_event(&self) -> Option<WebhookEvent> {
+        match self {
+            LiveEvent::CheckIn => Some(WebhookEvent::CheckIn),
+            LiveEvent::CheckOut => Some(WebhookEvent::CheckOut),
+            LiveEvent::StatusChange => None,
+        }
+    }
+}
+
+impl LiveUpdate {
+    /// Creates the update of an attendance change of a member.
+    ///
+    /// # Returns
+    /// Returns `None` if the member does not belong to a team.
+    pub fn new(
+        member: &Member,
+        event: LiveEvent,
+        status: Option<&str>,
+        date: NaiveDate,
+        at: NaiveDateTime,
+    ) -> Option<Self> {
+        Some(LiveUpdate {
+            event,
+            team_id: member.team_id?,
+            discord_id: member.discord_id.clone(),
+            username: member.username.clone(),
+            status: status.map(str::to_string),
+            date,
+            at,
+        })
+    }
+
+    /// Formats the update as a Server-Sent Event named after the event, with the update as its
+    /// JSON data.
+    pub fn to_sse(&self) -> String {
+        sse_event(
+            self.event.as_str(),
+            &serde_json::to_string(self).unwrap_or_default(),
+        )
+    }
+}
+
+impl Presence {
+    /// Lists who is in from the open check-ins of a team, oldest check-in first.
+    ///
+    /// # Arguments
+    /// * `check_ins` - The open attendance records with their members, oldest first.
+    ///
+    /// # Returns
+    /// Returns one entry per member, since their oldest open check-in.
+    pub fn from_check_ins(check_ins: Vec<(MemberAttendance, Member)>) -> Vec<Self> {
+        let mut presence: Vec<Presence> = Vec::new();
+        for (attendance, member) in check_ins {
+            let Some(since) = attendance.check_in_time else {
+                continue;
+            };
+            if presence.iter().any(|p| p.discord_id == member.discord_id) {
+                continue;
+            }
+            presence.push(Presence {
+                discord_id: member.discord_id,
+                username: member.username,
+                status: attendance.status,
+                since,
+                on_break: false,
+            });
+        }
+        presence
+    }
+
+    /// Formats how long the member has been in at `now`, e.g. `2h 05m`.
+    pub fn duration(&self, now: NaiveDateTime) -> String {
+        let minutes = (now - self.since).num_minutes().max(0);
+        match minutes / 60 {
+            0 => format!("{}m", minutes),
+            hours => format!("{}h {:02}m", hours, minutes % 60),
+        }
+    }
+}
+
+/// Formats a Server-Sent Event, `data` must fit on one line.
+pub fn sse_event(name: &str, data: &str) -> String {
+    format!("event: {}\ndata: {}\n\n", name, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member() -> Member {
+        Member {
+            id: 7,
+            team_id: Some(1),
+            username: "alice".to_string(),
+            discord_id: "200".to_string(),
+            position: None,
+            join_date: None,
+            left_date: None,
+        }
+    }
+
+    #[test]
+    fn formats_updates_as_server_sent_events() {
+        let at = NaiveDate::from_ymd_opt(2025, 3, 21)
+            .unwrap()
+            .and_hms_opt(9, 2, 11)
+            .unwrap();
+        let update = LiveUpdate::new(
+            &member(),
+            LiveEvent::CheckIn,
+            Some("Present"),
+            at.date(),
+            at,
+        )
+        .unwrap();
+
+        assert_eq!(
+            update.to_sse(),
+            "event: check_in\ndata: {\"event\":\"check_in\",\"team_id\":1,\"discord_id\":\"200\",\
+             \"username\":\"alice\",\"status\":\"Present\",\"date\":\"2025-03-21\",\
+             \"at\":\"2025-03-21T09:02:11\"}\n\n"
+        );
+    }
+
+    #[test]
+    fn skips_members_without_a_team() {
+        let at = NaiveDate::from_ymd_opt(2025, 3, 21)
+            .unwrap()
+            .and_hms_opt(18, 0, 0)
+            .unwrap();
+        let member = Member {
+            team_id: None,
+            ..member()
+        };
+
+        assert!(LiveUpdate::new(&member, LiveEvent::CheckOut, None, at.date(), at).is_none());
+        assert_eq!(LiveEvent::StatusChange.webhook_event(), None);
+    }
+
+    #[test]
+    fn lists_each_member_once_since_their_oldest_check_in() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 21).unwrap();
+        let record = |id: i32, hour: u32| MemberAttendance {
+            id,
+            team_id: Some(1),
+            member_id: Some(7),
+            date,
+            check_in_time: date.and_hms_opt(hour, 0, 0),
+            check_out_time: None,
+            status: Some("Present".to_string()),
+            rollcall_id: None,
+            event_id: None,
+            system_generated: false,
+        };
+
+        let presence =
+            Presence::from_check_ins(vec![(record(1, 9), member()), (record(2, 13), member())]);
+
+        assert_eq!(
+            presence,
+            vec![Presence {
+                discord_id: "200".to_string(),
+                username: "alice".to_string(),
+                status: Some("Present".to_string()),
+                since: date.and_hms_opt(9, 0, 0).unwrap(),
+                on_break: false,
+            }]
+        );
+        assert_eq!(
+            presence[0].duration(date.and_hms_opt(9, 42, 30).unwrap()),
+            "42m"
+        );
+        assert_eq!(
+            presence[0].duration(date.and_hms_opt(11, 5, 0).unwrap()),
+            "2h 05m"
+        );
+        assert_eq!(
+            presence[0].duration(date.and_hms_opt(8, 0, 0).unwrap()),
+            "0m"
+        );
+    }
+}
@@ -1,2 +1,12 @@
 pub mod model;
 pub mod table;
+pub mod permission;
+pub mod check_in_code;
+pub mod recurrence;
+pub mod leave;
+pub mod ics;
+pub mod reminder;
+pub mod correction;
+pub mod audit;
+pub mod webhook;
+pub mod live;
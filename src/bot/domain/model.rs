@@ -1,10 +1,26 @@
+use crate::schema::attendance_audit;
+use crate::schema::attendance_corrections;
+use crate::schema::audit_events;
+use crate::schema::event_exceptions;
+use crate::schema::events;
+use crate::schema::leave_balances;
+use crate::schema::leave_requests;
 use crate::schema::member_attendance;
 use crate::schema::members;
+use crate::schema::reminder_preferences;
+use crate::schema::reminder_rules;
+use crate::schema::rollcalls;
+use crate::schema::team_holidays;
+use crate::schema::team_roles;
+use crate::schema::team_voice_channels;
 use crate::schema::teams;
 use crate::schema::users;
+use crate::schema::webhook_deliveries;
+use crate::schema::webhooks;
 
 use chrono::NaiveDate;
 use chrono::NaiveDateTime;
+use chrono::NaiveTime;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -18,6 +34,9 @@ use serde::{Deserialize, Serialize};
 /// * `is_admin` - Indicates whether the user has admin privileges.
 /// * `created_at` - The timestamp when the user was created.
 /// * `updated_at` - The timestamp when the user was last updated.
+/// * `failed_login_attempts` - The number of failed API logins since the last successful one.
+/// * `last_failed_login_at` - The timestamp of the last failed API login.
+/// * `locked_until` - The timestamp until which API logins are locked.
 #[derive(Debug, Serialize, Deserialize, Queryable)]
 pub struct User {
     pub id: i32,
@@ -27,6 +46,9 @@ pub struct User {
     pub is_admin: Option<bool>,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
+    pub failed_login_attempts: i32,
+    pub last_failed_login_at: Option<NaiveDateTime>,
+    pub locked_until: Option<NaiveDateTime>,
 }
 
 /// Represents a new user to be inserted into the database.
@@ -54,12 +76,36 @@ pub struct NewUser {
 /// * `name` - The name of the team.
 /// * `admin_id` - The ID of the admin who created the team.
 /// * `created_at` - The timestamp when the team was created.
+/// * `guild_id` - The ID of the Discord server the team belongs to.
+/// * `archived_at` - The timestamp when the team was archived, if it is archived.
+/// * `discord_role_id` - The ID of the Discord role the members are synced with, if any.
+/// * `voice_min_minutes` - The minimum length of a voice session to be recorded.
+/// * `voice_debounce_seconds` - How long a member may leave a voice channel before being checked out.
+/// * `leave_channel_id` - The ID of the channel leave requests are posted to for review, if any.
+/// * `schedule_start` - The time (UTC) the working day of the team starts, if it has a schedule.
+/// * `schedule_grace_minutes` - How long after the start members are recorded as absent.
+/// * `report_channel_id` - The ID of the channel the daily no-show report is posted to, if any.
+/// * `absences_checked_on` - The last day the absences of the team were recorded.
+/// * `presence_channel_id` - The ID of the channel the presence board is kept in, if any.
+/// * `presence_message_id` - The ID of the message of the presence board, if it was posted.
 #[derive(Debug, Serialize, Deserialize, Queryable)]
 pub struct Team {
     pub id: i32,
     pub name: String,
     pub admin_id: Option<i32>,
     pub created_at: Option<NaiveDateTime>,
+    pub guild_id: String,
+    pub archived_at: Option<NaiveDateTime>,
+    pub discord_role_id: Option<String>,
+    pub voice_min_minutes: i32,
+    pub voice_debounce_seconds: i32,
+    pub leave_channel_id: Option<String>,
+    pub schedule_start: Option<NaiveTime>,
+    pub schedule_grace_minutes: i32,
+    pub report_channel_id: Option<String>,
+    pub absences_checked_on: Option<NaiveDate>,
+    pub presence_channel_id: Option<String>,
+    pub presence_message_id: Option<String>,
 }
 
 /// Represents a new team to be inserted into the database.
@@ -68,12 +114,14 @@ pub struct Team {
 /// * `name` - The name of the team.
 /// * `admin_id` - The ID of the admin who created the team.
 /// * `created_at` - The timestamp when the team was created.
+/// * `guild_id` - The ID of the Discord server the team belongs to.
 #[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
 #[diesel(table_name = teams)]
 pub struct NewTeam {
     pub name: String,
     pub admin_id: i32,
     pub created_at: chrono::NaiveDateTime,
+    pub guild_id: String,
 }
 
 /// Represents a member in the database.
@@ -85,6 +133,7 @@ pub struct NewTeam {
 /// * `discord_id` - The Discord ID of the member.
 /// * `position` - The position or role of the member in the team.
 /// * `join_date` - The date when the member joined the team.
+/// * `left_date` - The date when the member was deactivated, if they left the team.
 #[derive(Debug, Serialize, Deserialize, Queryable)]
 pub struct Member {
     pub id: i32,
@@ -93,6 +142,7 @@ pub struct Member {
     pub discord_id: String,
     pub position: Option<String>,
     pub join_date: Option<NaiveDate>,
+    pub left_date: Option<NaiveDate>,
 }
 
 /// Represents a new member to be inserted into the database.
@@ -123,6 +173,9 @@ pub struct NewMember<'a> {
 /// * `check_in_time` - The timestamp when the member checked in.
 /// * `check_out_time` - The timestamp when the member checked out.
 /// * `status` - The status of the attendance (e.g., "Present", "Late").
+/// * `rollcall_id` - The ID of the roll-call the record answers, if any.
+/// * `event_id` - The ID of the event the member checked in to, if any.
+/// * `system_generated` - Whether the record is an absence recorded by the daily check.
 #[derive(Debug, Serialize, Deserialize, Queryable)]
 pub struct MemberAttendance {
     pub id: i32,
@@ -132,6 +185,9 @@ pub struct MemberAttendance {
     pub check_in_time: Option<NaiveDateTime>,
     pub check_out_time: Option<NaiveDateTime>,
     pub status: Option<String>,
+    pub rollcall_id: Option<i32>,
+    pub event_id: Option<i32>,
+    pub system_generated: bool,
 }
 
 /// Represents a new check-in attendance record to be inserted into the database.
@@ -142,6 +198,7 @@ pub struct MemberAttendance {
 /// * `date` - The date of the attendance record.
 /// * `check_in_time` - The timestamp when the member checked in.
 /// * `status` - The status of the attendance (e.g., "Present", "Late").
+/// * `event_id` - The ID of the event the member checks in to, if any.
 #[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
 #[diesel(table_name = member_attendance)]
 pub struct CheckInAttendance {
@@ -150,4 +207,632 @@ pub struct CheckInAttendance {
     pub date: NaiveDate,
     pub check_in_time: NaiveDateTime,
     pub status: String,
+    pub event_id: Option<i32>,
+}
+
+/// Represents the answer of a member to a roll-call, to be inserted into the database.
+///
+/// # Fields
+/// * `member_id` - The ID of the member.
+/// * `team_id` - The ID of the team the member belongs to.
+/// * `date` - The date of the attendance record.
+/// * `check_in_time` - The timestamp when the member answered, `None` if they did not.
+/// * `check_out_time` - The same timestamp as `check_in_time`, a roll-call has no duration.
+/// * `status` - Either "Present", "Absent" or "Excused" (absent on approved leave).
+/// * `rollcall_id` - The ID of the roll-call.
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[diesel(table_name = member_attendance)]
+pub struct RollcallAttendance {
+    pub member_id: i32,
+    pub team_id: i32,
+    pub date: NaiveDate,
+    pub check_in_time: Option<NaiveDateTime>,
+    pub check_out_time: Option<NaiveDateTime>,
+    pub status: String,
+    pub rollcall_id: i32,
+}
+
+/// Represents an absence recorded by the daily check, to be inserted into the database.
+///
+/// # Fields
+/// * `member_id` - The ID of the member.
+/// * `team_id` - The ID of the team the member belongs to.
+/// * `date` - The day the member did not check in.
+/// * `status` - Always "Absent".
+/// * `system_generated` - Always `true`.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = member_attendance)]
+pub struct SystemAbsence {
+    pub member_id: i32,
+    pub team_id: i32,
+    pub date: NaiveDate,
+    pub status: String,
+    pub system_generated: bool,
+}
+
+/// Represents an attendance record added by hand, to be inserted into the database.
+///
+/// # Fields
+/// * `member_id` - The ID of the member.
+/// * `team_id` - The ID of the team the member belongs to.
+/// * `date` - The date of the attendance record.
+/// * `check_in_time` - The timestamp when the member checked in.
+/// * `check_out_time` - The timestamp when the member checked out, if they did.
+/// * `status` - The status of the attendance (e.g., "Present", "Late").
+#[derive(Debug, Insertable)]
+#[diesel(table_name = member_attendance)]
+pub struct ManualAttendance {
+    pub member_id: i32,
+    pub team_id: i32,
+    pub date: NaiveDate,
+    pub check_in_time: Option<NaiveDateTime>,
+    pub check_out_time: Option<NaiveDateTime>,
+    pub status: String,
+}
+
+/// Represents a change a member asks for on their attendance.
+///
+/// # Fields
+/// * `id` - The unique identifier of the request.
+/// * `team_id` - The ID of the team.
+/// * `member_id` - The ID of the member.
+/// * `attendance_id` - The ID of the record to change, `None` to add the missing record of
+///   `attendance_date`.
+/// * `attendance_date` - The day of the attendance record.
+/// * `check_in_time` - The requested check-in timestamp, `None` keeps the current one.
+/// * `check_out_time` - The requested check-out timestamp, `None` keeps the current one.
+/// * `clear_check_out` - Whether the check-out is removed.
+/// * `attendance_status` - The requested status, `None` keeps the current one.
+/// * `reason` - Why the record is wrong.
+/// * `status` - Either "pending", "approved" or "rejected".
+/// * `reviewed_by` - The Discord ID of the user who approved or rejected the request.
+/// * `reviewed_at` - The timestamp when the request was approved or rejected.
+/// * `created_at` - The timestamp when the request was made.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct AttendanceCorrection {
+    pub id: i32,
+    pub team_id: i32,
+    pub member_id: i32,
+    pub attendance_id: Option<i32>,
+    pub attendance_date: NaiveDate,
+    pub check_in_time: Option<NaiveDateTime>,
+    pub check_out_time: Option<NaiveDateTime>,
+    pub clear_check_out: bool,
+    pub attendance_status: Option<String>,
+    pub reason: String,
+    pub status: String,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// Represents a new attendance correction request to be inserted into the database.
+///
+/// # Fields
+/// * `team_id` - The ID of the team.
+/// * `member_id` - The ID of the member.
+/// * `attendance_id` - The ID of the record to change, `None` to add a missing record.
+/// * `attendance_date` - The day of the attendance record.
+/// * `check_in_time` - The requested check-in timestamp.
+/// * `check_out_time` - The requested check-out timestamp.
+/// * `clear_check_out` - Whether the check-out is removed.
+/// * `attendance_status` - The requested status.
+/// * `reason` - Why the record is wrong.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = attendance_corrections)]
+pub struct NewAttendanceCorrection {
+    pub team_id: i32,
+    pub member_id: i32,
+    pub attendance_id: Option<i32>,
+    pub attendance_date: NaiveDate,
+    pub check_in_time: Option<NaiveDateTime>,
+    pub check_out_time: Option<NaiveDateTime>,
+    pub clear_check_out: bool,
+    pub attendance_status: Option<String>,
+    pub reason: String,
+}
+
+/// Represents a change made to an attendance record by hand.
+///
+/// # Fields
+/// * `id` - The unique identifier of the audit entry.
+/// * `attendance_id` - The ID of the attendance record, `None` once it was deleted.
+/// * `correction_id` - The ID of the approved correction request the change comes from, if any.
+/// * `action` - Either "insert" or "update".
+/// * `actor_id` - The Discord ID of the user who made or approved the change.
+/// * `reason` - Why the record was changed.
+/// * `before_check_in_time` - The check-in timestamp before the change.
+/// * `before_check_out_time` - The check-out timestamp before the change.
+/// * `before_status` - The status before the change.
+/// * `after_check_in_time` - The check-in timestamp after the change.
+/// * `after_check_out_time` - The check-out timestamp after the change.
+/// * `after_status` - The status after the change.
+/// * `created_at` - The timestamp when the change was made.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct AttendanceAudit {
+    pub id: i32,
+    pub attendance_id: Option<i32>,
+    pub correction_id: Option<i32>,
+    pub action: String,
+    pub actor_id: String,
+    pub reason: String,
+    pub before_check_in_time: Option<NaiveDateTime>,
+    pub before_check_out_time: Option<NaiveDateTime>,
+    pub before_status: Option<String>,
+    pub after_check_in_time: Option<NaiveDateTime>,
+    pub after_check_out_time: Option<NaiveDateTime>,
+    pub after_status: Option<String>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// Represents a new audit entry to be inserted into the database.
+///
+/// # Fields
+/// See `AttendanceAudit`.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = attendance_audit)]
+pub struct NewAttendanceAudit {
+    pub attendance_id: i32,
+    pub correction_id: Option<i32>,
+    pub action: String,
+    pub actor_id: String,
+    pub reason: String,
+    pub before_check_in_time: Option<NaiveDateTime>,
+    pub before_check_out_time: Option<NaiveDateTime>,
+    pub before_status: Option<String>,
+    pub after_check_in_time: Option<NaiveDateTime>,
+    pub after_check_out_time: Option<NaiveDateTime>,
+    pub after_status: Option<String>,
+}
+
+/// Represents an administrative action in the audit log.
+///
+/// # Fields
+/// * `id` - The unique identifier for the event.
+/// * `actor_id` - The Discord ID of the user who made the change, or "system".
+/// * `source` - Where the change was made from: "discord", "api" or "system".
+/// * `action` - What was done, e.g. "team.rename".
+/// * `team_id` - The ID of the team, kept after the team is deleted.
+/// * `team_name` - The name of the team when the change was made.
+/// * `guild_id` - The ID of the Discord server of the team or the command.
+/// * `channel_id` - The ID of the Discord channel the command was sent in.
+/// * `target` - What the action applies to, e.g. the Discord ID of a member.
+/// * `details` - A JSON object with the changed values.
+/// * `created_at` - The timestamp when the change was made.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct AuditEvent {
+    pub id: i32,
+    pub actor_id: String,
+    pub source: String,
+    pub action: String,
+    pub team_id: Option<i32>,
+    pub team_name: Option<String>,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub target: Option<String>,
+    pub details: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// Represents a new audit event to be inserted into the database.
+///
+/// # Fields
+/// See `AuditEvent`.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = audit_events)]
+pub struct NewAuditEvent {
+    pub actor_id: String,
+    pub source: String,
+    pub action: String,
+    pub team_id: Option<i32>,
+    pub team_name: Option<String>,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub target: Option<String>,
+    pub details: String,
+}
+
+/// Represents a roll-call in the database.
+///
+/// # Fields
+/// * `id` - The unique identifier for the roll-call.
+/// * `team_id` - The ID of the team the roll-call is for.
+/// * `channel_id` - The ID of the channel the roll-call was posted in.
+/// * `message_id` - The ID of the roll-call message, once it has been posted.
+/// * `started_by` - The Discord ID of the user who started the roll-call.
+/// * `closes_at` - The timestamp when the roll-call closes.
+/// * `closed_at` - The timestamp when the roll-call was closed, if it is closed.
+/// * `created_at` - The timestamp when the roll-call was started.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct Rollcall {
+    pub id: i32,
+    pub team_id: i32,
+    pub channel_id: String,
+    pub message_id: Option<String>,
+    pub started_by: String,
+    pub closes_at: NaiveDateTime,
+    pub closed_at: Option<NaiveDateTime>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// Represents a new roll-call to be inserted into the database.
+///
+/// # Fields
+/// * `team_id` - The ID of the team the roll-call is for.
+/// * `channel_id` - The ID of the channel the roll-call is posted in.
+/// * `started_by` - The Discord ID of the user who started the roll-call.
+/// * `closes_at` - The timestamp when the roll-call closes.
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[diesel(table_name = rollcalls)]
+pub struct NewRollcall {
+    pub team_id: i32,
+    pub channel_id: String,
+    pub started_by: String,
+    pub closes_at: NaiveDateTime,
+}
+
+/// Represents an event of a team in the database.
+///
+/// # Fields
+/// * `id` - The unique identifier for the event.
+/// * `team_id` - The ID of the team the event belongs to.
+/// * `title` - The title of the event.
+/// * `starts_at` - The timestamp when the event starts.
+/// * `ends_at` - The timestamp when the event ends.
+/// * `code_secret` - The secret of the rotating check-in code, if check-ins require one.
+/// * `created_by` - The Discord ID of the user who created the event.
+/// * `created_at` - The timestamp when the event was created.
+/// * `recurrence_rule` - The iCalendar recurrence rule of the event, if it repeats.
+/// * `cancelled_at` - The timestamp when the event was cancelled, if it is cancelled.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct Event {
+    pub id: i32,
+    pub team_id: i32,
+    pub title: String,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    #[serde(skip_serializing)]
+    pub code_secret: Option<String>,
+    pub created_by: String,
+    pub created_at: Option<NaiveDateTime>,
+    pub recurrence_rule: Option<String>,
+    pub cancelled_at: Option<NaiveDateTime>,
+}
+
+/// Represents a new event to be inserted into the database.
+///
+/// # Fields
+/// * `team_id` - The ID of the team the event belongs to.
+/// * `title` - The title of the event.
+/// * `starts_at` - The timestamp when the event starts.
+/// * `ends_at` - The timestamp when the event ends.
+/// * `code_secret` - The secret of the rotating check-in code, if check-ins require one.
+/// * `created_by` - The Discord ID of the user who created the event.
+/// * `recurrence_rule` - The iCalendar recurrence rule of the event, if it repeats.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = events)]
+pub struct NewEvent {
+    pub team_id: i32,
+    pub title: String,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub code_secret: Option<String>,
+    pub created_by: String,
+    pub recurrence_rule: Option<String>,
+}
+
+/// Represents a skipped occurrence of a repeating event (an iCalendar `EXDATE`).
+///
+/// # Fields
+/// * `id` - The unique identifier of the exception.
+/// * `event_id` - The ID of the repeating event.
+/// * `starts_at` - The start of the occurrence that does not take place.
+/// * `created_at` - The timestamp when the occurrence was skipped.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct EventException {
+    pub id: i32,
+    pub event_id: i32,
+    pub starts_at: NaiveDateTime,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// Represents a new skipped occurrence to be inserted into the database.
+///
+/// # Fields
+/// * `event_id` - The ID of the repeating event.
+/// * `starts_at` - The start of the occurrence that does not take place.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = event_exceptions)]
+pub struct NewEventException {
+    pub event_id: i32,
+    pub starts_at: NaiveDateTime,
+}
+
+/// Represents a leave request of a member.
+///
+/// # Fields
+/// * `id` - The unique identifier of the request.
+/// * `team_id` - The ID of the team.
+/// * `member_id` - The ID of the member taking leave.
+/// * `leave_type` - The kind of leave (e.g., "vacation", "sick").
+/// * `starts_on` - The first day of leave.
+/// * `ends_on` - The last day of leave.
+/// * `reason` - Why the member takes leave.
+/// * `status` - Either "pending", "approved", "rejected" or "cancelled".
+/// * `reviewed_by` - The Discord ID of the user who approved or rejected the request.
+/// * `reviewed_at` - The timestamp when the request was approved or rejected.
+/// * `created_at` - The timestamp when the request was made.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct LeaveRequest {
+    pub id: i32,
+    pub team_id: i32,
+    pub member_id: i32,
+    pub leave_type: String,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub reason: String,
+    pub status: String,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// Represents a new leave request to be inserted into the database.
+///
+/// # Fields
+/// * `team_id` - The ID of the team.
+/// * `member_id` - The ID of the member taking leave.
+/// * `leave_type` - The kind of leave.
+/// * `starts_on` - The first day of leave.
+/// * `ends_on` - The last day of leave.
+/// * `reason` - Why the member takes leave.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = leave_requests)]
+pub struct NewLeaveRequest {
+    pub team_id: i32,
+    pub member_id: i32,
+    pub leave_type: String,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub reason: String,
+}
+
+/// Represents the yearly leave allowance of a member for a leave type.
+///
+/// # Fields
+/// * `id` - The unique identifier of the balance.
+/// * `member_id` - The ID of the member.
+/// * `year` - The calendar year the allowance applies to.
+/// * `leave_type` - The kind of leave.
+/// * `allowance_days` - The number of working days of leave the member may take.
+/// * `created_at` - The timestamp when the allowance was set.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct LeaveBalance {
+    pub id: i32,
+    pub member_id: i32,
+    pub year: i32,
+    pub leave_type: String,
+    pub allowance_days: i32,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// Represents a new leave allowance to be inserted into the database.
+///
+/// # Fields
+/// * `member_id` - The ID of the member.
+/// * `year` - The calendar year the allowance applies to.
+/// * `leave_type` - The kind of leave.
+/// * `allowance_days` - The number of working days of leave the member may take.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = leave_balances)]
+pub struct NewLeaveBalance {
+    pub member_id: i32,
+    pub year: i32,
+    pub leave_type: String,
+    pub allowance_days: i32,
+}
+
+/// Represents a day off of a team (e.g., a public holiday).
+///
+/// # Fields
+/// * `id` - The unique identifier of the holiday.
+/// * `team_id` - The ID of the team.
+/// * `holiday_date` - The day off.
+/// * `name` - The name of the holiday.
+/// * `created_at` - The timestamp when the holiday was added.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct Holiday {
+    pub id: i32,
+    pub team_id: i32,
+    pub holiday_date: NaiveDate,
+    pub name: String,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// Represents a new day off to be inserted into the database.
+///
+/// # Fields
+/// * `team_id` - The ID of the team.
+/// * `holiday_date` - The day off.
+/// * `name` - The name of the holiday.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = team_holidays)]
+pub struct NewHoliday {
+    pub team_id: i32,
+    pub holiday_date: NaiveDate,
+    pub name: String,
+}
+
+/// Represents a reminder rule of a team in the database.
+///
+/// # Fields
+/// * `id` - The unique identifier of the rule.
+/// * `team_id` - The ID of the team.
+/// * `kind` - Either "check_in" or "check_out".
+/// * `minutes_before_start` - How long before the schedule start of the team the rule fires.
+/// * `remind_at` - The time (UTC) the rule fires, set when `minutes_before_start` is not.
+/// * `last_sent_on` - The last day the reminders of the rule were sent.
+/// * `created_by` - The Discord ID of the user who added the rule.
+/// * `created_at` - The timestamp when the rule was added.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct ReminderRule {
+    pub id: i32,
+    pub team_id: i32,
+    pub kind: String,
+    pub minutes_before_start: Option<i32>,
+    pub remind_at: Option<NaiveTime>,
+    pub last_sent_on: Option<NaiveDate>,
+    pub created_by: String,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// Represents a new reminder rule to be inserted into the database.
+///
+/// # Fields
+/// * `team_id` - The ID of the team.
+/// * `kind` - Either "check_in" or "check_out".
+/// * `minutes_before_start` - How long before the schedule start of the team the rule fires.
+/// * `remind_at` - The time (UTC) the rule fires, set when `minutes_before_start` is not.
+/// * `created_by` - The Discord ID of the user adding the rule.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = reminder_rules)]
+pub struct NewReminderRule {
+    pub team_id: i32,
+    pub kind: String,
+    pub minutes_before_start: Option<i32>,
+    pub remind_at: Option<NaiveTime>,
+    pub created_by: String,
+}
+
+/// Represents the reminder settings of a user.
+///
+/// # Fields
+/// * `discord_id` - The Discord ID of the user.
+/// * `opted_out` - Whether the user turned reminders off.
+/// * `quiet_start` - The time (UTC) the quiet hours of the user start, if any.
+/// * `quiet_end` - The time (UTC) the quiet hours of the user end, if any.
+/// * `updated_at` - The timestamp when the settings last changed.
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable)]
+#[diesel(table_name = reminder_preferences)]
+pub struct ReminderPreference {
+    pub discord_id: String,
+    pub opted_out: bool,
+    pub quiet_start: Option<NaiveTime>,
+    pub quiet_end: Option<NaiveTime>,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+/// Represents a new team role assignment to be inserted into the database.
+///
+/// # Fields
+/// * `team_id` - The ID of the team.
+/// * `discord_id` - The Discord ID of the user receiving the role.
+/// * `role` - The name of the role (e.g., "owner", "manager").
+/// * `granted_by` - The Discord ID of the user who granted the role.
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[diesel(table_name = team_roles)]
+pub struct NewTeamRole {
+    pub team_id: i32,
+    pub discord_id: String,
+    pub role: String,
+    pub granted_by: Option<String>,
+}
+
+/// Represents a new link between a team and a voice channel to be inserted into the database.
+///
+/// # Fields
+/// * `team_id` - The ID of the team.
+/// * `channel_id` - The ID of the Discord voice channel.
+#[derive(Debug, Serialize, Deserialize, Insertable)]
+#[diesel(table_name = team_voice_channels)]
+pub struct NewTeamVoiceChannel {
+    pub team_id: i32,
+    pub channel_id: String,
+}
+
+/// Represents a webhook of a team in the database.
+///
+/// # Fields
+/// * `id` - The unique identifier of the webhook.
+/// * `team_id` - The ID of the team.
+/// * `url` - Where the events are posted.
+/// * `secret` - The key of the HMAC-SHA256 signature of each delivery.
+/// * `event_types` - The events the webhook subscribes to, comma separated.
+/// * `created_by` - The Discord ID of the user who added the webhook.
+/// * `created_at` - The timestamp when the webhook was added.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct Webhook {
+    pub id: i32,
+    pub team_id: i32,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub event_types: String,
+    pub created_by: String,
+    pub created_at: Option<NaiveDateTime>,
+}
+
+/// Represents a new webhook to be inserted into the database.
+///
+/// # Fields
+/// * `team_id` - The ID of the team.
+/// * `url` - Where the events are posted.
+/// * `secret` - The key of the HMAC-SHA256 signature of each delivery.
+/// * `event_types` - The events the webhook subscribes to, comma separated.
+/// * `created_by` - The Discord ID of the user adding the webhook.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = webhooks)]
+pub struct NewWebhook {
+    pub team_id: i32,
+    pub url: String,
+    pub secret: String,
+    pub event_types: String,
+    pub created_by: String,
+}
+
+/// Represents a delivery of an event to a webhook in the database, both outbox and log.
+///
+/// # Fields
+/// * `id` - The unique identifier of the delivery, sent to the receiver.
+/// * `webhook_id` - The ID of the webhook.
+/// * `event_type` - The event that was emitted.
+/// * `payload` - The JSON body posted to the webhook.
+/// * `status` - Either "pending", "delivered" or "failed".
+/// * `attempts` - How many times the delivery was attempted.
+/// * `next_attempt_at` - When the delivery is attempted next, while pending.
+/// * `response_status` - The HTTP status of the last response, if any.
+/// * `last_error` - Why the last attempt failed, if it did.
+/// * `delivered_at` - The timestamp when the receiver accepted the delivery.
+/// * `replay_of` - The ID of the delivery this one replays, if any.
+/// * `created_at` - The timestamp when the event was emitted.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
+pub struct WebhookDelivery {
+    pub id: i32,
+    pub webhook_id: i32,
+    pub event_type: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: NaiveDateTime,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<NaiveDateTime>,
+    pub replay_of: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+/// Represents a new pending delivery to be inserted into the outbox.
+///
+/// # Fields
+/// * `webhook_id` - The ID of the webhook.
+/// * `event_type` - The event that was emitted.
+/// * `payload` - The JSON body to post to the webhook.
+/// * `replay_of` - The ID of the delivery this one replays, if any.
+#[derive(Debug, Insertable)]
+#[diesel(table_name = webhook_deliveries)]
+pub struct NewWebhookDelivery {
+    pub webhook_id: i32,
+    pub event_type: String,
+    pub payload: String,
+    pub replay_of: Option<i32>,
 }
@@ -0,0 +1,88 @@
+use std::fmt;
+
+/// Represents the role a Discord user has in a team.
+///
+/// # Variants
+/// * `Owner` - Full control over the team, including roles.
+/// * `Manager` - Manages members and sees attendance.
+/// * `Viewer` - Sees members and attendance, but cannot change anything.
+/// * `Member` - Records their own attendance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Owner,
+    Manager,
+    Viewer,
+    Member,
+}
+
+/// Represents an action a handler needs permission for.
+///
+/// # Variants
+/// * `ManageTeam` - Rename, archive, delete or transfer the team.
+/// * `ManageRoles` - Grant or revoke roles in the team.
+/// * `ManageMembers` - Add members to the team.
+/// * `ViewMembers` - List the members of the team.
+/// * `ViewAttendance` - See the attendance records of the team.
+/// * `RecordAttendance` - Check in and out of the team.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    ManageTeam,
+    ManageRoles,
+    ManageMembers,
+    ViewMembers,
+    ViewAttendance,
+    RecordAttendance,
+}
+
+impl Role {
+    /// Parses a role name as stored in the database or typed in a command.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "owner" => Some(Role::Owner),
+            "manager" => Some(Role::Manager),
+            "viewer" => Some(Role::Viewer),
+            "member" => Some(Role::Member),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the role as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Owner => "owner",
+            Role::Manager => "manager",
+            Role::Viewer => "viewer",
+            Role::Member => "member",
+        }
+    }
+
+    /// Checks whether the role grants a permission.
+    pub fn allows(&self, permission: Permission) -> bool {
+        match permission {
+            Permission::ManageTeam | Permission::ManageRoles => matches!(self, Role::Owner),
+            Permission::ManageMembers => matches!(self, Role::Owner | Role::Manager),
+            Permission::ViewAttendance => {
+                matches!(self, Role::Owner | Role::Manager | Role::Viewer)
+            }
+            Permission::ViewMembers | Permission::RecordAttendance => true,
+        }
+    }
+}
+
+impl Permission {
+    /// Checks whether the action is still allowed once the team is archived.
+    ///
+    /// Archived teams are read-only, except for the owner managing the team itself.
+    pub fn allowed_when_archived(&self) -> bool {
+        matches!(
+            self,
+            Permission::ManageTeam | Permission::ViewMembers | Permission::ViewAttendance
+        )
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
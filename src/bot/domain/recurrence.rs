@@ -0,0 +1,746 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use std::str::FromStr;
+
+/// Upper bound of the periods (days, weeks, months or years) walked while expanding a rule,
+/// so a rule that never matches (e.g. `FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30`) still terminates.
+const MAX_PERIODS: i64 = 10_000;
+
+/// Largest `INTERVAL` accepted, larger values only describe dates no calendar can hold.
+const MAX_INTERVAL: u32 = 1_000;
+
+/// How often a recurrence rule repeats (the iCalendar `FREQ` part).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A weekday of a `BYDAY` part, optionally with its position in the month (e.g. `-1FR`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekdayNum {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+/// An iCalendar (RFC 5545) recurrence rule.
+///
+/// Supports `FREQ` (`DAILY`, `WEEKLY`, `MONTHLY`, `YEARLY`), `INTERVAL`, `COUNT`, `UNTIL`,
+/// `BYDAY`, `BYMONTHDAY`, `BYMONTH` and `WKST=MO`. Weeks start on Monday.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDateTime>,
+    pub by_day: Vec<WeekdayNum>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = String;
+
+    /// Parses a rule such as `FREQ=WEEKLY;INTERVAL=2;BYDAY=FR`, with or without `RRULE:`.
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim().to_uppercase();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(&rule);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut by_day = Vec::new();
+        let mut by_month_day = Vec::new();
+        let mut by_month = Vec::new();
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid rule part '{}'", part))?;
+            match key {
+                "FREQ" => {
+                    frequency = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(format!("FREQ={} is not supported", value)),
+                    })
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|interval| (1..=MAX_INTERVAL).contains(interval))
+                        .ok_or_else(|| {
+                            format!("INTERVAL must be a number from 1 to {}", MAX_INTERVAL)
+                        })?
+                }
+                "COUNT" => {
+                    count = Some(
+                        value
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|count| *count > 0)
+                            .ok_or("COUNT must be a positive number")?,
+                    )
+                }
+                "UNTIL" => until = Some(parse_until(value)?),
+                "BYDAY" => {
+                    by_day = value
+                        .split(',')
+                        .map(parse_weekday_num)
+                        .collect::<Result<_, _>>()?
+                }
+                "BYMONTHDAY" => {
+                    by_month_day = value
+                        .split(',')
+                        .map(|day| {
+                            day.parse::<i32>()
+                                .ok()
+                                .filter(|day| (1..=31).contains(&day.abs()))
+                                .ok_or_else(|| format!("Invalid BYMONTHDAY '{}'", day))
+                        })
+                        .collect::<Result<_, _>>()?
+                }
+                "BYMONTH" => {
+                    by_month = value
+                        .split(',')
+                        .map(|month| {
+                            month
+                                .parse::<u32>()
+                                .ok()
+                                .filter(|month| (1..=12).contains(month))
+                                .ok_or_else(|| format!("Invalid BYMONTH '{}'", month))
+                        })
+                        .collect::<Result<_, _>>()?
+                }
+                "WKST" if value == "MO" => {}
+                "WKST" => return Err("Only WKST=MO is supported".to_string()),
+                _ => return Err(format!("{} is not supported", key)),
+            }
+        }
+
+        let frequency = frequency.ok_or("The rule must start with FREQ=")?;
+        if count.is_some() && until.is_some() {
+            return Err("COUNT and UNTIL cannot be used together".to_string());
+        }
+        let has_ordinal = by_day.iter().any(|day| day.ordinal.is_some());
+        if has_ordinal && !matches!(frequency, Frequency::Monthly | Frequency::Yearly) {
+            return Err("Numbered BYDAY (e.g. 1MO) needs FREQ=MONTHLY or FREQ=YEARLY".to_string());
+        }
+        if frequency == Frequency::Weekly && !by_month_day.is_empty() {
+            return Err("BYMONTHDAY cannot be used with FREQ=WEEKLY".to_string());
+        }
+        if frequency == Frequency::Yearly && !by_day.is_empty() && by_month.is_empty() {
+            return Err("BYDAY needs BYMONTH with FREQ=YEARLY".to_string());
+        }
+
+        Ok(RecurrenceRule {
+            frequency,
+            interval,
+            count,
+            until,
+            by_day,
+            by_month_day,
+            by_month,
+        })
+    }
+}
+
+impl RecurrenceRule {
+    /// Returns the starts of the occurrences in `[from, to)`, oldest first.
+    ///
+    /// # Arguments
+    /// * `dtstart` - The start of the first occurrence of the event.
+    /// * `exdates` - The starts of the occurrences that do not take place.
+    /// * `from` - Occurrences starting before this point in time are left out.
+    /// * `to` - Occurrences starting at or after this point in time are left out.
+    pub fn occurrences_between(
+        &self,
+        dtstart: NaiveDateTime,
+        exdates: &[NaiveDateTime],
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> Vec<NaiveDateTime> {
+        let mut occurrences = Vec::new();
+        self.for_each_start(dtstart, |start| {
+            if start >= to {
+                return false;
+            }
+            if start >= from && !exdates.contains(&start) {
+                occurrences.push(start);
+            }
+            true
+        });
+        occurrences
+    }
+
+    /// Returns the start of the first occurrence at or after `from`, or `None` once the rule
+    /// has ended.
+    ///
+    /// # Arguments
+    /// * `dtstart` - The start of the first occurrence of the event.
+    /// * `exdates` - The starts of the occurrences that do not take place.
+    /// * `from` - Occurrences starting before this point in time are left out.
+    pub fn next_occurrence(
+        &self,
+        dtstart: NaiveDateTime,
+        exdates: &[NaiveDateTime],
+        from: NaiveDateTime,
+    ) -> Option<NaiveDateTime> {
+        let mut next = None;
+        self.for_each_start(dtstart, |start| {
+            if start >= from && !exdates.contains(&start) {
+                next = Some(start);
+                return false;
+            }
+            true
+        });
+        next
+    }
+
+    /// Walks the occurrences in order, `COUNT` included, until `visit` returns `false`.
+    ///
+    /// Per RFC 5545, occurrences removed by `EXDATE` still count towards `COUNT`, so
+    /// exceptions are left to the callers.
+    fn for_each_start(&self, dtstart: NaiveDateTime, mut visit: impl FnMut(NaiveDateTime) -> bool) {
+        let mut emitted = 0;
+        for period in 0..MAX_PERIODS {
+            // Past the last date chrono can represent, nothing is left to visit
+            let Some(dates) = self.period_dates(dtstart.date(), period) else {
+                return;
+            };
+            for date in dates {
+                let start = date.and_time(dtstart.time());
+                if start < dtstart {
+                    continue;
+                }
+                if self.until.is_some_and(|until| start > until)
+                    || self.count.is_some_and(|count| emitted >= count)
+                {
+                    return;
+                }
+                emitted += 1;
+                if !visit(start) {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Returns the dates the rule matches in the nth period after the one of `start`, or `None`
+    /// when the period is out of the range of dates.
+    fn period_dates(&self, start: NaiveDate, period: i64) -> Option<Vec<NaiveDate>> {
+        let step = period.checked_mul(self.interval as i64)?;
+        let mut dates = match self.frequency {
+            Frequency::Daily => vec![start.checked_add_signed(Duration::try_days(step)?)?],
+            Frequency::Weekly => {
+                let monday = start
+                    .checked_sub_signed(Duration::days(
+                        start.weekday().num_days_from_monday() as i64
+                    ))?
+                    .checked_add_signed(Duration::try_weeks(step)?)?;
+                let weekdays: Vec<Weekday> = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.iter().map(|day| day.weekday).collect()
+                };
+                weekdays
+                    .iter()
+                    .map(|weekday| {
+                        monday.checked_add_signed(Duration::days(
+                            weekday.num_days_from_monday() as i64
+                        ))
+                    })
+                    .collect::<Option<_>>()?
+            }
+            Frequency::Monthly => {
+                let months =
+                    (start.year() as i64 * 12 + start.month0() as i64).checked_add(step)?;
+                let year = i32::try_from(months.div_euclid(12)).ok()?;
+                NaiveDate::from_ymd_opt(year, 1, 1)?;
+                self.month_dates(year, months.rem_euclid(12) as u32 + 1, start.day())
+            }
+            Frequency::Yearly => {
+                let year = start.year().checked_add(i32::try_from(step).ok()?)?;
+                NaiveDate::from_ymd_opt(year, 1, 1)?;
+                let months: Vec<u32> = if !self.by_month.is_empty() {
+                    self.by_month.clone()
+                } else if !self.by_month_day.is_empty() {
+                    (1..=12).collect()
+                } else {
+                    vec![start.month()]
+                };
+                months
+                    .iter()
+                    .flat_map(|month| self.month_dates(year, *month, start.day()))
+                    .collect()
+            }
+        };
+
+        dates.retain(|date| self.matches_filters(*date));
+        dates.sort();
+        dates.dedup();
+        Some(dates)
+    }
+
+    /// Returns the dates of a month matching `BYMONTHDAY` and `BYDAY`, or the day of the
+    /// first occurrence when neither is given (skipped in months too short for it).
+    fn month_dates(&self, year: i32, month: u32, default_day: u32) -> Vec<NaiveDate> {
+        let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+            return Vec::new();
+        };
+        if self.by_month_day.is_empty() && self.by_day.is_empty() {
+            return NaiveDate::from_ymd_opt(year, month, default_day)
+                .into_iter()
+                .collect();
+        }
+
+        let length = days_in_month(year, month);
+        (0..length)
+            .map(|offset| first + Duration::days(offset as i64))
+            .filter(|date| {
+                (self.by_month_day.is_empty()
+                    || self
+                        .by_month_day
+                        .iter()
+                        .any(|day| month_day_matches(*day, date.day(), length)))
+                    && (self.by_day.is_empty()
+                        || self
+                            .by_day
+                            .iter()
+                            .any(|day| weekday_matches(day, *date, length)))
+            })
+            .collect()
+    }
+
+    /// Applies the parts that only narrow down the dates of a period.
+    fn matches_filters(&self, date: NaiveDate) -> bool {
+        if !self.by_month.is_empty() && !self.by_month.contains(&date.month()) {
+            return false;
+        }
+        if self.frequency != Frequency::Daily {
+            return true;
+        }
+
+        let length = days_in_month(date.year(), date.month());
+        (self.by_day.is_empty() || self.by_day.iter().any(|day| day.weekday == date.weekday()))
+            && (self.by_month_day.is_empty()
+                || self
+                    .by_month_day
+                    .iter()
+                    .any(|day| month_day_matches(*day, date.day(), length)))
+    }
+}
+
+fn parse_weekday_num(value: &str) -> Result<WeekdayNum, String> {
+    let invalid = || format!("Invalid BYDAY '{}'", value);
+    if value.len() < 2 || !value.is_ascii() {
+        return Err(invalid());
+    }
+
+    let (ordinal, weekday) = value.split_at(value.len() - 2);
+    let weekday = match weekday {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return Err(invalid()),
+    };
+    let ordinal = match ordinal {
+        "" => None,
+        ordinal => Some(
+            ordinal
+                .parse::<i32>()
+                .ok()
+                .filter(|ordinal| (1..=5).contains(&ordinal.abs()))
+                .ok_or_else(invalid)?,
+        ),
+    };
+
+    Ok(WeekdayNum { ordinal, weekday })
+}
+
+/// Parses an `UNTIL` value, a date (inclusive) or a date-time in UTC.
+fn parse_until(value: &str) -> Result<NaiveDateTime, String> {
+    let value = value.trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y%m%d")
+                .map(|date| date.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap()))
+        })
+        .map_err(|_| {
+            format!(
+                "Invalid UNTIL '{}', expected YYYYMMDD or YYYYMMDDTHHMMSSZ",
+                value
+            )
+        })
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|next| next.pred_opt())
+        .map(|last| last.day())
+        .unwrap_or(31)
+}
+
+fn month_day_matches(rule_day: i32, day: u32, length: u32) -> bool {
+    if rule_day > 0 {
+        rule_day as u32 == day
+    } else {
+        length as i32 + rule_day + 1 == day as i32
+    }
+}
+
+fn weekday_matches(rule_day: &WeekdayNum, date: NaiveDate, length: u32) -> bool {
+    if rule_day.weekday != date.weekday() {
+        return false;
+    }
+    match rule_day.ordinal {
+        None => true,
+        Some(ordinal) if ordinal > 0 => (date.day() as i32 - 1) / 7 + 1 == ordinal,
+        Some(ordinal) => (length as i32 - date.day() as i32) / 7 + 1 == -ordinal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn expand(rule: &str, dtstart: &str, from: &str, to: &str) -> Vec<String> {
+        rule.parse::<RecurrenceRule>()
+            .unwrap()
+            .occurrences_between(at(dtstart), &[], at(from), at(to))
+            .iter()
+            .map(|start| start.format("%Y-%m-%d %H:%M").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn weekday_standups() {
+        // 2025-03-14 is a Friday
+        assert_eq!(
+            expand(
+                "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR",
+                "2025-03-14 09:30",
+                "2025-03-14 00:00",
+                "2025-03-20 00:00",
+            ),
+            [
+                "2025-03-14 09:30",
+                "2025-03-17 09:30",
+                "2025-03-18 09:30",
+                "2025-03-19 09:30",
+            ]
+        );
+    }
+
+    #[test]
+    fn every_second_friday() {
+        assert_eq!(
+            expand(
+                "FREQ=WEEKLY;INTERVAL=2;BYDAY=FR",
+                "2025-03-07 16:00",
+                "2025-03-01 00:00",
+                "2025-04-30 00:00",
+            ),
+            [
+                "2025-03-07 16:00",
+                "2025-03-21 16:00",
+                "2025-04-04 16:00",
+                "2025-04-18 16:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn first_occurrence_follows_the_rule() {
+        // Created on a Sunday, the first standup is the next Monday
+        assert_eq!(
+            expand(
+                "FREQ=WEEKLY;BYDAY=MO",
+                "2025-03-16 09:30",
+                "2025-03-01 00:00",
+                "2025-03-25 00:00",
+            ),
+            ["2025-03-17 09:30", "2025-03-24 09:30"]
+        );
+    }
+
+    #[test]
+    fn daily_with_interval_and_count() {
+        assert_eq!(
+            expand(
+                "FREQ=DAILY;INTERVAL=3;COUNT=3",
+                "2025-02-26 08:00",
+                "2025-01-01 00:00",
+                "2026-01-01 00:00",
+            ),
+            ["2025-02-26 08:00", "2025-03-01 08:00", "2025-03-04 08:00"]
+        );
+    }
+
+    #[test]
+    fn daily_filtered_by_weekday() {
+        assert_eq!(
+            expand(
+                "FREQ=DAILY;BYDAY=SA,SU",
+                "2025-03-10 10:00",
+                "2025-03-10 00:00",
+                "2025-03-24 00:00",
+            ),
+            [
+                "2025-03-15 10:00",
+                "2025-03-16 10:00",
+                "2025-03-22 10:00",
+                "2025-03-23 10:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn until_is_inclusive() {
+        assert_eq!(
+            expand(
+                "FREQ=WEEKLY;UNTIL=20250324T093000Z",
+                "2025-03-10 09:30",
+                "2025-01-01 00:00",
+                "2026-01-01 00:00",
+            ),
+            ["2025-03-10 09:30", "2025-03-17 09:30", "2025-03-24 09:30"]
+        );
+        assert_eq!(
+            expand(
+                "FREQ=DAILY;UNTIL=20250312",
+                "2025-03-10 18:00",
+                "2025-01-01 00:00",
+                "2026-01-01 00:00",
+            ),
+            ["2025-03-10 18:00", "2025-03-11 18:00", "2025-03-12 18:00"]
+        );
+    }
+
+    #[test]
+    fn monthly_last_friday() {
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY;BYDAY=-1FR;COUNT=4",
+                "2025-01-31 15:00",
+                "2025-01-01 00:00",
+                "2026-01-01 00:00",
+            ),
+            [
+                "2025-01-31 15:00",
+                "2025-02-28 15:00",
+                "2025-03-28 15:00",
+                "2025-04-25 15:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_second_tuesday() {
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY;BYDAY=2TU",
+                "2025-03-01 12:00",
+                "2025-03-01 00:00",
+                "2025-06-01 00:00",
+            ),
+            ["2025-03-11 12:00", "2025-04-08 12:00", "2025-05-13 12:00"]
+        );
+    }
+
+    #[test]
+    fn monthly_skips_months_without_the_day() {
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY",
+                "2025-01-31 09:00",
+                "2025-01-01 00:00",
+                "2025-06-01 00:00",
+            ),
+            ["2025-01-31 09:00", "2025-03-31 09:00", "2025-05-31 09:00"]
+        );
+    }
+
+    #[test]
+    fn monthly_last_day() {
+        assert_eq!(
+            expand(
+                "FREQ=MONTHLY;BYMONTHDAY=-1",
+                "2024-01-15 17:00",
+                "2024-01-01 00:00",
+                "2024-04-01 00:00",
+            ),
+            ["2024-01-31 17:00", "2024-02-29 17:00", "2024-03-31 17:00"]
+        );
+    }
+
+    #[test]
+    fn yearly_on_leap_day() {
+        assert_eq!(
+            expand(
+                "FREQ=YEARLY",
+                "2024-02-29 10:00",
+                "2024-01-01 00:00",
+                "2033-01-01 00:00",
+            ),
+            ["2024-02-29 10:00", "2028-02-29 10:00", "2032-02-29 10:00"]
+        );
+    }
+
+    #[test]
+    fn yearly_by_month_and_weekday() {
+        // Fourth Thursday of November
+        assert_eq!(
+            expand(
+                "FREQ=YEARLY;BYMONTH=11;BYDAY=4TH",
+                "2025-01-01 18:00",
+                "2025-01-01 00:00",
+                "2028-01-01 00:00",
+            ),
+            ["2025-11-27 18:00", "2026-11-26 18:00", "2027-11-25 18:00"]
+        );
+    }
+
+    #[test]
+    fn exdates_are_skipped_but_count_towards_count() {
+        let rule: RecurrenceRule = "FREQ=WEEKLY;BYDAY=FR;COUNT=3".parse().unwrap();
+        let exdates = [at("2025-03-21 16:00")];
+
+        assert_eq!(
+            rule.occurrences_between(
+                at("2025-03-14 16:00"),
+                &exdates,
+                at("2025-01-01 00:00"),
+                at("2026-01-01 00:00"),
+            ),
+            [at("2025-03-14 16:00"), at("2025-03-28 16:00")]
+        );
+        assert_eq!(
+            rule.next_occurrence(at("2025-03-14 16:00"), &exdates, at("2025-03-15 00:00")),
+            Some(at("2025-03-28 16:00"))
+        );
+        assert_eq!(
+            rule.next_occurrence(at("2025-03-14 16:00"), &exdates, at("2025-03-29 00:00")),
+            None
+        );
+    }
+
+    #[test]
+    fn never_matching_rule_terminates() {
+        assert!(expand(
+            "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30",
+            "2025-01-01 09:00",
+            "2025-01-01 00:00",
+            "2100-01-01 00:00",
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn huge_interval_is_rejected_and_does_not_overflow() {
+        for rule in [
+            "FREQ=WEEKLY;INTERVAL=1300000;BYMONTH=2;BYDAY=MO",
+            "FREQ=DAILY;INTERVAL=100000000",
+        ] {
+            assert!(rule.parse::<RecurrenceRule>().is_err(), "{}", rule);
+        }
+
+        // Rules built past the parser stop at the end of the calendar instead of panicking
+        for frequency in [
+            Frequency::Daily,
+            Frequency::Weekly,
+            Frequency::Monthly,
+            Frequency::Yearly,
+        ] {
+            let rule = RecurrenceRule {
+                frequency,
+                interval: u32::MAX,
+                count: None,
+                until: None,
+                by_day: Vec::new(),
+                by_month_day: Vec::new(),
+                by_month: vec![2],
+            };
+            assert_eq!(
+                rule.next_occurrence(at("2025-03-03 09:00"), &[], at("2025-03-04 00:00")),
+                None
+            );
+        }
+
+        assert_eq!(
+            expand(
+                "FREQ=WEEKLY;INTERVAL=1000;BYMONTH=2;BYDAY=MO",
+                "2025-03-03 09:00",
+                "2025-01-01 00:00",
+                "2100-01-01 00:00",
+            ),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn parses_prefix_and_lowercase() {
+        let rule: RecurrenceRule = "RRULE:freq=weekly;interval=2;byday=fr;wkst=mo"
+            .parse()
+            .unwrap();
+        assert_eq!(rule.frequency, Frequency::Weekly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(
+            rule.by_day,
+            [WeekdayNum {
+                ordinal: None,
+                weekday: Weekday::Fri
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        for rule in [
+            "",
+            "BYDAY=MO",
+            "FREQ=HOURLY",
+            "FREQ=WEEKLY;INTERVAL=0",
+            "FREQ=WEEKLY;BYDAY=XX",
+            "FREQ=WEEKLY;BYDAY=1MO",
+            "FREQ=WEEKLY;BYMONTHDAY=1",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=YEARLY;BYDAY=MO",
+            "FREQ=DAILY;COUNT=2;UNTIL=20250101",
+            "FREQ=DAILY;BYSETPOS=1",
+            "FREQ=WEEKLY;WKST=SU",
+        ] {
+            assert!(rule.parse::<RecurrenceRule>().is_err(), "{}", rule);
+        }
+    }
+
+    #[test]
+    fn rejects_short_and_multibyte_weekdays() {
+        for rule in [
+            "FREQ=WEEKLY;BYDAY=M",
+            "FREQ=WEEKLY;BYDAY=€",
+            "FREQ=WEEKLY;BYDAY=1€",
+            "FREQ=MONTHLY;BYDAY=-1FÉ",
+        ] {
+            assert!(rule.parse::<RecurrenceRule>().is_err(), "{}", rule);
+        }
+    }
+}
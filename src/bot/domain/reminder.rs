@@ -0,0 +1,153 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use std::fmt;
+
+/// Represents what a reminder asks a member to do.
+///
+/// # Variants
+/// * `CheckIn` - Sent to the members without an attendance record for the day.
+/// * `CheckOut` - Sent to the members who are still checked in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderKind {
+    CheckIn,
+    CheckOut,
+}
+
+/// Represents when a reminder rule fires, every working day.
+///
+/// # Variants
+/// * `BeforeStart` - Some minutes before the schedule start of the team.
+/// * `At` - At a fixed time (UTC).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderTrigger {
+    BeforeStart(i32),
+    At(NaiveTime),
+}
+
+impl ReminderKind {
+    /// Parses a reminder kind as stored in the database or typed in a command.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().replace('-', "_").as_str() {
+            "check_in" => Some(ReminderKind::CheckIn),
+            "check_out" => Some(ReminderKind::CheckOut),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the reminder kind as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReminderKind::CheckIn => "check_in",
+            ReminderKind::CheckOut => "check_out",
+        }
+    }
+}
+
+impl ReminderTrigger {
+    /// Parses a trigger typed in a command, `before:<minutes>` or `at:<HH:MM>`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.split_once(':')? {
+            ("before", minutes) => minutes
+                .parse::<i32>()
+                .ok()
+                .filter(|minutes| *minutes > 0)
+                .map(ReminderTrigger::BeforeStart),
+            ("at", time) => NaiveTime::parse_from_str(time, "%H:%M")
+                .ok()
+                .map(ReminderTrigger::At),
+            _ => None,
+        }
+    }
+
+    /// Returns when the trigger fires on a day, or `None` if it depends on a schedule the team
+    /// does not have.
+    ///
+    /// # Arguments
+    /// * `date` - The day.
+    /// * `schedule_start` - The time (UTC) the working day of the team starts, if it has a
+    ///   schedule.
+    pub fn fires_at(
+        &self,
+        date: NaiveDate,
+        schedule_start: Option<NaiveTime>,
+    ) -> Option<NaiveDateTime> {
+        match self {
+            ReminderTrigger::BeforeStart(minutes) => {
+                Some(date.and_time(schedule_start?) - Duration::minutes((*minutes).into()))
+            }
+            ReminderTrigger::At(time) => Some(date.and_time(*time)),
+        }
+    }
+}
+
+impl fmt::Display for ReminderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for ReminderTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReminderTrigger::BeforeStart(minutes) => {
+                write!(f, "{} minutes before the schedule start", minutes)
+            }
+            ReminderTrigger::At(time) => write!(f, "at {} UTC", time.format("%H:%M")),
+        }
+    }
+}
+
+/// Checks whether a time falls within quiet hours, which may span midnight.
+///
+/// # Arguments
+/// * `time` - The time.
+/// * `start` - The start of the quiet hours, included.
+/// * `end` - The end of the quiet hours, excluded.
+pub fn in_quiet_hours(time: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        start <= time && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_triggers() {
+        assert_eq!(
+            ReminderTrigger::parse("before:15"),
+            Some(ReminderTrigger::BeforeStart(15))
+        );
+        assert_eq!(
+            ReminderTrigger::parse("at:18:00"),
+            Some(ReminderTrigger::At(time(18, 0)))
+        );
+        assert_eq!(ReminderTrigger::parse("before:0"), None);
+        assert_eq!(ReminderTrigger::parse("after:15"), None);
+    }
+
+    #[test]
+    fn fires_before_the_schedule_start() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        assert_eq!(
+            ReminderTrigger::BeforeStart(15).fires_at(date, Some(time(9, 0))),
+            Some(date.and_time(time(8, 45)))
+        );
+        assert_eq!(ReminderTrigger::BeforeStart(15).fires_at(date, None), None);
+    }
+
+    #[test]
+    fn quiet_hours_may_span_midnight() {
+        assert!(in_quiet_hours(time(23, 0), time(22, 0), time(7, 0)));
+        assert!(in_quiet_hours(time(6, 59), time(22, 0), time(7, 0)));
+        assert!(!in_quiet_hours(time(7, 0), time(22, 0), time(7, 0)));
+        assert!(in_quiet_hours(time(12, 30), time(12, 0), time(13, 0)));
+        assert!(!in_quiet_hours(time(18, 0), time(12, 0), time(13, 0)));
+    }
+}
@@ -4,10 +4,12 @@ use tabled::Tabled;
 /// Represents a team in a tabular format for display purposes.
 ///
 /// # Fields
+/// * `id` - The unique identifier for the team.
 /// * `name` - The name of the team.
 /// * `created_at` - The timestamp when the team was created, formatted as a string.
 #[derive(Debug, Tabled, Serialize, Deserialize)]
 pub struct TeamTable {
+    pub id: i32,
     pub name: String,
     pub created_at: String,
 }
@@ -16,24 +18,270 @@ pub struct TeamTable {
 ///
 /// # Fields
 /// * `username` - The username of the member.
+/// * `discord_id` - The Discord ID of the member.
+/// * `position` - The position of the member in the team.
 /// * `join_date` - The date when the member joined, formatted as a string.
+/// * `left_date` - The date when the member left, formatted as a string, or `-` if still active.
 #[derive(Debug, Tabled, Serialize, Deserialize)]
 pub struct MemberTable {
     pub username: String,
+    pub discord_id: String,
+    pub position: String,
     pub join_date: String,
+    pub left_date: String,
 }
 
 /// Represents a member's attendance record in a tabular format for display purposes.
 ///
 /// # Fields
+/// * `id` - The unique identifier of the attendance record.
 /// * `username` - The username of the member.
 /// * `check_in_time` - The timestamp when the member checked in, formatted as a string.
 /// * `check_out_time` - The timestamp when the member checked out, formatted as a string.
 /// * `status` - The status of the attendance (e.g., "Present", "Late").
 #[derive(Debug, Tabled, Serialize, Deserialize)]
 pub struct MemberAttendanceTable {
+    pub id: i32,
     pub username: String,
     pub check_in_time: String,
     pub check_out_time: String,
     pub status: String,
 }
+
+/// Represents an API key in a tabular format for display purposes.
+///
+/// # Fields
+/// * `id` - The unique identifier for the API key.
+/// * `name` - The name of the key.
+/// * `prefix` - The first characters of the key.
+/// * `scope` - The access level of the key.
+/// * `team` - The team the key is restricted to, or "All".
+/// * `last_used_at` - The timestamp when the key was last used, formatted as a string.
+/// * `status` - Whether the key is active or revoked.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct ApiKeyTable {
+    pub id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub scope: String,
+    pub team: String,
+    pub last_used_at: String,
+    pub status: String,
+}
+
+/// Represents a team role assignment in a tabular format for display purposes.
+///
+/// # Fields
+/// * `discord_id` - The Discord ID of the user.
+/// * `role` - The role of the user in the team.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct TeamRoleTable {
+    pub discord_id: String,
+    pub role: String,
+}
+
+/// Represents an event in a tabular format for display purposes.
+///
+/// # Fields
+/// * `id` - The unique identifier for the event.
+/// * `title` - The title of the event.
+/// * `starts_at` - The timestamp when the event starts, formatted as a string.
+/// * `ends_at` - The timestamp when the event ends, formatted as a string.
+/// * `repeats` - The recurrence rule of the event, or `-` if it does not repeat.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct EventTable {
+    pub id: i32,
+    pub title: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub repeats: String,
+}
+
+/// Represents the attendance of a member at an event in a tabular format for display purposes.
+///
+/// # Fields
+/// * `username` - The username of the member.
+/// * `status` - The status of the check-in, or "No-show" if the member did not check in.
+/// * `check_in_time` - The timestamp when the member checked in, formatted as a string.
+/// * `check_out_time` - The timestamp when the member checked out, formatted as a string.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct EventAttendanceTable {
+    pub username: String,
+    pub status: String,
+    pub check_in_time: String,
+    pub check_out_time: String,
+}
+
+/// Represents a leave request in a tabular format for display purposes.
+///
+/// # Fields
+/// * `id` - The unique identifier of the request.
+/// * `username` - The username of the member taking leave.
+/// * `leave_type` - The kind of leave.
+/// * `from` - The first day of leave.
+/// * `to` - The last day of leave.
+/// * `days` - The number of working days the leave covers.
+/// * `status` - The state of the request.
+/// * `reason` - Why the member takes leave.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct LeaveRequestTable {
+    pub id: i32,
+    pub username: String,
+    pub leave_type: String,
+    pub from: String,
+    pub to: String,
+    pub days: i64,
+    pub status: String,
+    pub reason: String,
+}
+
+/// Represents an attendance correction request in a tabular format for display purposes.
+///
+/// # Fields
+/// * `id` - The unique identifier of the request.
+/// * `username` - The username of the member.
+/// * `record` - The ID of the attendance record to change, or "new".
+/// * `date` - The day of the attendance record.
+/// * `change` - The requested values.
+/// * `status` - The state of the request.
+/// * `reason` - Why the record is wrong.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct AttendanceCorrectionTable {
+    pub id: i32,
+    pub username: String,
+    pub record: String,
+    pub date: String,
+    pub change: String,
+    pub status: String,
+    pub reason: String,
+}
+
+/// Represents a change made to an attendance record in a tabular format for display purposes.
+///
+/// # Fields
+/// * `at` - The timestamp when the change was made.
+/// * `action` - Either "insert" or "update".
+/// * `actor` - The Discord ID of the user who made or approved the change.
+/// * `before` - The values before the change.
+/// * `after` - The values after the change.
+/// * `reason` - Why the record was changed.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct AttendanceAuditTable {
+    pub at: String,
+    pub action: String,
+    pub actor: String,
+    pub before: String,
+    pub after: String,
+    pub reason: String,
+}
+
+/// Represents an administrative action in a tabular format for display purposes.
+///
+/// # Fields
+/// * `at` - The timestamp when the change was made.
+/// * `actor` - The Discord ID of the user who made the change, and where from.
+/// * `action` - What was done, e.g. "team.rename".
+/// * `target` - What the action applies to.
+/// * `details` - The changed values.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct AuditEventTable {
+    pub at: String,
+    pub actor: String,
+    pub action: String,
+    pub target: String,
+    pub details: String,
+}
+
+/// Represents the leave a member took in a year in a tabular format for display purposes.
+///
+/// # Fields
+/// * `username` - The username of the member.
+/// * `leave_type` - The kind of leave.
+/// * `allowance` - The number of working days the member may take, or `-` if not limited.
+/// * `used` - The number of working days of approved leave.
+/// * `remaining` - The number of working days left, or `-` if not limited.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct LeaveBalanceTable {
+    pub username: String,
+    pub leave_type: String,
+    pub allowance: String,
+    pub used: i64,
+    pub remaining: String,
+}
+
+/// Represents a day off of a team in a tabular format for display purposes.
+///
+/// # Fields
+/// * `date` - The day off.
+/// * `weekday` - The day of the week.
+/// * `name` - The name of the holiday.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct HolidayTable {
+    pub date: String,
+    pub weekday: String,
+    pub name: String,
+}
+
+/// Represents a reminder rule of a team in a tabular format for display purposes.
+///
+/// # Fields
+/// * `id` - The unique identifier of the rule.
+/// * `kind` - Either "check_in" or "check_out".
+/// * `when` - When the rule fires, e.g. "at 18:00 UTC".
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct ReminderRuleTable {
+    pub id: i32,
+    pub kind: String,
+    pub when: String,
+}
+
+/// Represents a webhook of a team in a tabular format for display purposes.
+///
+/// # Fields
+/// * `id` - The unique identifier of the webhook.
+/// * `url` - Where the events are posted.
+/// * `events` - The events the webhook subscribes to.
+/// * `pending` - The number of deliveries waiting to be sent.
+/// * `failed` - The number of deliveries that ran out of attempts.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct WebhookTable {
+    pub id: i32,
+    pub url: String,
+    pub events: String,
+    pub pending: i64,
+    pub failed: i64,
+}
+
+/// Represents a webhook delivery in a tabular format for display purposes.
+///
+/// # Fields
+/// * `id` - The unique identifier of the delivery.
+/// * `event` - The event that was emitted.
+/// * `status` - Either "pending", "delivered" or "failed".
+/// * `attempts` - How many times the delivery was attempted.
+/// * `response` - The HTTP status of the last response, or why the last attempt failed.
+/// * `created_at` - When the event was emitted, formatted as a string.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct WebhookDeliveryTable {
+    pub id: i32,
+    pub event: String,
+    pub status: String,
+    pub attempts: i32,
+    pub response: String,
+    pub created_at: String,
+}
+
+/// Represents a member who is in right now in a tabular format for display purposes.
+///
+/// # Fields
+/// * `member` - The name of the member.
+/// * `status` - The status of the open attendance record, or `-`.
+/// * `since` - When the member checked in (UTC), with the day if it was not today.
+/// * `duration` - How long the member has been in.
+#[derive(Debug, Tabled, Serialize, Deserialize)]
+pub struct PresenceTable {
+    pub member: String,
+    pub status: String,
+    pub since: String,
+    pub duration: String,
+}
@@ -0,0 +1,245 @@
+use chrono::Duration;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use std::fmt;
+
+/// Represents an attendance event a webhook can subscribe to.
+///
+/// # Variants
+/// * `CheckIn` - A member checked in, by command, code, voice channel or roll-call.
+/// * `CheckOut` - A member checked out.
+/// * `MemberAdded` - A member was added to the team, or came back to it.
+/// * `AbsenceDetected` - The daily check recorded members who did not show up as absent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    CheckIn,
+    CheckOut,
+    MemberAdded,
+    AbsenceDetected,
+}
+
+/// Represents the state of a webhook delivery.
+///
+/// # Variants
+/// * `Pending` - Waiting for its next attempt.
+/// * `Delivered` - The receiver answered with a 2xx status.
+/// * `Failed` - Every attempt failed, it is no longer retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+/// Every event a webhook can subscribe to, in the order they are shown.
+pub const WEBHOOK_EVENTS: [WebhookEvent; 4] = [
+    WebhookEvent::CheckIn,
+    WebhookEvent::CheckOut,
+    WebhookEvent::MemberAdded,
+    WebhookEvent::AbsenceDetected,
+];
+
+impl WebhookEvent {
+    /// Parses an event type as stored in the database or typed in a command.
+    pub fn parse(value: &str) -> Option<Self> {
+        WEBHOOK_EVENTS
+            .into_iter()
+            .find(|event| event.as_str() == value.trim().to_lowercase().replace('-', "_"))
+    }
+
+    /// Returns the name of the event type as stored in the database and sent to receivers.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::CheckIn => "check_in",
+            WebhookEvent::CheckOut => "check_out",
+            WebhookEvent::MemberAdded => "member_added",
+            WebhookEvent::AbsenceDetected => "absence_detected",
+        }
+    }
+
+    /// Parses a comma separated list of event types, `all` subscribes to every event.
+    ///
+    /// # Errors
+    /// Returns an error if the list is empty or an event type is unknown.
+    pub fn parse_list(value: &str) -> Result<Vec<Self>, String> {
+        if value.trim().eq_ignore_ascii_case("all") {
+            return Ok(WEBHOOK_EVENTS.to_vec());
+        }
+
+        let mut events = Vec::new();
+        for name in value.split(',').filter(|name| !name.trim().is_empty()) {
+            let event = WebhookEvent::parse(name).ok_or_else(|| {
+                format!(
+                    "Unknown event '{}', use {} or all",
+                    name.trim(),
+                    join(&WEBHOOK_EVENTS)
+                )
+            })?;
+            if !events.contains(&event) {
+                events.push(event);
+            }
+        }
+
+        if events.is_empty() {
+            return Err("At least one event type is required".to_string());
+        }
+        Ok(events)
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl DeliveryStatus {
+    /// Returns the name of the status as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Generates the random secret a new webhook signs its deliveries with, hex encoded.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Joins event types as stored in the database, e.g. `check_in,check_out`.
+pub fn join(events: &[WebhookEvent]) -> String {
+    events
+        .iter()
+        .map(WebhookEvent::as_str)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Checks that a webhook URL can be delivered to.
+///
+/// # Errors
+/// Returns an error if the URL is too long, invalid or not `http`/`https`.
+pub fn validate_url(url: &str) -> Result<(), String> {
+    if url.len() > 500 {
+        return Err("The URL cannot be longer than 500 characters".to_string());
+    }
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => Ok(()),
+        _ => Err(format!("'{}' is not an http(s) URL", url)),
+    }
+}
+
+/// Signs the body of a delivery, the receiver recomputes it to check where it came from.
+///
+/// # Arguments
+/// * `secret` - The secret of the webhook.
+/// * `timestamp` - The time of the attempt in seconds since the Unix epoch, sent along so the
+///   receiver can reject old deliveries.
+/// * `body` - The JSON body of the delivery.
+///
+/// # Returns
+/// Returns `sha256=` followed by the hex encoded HMAC-SHA256 of `<timestamp>.<body>`.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+/// Returns how long to wait before the next attempt of a delivery, doubling after each failure.
+///
+/// # Arguments
+/// * `attempts` - How many attempts failed so far, at least 1.
+/// * `base_seconds` - The wait after the first failure.
+/// * `max_seconds` - The longest wait.
+pub fn retry_delay(attempts: i32, base_seconds: i64, max_seconds: i64) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 30) as u32;
+    Duration::seconds(base_seconds.saturating_mul(1 << exponent).min(max_seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_event_lists() {
+        assert_eq!(
+            WebhookEvent::parse_list("check-in, Check_Out,check_in").unwrap(),
+            vec![WebhookEvent::CheckIn, WebhookEvent::CheckOut]
+        );
+        assert_eq!(
+            WebhookEvent::parse_list("all").unwrap(),
+            WEBHOOK_EVENTS.to_vec()
+        );
+        assert_eq!(
+            join(&WEBHOOK_EVENTS),
+            "check_in,check_out,member_added,absence_detected"
+        );
+        assert!(WebhookEvent::parse_list("check_in,lunch").is_err());
+        assert!(WebhookEvent::parse_list(",").is_err());
+    }
+
+    #[test]
+    fn signs_the_timestamp_and_body() {
+        // echo -n '1700000000.{"event":"check_in"}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            sign("secret", 1_700_000_000, r#"{"event":"check_in"}"#),
+            "sha256=d22f4e942448f88c8eebe4bc29db1d32a674d49d48875adfe49f85b4e709828b"
+        );
+        assert_ne!(
+            sign("secret", 1_700_000_001, r#"{"event":"check_in"}"#),
+            sign("secret", 1_700_000_000, r#"{"event":"check_in"}"#)
+        );
+        assert_ne!(
+            sign("other", 1_700_000_000, r#"{"event":"check_in"}"#),
+            sign("secret", 1_700_000_000, r#"{"event":"check_in"}"#)
+        );
+    }
+
+    #[test]
+    fn gives_up_about_an_hour_after_the_first_attempt() {
+        use crate::config::constant::{
+            WEBHOOK_MAX_ATTEMPTS, WEBHOOK_RETRY_BASE_SECONDS, WEBHOOK_RETRY_MAX_SECONDS,
+        };
+
+        // The last attempt is not followed by a retry
+        let total: Duration = (1..WEBHOOK_MAX_ATTEMPTS)
+            .map(|attempts| {
+                retry_delay(
+                    attempts,
+                    WEBHOOK_RETRY_BASE_SECONDS,
+                    WEBHOOK_RETRY_MAX_SECONDS,
+                )
+            })
+            .sum();
+        assert_eq!(
+            total,
+            Duration::seconds(30 + 60 + 120 + 240 + 480 + 960 + 1920)
+        );
+    }
+
+    #[test]
+    fn doubles_the_retry_delay_up_to_the_maximum() {
+        assert_eq!(retry_delay(1, 30, 3600), Duration::seconds(30));
+        assert_eq!(retry_delay(3, 30, 3600), Duration::seconds(120));
+        assert_eq!(retry_delay(10, 30, 3600), Duration::seconds(3600));
+        assert_eq!(retry_delay(i32::MAX, 30, 3600), Duration::seconds(3600));
+    }
+
+    #[test]
+    fn accepts_only_http_urls() {
+        assert!(validate_url("http://127.0.0.1:9000/hook").is_ok());
+        assert!(validate_url("https://example.com/attendance").is_ok());
+        assert!(validate_url("ftp://example.com").is_err());
+        assert!(validate_url("not a url").is_err());
+    }
+}
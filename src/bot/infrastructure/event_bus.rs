@@ -0,0 +1,33 @@
+use std::sync::OnceLock;
+
+use tokio::sync::broadcast;
+
+use crate::bot::domain::live::LiveUpdate;
+use crate::config::constant::LIVE_FEED_CAPACITY;
+
+/// The attendance changes of every team, shared by the bot and the API in this process.
+static LIVE_UPDATES: OnceLock<broadcast::Sender<LiveUpdate>> = OnceLock::new();
+
+fn sender() -> &'static broadcast::Sender<LiveUpdate> {
+    LIVE_UPDATES.get_or_init(|| broadcast::channel(LIVE_FEED_CAPACITY).0)
+}
+
+/// Publishes an attendance change to every open live feed.
+///
+/// # Arguments
+/// * `update` - The attendance change.
+///
+/// # Behavior
+/// - Does nothing when no live feed is open.
+pub fn publish(update: LiveUpdate) {
+    let _ = sender().send(update);
+}
+
+/// Subscribes to the attendance changes of every team published from now on.
+///
+/// # Returns
+/// Returns a receiver that skips ahead, reporting how many changes it missed, when it falls
+/// more than `LIVE_FEED_CAPACITY` changes behind.
+pub fn subscribe() -> broadcast::Receiver<LiveUpdate> {
+    sender().subscribe()
+}
@@ -1 +1,3 @@
 pub mod persistence;
+pub mod webhook_client;
+pub mod event_bus;
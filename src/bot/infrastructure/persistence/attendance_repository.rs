@@ -1,16 +1,17 @@
 use crate::{
     bot::domain::{
-        model::{CheckInAttendance, Member, MemberAttendance},
+        model::{CheckInAttendance, Member, MemberAttendance, SystemAbsence},
         table::MemberAttendanceTable,
     },
     schema::{
         members::id,
-        teams::dsl::{id as team_ids, teams},
+        teams::dsl::{archived_at, id as team_ids, teams},
     },
 };
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use diesel::dsl::exists;
 use diesel::prelude::*;
+use std::collections::HashSet;
 
 /// Records a check-in for a member in a specific team.
 ///
@@ -19,17 +20,19 @@ use diesel::prelude::*;
 /// * `mem_id` - The ID of the member checking in.
 /// * `team_id` - The ID of the team the member is checking into.
 /// * `status` - The status of the check-in (e.g., "Present", "Late").
+/// * `event` - The ID of the event the member checks in to, if any.
 ///
 /// # Errors
 /// Returns an error if:
 /// - The member does not exist.
-/// - The team does not exist.
+/// - The team does not exist or is archived.
 /// - The check-in record cannot be inserted into the database.
 pub fn check_in(
     conn: &mut PgConnection,
     mem_id: i32,
     team_id: i32,
     status: String,
+    event: Option<i32>,
 ) -> Result<(), String> {
     use crate::schema::{member_attendance::dsl::member_attendance, members::dsl::members};
 
@@ -44,15 +47,15 @@ pub fn check_in(
         return Err(format!("Member with ID {} does not exist.", mem_id));
     }
 
-    // Check if the team_id exists in the members table
+    // Check if the team_id exists in the teams table and still accepts check-ins
     let team_exists: bool = diesel::select(exists(
-            teams.filter(team_ids.eq(team_id)), // id should be the primary key of members
+            teams.filter(team_ids.eq(team_id)).filter(archived_at.is_null()),
     ))
         .get_result(conn)
         .map_err(|e| format!("Failed to check team existence: {}", e))?;
 
     if !team_exists {
-        return Err(format!("Member with ID {} does not exist.", team_id));
+        return Err(format!("Team with ID {} does not exist or is archived.", team_id));
     }
 
     // Insert into member_attendance
@@ -62,6 +65,7 @@ pub fn check_in(
         check_in_time: Utc::now().naive_utc(),
         date: Utc::now().date_naive(),
         status,
+        event_id: event,
     };
 
     diesel::insert_into(member_attendance)
@@ -72,6 +76,37 @@ pub fn check_in(
     Ok(())
 }
 
+/// Deletes the absence recorded by the daily check for a member, once they showed up anyway.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `user_id` - The ID of the member.
+/// * `team` - The ID of the team.
+/// * `day` - The day of the absence.
+///
+/// # Errors
+/// Returns an error if the attendance record cannot be deleted from the database.
+pub fn delete_system_absence(
+    conn: &mut PgConnection,
+    user_id: i32,
+    team: i32,
+    day: NaiveDate,
+) -> Result<(), String> {
+    use crate::schema::member_attendance::dsl::*;
+
+    diesel::delete(
+        member_attendance
+            .filter(member_id.eq(user_id))
+            .filter(team_id.eq(team))
+            .filter(date.eq(day))
+            .filter(system_generated.eq(true)),
+    )
+    .execute(conn)
+    .map_err(|e| format!("Failed to replace the recorded absence: {}", e))?;
+
+    Ok(())
+}
+
 /// Records a check-out for a member.
 ///
 /// # Arguments
@@ -90,6 +125,7 @@ pub fn check_out(conn: &mut PgConnection, user_id: i32) -> Result<(), String> {
     let check_out_member = diesel::update(
         member_attendance
         .filter(member_id.eq(user_id))
+        .filter(check_in_time.is_not_null())
         .filter(check_out_time.is_null()),
     )
         .set(check_out_time.eq(now))
@@ -103,11 +139,79 @@ pub fn check_out(conn: &mut PgConnection, user_id: i32) -> Result<(), String> {
     Ok(())
 }
 
+/// Checks whether a member already checked in to an event.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `user_id` - The ID of the member.
+/// * `event` - The ID of the event.
+///
+/// # Errors
+/// Returns an error if the attendance records cannot be fetched from the database.
+pub fn has_event_check_in(conn: &mut PgConnection, user_id: i32, event: i32) -> Result<bool, String> {
+    use crate::schema::member_attendance::dsl::*;
+
+    diesel::select(exists(
+        member_attendance
+            .filter(member_id.eq(user_id))
+            .filter(event_id.eq(event)),
+    ))
+    .get_result(conn)
+    .map_err(|e| format!("Failed to check event check-in: {}", e))
+}
+
+/// Finds the check-in of a member that has not been checked out yet.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `user_id` - The ID of the member.
+///
+/// # Returns
+/// Returns the open attendance record, or `None` if the member is not checked in.
+///
+/// # Errors
+/// Returns an error if the attendance record cannot be fetched from the database.
+pub fn find_open_check_in(
+    conn: &mut PgConnection,
+    user_id: i32,
+) -> Result<Option<MemberAttendance>, String> {
+    use crate::schema::member_attendance::dsl::*;
+
+    member_attendance
+        .filter(member_id.eq(user_id))
+        .filter(check_in_time.is_not_null())
+        .filter(check_out_time.is_null())
+        .order(check_in_time.desc())
+        .first::<MemberAttendance>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch check-in: {}", e))
+}
+
+/// Deletes an attendance record.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `attendance_id` - The ID of the attendance record.
+///
+/// # Errors
+/// Returns an error if the attendance record cannot be deleted from the database.
+pub fn delete_attendance(conn: &mut PgConnection, attendance_id: i32) -> Result<(), String> {
+    use crate::schema::member_attendance::dsl::*;
+
+    diesel::delete(member_attendance.filter(id.eq(attendance_id)))
+        .execute(conn)
+        .map_err(|e| format!("Failed to delete attendance record: {}", e))?;
+
+    Ok(())
+}
+
 /// Retrieves attendance records for all members of a specific team.
 ///
 /// # Arguments
 /// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild` - The ID of the Discord server the team belongs to.
 /// * `team_name` - The name of the team whose attendance records are being fetched.
+/// * `holidays` - The days off of the team, absences on these days are shown as "Holiday".
 ///
 /// # Returns
 /// Returns a `Vec<MemberAttendanceTable>` containing the attendance records for the team.
@@ -118,12 +222,15 @@ pub fn check_out(conn: &mut PgConnection, user_id: i32) -> Result<(), String> {
 /// - The attendance records cannot be fetched from the database.
 pub fn get_member_attendance_by_team(
     conn: &mut PgConnection,
+    guild: &str,
     team_name: &str,
+    holidays: &HashSet<NaiveDate>,
 ) -> Result<Vec<MemberAttendanceTable>, String> {
     use crate::schema::{member_attendance, members, teams};
 
     // Find the team ID by name
     let team_id: i32 = teams::table
+        .filter(teams::guild_id.eq(guild))
         .filter(teams::name.eq(team_name))
         .select(teams::id)
         .first::<i32>(conn)
@@ -150,6 +257,7 @@ pub fn get_member_attendance_by_team(
     let attendance_tables = attendance_data
         .into_iter()
         .map(|(attendance, member)| MemberAttendanceTable {
+            id: attendance.id,
             username: member.username,
             check_in_time: attendance
                 .check_in_time
@@ -159,9 +267,148 @@ pub fn get_member_attendance_by_team(
                     .check_out_time
                     .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
                     .unwrap_or_else(|| "N/A".to_string()),
-                    status: attendance.status.unwrap_or_else(|| "N/A".to_string()),
+                    status: match attendance.status {
+                        Some(status) if status == "Absent" && holidays.contains(&attendance.date) => {
+                            "Holiday".to_string()
+                        }
+                        status => status.unwrap_or_else(|| "N/A".to_string()),
+                    },
         })
     .collect();
 
     Ok(attendance_tables)
 }
+
+/// Marks the absences of a member within a range of days as excused.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `user_id` - The ID of the member.
+/// * `from` - The first day of the range.
+/// * `to` - The last day of the range.
+///
+/// # Errors
+/// Returns an error if the attendance records cannot be updated in the database.
+pub fn excuse_absences(
+    conn: &mut PgConnection,
+    user_id: i32,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<(), String> {
+    use crate::schema::member_attendance::dsl::*;
+
+    diesel::update(
+        member_attendance
+            .filter(member_id.eq(user_id))
+            .filter(status.eq("Absent"))
+            .filter(date.between(from, to)),
+    )
+    .set(status.eq("Excused"))
+    .execute(conn)
+    .map_err(|e| format!("Failed to excuse absences: {}", e))?;
+
+    Ok(())
+}
+
+/// Retrieves the members of a team who have an attendance record on a day.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `day` - The day.
+///
+/// # Returns
+/// Returns the IDs of the members, whatever the status of their records.
+///
+/// # Errors
+/// Returns an error if the attendance records cannot be fetched from the database.
+pub fn get_recorded_members(
+    conn: &mut PgConnection,
+    team: i32,
+    day: NaiveDate,
+) -> Result<HashSet<i32>, String> {
+    use crate::schema::member_attendance::dsl::*;
+
+    Ok(member_attendance
+        .filter(team_id.eq(team))
+        .filter(date.eq(day))
+        .select(member_id)
+        .distinct()
+        .load::<Option<i32>>(conn)
+        .map_err(|e| format!("Failed to fetch attendance: {}", e))?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+/// Records the absences found by the daily check.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `absences` - The absences to insert.
+///
+/// # Errors
+/// Returns an error if the attendance records cannot be inserted into the database.
+pub fn record_absences(conn: &mut PgConnection, absences: &[SystemAbsence]) -> Result<(), String> {
+    use crate::schema::member_attendance::dsl::*;
+
+    diesel::insert_into(member_attendance)
+        .values(absences)
+        .execute(conn)
+        .map_err(|e| format!("Failed to record absences: {}", e))?;
+
+    Ok(())
+}
+
+/// Retrieves the members of a team who are checked in and have not checked out yet.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the attendance records cannot be fetched from the database.
+pub fn get_checked_in_members(conn: &mut PgConnection, team: i32) -> Result<HashSet<i32>, String> {
+    use crate::schema::member_attendance::dsl::*;
+
+    Ok(member_attendance
+        .filter(team_id.eq(team))
+        .filter(check_in_time.is_not_null())
+        .filter(check_out_time.is_null())
+        .select(member_id)
+        .distinct()
+        .load::<Option<i32>>(conn)
+        .map_err(|e| format!("Failed to fetch check-ins: {}", e))?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+/// Retrieves the open check-ins of the current members of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+///
+/// # Returns
+/// Returns each attendance record that has not been checked out yet together with its member,
+/// oldest check-in first.
+///
+/// # Errors
+/// Returns an error if the attendance records cannot be fetched from the database.
+pub fn get_open_check_ins(
+    conn: &mut PgConnection,
+    team: i32,
+) -> Result<Vec<(MemberAttendance, Member)>, String> {
+    use crate::schema::{member_attendance, members};
+
+    member_attendance::table
+        .inner_join(members::table)
+        .filter(member_attendance::team_id.eq(team))
+        .filter(member_attendance::check_in_time.is_not_null())
+        .filter(member_attendance::check_out_time.is_null())
+        .filter(members::left_date.is_null())
+        .order((member_attendance::check_in_time.asc(), member_attendance::id.asc()))
+        .load::<(MemberAttendance, Member)>(conn)
+        .map_err(|e| format!("Failed to fetch check-ins: {}", e))
+}
@@ -0,0 +1,128 @@
+use crate::bot::domain::audit::AuditFilter;
+use crate::bot::domain::model::{AuditEvent, NewAuditEvent};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+/// Appends an event to the audit log.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `event` - The event to insert.
+///
+/// # Errors
+/// Returns an error if the event cannot be inserted into the database.
+pub fn insert_event(conn: &mut PgConnection, event: &NewAuditEvent) -> Result<(), String> {
+    use crate::schema::audit_events::dsl::*;
+
+    diesel::insert_into(audit_events)
+        .values(event)
+        .execute(conn)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to record audit event: {}", e))
+}
+
+/// Retrieves the audit events of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team, which may have been deleted since.
+/// * `filter` - The filters of the query.
+///
+/// # Returns
+/// Returns the matching events, newest first.
+///
+/// # Errors
+/// Returns an error if the events cannot be fetched from the database.
+pub fn get_team_events(
+    conn: &mut PgConnection,
+    team: i32,
+    filter: &AuditFilter,
+) -> Result<Vec<AuditEvent>, String> {
+    use crate::schema::audit_events::dsl::*;
+
+    let query = filtered(filter).filter(team_id.eq(team));
+
+    query
+        .order(id.desc())
+        .limit(filter.limit)
+        .load::<AuditEvent>(conn)
+        .map_err(|e| format!("Failed to fetch audit events: {}", e))
+}
+
+/// Retrieves the audit events visible to a user: those of the teams they own and every action
+/// they made themselves.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `user_discord_id` - The Discord ID of the user.
+/// * `filter` - The filters of the query.
+///
+/// # Returns
+/// Returns the matching events, newest first.
+///
+/// # Errors
+/// Returns an error if the events cannot be fetched from the database.
+pub fn get_owner_events(
+    conn: &mut PgConnection,
+    user_discord_id: &str,
+    filter: &AuditFilter,
+) -> Result<Vec<AuditEvent>, String> {
+    use crate::schema::audit_events::dsl::*;
+    use crate::schema::team_roles;
+
+    let owned_teams = team_roles::table
+        .filter(team_roles::discord_id.eq(user_discord_id))
+        .filter(team_roles::role.eq("owner"))
+        .select(team_roles::team_id.nullable());
+    let query =
+        filtered(filter).filter(team_id.eq_any(owned_teams).or(actor_id.eq(user_discord_id)));
+
+    query
+        .order(id.desc())
+        .limit(filter.limit)
+        .load::<AuditEvent>(conn)
+        .map_err(|e| format!("Failed to fetch audit events: {}", e))
+}
+
+/// Removes the audit events made before a moment.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `before` - Events made before this timestamp are removed.
+///
+/// # Returns
+/// Returns the number of removed events.
+///
+/// # Errors
+/// Returns an error if the events cannot be removed from the database.
+pub fn delete_events_before(
+    conn: &mut PgConnection,
+    before: NaiveDateTime,
+) -> Result<usize, String> {
+    use crate::schema::audit_events::dsl::*;
+
+    diesel::delete(audit_events.filter(created_at.lt(before)))
+        .execute(conn)
+        .map_err(|e| format!("Failed to remove old audit events: {}", e))
+}
+
+/// Builds the part of an audit query shared by every scope.
+fn filtered(filter: &AuditFilter) -> crate::schema::audit_events::BoxedQuery<'_, diesel::pg::Pg> {
+    use crate::schema::audit_events::dsl::*;
+
+    let mut query = audit_events.into_boxed();
+    if let Some(actor) = &filter.actor_id {
+        query = query.filter(actor_id.eq(actor));
+    }
+    if let Some(prefix) = &filter.action {
+        // `member` matches every member action, `member.add` only itself
+        query = query.filter(action.eq(prefix).or(action.like(format!("{}.%", prefix))));
+    }
+    if let Some(since) = filter.since_time() {
+        query = query.filter(created_at.ge(since));
+    }
+    if let Some(until) = filter.until_time() {
+        query = query.filter(created_at.lt(until));
+    }
+    query
+}
@@ -0,0 +1,287 @@
+use crate::bot::domain::correction::AttendanceValues;
+use crate::bot::domain::model::{
+    AttendanceAudit, AttendanceCorrection, ManualAttendance, Member, MemberAttendance,
+    NewAttendanceAudit, NewAttendanceCorrection,
+};
+use chrono::{NaiveDate, Utc};
+use diesel::prelude::*;
+
+/// Finds an attendance record by its ID together with its member.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `attendance` - The ID of the attendance record.
+///
+/// # Returns
+/// Returns the attendance record and its member, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the attendance record cannot be fetched from the database.
+pub fn find_attendance(
+    conn: &mut PgConnection,
+    attendance: i32,
+) -> Result<Option<(MemberAttendance, Member)>, String> {
+    use crate::schema::{member_attendance, members};
+
+    member_attendance::table
+        .inner_join(members::table)
+        .filter(member_attendance::id.eq(attendance))
+        .first::<(MemberAttendance, Member)>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch attendance record: {}", e))
+}
+
+/// Changes the values of an attendance record and records the change in the audit trail.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `attendance` - The ID of the attendance record.
+/// * `values` - The new values of the record.
+/// * `audit` - The audit entry of the change.
+///
+/// # Behavior
+/// - A record changed by hand is no longer treated as a system-generated absence, so a later
+///   check-in does not replace it.
+///
+/// # Returns
+/// Returns the updated attendance record.
+///
+/// # Errors
+/// Returns an error if the attendance record or the audit entry cannot be written to the
+/// database.
+pub fn update_attendance(
+    conn: &mut PgConnection,
+    attendance: i32,
+    values: &AttendanceValues,
+    audit: &NewAttendanceAudit,
+) -> Result<MemberAttendance, String> {
+    use crate::schema::attendance_audit;
+    use crate::schema::member_attendance::dsl::*;
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        let record = diesel::update(member_attendance.filter(id.eq(attendance)))
+            .set((
+                check_in_time.eq(values.check_in_time),
+                check_out_time.eq(values.check_out_time),
+                status.eq(&values.status),
+                system_generated.eq(false),
+            ))
+            .get_result::<MemberAttendance>(conn)?;
+        diesel::insert_into(attendance_audit::table)
+            .values(audit)
+            .execute(conn)?;
+        Ok(record)
+    })
+    .map_err(|e| format!("Failed to update attendance record: {}", e))
+}
+
+/// Inserts an attendance record added by hand and records it in the audit trail.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `record` - The attendance record to insert.
+/// * `audit` - The audit entry of the change, its `attendance_id` is set to the new record.
+///
+/// # Returns
+/// Returns the inserted attendance record.
+///
+/// # Errors
+/// Returns an error if the attendance record or the audit entry cannot be written to the
+/// database.
+pub fn insert_attendance(
+    conn: &mut PgConnection,
+    record: &ManualAttendance,
+    audit: NewAttendanceAudit,
+) -> Result<MemberAttendance, String> {
+    use crate::schema::{attendance_audit, member_attendance};
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        let inserted = diesel::insert_into(member_attendance::table)
+            .values(record)
+            .get_result::<MemberAttendance>(conn)?;
+        diesel::insert_into(attendance_audit::table)
+            .values(NewAttendanceAudit {
+                attendance_id: inserted.id,
+                ..audit
+            })
+            .execute(conn)?;
+        Ok(inserted)
+    })
+    .map_err(|e| format!("Failed to add attendance record: {}", e))
+}
+
+/// Retrieves the audit trail of an attendance record.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `attendance` - The ID of the attendance record.
+///
+/// # Returns
+/// Returns the changes made to the record, oldest first.
+///
+/// # Errors
+/// Returns an error if the audit entries cannot be fetched from the database.
+pub fn get_audit(conn: &mut PgConnection, attendance: i32) -> Result<Vec<AttendanceAudit>, String> {
+    use crate::schema::attendance_audit::dsl::*;
+
+    attendance_audit
+        .filter(attendance_id.eq(attendance))
+        .order(id.asc())
+        .load::<AttendanceAudit>(conn)
+        .map_err(|e| format!("Failed to fetch attendance history: {}", e))
+}
+
+/// Inserts a new attendance correction request.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_correction` - The correction request to insert.
+///
+/// # Returns
+/// Returns the inserted correction request.
+///
+/// # Errors
+/// Returns an error if the correction request cannot be inserted into the database.
+pub fn create_correction(
+    conn: &mut PgConnection,
+    new_correction: &NewAttendanceCorrection,
+) -> Result<AttendanceCorrection, String> {
+    use crate::schema::attendance_corrections::dsl::*;
+
+    diesel::insert_into(attendance_corrections)
+        .values(new_correction)
+        .get_result::<AttendanceCorrection>(conn)
+        .map_err(|e| format!("Failed to create correction request: {}", e))
+}
+
+/// Finds an attendance correction request by its ID together with the member who made it.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `correction` - The ID of the correction request.
+///
+/// # Returns
+/// Returns the correction request and its member, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the correction request cannot be fetched from the database.
+pub fn find_correction(
+    conn: &mut PgConnection,
+    correction: i32,
+) -> Result<Option<(AttendanceCorrection, Member)>, String> {
+    use crate::schema::{attendance_corrections, members};
+
+    attendance_corrections::table
+        .inner_join(members::table)
+        .filter(attendance_corrections::id.eq(correction))
+        .first::<(AttendanceCorrection, Member)>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch correction request: {}", e))
+}
+
+/// Finds a pending correction request of a member for the same record or day.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `member` - The ID of the member.
+/// * `attendance` - The ID of the attendance record, or `None` for a missing record.
+/// * `day` - The day of the attendance record.
+///
+/// # Errors
+/// Returns an error if the correction requests cannot be fetched from the database.
+pub fn find_pending_correction(
+    conn: &mut PgConnection,
+    member: i32,
+    attendance: Option<i32>,
+    day: NaiveDate,
+) -> Result<Option<AttendanceCorrection>, String> {
+    use crate::schema::attendance_corrections::dsl::*;
+
+    let query = attendance_corrections
+        .filter(member_id.eq(member))
+        .filter(status.eq("pending"))
+        .into_boxed();
+    let query = match attendance {
+        Some(attendance) => query.filter(attendance_id.eq(attendance)),
+        None => query
+            .filter(attendance_id.is_null())
+            .filter(attendance_date.eq(day)),
+    };
+
+    query
+        .first::<AttendanceCorrection>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch correction requests: {}", e))
+}
+
+/// Retrieves the correction requests of a team together with the members who made them.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `only_status` - Only the requests in this status, or all of them if `None`.
+///
+/// # Returns
+/// Returns the requests, newest first.
+///
+/// # Errors
+/// Returns an error if the correction requests cannot be fetched from the database.
+pub fn get_corrections(
+    conn: &mut PgConnection,
+    team: i32,
+    only_status: Option<&str>,
+) -> Result<Vec<(AttendanceCorrection, Member)>, String> {
+    use crate::schema::{attendance_corrections, members};
+
+    let mut query = attendance_corrections::table
+        .inner_join(members::table)
+        .filter(attendance_corrections::team_id.eq(team))
+        .into_boxed();
+    if let Some(only_status) = only_status {
+        query = query.filter(attendance_corrections::status.eq(only_status));
+    }
+
+    query
+        .order(attendance_corrections::id.desc())
+        .load::<(AttendanceCorrection, Member)>(conn)
+        .map_err(|e| format!("Failed to fetch correction requests: {}", e))
+}
+
+/// Moves a correction request to a new status if it is still in the expected one.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `correction` - The ID of the correction request.
+/// * `from_status` - The status the request must be in.
+/// * `new_status` - The new status.
+/// * `reviewer` - The Discord ID of the user reviewing the request.
+///
+/// # Returns
+/// Returns `false` if the request was not in the expected status.
+///
+/// # Errors
+/// Returns an error if the correction request cannot be updated in the database.
+pub fn set_status(
+    conn: &mut PgConnection,
+    correction: i32,
+    from_status: &str,
+    new_status: &str,
+    reviewer: &str,
+) -> Result<bool, String> {
+    use crate::schema::attendance_corrections::dsl::*;
+
+    let updated = diesel::update(
+        attendance_corrections
+            .filter(id.eq(correction))
+            .filter(status.eq(from_status)),
+    )
+    .set((
+        status.eq(new_status),
+        reviewed_by.eq(reviewer),
+        reviewed_at.eq(Utc::now().naive_utc()),
+    ))
+    .execute(conn)
+    .map_err(|e| format!("Failed to update correction request: {}", e))?;
+
+    Ok(updated > 0)
+}
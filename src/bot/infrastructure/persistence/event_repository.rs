@@ -0,0 +1,213 @@
+use crate::bot::domain::model::{
+    Event, EventException, Member, MemberAttendance, NewEvent, NewEventException,
+};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+/// Inserts a new event.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_event` - The event to insert.
+///
+/// # Returns
+/// Returns the inserted event.
+///
+/// # Errors
+/// Returns an error if the event cannot be inserted into the database.
+pub fn create_event(conn: &mut PgConnection, new_event: &NewEvent) -> Result<Event, String> {
+    use crate::schema::events::dsl::*;
+
+    diesel::insert_into(events)
+        .values(new_event)
+        .get_result::<Event>(conn)
+        .map_err(|e| format!("Failed to create event: {}", e))
+}
+
+/// Finds an event by its ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `event` - The ID of the event.
+///
+/// # Returns
+/// Returns the event, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the event cannot be fetched from the database.
+pub fn find_event(conn: &mut PgConnection, event: i32) -> Result<Option<Event>, String> {
+    use crate::schema::events::dsl::*;
+
+    events
+        .filter(id.eq(event))
+        .first::<Event>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch event: {}", e))
+}
+
+/// Retrieves the events of a team that require a check-in code and are running at a point in time.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `at` - The point in time.
+///
+/// # Errors
+/// Returns an error if the events cannot be fetched from the database.
+pub fn get_running_code_events(
+    conn: &mut PgConnection,
+    team: i32,
+    at: NaiveDateTime,
+) -> Result<Vec<Event>, String> {
+    use crate::schema::events::dsl::*;
+
+    events
+        .filter(team_id.eq(team))
+        .filter(code_secret.is_not_null())
+        .filter(cancelled_at.is_null())
+        .filter(starts_at.le(at))
+        .filter(ends_at.gt(at))
+        .load::<Event>(conn)
+        .map_err(|e| format!("Failed to fetch events: {}", e))
+}
+
+/// Retrieves the events of a team that have not ended yet or repeat, oldest first.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `from` - Events that ended before this point in time are left out.
+///
+/// # Errors
+/// Returns an error if the events cannot be fetched from the database.
+pub fn get_upcoming_events(
+    conn: &mut PgConnection,
+    team: i32,
+    from: NaiveDateTime,
+) -> Result<Vec<Event>, String> {
+    use crate::schema::events::dsl::*;
+
+    events
+        .filter(team_id.eq(team))
+        .filter(cancelled_at.is_null())
+        .filter(ends_at.gt(from).or(recurrence_rule.is_not_null()))
+        .order(starts_at.asc())
+        .load::<Event>(conn)
+        .map_err(|e| format!("Failed to fetch events: {}", e))
+}
+
+/// Retrieves the events of a team that may have an occurrence in a window of time.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `from` - The start of the window.
+/// * `to` - The end of the window.
+///
+/// # Returns
+/// Returns the one-off events overlapping the window and the repeating events that start
+/// before its end, oldest first. Cancelled events are left out.
+///
+/// # Errors
+/// Returns an error if the events cannot be fetched from the database.
+pub fn get_events_in_window(
+    conn: &mut PgConnection,
+    team: i32,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<Vec<Event>, String> {
+    use crate::schema::events::dsl::*;
+
+    events
+        .filter(team_id.eq(team))
+        .filter(cancelled_at.is_null())
+        .filter(starts_at.lt(to))
+        .filter(ends_at.gt(from).or(recurrence_rule.is_not_null()))
+        .order(starts_at.asc())
+        .load::<Event>(conn)
+        .map_err(|e| format!("Failed to fetch events: {}", e))
+}
+
+/// Inserts a skipped occurrence of a repeating event.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_exception` - The occurrence to skip.
+///
+/// # Errors
+/// Returns an error if the exception cannot be inserted into the database.
+pub fn add_exception(
+    conn: &mut PgConnection,
+    new_exception: &NewEventException,
+) -> Result<EventException, String> {
+    use crate::schema::event_exceptions::dsl::*;
+
+    diesel::insert_into(event_exceptions)
+        .values(new_exception)
+        .get_result::<EventException>(conn)
+        .map_err(|e| format!("Failed to skip occurrence: {}", e))
+}
+
+/// Retrieves the skipped occurrences of events.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `events` - The IDs of the events.
+///
+/// # Errors
+/// Returns an error if the exceptions cannot be fetched from the database.
+pub fn get_exceptions(
+    conn: &mut PgConnection,
+    events: &[i32],
+) -> Result<Vec<EventException>, String> {
+    use crate::schema::event_exceptions::dsl::*;
+
+    event_exceptions
+        .filter(event_id.eq_any(events))
+        .load::<EventException>(conn)
+        .map_err(|e| format!("Failed to fetch skipped occurrences: {}", e))
+}
+
+/// Marks an event as cancelled.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `event` - The ID of the event.
+///
+/// # Returns
+/// Returns `false` if the event was already cancelled.
+///
+/// # Errors
+/// Returns an error if the event cannot be updated in the database.
+pub fn cancel_event(conn: &mut PgConnection, event: i32) -> Result<bool, String> {
+    use crate::schema::events::dsl::*;
+
+    let cancelled = diesel::update(events.filter(id.eq(event)).filter(cancelled_at.is_null()))
+        .set(cancelled_at.eq(chrono::Utc::now().naive_utc()))
+        .execute(conn)
+        .map_err(|e| format!("Failed to cancel event: {}", e))?;
+
+    Ok(cancelled > 0)
+}
+
+/// Retrieves the check-ins to an event together with the members who checked in.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `event` - The ID of the event.
+///
+/// # Errors
+/// Returns an error if the attendance records cannot be fetched from the database.
+pub fn get_event_attendance(
+    conn: &mut PgConnection,
+    event: i32,
+) -> Result<Vec<(MemberAttendance, Member)>, String> {
+    use crate::schema::{member_attendance, members};
+
+    member_attendance::table
+        .inner_join(members::table)
+        .filter(member_attendance::event_id.eq(event))
+        .order(member_attendance::check_in_time.asc())
+        .load::<(MemberAttendance, Member)>(conn)
+        .map_err(|e| format!("Failed to fetch event attendance: {}", e))
+}
@@ -0,0 +1,98 @@
+use crate::bot::domain::model::{Holiday, NewHoliday};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+
+/// Inserts the days off of a team, leaving out the days that already are.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_holidays` - The days off to insert.
+///
+/// # Returns
+/// Returns the number of inserted days.
+///
+/// # Errors
+/// Returns an error if the days off cannot be inserted into the database.
+pub fn add_holidays(conn: &mut PgConnection, new_holidays: &[NewHoliday]) -> Result<usize, String> {
+    use crate::schema::team_holidays::dsl::*;
+
+    diesel::insert_into(team_holidays)
+        .values(new_holidays)
+        .on_conflict((team_id, holiday_date))
+        .do_nothing()
+        .execute(conn)
+        .map_err(|e| format!("Failed to add holidays: {}", e))
+}
+
+/// Retrieves the days off of a team within a range of days.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `from` - The first day of the range.
+/// * `to` - The last day of the range.
+///
+/// # Returns
+/// Returns the days off ordered by date.
+///
+/// # Errors
+/// Returns an error if the days off cannot be fetched from the database.
+pub fn get_holidays(
+    conn: &mut PgConnection,
+    team: i32,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<Holiday>, String> {
+    use crate::schema::team_holidays::dsl::*;
+
+    team_holidays
+        .filter(team_id.eq(team))
+        .filter(holiday_date.between(from, to))
+        .order(holiday_date.asc())
+        .load::<Holiday>(conn)
+        .map_err(|e| format!("Failed to fetch holidays: {}", e))
+}
+
+/// Retrieves the dates of every day off of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the days off cannot be fetched from the database.
+pub fn get_holiday_dates(conn: &mut PgConnection, team: i32) -> Result<Vec<NaiveDate>, String> {
+    use crate::schema::team_holidays::dsl::*;
+
+    team_holidays
+        .filter(team_id.eq(team))
+        .select(holiday_date)
+        .order(holiday_date.asc())
+        .load::<NaiveDate>(conn)
+        .map_err(|e| format!("Failed to fetch holidays: {}", e))
+}
+
+/// Deletes a day off of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `date` - The day off.
+///
+/// # Returns
+/// Returns `true` if the day was a day off.
+///
+/// # Errors
+/// Returns an error if the day off cannot be deleted from the database.
+pub fn remove_holiday(conn: &mut PgConnection, team: i32, date: NaiveDate) -> Result<bool, String> {
+    use crate::schema::team_holidays::dsl::*;
+
+    diesel::delete(
+        team_holidays
+            .filter(team_id.eq(team))
+            .filter(holiday_date.eq(date)),
+    )
+    .execute(conn)
+    .map(|deleted| deleted > 0)
+    .map_err(|e| format!("Failed to remove holiday: {}", e))
+}
@@ -0,0 +1,262 @@
+use crate::bot::domain::model::{
+    LeaveBalance, LeaveRequest, Member, NewLeaveBalance, NewLeaveRequest,
+};
+use chrono::{NaiveDate, Utc};
+use diesel::prelude::*;
+
+/// Inserts a new leave request.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_request` - The leave request to insert.
+///
+/// # Returns
+/// Returns the inserted leave request.
+///
+/// # Errors
+/// Returns an error if the leave request cannot be inserted into the database.
+pub fn create_request(
+    conn: &mut PgConnection,
+    new_request: &NewLeaveRequest,
+) -> Result<LeaveRequest, String> {
+    use crate::schema::leave_requests::dsl::*;
+
+    diesel::insert_into(leave_requests)
+        .values(new_request)
+        .get_result::<LeaveRequest>(conn)
+        .map_err(|e| format!("Failed to create leave request: {}", e))
+}
+
+/// Finds a leave request by its ID together with the member who made it.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `request` - The ID of the leave request.
+///
+/// # Returns
+/// Returns the leave request and its member, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the leave request cannot be fetched from the database.
+pub fn find_request(
+    conn: &mut PgConnection,
+    request: i32,
+) -> Result<Option<(LeaveRequest, Member)>, String> {
+    use crate::schema::{leave_requests, members};
+
+    leave_requests::table
+        .inner_join(members::table)
+        .filter(leave_requests::id.eq(request))
+        .first::<(LeaveRequest, Member)>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch leave request: {}", e))
+}
+
+/// Retrieves the leave requests of a team together with the members who made them.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `only_status` - Only the requests in this status, or all of them if `None`.
+///
+/// # Returns
+/// Returns the requests ordered by their first day of leave.
+///
+/// # Errors
+/// Returns an error if the leave requests cannot be fetched from the database.
+pub fn get_requests(
+    conn: &mut PgConnection,
+    team: i32,
+    only_status: Option<&str>,
+) -> Result<Vec<(LeaveRequest, Member)>, String> {
+    use crate::schema::{leave_requests, members};
+
+    let mut query = leave_requests::table
+        .inner_join(members::table)
+        .filter(leave_requests::team_id.eq(team))
+        .into_boxed();
+    if let Some(only_status) = only_status {
+        query = query.filter(leave_requests::status.eq(only_status));
+    }
+
+    query
+        .order((leave_requests::starts_on.asc(), leave_requests::id.asc()))
+        .load::<(LeaveRequest, Member)>(conn)
+        .map_err(|e| format!("Failed to fetch leave requests: {}", e))
+}
+
+/// Retrieves the leave requests of a member in some states that overlap a range of days.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `member` - The ID of the member.
+/// * `from` - The first day of the range.
+/// * `to` - The last day of the range.
+/// * `statuses` - The states of the requests to include.
+///
+/// # Errors
+/// Returns an error if the leave requests cannot be fetched from the database.
+pub fn get_member_requests_between(
+    conn: &mut PgConnection,
+    member: i32,
+    from: NaiveDate,
+    to: NaiveDate,
+    statuses: &[&str],
+) -> Result<Vec<LeaveRequest>, String> {
+    use crate::schema::leave_requests::dsl::*;
+
+    leave_requests
+        .filter(member_id.eq(member))
+        .filter(status.eq_any(statuses))
+        .filter(starts_on.le(to))
+        .filter(ends_on.ge(from))
+        .load::<LeaveRequest>(conn)
+        .map_err(|e| format!("Failed to fetch leave requests: {}", e))
+}
+
+/// Retrieves the approved leave requests of a team that overlap a range of days.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `from` - The first day of the range.
+/// * `to` - The last day of the range.
+///
+/// # Errors
+/// Returns an error if the leave requests cannot be fetched from the database.
+pub fn get_approved_between(
+    conn: &mut PgConnection,
+    team: i32,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<LeaveRequest>, String> {
+    use crate::schema::leave_requests::dsl::*;
+
+    leave_requests
+        .filter(team_id.eq(team))
+        .filter(status.eq("approved"))
+        .filter(starts_on.le(to))
+        .filter(ends_on.ge(from))
+        .load::<LeaveRequest>(conn)
+        .map_err(|e| format!("Failed to fetch leave requests: {}", e))
+}
+
+/// Moves a leave request from one state to another.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `request` - The ID of the leave request.
+/// * `from_statuses` - The states the request may currently be in.
+/// * `new_status` - The new state of the request.
+/// * `reviewer` - The Discord ID of the reviewer, recorded when the request is reviewed.
+///
+/// # Returns
+/// Returns `false` if the request is not in one of `from_statuses` anymore.
+///
+/// # Errors
+/// Returns an error if the leave request cannot be updated in the database.
+pub fn set_status(
+    conn: &mut PgConnection,
+    request: i32,
+    from_statuses: &[&str],
+    new_status: &str,
+    reviewer: Option<&str>,
+) -> Result<bool, String> {
+    use crate::schema::leave_requests::dsl::*;
+
+    let target = leave_requests
+        .filter(id.eq(request))
+        .filter(status.eq_any(from_statuses));
+    let updated = match reviewer {
+        Some(reviewer) => diesel::update(target)
+            .set((
+                status.eq(new_status),
+                reviewed_by.eq(reviewer),
+                reviewed_at.eq(Utc::now().naive_utc()),
+            ))
+            .execute(conn),
+        None => diesel::update(target)
+            .set(status.eq(new_status))
+            .execute(conn),
+    }
+    .map_err(|e| format!("Failed to update leave request: {}", e))?;
+
+    Ok(updated > 0)
+}
+
+/// Sets the yearly allowance of a member for a leave type, replacing the previous one.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_balance` - The allowance to set.
+///
+/// # Errors
+/// Returns an error if the allowance cannot be saved in the database.
+pub fn set_balance(conn: &mut PgConnection, new_balance: &NewLeaveBalance) -> Result<(), String> {
+    use crate::schema::leave_balances::dsl::*;
+
+    diesel::insert_into(leave_balances)
+        .values(new_balance)
+        .on_conflict((member_id, year, leave_type))
+        .do_update()
+        .set(allowance_days.eq(new_balance.allowance_days))
+        .execute(conn)
+        .map_err(|e| format!("Failed to set leave allowance: {}", e))?;
+
+    Ok(())
+}
+
+/// Finds the yearly allowance of a member for a leave type.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `member` - The ID of the member.
+/// * `balance_year` - The calendar year.
+/// * `kind` - The leave type.
+///
+/// # Returns
+/// Returns the allowance, or `None` if the member is not limited.
+///
+/// # Errors
+/// Returns an error if the allowance cannot be fetched from the database.
+pub fn find_balance(
+    conn: &mut PgConnection,
+    member: i32,
+    balance_year: i32,
+    kind: &str,
+) -> Result<Option<LeaveBalance>, String> {
+    use crate::schema::leave_balances::dsl::*;
+
+    leave_balances
+        .filter(member_id.eq(member))
+        .filter(year.eq(balance_year))
+        .filter(leave_type.eq(kind))
+        .first::<LeaveBalance>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch leave allowance: {}", e))
+}
+
+/// Retrieves the allowances of the members of a team for a year.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `balance_year` - The calendar year.
+///
+/// # Errors
+/// Returns an error if the allowances cannot be fetched from the database.
+pub fn get_balances(
+    conn: &mut PgConnection,
+    team: i32,
+    balance_year: i32,
+) -> Result<Vec<(LeaveBalance, Member)>, String> {
+    use crate::schema::{leave_balances, members};
+
+    leave_balances::table
+        .inner_join(members::table)
+        .filter(members::team_id.eq(team))
+        .filter(leave_balances::year.eq(balance_year))
+        .order((members::username.asc(), leave_balances::leave_type.asc()))
+        .load::<(LeaveBalance, Member)>(conn)
+        .map_err(|e| format!("Failed to fetch leave allowances: {}", e))
+}
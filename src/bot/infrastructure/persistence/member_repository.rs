@@ -0,0 +1,180 @@
+use crate::bot::domain::model::Member;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+
+/// Finds the member of a team with a specific Discord ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `member_discord_id` - The Discord ID of the member.
+///
+/// # Returns
+/// Returns the member, or `None` if the user is not a member of the team.
+///
+/// # Errors
+/// Returns an error if the member cannot be fetched from the database.
+pub fn find_member(
+    conn: &mut PgConnection,
+    team: i32,
+    member_discord_id: &str,
+) -> Result<Option<Member>, String> {
+    use crate::schema::members::dsl::*;
+
+    members
+        .filter(team_id.eq(team))
+        .filter(discord_id.eq(member_discord_id))
+        .first::<Member>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch member: {}", e))
+}
+
+/// Finds a member by ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `member` - The ID of the member.
+///
+/// # Returns
+/// Returns the member, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the member cannot be fetched from the database.
+pub fn find_member_by_id(conn: &mut PgConnection, member: i32) -> Result<Option<Member>, String> {
+    use crate::schema::members::dsl::*;
+
+    members
+        .find(member)
+        .first::<Member>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch member: {}", e))
+}
+
+/// Locks a member until the end of the current transaction, so changes to their leave and
+/// attendance are made one after the other.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `member` - The ID of the member.
+///
+/// # Errors
+/// Returns an error if the member cannot be locked.
+pub fn lock_member(conn: &mut PgConnection, member: i32) -> Result<(), String> {
+    use crate::schema::members::dsl::*;
+
+    members
+        .find(member)
+        .select(id)
+        .for_update()
+        .first::<i32>(conn)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to lock member: {}", e))
+}
+
+/// Retrieves the members of a team who have not left it.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the members cannot be fetched from the database.
+pub fn get_active_members(conn: &mut PgConnection, team: i32) -> Result<Vec<Member>, String> {
+    use crate::schema::members::dsl::*;
+
+    members
+        .filter(team_id.eq(team))
+        .filter(left_date.is_null())
+        .load::<Member>(conn)
+        .map_err(|e| format!("Failed to fetch members: {}", e))
+}
+
+/// Deletes a member together with their attendance records.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `member` - The ID of the member.
+///
+/// # Errors
+/// Returns an error if the member cannot be deleted from the database.
+pub fn delete_member(conn: &mut PgConnection, member: i32) -> Result<(), String> {
+    use crate::schema::members::dsl::*;
+
+    diesel::delete(members.filter(id.eq(member)))
+        .execute(conn)
+        .map_err(|e| format!("Failed to remove member: {}", e))?;
+
+    Ok(())
+}
+
+/// Updates the position of a member.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `member` - The ID of the member.
+/// * `new_position` - The new position of the member.
+///
+/// # Errors
+/// Returns an error if the member cannot be updated in the database.
+pub fn set_position(
+    conn: &mut PgConnection,
+    member: i32,
+    new_position: &str,
+) -> Result<(), String> {
+    use crate::schema::members::dsl::*;
+
+    diesel::update(members.filter(id.eq(member)))
+        .set(position.eq(new_position))
+        .execute(conn)
+        .map_err(|e| format!("Failed to update position: {}", e))?;
+
+    Ok(())
+}
+
+/// Updates the display name of a member.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `member` - The ID of the member.
+/// * `new_username` - The new name of the member.
+///
+/// # Errors
+/// Returns an error if the member cannot be updated in the database.
+pub fn set_username(
+    conn: &mut PgConnection,
+    member: i32,
+    new_username: &str,
+) -> Result<(), String> {
+    use crate::schema::members::dsl::*;
+
+    diesel::update(members.filter(id.eq(member)))
+        .set(username.eq(new_username))
+        .execute(conn)
+        .map_err(|e| format!("Failed to rename member: {}", e))?;
+
+    Ok(())
+}
+
+/// Marks a member as having left the team, or as active again.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `member` - The ID of the member.
+/// * `date` - The date the member left, or `None` to reactivate them.
+///
+/// # Errors
+/// Returns an error if the member cannot be updated in the database.
+pub fn set_left_date(
+    conn: &mut PgConnection,
+    member: i32,
+    date: Option<NaiveDate>,
+) -> Result<(), String> {
+    use crate::schema::members::dsl::*;
+
+    diesel::update(members.filter(id.eq(member)))
+        .set(left_date.eq(date))
+        .execute(conn)
+        .map_err(|e| format!("Failed to update member: {}", e))?;
+
+    Ok(())
+}
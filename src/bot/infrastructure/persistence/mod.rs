@@ -1,3 +1,14 @@
 pub mod team_repository;
 pub mod user_repository;
 pub mod attendance_repository;
+pub mod role_repository;
+pub mod member_repository;
+pub mod voice_repository;
+pub mod rollcall_repository;
+pub mod event_repository;
+pub mod leave_repository;
+pub mod holiday_repository;
+pub mod reminder_repository;
+pub mod correction_repository;
+pub mod audit_repository;
+pub mod webhook_repository;
@@ -0,0 +1,204 @@
+use crate::bot::domain::model::{NewReminderRule, ReminderPreference, ReminderRule, Team};
+use chrono::{NaiveDate, NaiveTime, Utc};
+use diesel::prelude::*;
+
+/// Inserts a reminder rule.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_rule` - The rule to insert.
+///
+/// # Returns
+/// Returns the inserted rule.
+///
+/// # Errors
+/// Returns an error if the rule cannot be inserted into the database.
+pub fn create_rule(
+    conn: &mut PgConnection,
+    new_rule: &NewReminderRule,
+) -> Result<ReminderRule, String> {
+    use crate::schema::reminder_rules::dsl::*;
+
+    diesel::insert_into(reminder_rules)
+        .values(new_rule)
+        .get_result::<ReminderRule>(conn)
+        .map_err(|e| format!("Failed to add reminder: {}", e))
+}
+
+/// Retrieves the reminder rules of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the rules cannot be fetched from the database.
+pub fn get_rules(conn: &mut PgConnection, team: i32) -> Result<Vec<ReminderRule>, String> {
+    use crate::schema::reminder_rules::dsl::*;
+
+    reminder_rules
+        .filter(team_id.eq(team))
+        .order(id.asc())
+        .load::<ReminderRule>(conn)
+        .map_err(|e| format!("Failed to fetch reminders: {}", e))
+}
+
+/// Deletes a reminder rule of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `rule` - The ID of the rule.
+///
+/// # Returns
+/// Returns `false` if the team has no such rule.
+///
+/// # Errors
+/// Returns an error if the rule cannot be deleted from the database.
+pub fn delete_rule(conn: &mut PgConnection, team: i32, rule: i32) -> Result<bool, String> {
+    use crate::schema::reminder_rules::dsl::*;
+
+    let deleted = diesel::delete(reminder_rules.filter(id.eq(rule)).filter(team_id.eq(team)))
+        .execute(conn)
+        .map_err(|e| format!("Failed to remove reminder: {}", e))?;
+
+    Ok(deleted > 0)
+}
+
+/// Retrieves the reminder rules of the active teams that were not sent on a day.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `day` - The day.
+///
+/// # Returns
+/// Returns each rule together with its team.
+///
+/// # Errors
+/// Returns an error if the rules cannot be fetched from the database.
+pub fn get_unsent_rules(
+    conn: &mut PgConnection,
+    day: NaiveDate,
+) -> Result<Vec<(ReminderRule, Team)>, String> {
+    use crate::schema::{reminder_rules, teams};
+
+    reminder_rules::table
+        .inner_join(teams::table)
+        .filter(teams::archived_at.is_null())
+        .filter(
+            reminder_rules::last_sent_on
+                .is_null()
+                .or(reminder_rules::last_sent_on.lt(day)),
+        )
+        .select((reminder_rules::all_columns, teams::all_columns))
+        .load::<(ReminderRule, Team)>(conn)
+        .map_err(|e| format!("Failed to fetch reminders: {}", e))
+}
+
+/// Marks a reminder rule as sent on a day.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `rule` - The ID of the rule.
+/// * `day` - The day.
+///
+/// # Returns
+/// Returns `false` if the rule was already sent that day.
+///
+/// # Errors
+/// Returns an error if the rule cannot be updated in the database.
+pub fn mark_sent(conn: &mut PgConnection, rule: i32, day: NaiveDate) -> Result<bool, String> {
+    use crate::schema::reminder_rules::dsl::*;
+
+    let updated = diesel::update(
+        reminder_rules
+            .filter(id.eq(rule))
+            .filter(last_sent_on.is_null().or(last_sent_on.lt(day))),
+    )
+    .set(last_sent_on.eq(day))
+    .execute(conn)
+    .map_err(|e| format!("Failed to update reminder: {}", e))?;
+
+    Ok(updated > 0)
+}
+
+/// Retrieves the reminder settings of users.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `discord_ids` - The Discord IDs of the users.
+///
+/// # Returns
+/// Returns the settings of the users who changed them, the others have none.
+///
+/// # Errors
+/// Returns an error if the settings cannot be fetched from the database.
+pub fn get_preferences(
+    conn: &mut PgConnection,
+    discord_ids: &[String],
+) -> Result<Vec<ReminderPreference>, String> {
+    use crate::schema::reminder_preferences::dsl::*;
+
+    reminder_preferences
+        .filter(discord_id.eq_any(discord_ids))
+        .load::<ReminderPreference>(conn)
+        .map_err(|e| format!("Failed to fetch reminder settings: {}", e))
+}
+
+/// Turns the reminders of a user on or off.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `user` - The Discord ID of the user.
+/// * `off` - Whether the user opts out of reminders.
+///
+/// # Errors
+/// Returns an error if the settings cannot be saved to the database.
+pub fn set_opted_out(conn: &mut PgConnection, user: &str, off: bool) -> Result<(), String> {
+    use crate::schema::reminder_preferences::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    diesel::insert_into(reminder_preferences)
+        .values((discord_id.eq(user), opted_out.eq(off), updated_at.eq(now)))
+        .on_conflict(discord_id)
+        .do_update()
+        .set((opted_out.eq(off), updated_at.eq(now)))
+        .execute(conn)
+        .map_err(|e| format!("Failed to save reminder settings: {}", e))?;
+
+    Ok(())
+}
+
+/// Sets or clears the quiet hours of a user.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `user` - The Discord ID of the user.
+/// * `hours` - The start and end (UTC) of the quiet hours, or `None` to clear them.
+///
+/// # Errors
+/// Returns an error if the settings cannot be saved to the database.
+pub fn set_quiet_hours(
+    conn: &mut PgConnection,
+    user: &str,
+    hours: Option<(NaiveTime, NaiveTime)>,
+) -> Result<(), String> {
+    use crate::schema::reminder_preferences::dsl::*;
+
+    let now = Utc::now().naive_utc();
+    let (start, end) = (hours.map(|(start, _)| start), hours.map(|(_, end)| end));
+    diesel::insert_into(reminder_preferences)
+        .values((
+            discord_id.eq(user),
+            quiet_start.eq(start),
+            quiet_end.eq(end),
+            updated_at.eq(now),
+        ))
+        .on_conflict(discord_id)
+        .do_update()
+        .set((quiet_start.eq(start), quiet_end.eq(end), updated_at.eq(now)))
+        .execute(conn)
+        .map_err(|e| format!("Failed to save reminder settings: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use crate::bot::domain::model::NewTeamRole;
+use crate::bot::domain::table::TeamRoleTable;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+
+/// Retrieves the role of a user in a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `user_discord_id` - The Discord ID of the user.
+///
+/// # Returns
+/// Returns the name of the role, or `None` if the user has no role in the team.
+///
+/// # Errors
+/// Returns an error if the role cannot be fetched from the database.
+pub fn get_role(
+    conn: &mut PgConnection,
+    team: i32,
+    user_discord_id: &str,
+) -> Result<Option<String>, String> {
+    use crate::schema::team_roles::dsl::*;
+
+    team_roles
+        .filter(team_id.eq(team))
+        .filter(discord_id.eq(user_discord_id))
+        .select(role)
+        .first::<String>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch role: {}", e))
+}
+
+/// Grants a role to a user in a team, replacing any role they already had.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_role` - The role assignment to store.
+///
+/// # Errors
+/// Returns an error if the role cannot be stored in the database.
+pub fn set_role(conn: &mut PgConnection, new_role: &NewTeamRole) -> Result<(), String> {
+    use crate::schema::team_roles::dsl::*;
+
+    diesel::insert_into(team_roles)
+        .values(new_role)
+        .on_conflict((team_id, discord_id))
+        .do_update()
+        .set((role.eq(excluded(role)), granted_by.eq(excluded(granted_by))))
+        .execute(conn)
+        .map_err(|e| format!("Failed to grant role: {}", e))?;
+
+    Ok(())
+}
+
+/// Grants a role to a user in a team unless they already have one.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_role` - The role assignment to store.
+///
+/// # Errors
+/// Returns an error if the role cannot be stored in the database.
+pub fn add_role_if_missing(conn: &mut PgConnection, new_role: &NewTeamRole) -> Result<(), String> {
+    use crate::schema::team_roles::dsl::team_roles;
+
+    diesel::insert_into(team_roles)
+        .values(new_role)
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map_err(|e| format!("Failed to grant role: {}", e))?;
+
+    Ok(())
+}
+
+/// Removes the role of a user in a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `user_discord_id` - The Discord ID of the user.
+///
+/// # Errors
+/// Returns an error if:
+/// - The user has no role in the team.
+/// - The role cannot be deleted from the database.
+pub fn remove_role(conn: &mut PgConnection, team: i32, user_discord_id: &str) -> Result<(), String> {
+    use crate::schema::team_roles::dsl::*;
+
+    let removed = diesel::delete(
+        team_roles
+            .filter(team_id.eq(team))
+            .filter(discord_id.eq(user_discord_id)),
+    )
+    .execute(conn)
+    .map_err(|e| format!("Failed to revoke role: {}", e))?;
+
+    if removed == 0 {
+        return Err("User has no role in this team!".to_string());
+    }
+
+    Ok(())
+}
+
+/// Counts the owners of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the owners cannot be counted.
+pub fn count_owners(conn: &mut PgConnection, team: i32) -> Result<i64, String> {
+    use crate::schema::team_roles::dsl::*;
+
+    team_roles
+        .filter(team_id.eq(team))
+        .filter(role.eq("owner"))
+        .count()
+        .get_result(conn)
+        .map_err(|e| format!("Failed to count owners: {}", e))
+}
+
+/// Retrieves all role assignments of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+///
+/// # Returns
+/// Returns a `Vec<TeamRoleTable>` containing the role assignments of the team.
+///
+/// # Errors
+/// Returns an error if the roles cannot be fetched from the database.
+pub fn get_team_roles(conn: &mut PgConnection, team: i32) -> Result<Vec<TeamRoleTable>, String> {
+    use crate::schema::team_roles::dsl::*;
+
+    let roles: Vec<(String, String)> = team_roles
+        .filter(team_id.eq(team))
+        .order(id)
+        .select((discord_id, role))
+        .load(conn)
+        .map_err(|e| format!("Failed to fetch roles: {}", e))?;
+
+    Ok(roles
+        .into_iter()
+        .map(|(user, user_role)| TeamRoleTable {
+            discord_id: user,
+            role: user_role,
+        })
+        .collect())
+}
@@ -0,0 +1,181 @@
+use crate::bot::domain::model::{NewRollcall, Rollcall, RollcallAttendance};
+use chrono::Utc;
+use diesel::prelude::*;
+
+/// Inserts a new roll-call.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_rollcall` - The roll-call to insert.
+///
+/// # Returns
+/// Returns the inserted roll-call.
+///
+/// # Errors
+/// Returns an error if the roll-call cannot be inserted into the database.
+pub fn create_rollcall(
+    conn: &mut PgConnection,
+    new_rollcall: &NewRollcall,
+) -> Result<Rollcall, String> {
+    use crate::schema::rollcalls::dsl::*;
+
+    diesel::insert_into(rollcalls)
+        .values(new_rollcall)
+        .get_result::<Rollcall>(conn)
+        .map_err(|e| format!("Failed to create roll-call: {}", e))
+}
+
+/// Stores the ID of the message a roll-call was posted as.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `rollcall` - The ID of the roll-call.
+/// * `message` - The ID of the Discord message.
+///
+/// # Errors
+/// Returns an error if the roll-call cannot be updated in the database.
+pub fn set_message(conn: &mut PgConnection, rollcall: i32, message: &str) -> Result<(), String> {
+    use crate::schema::rollcalls::dsl::*;
+
+    diesel::update(rollcalls.filter(id.eq(rollcall)))
+        .set(message_id.eq(message))
+        .execute(conn)
+        .map_err(|e| format!("Failed to update roll-call: {}", e))?;
+
+    Ok(())
+}
+
+/// Deletes a roll-call.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `rollcall` - The ID of the roll-call.
+///
+/// # Errors
+/// Returns an error if the roll-call cannot be deleted from the database.
+pub fn delete_rollcall(conn: &mut PgConnection, rollcall: i32) -> Result<(), String> {
+    use crate::schema::rollcalls::dsl::*;
+
+    diesel::delete(rollcalls.filter(id.eq(rollcall)))
+        .execute(conn)
+        .map_err(|e| format!("Failed to delete roll-call: {}", e))?;
+
+    Ok(())
+}
+
+/// Finds a roll-call by its ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `rollcall` - The ID of the roll-call.
+///
+/// # Returns
+/// Returns the roll-call, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the roll-call cannot be fetched from the database.
+pub fn find_rollcall(conn: &mut PgConnection, rollcall: i32) -> Result<Option<Rollcall>, String> {
+    use crate::schema::rollcalls::dsl::*;
+
+    rollcalls
+        .filter(id.eq(rollcall))
+        .first::<Rollcall>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch roll-call: {}", e))
+}
+
+/// Retrieves the posted roll-calls that have not been closed yet.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Errors
+/// Returns an error if the roll-calls cannot be fetched from the database.
+pub fn get_open_rollcalls(conn: &mut PgConnection) -> Result<Vec<Rollcall>, String> {
+    use crate::schema::rollcalls::dsl::*;
+
+    rollcalls
+        .filter(closed_at.is_null())
+        .filter(message_id.is_not_null())
+        .load::<Rollcall>(conn)
+        .map_err(|e| format!("Failed to fetch roll-calls: {}", e))
+}
+
+/// Marks a roll-call as closed.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `rollcall` - The ID of the roll-call.
+///
+/// # Returns
+/// Returns `false` if the roll-call was already closed.
+///
+/// # Errors
+/// Returns an error if the roll-call cannot be updated in the database.
+pub fn close_rollcall(conn: &mut PgConnection, rollcall: i32) -> Result<bool, String> {
+    use crate::schema::rollcalls::dsl::*;
+
+    let closed = diesel::update(
+        rollcalls
+            .filter(id.eq(rollcall))
+            .filter(closed_at.is_null()),
+    )
+    .set(closed_at.eq(Utc::now().naive_utc()))
+    .execute(conn)
+    .map_err(|e| format!("Failed to close roll-call: {}", e))?;
+
+    Ok(closed > 0)
+}
+
+/// Records the answer of a member to a roll-call.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `answer` - The attendance record of the member.
+///
+/// # Errors
+/// Returns an error if:
+/// - The member already answered the roll-call.
+/// - The attendance record cannot be inserted into the database.
+pub fn record_answer(conn: &mut PgConnection, answer: &RollcallAttendance) -> Result<(), String> {
+    use crate::schema::member_attendance::dsl::*;
+    use diesel::result::{DatabaseErrorKind, Error};
+
+    diesel::insert_into(member_attendance)
+        .values(answer)
+        .execute(conn)
+        .map_err(|e| match e {
+            Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                "You already answered this roll-call.".to_string()
+            }
+            e => format!("Failed to record roll-call answer: {}", e),
+        })?;
+
+    Ok(())
+}
+
+/// Retrieves the answers to a roll-call.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `rollcall` - The ID of the roll-call.
+///
+/// # Returns
+/// Returns the ID, name and status of every member who has an answer recorded.
+///
+/// # Errors
+/// Returns an error if the answers cannot be fetched from the database.
+pub fn get_answers(
+    conn: &mut PgConnection,
+    rollcall: i32,
+) -> Result<Vec<(i32, String, Option<String>)>, String> {
+    use crate::schema::{member_attendance, members};
+
+    member_attendance::table
+        .inner_join(members::table)
+        .filter(member_attendance::rollcall_id.eq(rollcall))
+        .order(members::username.asc())
+        .select((members::id, members::username, member_attendance::status))
+        .load(conn)
+        .map_err(|e| format!("Failed to fetch roll-call answers: {}", e))
+}
@@ -1,12 +1,13 @@
 use crate::bot::domain::model::{Member, NewMember, NewTeam, Team};
 use crate::bot::domain::table::{MemberTable, TeamTable};
-use chrono::Utc;
+use chrono::{NaiveDate, NaiveTime, Utc};
 use diesel::prelude::*;
 
-/// Retrieves all teams created by a specific admin.
+/// Retrieves all teams created by a specific admin in a Discord server.
 ///
 /// # Arguments
 /// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild` - The ID of the Discord server.
 /// * `admin_discord_id` - The Discord ID of the admin whose teams are being fetched.
 ///
 /// # Returns
@@ -18,6 +19,7 @@ use diesel::prelude::*;
 /// - The teams cannot be fetched from the database.
 pub fn get_admin_teams(
     conn: &mut PgConnection,
+    guild: &str,
     admin_discord_id: &str,
 ) -> Result<Vec<TeamTable>, String> {
     use crate::schema::teams::dsl::*;
@@ -30,9 +32,11 @@ pub fn get_admin_teams(
         .first(conn)
         .map_err(|e| format!("Failed to find admin: {}", e))?;
 
-    // Fetch teams created by the admin
+    // Fetch the active teams created by the admin in the server
     let teams_data: Vec<Team> = teams
         .filter(admin_id.eq(admin_user_id))
+        .filter(guild_id.eq(guild))
+        .filter(archived_at.is_null())
         .load::<Team>(conn)
         .map_err(|e| format!("Failed to fetch teams: {}", e))?;
 
@@ -40,6 +44,7 @@ pub fn get_admin_teams(
     let team_tables = teams_data
         .into_iter()
         .map(|team| TeamTable {
+            id: team.id,
             name: team.name,
             created_at: team
                 .created_at
@@ -55,6 +60,7 @@ pub fn get_admin_teams(
 ///
 /// # Arguments
 /// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild` - The ID of the Discord server the team belongs to.
 /// * `team_name` - The name of the team whose members are being fetched.
 ///
 /// # Returns
@@ -64,16 +70,15 @@ pub fn get_admin_teams(
 /// Returns an error if:
 /// - The team cannot be found.
 /// - The members cannot be fetched from the database.
-pub fn get_members(conn: &mut PgConnection, team_name: &str) -> Result<Vec<MemberTable>, String> {
+pub fn get_members(
+    conn: &mut PgConnection,
+    guild: &str,
+    team_name: &str,
+) -> Result<Vec<MemberTable>, String> {
     use crate::schema::members::dsl::*;
-    use crate::schema::teams::dsl::{id as team_id_column, name as team_name_column, teams};
 
     // Find the team ID by name
-    let team_ids: i32 = teams
-        .filter(team_name_column.eq(team_name))
-        .select(team_id_column)
-        .first(conn)
-        .map_err(|e| format!("Failed to find team: {}", e))?;
+    let team_ids = find_team_id(conn, guild, team_name)?;
 
     // Fetch members of the team
     let members_data: Vec<Member> = members
@@ -86,39 +91,511 @@ pub fn get_members(conn: &mut PgConnection, team_name: &str) -> Result<Vec<Membe
         .into_iter()
         .map(|member| MemberTable {
             username: member.username,
+            discord_id: member.discord_id,
+            position: member.position.unwrap_or_else(|| "N/A".to_string()),
             join_date: member
                 .join_date
                 .map(|dt| dt.format("%Y-%m-%d").to_string())
                 .unwrap_or_else(|| "N/A".to_string()),
+            left_date: member
+                .left_date
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "-".to_string()),
         })
         .collect();
 
     Ok(member_tables)
 }
 
+/// Finds a team by name in a Discord server.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild` - The ID of the Discord server the team belongs to.
+/// * `team_name` - The name of the team.
+///
+/// # Returns
+/// Returns the ID of the team.
+///
+/// # Errors
+/// Returns an error if the team does not exist or cannot be fetched.
+pub fn find_team_id(conn: &mut PgConnection, guild: &str, team_name: &str) -> Result<i32, String> {
+    use crate::schema::teams::dsl::*;
+
+    teams
+        .filter(guild_id.eq(guild))
+        .filter(name.eq(team_name))
+        .select(id)
+        .first(conn)
+        .map_err(|e| {
+            if e == diesel::result::Error::NotFound {
+                format!("Team '{}' not found", team_name)
+            } else {
+                format!("Failed to fetch team '{}': {}", team_name, e)
+            }
+        })
+}
+
+/// Finds a team by name among the teams created by a specific admin.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild` - The ID of the Discord server the team belongs to, if known.
+/// * `team_name` - The name of the team.
+/// * `admin_user_id` - The ID of the admin who created the team.
+///
+/// # Returns
+/// Returns the ID of the team.
+///
+/// # Errors
+/// Returns an error if:
+/// - The team does not exist or belongs to another admin.
+/// - No server is given and the admin has teams with this name in several servers.
+pub fn find_admin_team(
+    conn: &mut PgConnection,
+    guild: Option<&str>,
+    team_name: &str,
+    admin_user_id: i32,
+) -> Result<i32, String> {
+    use crate::schema::teams::dsl::*;
+
+    let mut query = teams
+        .filter(name.eq(team_name))
+        .filter(admin_id.eq(admin_user_id))
+        .select(id)
+        .into_boxed();
+    if let Some(guild) = guild {
+        query = query.filter(guild_id.eq(guild));
+    }
+
+    let team_ids: Vec<i32> = query
+        .limit(2)
+        .load(conn)
+        .map_err(|e| format!("Failed to fetch team '{}': {}", team_name, e))?;
+
+    match team_ids.as_slice() {
+        [team] => Ok(*team),
+        [] => Err(format!("Team '{}' not found", team_name)),
+        _ => Err(format!(
+            "Team '{}' exists in several servers, specify its guild ID",
+            team_name
+        )),
+    }
+}
+
 /// Creates a new team in the database.
 ///
 /// # Arguments
 /// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild` - The ID of the Discord server the team belongs to.
 /// * `name` - The name of the team to create.
 /// * `admin` - The ID of the admin creating the team.
 ///
+/// # Returns
+/// Returns the ID of the created team.
+///
 /// # Errors
 /// Returns an error if the team cannot be created in the database.
-pub fn create_team(conn: &mut PgConnection, name: &str, admin: i32) -> Result<(), String> {
-    use crate::schema::teams::dsl::teams;
+pub fn create_team(
+    conn: &mut PgConnection,
+    guild: &str,
+    name: &str,
+    admin: i32,
+) -> Result<i32, String> {
+    use crate::schema::teams::dsl::{id, teams};
 
     let new_team = NewTeam {
         name: name.to_string(),
         admin_id: admin,
         created_at: Utc::now().naive_utc(),
+        guild_id: guild.to_string(),
     };
 
-    diesel::insert_into(teams)
+    let team_id = diesel::insert_into(teams)
         .values(&new_team)
-        .execute(conn)
+        .returning(id)
+        .get_result(conn)
         .map_err(|_| "Failed to create team")?;
 
+    Ok(team_id)
+}
+
+/// Finds a team by ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+///
+/// # Returns
+/// Returns the team, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the team cannot be fetched from the database.
+pub fn find_team(conn: &mut PgConnection, team: i32) -> Result<Option<Team>, String> {
+    use crate::schema::teams::dsl::*;
+
+    teams
+        .filter(id.eq(team))
+        .first::<Team>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch team: {}", e))
+}
+
+/// Renames a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `new_name` - The new name of the team.
+///
+/// # Errors
+/// Returns an error if:
+/// - Another team in the same server already has this name.
+/// - The team cannot be updated in the database.
+pub fn rename_team(conn: &mut PgConnection, team: i32, new_name: &str) -> Result<(), String> {
+    use crate::schema::teams::dsl::*;
+    use diesel::result::{DatabaseErrorKind, Error};
+
+    diesel::update(teams.filter(id.eq(team)))
+        .set(name.eq(new_name))
+        .execute(conn)
+        .map_err(|e| match e {
+            Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                format!("A team named '{}' already exists in this server", new_name)
+            }
+            e => format!("Failed to rename team: {}", e),
+        })?;
+
+    Ok(())
+}
+
+/// Archives or restores a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `archived` - Whether the team should be archived.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn set_archived(conn: &mut PgConnection, team: i32, archived: bool) -> Result<(), String> {
+    use crate::schema::teams::dsl::*;
+
+    let timestamp = archived.then(|| Utc::now().naive_utc());
+    diesel::update(teams.filter(id.eq(team)))
+        .set(archived_at.eq(timestamp))
+        .execute(conn)
+        .map_err(|e| format!("Failed to archive team: {}", e))?;
+
+    Ok(())
+}
+
+/// Hands a team over to another admin.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `new_admin_id` - The ID of the admin taking over the team.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn set_admin(conn: &mut PgConnection, team: i32, new_admin_id: i32) -> Result<(), String> {
+    use crate::schema::teams::dsl::*;
+
+    diesel::update(teams.filter(id.eq(team)))
+        .set(admin_id.eq(new_admin_id))
+        .execute(conn)
+        .map_err(|e| format!("Failed to transfer team: {}", e))?;
+
+    Ok(())
+}
+
+/// Binds a team to a Discord role, or removes the binding.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `role` - The ID of the Discord role, or `None` to stop syncing.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn set_discord_role(
+    conn: &mut PgConnection,
+    team: i32,
+    role: Option<&str>,
+) -> Result<(), String> {
+    use crate::schema::teams::dsl::*;
+
+    diesel::update(teams.filter(id.eq(team)))
+        .set(discord_role_id.eq(role))
+        .execute(conn)
+        .map_err(|e| format!("Failed to update team role: {}", e))?;
+
+    Ok(())
+}
+
+/// Updates the voice attendance settings of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `min_minutes` - The minimum length of a voice session to be recorded.
+/// * `debounce_seconds` - How long a member may leave a voice channel before being checked out.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn set_voice_settings(
+    conn: &mut PgConnection,
+    team: i32,
+    min_minutes: i32,
+    debounce_seconds: i32,
+) -> Result<(), String> {
+    use crate::schema::teams::dsl::*;
+
+    diesel::update(teams.filter(id.eq(team)))
+        .set((
+            voice_min_minutes.eq(min_minutes),
+            voice_debounce_seconds.eq(debounce_seconds),
+        ))
+        .execute(conn)
+        .map_err(|e| format!("Failed to update voice settings: {}", e))?;
+
+    Ok(())
+}
+
+/// Sets the channel leave requests of a team are posted to for review.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `channel` - The ID of the Discord channel, or `None` to send them to the team admin.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn set_leave_channel(
+    conn: &mut PgConnection,
+    team: i32,
+    channel: Option<&str>,
+) -> Result<(), String> {
+    use crate::schema::teams::dsl::*;
+
+    diesel::update(teams.filter(id.eq(team)))
+        .set(leave_channel_id.eq(channel))
+        .execute(conn)
+        .map_err(|e| format!("Failed to update leave channel: {}", e))?;
+
+    Ok(())
+}
+
+/// Sets the daily schedule of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `start` - The time (UTC) the working day starts, or `None` to stop the absence check.
+/// * `grace_minutes` - How long after the start members are recorded as absent.
+/// * `channel` - The ID of the channel the daily no-show report is posted to, if any.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn set_schedule(
+    conn: &mut PgConnection,
+    team: i32,
+    start: Option<NaiveTime>,
+    grace_minutes: i32,
+    channel: Option<&str>,
+) -> Result<(), String> {
+    use crate::schema::teams::dsl::*;
+
+    diesel::update(teams.filter(id.eq(team)))
+        .set((
+            schedule_start.eq(start),
+            schedule_grace_minutes.eq(grace_minutes),
+            report_channel_id.eq(channel),
+        ))
+        .execute(conn)
+        .map_err(|e| format!("Failed to update schedule: {}", e))?;
+
+    Ok(())
+}
+
+/// Retrieves the active teams with a daily schedule whose absences were not checked on a day.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `day` - The day.
+///
+/// # Errors
+/// Returns an error if the teams cannot be fetched from the database.
+pub fn get_unchecked_scheduled_teams(
+    conn: &mut PgConnection,
+    day: NaiveDate,
+) -> Result<Vec<Team>, String> {
+    use crate::schema::teams::dsl::*;
+
+    teams
+        .filter(archived_at.is_null())
+        .filter(schedule_start.is_not_null())
+        .filter(absences_checked_on.is_null().or(absences_checked_on.lt(day)))
+        .load::<Team>(conn)
+        .map_err(|e| format!("Failed to fetch scheduled teams: {}", e))
+}
+
+/// Marks the absences of a team as checked on a day.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `day` - The day.
+///
+/// # Returns
+/// Returns `false` if the absences of that day were already checked.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn mark_absences_checked(
+    conn: &mut PgConnection,
+    team: i32,
+    day: NaiveDate,
+) -> Result<bool, String> {
+    use crate::schema::teams::dsl::*;
+
+    let updated = diesel::update(
+        teams
+            .filter(id.eq(team))
+            .filter(absences_checked_on.is_null().or(absences_checked_on.lt(day))),
+    )
+    .set(absences_checked_on.eq(day))
+    .execute(conn)
+    .map_err(|e| format!("Failed to update team: {}", e))?;
+
+    Ok(updated > 0)
+}
+
+/// Sets where the presence board of a team is kept.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `channel` - The ID of the channel of the board, or `None` to remove the board.
+/// * `message` - The ID of the message of the board, if it was posted.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn set_presence_board(
+    conn: &mut PgConnection,
+    team: i32,
+    channel: Option<&str>,
+    message: Option<&str>,
+) -> Result<(), String> {
+    use crate::schema::teams::dsl::*;
+
+    diesel::update(teams.filter(id.eq(team)))
+        .set((
+            presence_channel_id.eq(channel),
+            presence_message_id.eq(message),
+        ))
+        .execute(conn)
+        .map_err(|e| format!("Failed to update presence board: {}", e))?;
+
+    Ok(())
+}
+
+/// Replaces the message of the presence board of a team, unless the board was moved or removed
+/// in the meantime.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `channel` - The ID of the channel the message was posted in.
+/// * `message` - The ID of the new message.
+///
+/// # Errors
+/// Returns an error if the team cannot be updated in the database.
+pub fn set_presence_message(
+    conn: &mut PgConnection,
+    team: i32,
+    channel: &str,
+    message: &str,
+) -> Result<(), String> {
+    use crate::schema::teams::dsl::*;
+
+    diesel::update(
+        teams
+            .filter(id.eq(team))
+            .filter(presence_channel_id.eq(channel)),
+    )
+    .set(presence_message_id.eq(message))
+    .execute(conn)
+    .map_err(|e| format!("Failed to update presence board: {}", e))?;
+
+    Ok(())
+}
+
+/// Retrieves the active teams that keep a presence board.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+///
+/// # Errors
+/// Returns an error if the teams cannot be fetched from the database.
+pub fn get_presence_board_teams(conn: &mut PgConnection) -> Result<Vec<Team>, String> {
+    use crate::schema::teams::dsl::*;
+
+    teams
+        .filter(archived_at.is_null())
+        .filter(presence_channel_id.is_not_null())
+        .order(id.asc())
+        .load::<Team>(conn)
+        .map_err(|e| format!("Failed to fetch presence boards: {}", e))
+}
+
+/// Retrieves the active teams of a Discord server that are bound to a Discord role.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild` - The ID of the Discord server.
+///
+/// # Returns
+/// Returns the ID of each team together with the ID of its Discord role.
+///
+/// # Errors
+/// Returns an error if the teams cannot be fetched from the database.
+pub fn get_role_bound_teams(
+    conn: &mut PgConnection,
+    guild: &str,
+) -> Result<Vec<(i32, String)>, String> {
+    use crate::schema::teams::dsl::*;
+
+    let bound: Vec<(i32, Option<String>)> = teams
+        .filter(guild_id.eq(guild))
+        .filter(archived_at.is_null())
+        .filter(discord_role_id.is_not_null())
+        .select((id, discord_role_id))
+        .load(conn)
+        .map_err(|e| format!("Failed to fetch teams: {}", e))?;
+
+    Ok(bound
+        .into_iter()
+        .filter_map(|(team, role)| role.map(|role| (team, role)))
+        .collect())
+}
+
+/// Deletes a team together with its members, attendance records, roles and API keys.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the team cannot be deleted from the database.
+pub fn delete_team(conn: &mut PgConnection, team: i32) -> Result<(), String> {
+    use crate::schema::teams::dsl::*;
+
+    diesel::delete(teams.filter(id.eq(team)))
+        .execute(conn)
+        .map_err(|e| format!("Failed to delete team: {}", e))?;
+
     Ok(())
 }
 
@@ -131,7 +608,9 @@ pub fn create_team(conn: &mut PgConnection, name: &str, admin: i32) -> Result<()
 /// * `team_id_value` - The ID of the team to which the member is being assigned.
 ///
 /// # Errors
-/// Returns an error if the member cannot be assigned to the team.
+/// Returns an error if:
+/// - The user is already a member of the team.
+/// - The member cannot be assigned to the team.
 pub fn assign_member(
     conn: &mut PgConnection,
     dc_id: &str,
@@ -139,6 +618,7 @@ pub fn assign_member(
     team_id_value: i32,
 ) -> Result<(), String> {
     use crate::schema::members::dsl::*;
+    use diesel::result::{DatabaseErrorKind, Error};
 
     let new_member = NewMember {
         username: username_string,
@@ -151,7 +631,12 @@ pub fn assign_member(
     diesel::insert_into(members)
         .values(&new_member)
         .execute(conn)
-        .map_err(|_| "Failed to assign member")?;
+        .map_err(|e| match e {
+            Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                "User is already a member of this team"
+            }
+            _ => "Failed to assign member",
+        })?;
 
     Ok(())
 }
@@ -1,5 +1,5 @@
 use crate::bot::domain::model::NewUser;
-use bcrypt::hash;
+use bcrypt::{hash, DEFAULT_COST};
 use chrono::Utc;
 use diesel::prelude::*;
 
@@ -23,7 +23,7 @@ pub fn register_admin(
 ) -> Result<(), String> {
     use crate::schema::users::dsl::users;
 
-    let hashed_password = hash(password, 4).map_err(|_| "Password hashing failed!")?;
+    let hashed_password = hash(password, DEFAULT_COST).map_err(|_| "Password hashing failed!")?;
 
     let new_user = NewUser {
         discord_id: discord_id.to_string(),
@@ -40,3 +40,45 @@ pub fn register_admin(
 
     Ok(())
 }
+
+/// Finds the ID of a registered user by Discord ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `user_discord_id` - The Discord ID of the user.
+///
+/// # Errors
+/// Returns an error if the user is not registered or cannot be fetched.
+pub fn find_user_id(conn: &mut PgConnection, user_discord_id: &str) -> Result<i32, String> {
+    use crate::schema::users::dsl::{discord_id, id, users};
+
+    users
+        .filter(discord_id.eq(user_discord_id))
+        .select(id)
+        .first(conn)
+        .map_err(|e| {
+            if e == diesel::result::Error::NotFound {
+                "User not found!".to_string()
+            } else {
+                format!("Failed to fetch user: {}", e)
+            }
+        })
+}
+
+/// Finds the Discord ID of a registered user.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `user_id` - The ID of the user.
+///
+/// # Errors
+/// Returns an error if the user does not exist or cannot be fetched.
+pub fn find_discord_id(conn: &mut PgConnection, user_id: i32) -> Result<String, String> {
+    use crate::schema::users::dsl::{discord_id, id, users};
+
+    users
+        .filter(id.eq(user_id))
+        .select(discord_id)
+        .first(conn)
+        .map_err(|e| format!("Failed to fetch user: {}", e))
+}
@@ -0,0 +1,107 @@
+use crate::bot::domain::model::{NewTeamVoiceChannel, Team};
+use diesel::prelude::*;
+
+/// Links a voice channel to a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `channel` - The ID of the Discord voice channel.
+///
+/// # Errors
+/// Returns an error if:
+/// - The channel is already linked to the team.
+/// - The link cannot be inserted into the database.
+pub fn link_channel(conn: &mut PgConnection, team: i32, channel: &str) -> Result<(), String> {
+    use crate::schema::team_voice_channels::dsl::*;
+    use diesel::result::{DatabaseErrorKind, Error};
+
+    diesel::insert_into(team_voice_channels)
+        .values(&NewTeamVoiceChannel {
+            team_id: team,
+            channel_id: channel.to_string(),
+        })
+        .execute(conn)
+        .map_err(|e| match e {
+            Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                "Voice channel is already linked to this team".to_string()
+            }
+            e => format!("Failed to link voice channel: {}", e),
+        })?;
+
+    Ok(())
+}
+
+/// Removes the link between a voice channel and a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `channel` - The ID of the Discord voice channel.
+///
+/// # Errors
+/// Returns an error if:
+/// - The channel is not linked to the team.
+/// - The link cannot be deleted from the database.
+pub fn unlink_channel(conn: &mut PgConnection, team: i32, channel: &str) -> Result<(), String> {
+    use crate::schema::team_voice_channels::dsl::*;
+
+    let deleted = diesel::delete(
+        team_voice_channels
+            .filter(team_id.eq(team))
+            .filter(channel_id.eq(channel)),
+    )
+    .execute(conn)
+    .map_err(|e| format!("Failed to unlink voice channel: {}", e))?;
+
+    if deleted == 0 {
+        return Err("Voice channel is not linked to this team".to_string());
+    }
+
+    Ok(())
+}
+
+/// Retrieves the voice channels linked to a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the channels cannot be fetched from the database.
+pub fn get_channels(conn: &mut PgConnection, team: i32) -> Result<Vec<String>, String> {
+    use crate::schema::team_voice_channels::dsl::*;
+
+    team_voice_channels
+        .filter(team_id.eq(team))
+        .order(created_at.asc())
+        .select(channel_id)
+        .load(conn)
+        .map_err(|e| format!("Failed to fetch voice channels: {}", e))
+}
+
+/// Retrieves the active teams of a Discord server together with each of their voice channels.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `guild` - The ID of the Discord server.
+///
+/// # Returns
+/// Returns one `(Team, channel_id)` pair per linked voice channel.
+///
+/// # Errors
+/// Returns an error if the teams cannot be fetched from the database.
+pub fn get_voice_links(
+    conn: &mut PgConnection,
+    guild: &str,
+) -> Result<Vec<(Team, String)>, String> {
+    use crate::schema::{team_voice_channels, teams};
+
+    teams::table
+        .inner_join(team_voice_channels::table)
+        .filter(teams::guild_id.eq(guild))
+        .filter(teams::archived_at.is_null())
+        .select((teams::all_columns, team_voice_channels::channel_id))
+        .load::<(Team, String)>(conn)
+        .map_err(|e| format!("Failed to fetch voice channels: {}", e))
+}
@@ -0,0 +1,285 @@
+use crate::bot::domain::model::{NewWebhook, NewWebhookDelivery, Webhook, WebhookDelivery};
+use crate::bot::domain::webhook::DeliveryStatus;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+
+/// Inserts a webhook.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `new_webhook` - The webhook to insert.
+///
+/// # Returns
+/// Returns the inserted webhook.
+///
+/// # Errors
+/// Returns an error if the webhook cannot be inserted into the database.
+pub fn create_webhook(
+    conn: &mut PgConnection,
+    new_webhook: &NewWebhook,
+) -> Result<Webhook, String> {
+    use crate::schema::webhooks::dsl::*;
+
+    diesel::insert_into(webhooks)
+        .values(new_webhook)
+        .get_result::<Webhook>(conn)
+        .map_err(|e| format!("Failed to add webhook: {}", e))
+}
+
+/// Retrieves the webhooks of a team.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+///
+/// # Errors
+/// Returns an error if the webhooks cannot be fetched from the database.
+pub fn get_webhooks(conn: &mut PgConnection, team: i32) -> Result<Vec<Webhook>, String> {
+    use crate::schema::webhooks::dsl::*;
+
+    webhooks
+        .filter(team_id.eq(team))
+        .order(id.asc())
+        .load::<Webhook>(conn)
+        .map_err(|e| format!("Failed to fetch webhooks: {}", e))
+}
+
+/// Finds a webhook by ID.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `webhook` - The ID of the webhook.
+///
+/// # Returns
+/// Returns the webhook, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the webhook cannot be fetched from the database.
+pub fn find_webhook(conn: &mut PgConnection, webhook: i32) -> Result<Option<Webhook>, String> {
+    use crate::schema::webhooks::dsl::*;
+
+    webhooks
+        .find(webhook)
+        .first::<Webhook>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch webhook: {}", e))
+}
+
+/// Deletes a webhook of a team together with its deliveries.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `team` - The ID of the team.
+/// * `webhook` - The ID of the webhook.
+///
+/// # Returns
+/// Returns `false` if the team has no such webhook.
+///
+/// # Errors
+/// Returns an error if the webhook cannot be deleted from the database.
+pub fn delete_webhook(conn: &mut PgConnection, team: i32, webhook: i32) -> Result<bool, String> {
+    use crate::schema::webhooks::dsl::*;
+
+    diesel::delete(webhooks.filter(team_id.eq(team)).filter(id.eq(webhook)))
+        .execute(conn)
+        .map(|deleted| deleted > 0)
+        .map_err(|e| format!("Failed to remove webhook: {}", e))
+}
+
+/// Counts the deliveries of webhooks by status.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `webhook_ids` - The IDs of the webhooks.
+///
+/// # Returns
+/// Returns `(webhook ID, status, count)` for each status a webhook has deliveries in.
+///
+/// # Errors
+/// Returns an error if the deliveries cannot be counted.
+pub fn count_deliveries(
+    conn: &mut PgConnection,
+    webhook_ids: &[i32],
+) -> Result<Vec<(i32, String, i64)>, String> {
+    use crate::schema::webhook_deliveries::dsl::*;
+
+    webhook_deliveries
+        .filter(webhook_id.eq_any(webhook_ids))
+        .group_by((webhook_id, status))
+        .select((webhook_id, status, diesel::dsl::count_star()))
+        .load::<(i32, String, i64)>(conn)
+        .map_err(|e| format!("Failed to count webhook deliveries: {}", e))
+}
+
+/// Adds pending deliveries to the outbox.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `deliveries` - The deliveries to insert.
+///
+/// # Returns
+/// Returns the inserted deliveries.
+///
+/// # Errors
+/// Returns an error if the deliveries cannot be inserted into the database.
+pub fn create_deliveries(
+    conn: &mut PgConnection,
+    deliveries: &[NewWebhookDelivery],
+) -> Result<Vec<WebhookDelivery>, String> {
+    use crate::schema::webhook_deliveries::dsl::*;
+
+    diesel::insert_into(webhook_deliveries)
+        .values(deliveries)
+        .get_results::<WebhookDelivery>(conn)
+        .map_err(|e| format!("Failed to queue webhook deliveries: {}", e))
+}
+
+/// Retrieves the latest deliveries of a webhook.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `webhook` - The ID of the webhook.
+/// * `limit` - How many deliveries to return at most.
+///
+/// # Returns
+/// Returns the deliveries, newest first.
+///
+/// # Errors
+/// Returns an error if the deliveries cannot be fetched from the database.
+pub fn get_deliveries(
+    conn: &mut PgConnection,
+    webhook: i32,
+    limit: i64,
+) -> Result<Vec<WebhookDelivery>, String> {
+    use crate::schema::webhook_deliveries::dsl::*;
+
+    webhook_deliveries
+        .filter(webhook_id.eq(webhook))
+        .order(id.desc())
+        .limit(limit)
+        .load::<WebhookDelivery>(conn)
+        .map_err(|e| format!("Failed to fetch webhook deliveries: {}", e))
+}
+
+/// Finds a delivery by ID together with its webhook.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `delivery` - The ID of the delivery.
+///
+/// # Returns
+/// Returns the delivery and its webhook, or `None` if it does not exist.
+///
+/// # Errors
+/// Returns an error if the delivery cannot be fetched from the database.
+pub fn find_delivery(
+    conn: &mut PgConnection,
+    delivery: i32,
+) -> Result<Option<(WebhookDelivery, Webhook)>, String> {
+    use crate::schema::{webhook_deliveries, webhooks};
+
+    webhook_deliveries::table
+        .inner_join(webhooks::table)
+        .filter(webhook_deliveries::id.eq(delivery))
+        .first::<(WebhookDelivery, Webhook)>(conn)
+        .optional()
+        .map_err(|e| format!("Failed to fetch webhook delivery: {}", e))
+}
+
+/// Retrieves the pending deliveries whose next attempt is due, oldest first.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `now` - The current time.
+/// * `limit` - How many deliveries to return at most.
+///
+/// # Returns
+/// Returns each delivery together with its webhook.
+///
+/// # Errors
+/// Returns an error if the deliveries cannot be fetched from the database.
+pub fn get_due_deliveries(
+    conn: &mut PgConnection,
+    now: NaiveDateTime,
+    limit: i64,
+) -> Result<Vec<(WebhookDelivery, Webhook)>, String> {
+    use crate::schema::{webhook_deliveries, webhooks};
+
+    webhook_deliveries::table
+        .inner_join(webhooks::table)
+        .filter(webhook_deliveries::status.eq(DeliveryStatus::Pending.as_str()))
+        .filter(webhook_deliveries::next_attempt_at.le(now))
+        .order(webhook_deliveries::id.asc())
+        .limit(limit)
+        .load::<(WebhookDelivery, Webhook)>(conn)
+        .map_err(|e| format!("Failed to fetch due webhook deliveries: {}", e))
+}
+
+/// Records a successful attempt of a delivery.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `delivery` - The ID of the delivery.
+/// * `response` - The HTTP status the receiver answered with.
+/// * `now` - The time of the attempt.
+///
+/// # Errors
+/// Returns an error if the delivery cannot be updated in the database.
+pub fn mark_delivered(
+    conn: &mut PgConnection,
+    delivery: i32,
+    response: i32,
+    now: NaiveDateTime,
+) -> Result<(), String> {
+    use crate::schema::webhook_deliveries::dsl::*;
+
+    diesel::update(webhook_deliveries.find(delivery))
+        .set((
+            status.eq(DeliveryStatus::Delivered.as_str()),
+            attempts.eq(attempts + 1),
+            response_status.eq(Some(response)),
+            last_error.eq(None::<String>),
+            delivered_at.eq(Some(now)),
+        ))
+        .execute(conn)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to update webhook delivery: {}", e))
+}
+
+/// Records a failed attempt of a delivery.
+///
+/// # Arguments
+/// * `conn` - A mutable reference to the PostgreSQL connection.
+/// * `delivery` - The ID of the delivery.
+/// * `response` - The HTTP status the receiver answered with, if it answered.
+/// * `error` - Why the attempt failed.
+/// * `retry_at` - When to attempt the delivery again, `None` when it ran out of attempts.
+///
+/// # Errors
+/// Returns an error if the delivery cannot be updated in the database.
+pub fn mark_failed(
+    conn: &mut PgConnection,
+    delivery: i32,
+    response: Option<i32>,
+    error: &str,
+    retry_at: Option<NaiveDateTime>,
+) -> Result<(), String> {
+    use crate::schema::webhook_deliveries::dsl::*;
+
+    let new_status = match retry_at {
+        Some(_) => DeliveryStatus::Pending,
+        None => DeliveryStatus::Failed,
+    };
+
+    diesel::update(webhook_deliveries.find(delivery))
+        .set((
+            status.eq(new_status.as_str()),
+            attempts.eq(attempts + 1),
+            response_status.eq(response),
+            last_error.eq(Some(error)),
+            next_attempt_at.eq(retry_at.unwrap_or_else(|| Utc::now().naive_utc())),
+        ))
+        .execute(conn)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to update webhook delivery: {}", e))
+}
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use crate::config::constant::WEBHOOK_TIMEOUT_SECONDS;
+
+/// Posts a webhook delivery to its receiver.
+///
+/// # Arguments
+/// * `url` - The URL of the webhook.
+/// * `headers` - The headers describing and signing the delivery.
+/// * `body` - The JSON body of the delivery.
+///
+/// # Returns
+/// Returns the HTTP status the receiver answered with, whatever it is.
+///
+/// # Errors
+/// Returns an error if the receiver cannot be reached or does not answer in time.
+pub async fn post(url: &str, headers: &[(&str, String)], body: String) -> Result<u16, String> {
+    let mut request = reqwest::Client::new()
+        .post(url)
+        .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECONDS))
+        .header("Content-Type", "application/json")
+        .body(body);
+    for (name, value) in headers {
+        request = request.header(*name, value);
+    }
+
+    request
+        .send()
+        .await
+        .map(|response| response.status().as_u16())
+        .map_err(|e| {
+            if e.is_timeout() {
+                "Timed out".to_string()
+            } else if e.is_connect() {
+                "Connection failed".to_string()
+            } else {
+                e.to_string()
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    /// Accepts one request on a local port and answers it with `status`.
+    ///
+    /// # Returns
+    /// Returns the URL to post to and a handle resolving to the raw request received.
+    async fn receiver(status: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !is_complete(&request) {
+                let read = socket.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        (url, handle)
+    }
+
+    /// Checks whether the headers and the whole body of a request were read.
+    fn is_complete(request: &[u8]) -> bool {
+        let text = String::from_utf8_lossy(request);
+        let Some((head, body)) = text.split_once("\r\n\r\n") else {
+            return false;
+        };
+        let length = head
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse::<usize>().ok())?
+            })
+            .unwrap_or(0);
+        body.len() >= length
+    }
+
+    #[tokio::test]
+    async fn posts_the_body_with_its_headers() {
+        let (url, request) = receiver("204 No Content").await;
+        let headers = [
+            ("X-Attendance-Event", "check_in".to_string()),
+            ("X-Attendance-Signature", "sha256=abc".to_string()),
+        ];
+
+        let status = post(&url, &headers, r#"{"event":"check_in"}"#.to_string()).await;
+        assert_eq!(status, Ok(204));
+
+        let request = request.await.unwrap().to_lowercase();
+        assert!(request.starts_with("post /hook http/1.1"));
+        assert!(request.contains("content-type: application/json"));
+        assert!(request.contains("x-attendance-event: check_in"));
+        assert!(request.contains("x-attendance-signature: sha256=abc"));
+        assert!(request.ends_with(r#"{"event":"check_in"}"#));
+    }
+
+    #[tokio::test]
+    async fn returns_error_statuses_as_they_are() {
+        let (url, request) = receiver("500 Internal Server Error").await;
+
+        assert_eq!(post(&url, &[], "{}".to_string()).await, Ok(500));
+        request.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reports_unreachable_receivers() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        drop(listener);
+
+        assert_eq!(
+            post(&url, &[], "{}".to_string()).await,
+            Err("Connection failed".to_string())
+        );
+    }
+}
@@ -1,23 +1,215 @@
-pub const HELP_MESSAGES: &str = "
+// one entry per Discord message, each has to stay under the 2000 character limit
+pub const HELP_MESSAGES: &[&str] = &[
+    "
 **Attendance Bot Commands**  
 
 Hi! I'm the Attendance Bot. Here are the commands you can use:
 
 🛠 **Admin Commands**  
 🔹 `!AB register {password}`  → Register as Admin team  
+🔹 `!AB revoke_sessions`  → Log out every API session  
+🔹 `!AB apikey create {name} {read|write} {guild_id/team_name?}`  → Create an API key (DM only)  
+🔹 `!AB apikey list`  → Show your API keys (DM only)  
+🔹 `!AB apikey revoke {id}`  → Revoke an API key (DM only)  
 
 👥 **Team Management**  
 🔹 `!AB create_team {team_name}`  → Create a new team
 🔹 `!AB show_team`  → Show existing teams  
-
+🔹 `!AB rename_team {team_name} {new_name}`  → Rename a team  
+🔹 `!AB archive_team {team_name}`  → Archive a team (read-only)  
+🔹 `!AB transfer_team {team_name} @user`  → Hand a team over to another admin  
+🔹 `!AB delete_team {team_name}`  → Delete a team  
+🔹 `!AB grant_role {team_name} @user {owner|manager|viewer|member}`  → Grant a team role  
+🔹 `!AB revoke_role {team_name} @user`  → Revoke a team role  
+🔹 `!AB show_roles {team_name}`  → Show the roles of a team  
+🔹 `!AB link_role {team_name} @role`  → Keep the members of a team in sync with a Discord role  
+🔹 `!AB unlink_role {team_name}`  → Stop syncing a team with its Discord role  
+🔹 `!AB sync_team {team_name}`  → Add the role holders and deactivate members without the role  
+",
+    "
 👤 **Member Management**  
-🔹 `!AB add_member {team_name} @member_name`  → Add a member to a team  
+🔹 `!AB add_member {team_name} @user [@user ...] [as {full name}]`  → Add members to a team  
 🔹 `!AB show_members {team_name}`  → Show members of a team  
+🔹 `!AB remove_member {team_name} @user`  → Remove a member and their attendance records  
+🔹 `!AB set_position {team_name} @user {position}`  → Change the position of a member  
+🔹 `!AB rename_member {team_name} @user {new_name}`  → Change the name of a member  
+🔹 `!AB deactivate_member {team_name} @user`  → Mark a member as having left the team  
 🔹 `!AB show_members_attendance {team_name}`  → Show attendance for a team's members  
 
 ⏳ **Attendance Tracking**  
 🔹 `!AB check_in {team_name} {status}`  → Start session (Check-in)  
+🔹 `!AB check_in {team_name} code:{123456}`  → Check in to an on-site event with its code  
 🔹 `!AB check_out {team_name}`  → End session (Check-out)  
+🔹 `!AB link_voice {team_name} #channel`  → Check members in while they are in a voice channel  
+🔹 `!AB unlink_voice {team_name} #channel`  → Stop tracking a voice channel  
+🔹 `!AB voice_settings {team_name} [{min_minutes} {debounce_seconds}]`  → Show or change voice attendance settings  
+🔹 `!AB rollcall {team_name} {duration}`  → Post a roll-call with a Present button (e.g. 10m)  
+🔹 `!AB checkin_code {team_name} {duration} {title?}`  → Show a rotating check-in code for an on-site event  
+🔹 `!AB whos_in {team_name}`  → Show who is checked in right now  
+🔹 `!AB whos_in {team_name} board {#channel|off}`  → Keep a pinned who's-in board in a channel  
+",
+    "
+📅 **Events**  
+🔹 `!AB event create {team_name} {YYYY-MM-DD} {HH:MM} {duration} {title} [repeat:{RRULE}]`  → Schedule an event (UTC)  
+🔹 `!AB event list {team_name}`  → Show upcoming events  
+🔹 `!AB event cancel {team_name} {id}`  → Cancel an event  
+🔹 `!AB event skip {team_name} {id} {YYYY-MM-DD}`  → Skip one occurrence of a repeating event  
+🔹 `!AB event report {team_name} {id} [{YYYY-MM-DD}]`  → Show who attended an event and the no-shows  
+
+🌴 **Leave**  
+🔹 `!AB leave request {team_name} {YYYY-MM-DD} {YYYY-MM-DD} {type} {reason}`  → Request leave (vacation, sick, personal, other)  
+🔹 `!AB leave list {team_name} [pending]`  → Show leave requests  
+🔹 `!AB leave cancel {team_name} {id}`  → Withdraw your leave request  
+🔹 `!AB leave channel {team_name} [#channel]`  → Set where leave requests are reviewed  
+🔹 `!AB leave balance {team_name} [{year}]`  → Show leave taken against the allowances  
+🔹 `!AB leave balance {team_name} @user {year} {type} {days}`  → Set a yearly leave allowance  
+
+🏖 **Holidays**  
+🔹 `!AB holiday add {team_name} {YYYY-MM-DD} {name}`  → Add a day off  
+🔹 `!AB holiday list {team_name} [year]`  → Show the holidays of a year  
+🔹 `!AB holiday remove {team_name} {YYYY-MM-DD}`  → Remove a day off  
+🔹 `!AB holiday import {team_name}`  → Import the attached .ics file  
+
+🕘 **Schedule**  
+🔹 `!AB schedule {team_name}`  → Show the daily schedule  
+🔹 `!AB schedule {team_name} {HH:MM} {grace_minutes} [#channel]`  → Record no-shows as absent (UTC)  
+🔹 `!AB schedule {team_name} off`  → Stop recording no-shows  
+",
+    "
+⏰ **Reminders**  
+🔹 `!AB reminders`  → Show your reminder settings  
+🔹 `!AB reminders on|off`  → Turn your reminders on or off  
+🔹 `!AB reminders quiet {HH:MM} {HH:MM}`  → Set your quiet hours (UTC), `quiet off` to clear them  
+🔹 `!AB reminders add {team_name} {check_in|check_out} {before:minutes|at:HH:MM}`  → Remind members by DM  
+🔹 `!AB reminders list {team_name}`  → Show the reminders of a team  
+🔹 `!AB reminders remove {team_name} {id}`  → Remove a reminder  
+
+✏️ **Corrections**  
+🔹 `!AB attendance edit {team_name} {id} [in:HH:MM] [out:HH:MM|-] [status:X] {reason}`  → Fix a record  
+🔹 `!AB attendance add {team_name} @user {YYYY-MM-DD} in:HH:MM [out:HH:MM] {reason}`  → Add a missing record  
+🔹 `!AB attendance request {team_name} {id|YYYY-MM-DD} ... {reason}`  → Ask for a correction  
+🔹 `!AB attendance corrections {team_name} [all]`  → Show correction requests  
+🔹 `!AB attendance approve|reject {team_name} {id}`  → Review a correction request  
+🔹 `!AB attendance history {id}`  → Show the changes made to a record  
+
+📜 **Audit Log**  
+🔹 `!AB audit {team_name} [action:X] [actor:@user] [since:YYYY-MM-DD] [until:YYYY-MM-DD] [limit:N]`  → Show who changed what  
+
+🔗 **Webhooks**  
+🔹 `!AB webhook add {team_name} {url} {events|all}`  → Post events to a URL, the secret comes by DM  
+🔹 `!AB webhook list {team_name}`  → Show the webhooks of a team  
+🔹 `!AB webhook remove {team_name} {id}`  → Remove a webhook  
+🔹 `!AB webhook deliveries {team_name} {id}`  → Show the latest deliveries  
+🔹 `!AB webhook replay {team_name} {delivery_id}`  → Send a delivery again  
 
 ⚠️ **Note:** Ensure you have the correct permissions and passwords for Admin-related commands.
-";
+",
+];
+
+// status of the check-ins recorded from voice channel presence
+pub const VOICE_CHECK_IN_STATUS: &str = "Voice";
+
+// how long before a scheduled event starts members can check in to it
+pub const EVENT_CHECK_IN_GRACE_MINUTES: i64 = 15;
+
+// longest a single occurrence of an event can last
+pub const EVENT_MAX_HOURS: i64 = 24;
+
+// lifetime of the JWT access token returned by /login and /auth/refresh
+pub const ACCESS_TOKEN_TTL_MINUTES: i64 = 15;
+
+// lifetime of a refresh token before the user has to log in again
+pub const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+
+// failed logins (per IP or per account) allowed before exponential backoff kicks in
+pub const LOGIN_FREE_ATTEMPTS: u32 = 3;
+
+// upper bound of the backoff between two login attempts
+pub const LOGIN_MAX_BACKOFF_SECONDS: u64 = 300;
+
+// failed logins after which the account is locked
+pub const LOGIN_LOCKOUT_THRESHOLD: i32 = 10;
+
+// how long a locked account stays locked
+pub const LOGIN_LOCKOUT_MINUTES: i64 = 30;
+
+// longest leave a single request can cover, in calendar days
+pub const LEAVE_MAX_DAYS: i64 = 365;
+
+// largest .ics file accepted by a holiday import
+pub const HOLIDAY_IMPORT_MAX_BYTES: u32 = 1024 * 1024;
+
+// most days a single holiday import can add
+pub const HOLIDAY_IMPORT_MAX_DAYS: usize = 1000;
+
+// how often the bot looks for teams whose absences are due to be recorded
+pub const ABSENCE_CHECK_INTERVAL_SECONDS: u64 = 60;
+
+// longest grace period after the start of a team's working day before absences are recorded
+pub const SCHEDULE_MAX_GRACE_MINUTES: i32 = 720;
+
+// how often the bot looks for reminders that are due
+pub const REMINDER_CHECK_INTERVAL_SECONDS: u64 = 60;
+
+// reminders found this late (e.g. after a restart) are dropped instead of sent
+pub const REMINDER_MAX_DELAY_MINUTES: i64 = 30;
+
+// most reminder rules a team can have
+pub const REMINDER_MAX_RULES: usize = 10;
+
+// how often audit events older than the retention period are removed
+pub const AUDIT_PURGE_INTERVAL_SECONDS: u64 = 24 * 60 * 60;
+
+// how long audit events are kept when AUDIT_RETENTION_DAYS is not set
+pub const AUDIT_DEFAULT_RETENTION_DAYS: i64 = 365;
+
+// how often the bot sends the webhook deliveries that are due
+pub const WEBHOOK_DELIVERY_INTERVAL_SECONDS: u64 = 5;
+
+// most deliveries sent in one round, the rest wait for the next one
+pub const WEBHOOK_DELIVERY_BATCH_SIZE: i64 = 50;
+
+// how long a receiver has to answer a delivery
+pub const WEBHOOK_TIMEOUT_SECONDS: u64 = 10;
+
+// a delivery is given up after this many failed attempts
+pub const WEBHOOK_MAX_ATTEMPTS: i32 = 8;
+
+// wait after the first failed attempt, doubled after each further one
+pub const WEBHOOK_RETRY_BASE_SECONDS: i64 = 30;
+
+// longest wait between two attempts of a delivery
+pub const WEBHOOK_RETRY_MAX_SECONDS: i64 = 60 * 60;
+
+// most webhooks a team can have
+pub const WEBHOOK_MAX_PER_TEAM: usize = 10;
+
+// how many deliveries the delivery log shows
+pub const WEBHOOK_DELIVERY_LOG_LIMIT: i64 = 10;
+
+// updates the live feed keeps for a slow client before it skips ahead
+pub const LIVE_FEED_CAPACITY: usize = 256;
+
+// how often an idle live feed sends a comment, so proxies keep the connection open
+pub const LIVE_FEED_KEEP_ALIVE_SECONDS: u64 = 15;
+
+// longest a live feed stays open, the client then reconnects and is authenticated again
+pub const LIVE_FEED_MAX_SECONDS: u64 = 60 * 60;
+
+// how long a client waits before reconnecting to a closed live feed
+pub const LIVE_FEED_RETRY_MILLISECONDS: u64 = 5000;
+
+// how long the presence boards wait after an attendance change, so a burst is a single edit
+pub const PRESENCE_BOARD_DEBOUNCE_SECONDS: u64 = 2;
+
+// how long a WebSocket client may stay silent, including pongs, before it is disconnected
+pub const WS_CLIENT_TIMEOUT_SECONDS: u64 = 45;
+
+// teams a single WebSocket connection can subscribe to
+pub const WS_MAX_SUBSCRIPTIONS: usize = 20;
+
+// largest message a WebSocket client can send, the protocol only needs a few bytes
+pub const WS_MAX_FRAME_BYTES: usize = 4096;
+
+// frames waiting to be written to a WebSocket client before the connection stops reading
+pub const WS_SEND_BUFFER: usize = 64;
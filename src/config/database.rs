@@ -1,5 +1,6 @@
 use diesel::pg::PgConnection;
 use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::Connection;
 
 use super::settings::Settings;
 
@@ -13,3 +14,94 @@ pub fn establish_connection() -> DBPool {
         .build(manager)
         .expect("Failed to connect to database!")
 }
+
+/// Runs `f` in a transaction, which is rolled back if `f` returns an error.
+///
+/// # Errors
+/// Returns the error of `f`, or an error if the transaction cannot be started or committed.
+pub fn transaction<T, E: From<String>>(
+    conn: &mut PgConnection,
+    f: impl FnOnce(&mut PgConnection) -> Result<T, E>,
+) -> Result<T, E> {
+    let mut error = None;
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        f(conn).map_err(|e| {
+            error = Some(e);
+            diesel::result::Error::RollbackTransaction
+        })
+    })
+    .map_err(|e| error.unwrap_or_else(|| format!("Transaction failed: {}", e).into()))
+}
+
+/// Fixtures for the tests that need a database. They run against `DATABASE_URL` and are
+/// marked `#[ignore]`, run them with `cargo test -- --ignored`.
+#[cfg(test)]
+pub mod testing {
+    use crate::schema::{members, teams};
+    use diesel::prelude::*;
+
+    /// Runs `test` in a transaction that is rolled back afterwards.
+    pub fn with_connection(test: impl FnOnce(&mut PgConnection)) {
+        let url = std::env::var("DATABASE_URL")
+            .expect("DATABASE_URL must be set to run the database tests");
+        let mut conn = PgConnection::establish(&url).expect("Failed to connect to database");
+        conn.test_transaction::<_, diesel::result::Error, _>(|conn| {
+            test(conn);
+            Ok(())
+        });
+    }
+
+    /// Runs `test` with the IDs of a new team and of its only member, discord ID `1`.
+    pub fn with_member(test: impl FnOnce(&mut PgConnection, i32, i32)) {
+        with_connection(|conn| {
+            let team_id = diesel::insert_into(teams::table)
+                .values((teams::name.eq("test"), teams::guild_id.eq("test")))
+                .returning(teams::id)
+                .get_result(conn)
+                .unwrap();
+            let member_id = diesel::insert_into(members::table)
+                .values((
+                    members::username.eq("member"),
+                    members::discord_id.eq("1"),
+                    members::team_id.eq(team_id),
+                ))
+                .returning(members::id)
+                .get_result(conn)
+                .unwrap();
+
+            test(conn, team_id, member_id);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::teams;
+    use diesel::prelude::*;
+
+    #[test]
+    #[ignore = "needs a database"]
+    fn rolls_back_when_the_closure_fails() {
+        testing::with_connection(|conn| {
+            let result: Result<(), String> = transaction(conn, |conn| {
+                diesel::insert_into(teams::table)
+                    .values((
+                        teams::name.eq("rollback-test"),
+                        teams::guild_id.eq("rollback"),
+                    ))
+                    .execute(conn)
+                    .map_err(|e| e.to_string())?;
+                Err("Owner role could not be granted".to_string())
+            });
+            assert_eq!(result, Err("Owner role could not be granted".to_string()));
+
+            let left: i64 = teams::table
+                .filter(teams::guild_id.eq("rollback"))
+                .count()
+                .get_result(conn)
+                .unwrap();
+            assert_eq!(left, 0);
+        });
+    }
+}
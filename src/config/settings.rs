@@ -2,6 +2,8 @@ use std::env;
 
 use dotenvy::dotenv;
 
+use crate::config::constant::AUDIT_DEFAULT_RETENTION_DAYS;
+
 
 // struct environment
 pub struct Settings {
@@ -19,3 +21,56 @@ impl Settings {
         }
     }
 }
+
+// Discord OAuth2 application used for `/auth/discord/*`. The endpoints can be
+// overridden so the flow can run against a local mock server.
+#[derive(Clone)]
+pub struct DiscordOAuthSettings {
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    pub authorize_url: String,
+    pub token_url: String,
+    pub api_base_url: String,
+}
+
+impl DiscordOAuthSettings {
+    // Returns `None` when no OAuth2 application is configured.
+    pub fn from_env() -> Option<Self> {
+        dotenv().ok();
+
+        Some(Self {
+            client_id: env::var("DISCORD_CLIENT_ID").ok()?,
+            client_secret: env::var("DISCORD_CLIENT_SECRET").ok()?,
+            redirect_uri: env::var("DISCORD_REDIRECT_URI").ok()?,
+            authorize_url: env::var("DISCORD_OAUTH_AUTHORIZE_URL")
+                .unwrap_or_else(|_| "https://discord.com/oauth2/authorize".to_string()),
+            token_url: env::var("DISCORD_OAUTH_TOKEN_URL")
+                .unwrap_or_else(|_| "https://discord.com/api/oauth2/token".to_string()),
+            api_base_url: env::var("DISCORD_API_BASE_URL")
+                .unwrap_or_else(|_| "https://discord.com/api".to_string()),
+        })
+    }
+}
+
+// How long the audit log keeps administrative actions, from `AUDIT_RETENTION_DAYS`.
+// `0` keeps them forever.
+#[derive(Clone, Copy)]
+pub struct AuditSettings {
+    pub retention_days: Option<i64>,
+}
+
+impl AuditSettings {
+    pub fn from_env() -> Self {
+        dotenv().ok();
+
+        let days = env::var("AUDIT_RETENTION_DAYS")
+            .ok()
+            .and_then(|days| days.parse::<i64>().ok())
+            .unwrap_or(AUDIT_DEFAULT_RETENTION_DAYS);
+
+        Self {
+            retention_days: (days > 0).then_some(days),
+        }
+    }
+}
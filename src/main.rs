@@ -4,8 +4,8 @@ mod config;
 mod schema;
 
 use api::adapters::http_server::{self};
-use bcrypt::verify;
 use bot::adapters::discord_bot::Handler;
+use bot::adapters::handler::webhooks;
 use config::{database, logger, settings};
 use serenity::prelude::*;
 use tokio::task;
@@ -24,6 +24,9 @@ async fn main() {
     let db_pool = database::establish_connection();
     log::info!("Database connection established!");
 
+    // Send webhook deliveries, whether they come from the bot or the API
+    webhooks::start_webhook_deliveries(db_pool.clone());
+
     // run discord bot and actix api in parallel
     let bot_discord = task::spawn(run_discord_bot(db_pool.clone(), config.discord_token));
     let api_discord = task::spawn(run_api_server());
@@ -38,7 +41,11 @@ async fn run_discord_bot(
     token: String,
 ) {
     // Initialize Discord Client
-    let intents = GatewayIntents::GUILD_MESSAGES | GatewayIntents::MESSAGE_CONTENT;
+    let intents = GatewayIntents::GUILD_MESSAGES
+        | GatewayIntents::DIRECT_MESSAGES
+        | GatewayIntents::MESSAGE_CONTENT
+        | GatewayIntents::GUILD_MEMBERS
+        | GatewayIntents::GUILD_VOICE_STATES;
 
     let mut client = Client::builder(&token, intents)
         .event_handler(Handler::new(db_pool.clone()))
@@ -1,5 +1,153 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_keys (id) {
+        id -> Int4,
+        user_id -> Int4,
+        team_id -> Nullable<Int4>,
+        #[max_length = 50]
+        name -> Varchar,
+        #[max_length = 16]
+        key_prefix -> Varchar,
+        #[max_length = 64]
+        key_hash -> Varchar,
+        #[max_length = 10]
+        scope -> Varchar,
+        last_used_at -> Nullable<Timestamp>,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    attendance_audit (id) {
+        id -> Int4,
+        attendance_id -> Nullable<Int4>,
+        correction_id -> Nullable<Int4>,
+        #[max_length = 10]
+        action -> Varchar,
+        #[max_length = 50]
+        actor_id -> Varchar,
+        #[max_length = 255]
+        reason -> Varchar,
+        before_check_in_time -> Nullable<Timestamp>,
+        before_check_out_time -> Nullable<Timestamp>,
+        #[max_length = 20]
+        before_status -> Nullable<Varchar>,
+        after_check_in_time -> Nullable<Timestamp>,
+        after_check_out_time -> Nullable<Timestamp>,
+        #[max_length = 20]
+        after_status -> Nullable<Varchar>,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    attendance_corrections (id) {
+        id -> Int4,
+        team_id -> Int4,
+        member_id -> Int4,
+        attendance_id -> Nullable<Int4>,
+        attendance_date -> Date,
+        check_in_time -> Nullable<Timestamp>,
+        check_out_time -> Nullable<Timestamp>,
+        clear_check_out -> Bool,
+        #[max_length = 20]
+        attendance_status -> Nullable<Varchar>,
+        #[max_length = 255]
+        reason -> Varchar,
+        #[max_length = 10]
+        status -> Varchar,
+        #[max_length = 50]
+        reviewed_by -> Nullable<Varchar>,
+        reviewed_at -> Nullable<Timestamp>,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    audit_events (id) {
+        id -> Int4,
+        #[max_length = 50]
+        actor_id -> Varchar,
+        #[max_length = 10]
+        source -> Varchar,
+        #[max_length = 50]
+        action -> Varchar,
+        team_id -> Nullable<Int4>,
+        #[max_length = 50]
+        team_name -> Nullable<Varchar>,
+        #[max_length = 50]
+        guild_id -> Nullable<Varchar>,
+        #[max_length = 50]
+        channel_id -> Nullable<Varchar>,
+        #[max_length = 100]
+        target -> Nullable<Varchar>,
+        details -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    event_exceptions (id) {
+        id -> Int4,
+        event_id -> Int4,
+        starts_at -> Timestamp,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    events (id) {
+        id -> Int4,
+        team_id -> Int4,
+        #[max_length = 100]
+        title -> Varchar,
+        starts_at -> Timestamp,
+        ends_at -> Timestamp,
+        #[max_length = 64]
+        code_secret -> Nullable<Varchar>,
+        #[max_length = 50]
+        created_by -> Varchar,
+        created_at -> Nullable<Timestamp>,
+        #[max_length = 255]
+        recurrence_rule -> Nullable<Varchar>,
+        cancelled_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    leave_balances (id) {
+        id -> Int4,
+        member_id -> Int4,
+        year -> Int4,
+        #[max_length = 20]
+        leave_type -> Varchar,
+        allowance_days -> Int4,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    leave_requests (id) {
+        id -> Int4,
+        team_id -> Int4,
+        member_id -> Int4,
+        #[max_length = 20]
+        leave_type -> Varchar,
+        starts_on -> Date,
+        ends_on -> Date,
+        #[max_length = 255]
+        reason -> Varchar,
+        #[max_length = 10]
+        status -> Varchar,
+        #[max_length = 50]
+        reviewed_by -> Nullable<Varchar>,
+        reviewed_at -> Nullable<Timestamp>,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     member_attendance (id) {
         id -> Int4,
@@ -10,6 +158,9 @@ diesel::table! {
         check_out_time -> Nullable<Timestamp>,
         #[max_length = 20]
         status -> Nullable<Varchar>,
+        rollcall_id -> Nullable<Int4>,
+        event_id -> Nullable<Int4>,
+        system_generated -> Bool,
     }
 }
 
@@ -24,6 +175,97 @@ diesel::table! {
         #[max_length = 50]
         position -> Nullable<Varchar>,
         join_date -> Nullable<Date>,
+        left_date -> Nullable<Date>,
+    }
+}
+
+diesel::table! {
+    refresh_tokens (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        #[max_length = 64]
+        access_jti -> Varchar,
+        expires_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    reminder_preferences (discord_id) {
+        #[max_length = 50]
+        discord_id -> Varchar,
+        opted_out -> Bool,
+        quiet_start -> Nullable<Time>,
+        quiet_end -> Nullable<Time>,
+        updated_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    reminder_rules (id) {
+        id -> Int4,
+        team_id -> Int4,
+        #[max_length = 10]
+        kind -> Varchar,
+        minutes_before_start -> Nullable<Int4>,
+        remind_at -> Nullable<Time>,
+        last_sent_on -> Nullable<Date>,
+        #[max_length = 50]
+        created_by -> Varchar,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    revoked_tokens (jti) {
+        #[max_length = 64]
+        jti -> Varchar,
+        expires_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    rollcalls (id) {
+        id -> Int4,
+        team_id -> Int4,
+        #[max_length = 50]
+        channel_id -> Varchar,
+        #[max_length = 50]
+        message_id -> Nullable<Varchar>,
+        #[max_length = 50]
+        started_by -> Varchar,
+        closes_at -> Timestamp,
+        closed_at -> Nullable<Timestamp>,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    team_holidays (id) {
+        id -> Int4,
+        team_id -> Int4,
+        holiday_date -> Date,
+        #[max_length = 100]
+        name -> Varchar,
+        created_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    team_roles (id) {
+        id -> Int4,
+        team_id -> Int4,
+        #[max_length = 50]
+        discord_id -> Varchar,
+        #[max_length = 20]
+        role -> Varchar,
+        #[max_length = 50]
+        granted_by -> Nullable<Varchar>,
+        created_at -> Nullable<Timestamp>,
     }
 }
 
@@ -34,6 +276,34 @@ diesel::table! {
         name -> Varchar,
         admin_id -> Nullable<Int4>,
         created_at -> Nullable<Timestamp>,
+        #[max_length = 50]
+        guild_id -> Varchar,
+        archived_at -> Nullable<Timestamp>,
+        #[max_length = 50]
+        discord_role_id -> Nullable<Varchar>,
+        voice_min_minutes -> Int4,
+        voice_debounce_seconds -> Int4,
+        #[max_length = 50]
+        leave_channel_id -> Nullable<Varchar>,
+        schedule_start -> Nullable<Time>,
+        schedule_grace_minutes -> Int4,
+        #[max_length = 50]
+        report_channel_id -> Nullable<Varchar>,
+        absences_checked_on -> Nullable<Date>,
+        #[max_length = 50]
+        presence_channel_id -> Nullable<Varchar>,
+        #[max_length = 50]
+        presence_message_id -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    team_voice_channels (id) {
+        id -> Int4,
+        team_id -> Int4,
+        #[max_length = 50]
+        channel_id -> Varchar,
+        created_at -> Nullable<Timestamp>,
     }
 }
 
@@ -49,17 +319,95 @@ diesel::table! {
         is_admin -> Nullable<Bool>,
         created_at -> Nullable<Timestamp>,
         updated_at -> Nullable<Timestamp>,
+        failed_login_attempts -> Int4,
+        last_failed_login_at -> Nullable<Timestamp>,
+        locked_until -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    webhook_deliveries (id) {
+        id -> Int4,
+        webhook_id -> Int4,
+        #[max_length = 30]
+        event_type -> Varchar,
+        payload -> Text,
+        #[max_length = 10]
+        status -> Varchar,
+        attempts -> Int4,
+        next_attempt_at -> Timestamp,
+        response_status -> Nullable<Int4>,
+        last_error -> Nullable<Text>,
+        delivered_at -> Nullable<Timestamp>,
+        replay_of -> Nullable<Int4>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    webhooks (id) {
+        id -> Int4,
+        team_id -> Int4,
+        #[max_length = 500]
+        url -> Varchar,
+        #[max_length = 64]
+        secret -> Varchar,
+        #[max_length = 100]
+        event_types -> Varchar,
+        #[max_length = 50]
+        created_by -> Varchar,
+        created_at -> Nullable<Timestamp>,
     }
 }
 
+diesel::joinable!(api_keys -> teams (team_id));
+diesel::joinable!(api_keys -> users (user_id));
+diesel::joinable!(attendance_audit -> attendance_corrections (correction_id));
+diesel::joinable!(attendance_audit -> member_attendance (attendance_id));
+diesel::joinable!(attendance_corrections -> member_attendance (attendance_id));
+diesel::joinable!(attendance_corrections -> members (member_id));
+diesel::joinable!(attendance_corrections -> teams (team_id));
+diesel::joinable!(event_exceptions -> events (event_id));
+diesel::joinable!(events -> teams (team_id));
+diesel::joinable!(leave_balances -> members (member_id));
+diesel::joinable!(leave_requests -> members (member_id));
+diesel::joinable!(leave_requests -> teams (team_id));
+diesel::joinable!(member_attendance -> events (event_id));
 diesel::joinable!(member_attendance -> members (member_id));
+diesel::joinable!(member_attendance -> rollcalls (rollcall_id));
 diesel::joinable!(member_attendance -> teams (team_id));
 diesel::joinable!(members -> teams (team_id));
+diesel::joinable!(refresh_tokens -> users (user_id));
+diesel::joinable!(reminder_rules -> teams (team_id));
+diesel::joinable!(rollcalls -> teams (team_id));
+diesel::joinable!(team_holidays -> teams (team_id));
+diesel::joinable!(team_roles -> teams (team_id));
+diesel::joinable!(team_voice_channels -> teams (team_id));
 diesel::joinable!(teams -> users (admin_id));
+diesel::joinable!(webhook_deliveries -> webhooks (webhook_id));
+diesel::joinable!(webhooks -> teams (team_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_keys,
+    attendance_audit,
+    attendance_corrections,
+    audit_events,
+    event_exceptions,
+    events,
+    leave_balances,
+    leave_requests,
     member_attendance,
     members,
+    refresh_tokens,
+    reminder_preferences,
+    reminder_rules,
+    revoked_tokens,
+    rollcalls,
+    team_holidays,
+    team_roles,
+    team_voice_channels,
     teams,
     users,
+    webhook_deliveries,
+    webhooks,
 );